@@ -0,0 +1,198 @@
+//! Append-only log of local operations
+//!
+//! Every mutation made through `pacli` or `patui` is appended as one JSON
+//! object per line to `activity.jsonl` in the data directory, so users can
+//! review what they did and when. Recording is best effort: a failure to
+//! write the log never fails the operation itself.
+
+use crate::config::Config;
+use anyhow::Result;
+use pali_types::Todo;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+/// Which frontend performed an operation
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Cli,
+    Tui,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cli => write!(f, "cli"),
+            Self::Tui => write!(f, "tui"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ActivityEntry {
+    pub timestamp: i64,
+    pub source: Source,
+    pub operation: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub todo_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Error message if the operation failed, `None` on success
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ActivityEntry {
+    #[must_use]
+    pub fn new(source: Source, operation: impl Into<String>) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp(),
+            source,
+            operation: operation.into(),
+            todo_id: None,
+            title: None,
+            error: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_todo(mut self, todo: &Todo) -> Self {
+        self.todo_id = Some(todo.id.clone());
+        self.title = Some(todo.title.clone());
+        self
+    }
+
+    #[must_use]
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.todo_id = Some(id.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    #[must_use]
+    pub fn failed(mut self, error: impl std::fmt::Display) -> Self {
+        self.error = Some(error.to_string());
+        self
+    }
+
+    #[must_use]
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Returns the path to the activity log
+///
+/// # Errors
+///
+/// Returns an error if the data directory cannot be determined
+pub fn log_path() -> Result<PathBuf> {
+    Ok(Config::data_dir()?.join("activity.jsonl"))
+}
+
+fn append(entry: &ActivityEntry) -> Result<()> {
+    let path = log_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// Appends an entry to the activity log (best effort)
+pub fn record(entry: &ActivityEntry) {
+    if let Err(err) = append(entry) {
+        log::warn!("Could not write activity log: {err}");
+    }
+}
+
+/// Records the outcome of an operation, passing the result through
+///
+/// # Errors
+///
+/// Returns the original error of `result`, unchanged
+pub fn track<T>(entry: ActivityEntry, result: Result<T>) -> Result<T> {
+    match &result {
+        Ok(_) => record(&entry),
+        Err(err) => record(&entry.failed(err)),
+    }
+    result
+}
+
+/// Records the outcome of an operation returning a todo, filling in its ID and title
+///
+/// # Errors
+///
+/// Returns the original error of `result`, unchanged
+pub fn track_todo(entry: ActivityEntry, result: Result<Todo>) -> Result<Todo> {
+    match &result {
+        Ok(todo) => record(&entry.with_todo(todo)),
+        Err(err) => record(&entry.failed(err)),
+    }
+    result
+}
+
+/// Reads all entries recorded at or after `since` (Unix timestamp)
+///
+/// Lines that cannot be parsed are skipped.
+///
+/// # Errors
+///
+/// Returns an error if the log exists but cannot be read
+pub fn read_since(since: i64) -> Result<Vec<ActivityEntry>> {
+    let path = log_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let reader = BufReader::new(fs::File::open(path)?);
+    let mut entries = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        match serde_json::from_str::<ActivityEntry>(&line) {
+            Ok(entry) if entry.timestamp >= since => entries.push(entry),
+            Ok(_) => {}
+            Err(err) => log::debug!("Skipping malformed activity log line: {err}"),
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_builder() {
+        let entry = ActivityEntry::new(Source::Cli, "delete")
+            .with_id("abc")
+            .with_title("Buy milk");
+        assert!(entry.succeeded());
+        assert_eq!(entry.todo_id.as_deref(), Some("abc"));
+
+        let entry = entry.failed("Server error");
+        assert!(!entry.succeeded());
+        assert_eq!(entry.error.as_deref(), Some("Server error"));
+    }
+
+    #[test]
+    fn test_entry_serialization() {
+        let entry = ActivityEntry::new(Source::Tui, "toggle").with_id("abc");
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(json.contains("\"source\":\"tui\""));
+        assert!(!json.contains("error"));
+
+        let deserialized: ActivityEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, entry);
+    }
+}
@@ -1,10 +1,24 @@
+use crate::cache::TodoCache;
 use crate::config::Config;
+use crate::hooks::{self, HookEvent};
+use crate::id_index::IdIndex;
+use crate::interceptor::Interceptor;
+use crate::metrics;
+use crate::redact;
+use crate::term;
+use crate::timings;
+use crate::trace::{self, REQUEST_ID_HEADER};
+use crate::transport::{self, Transport};
 use anyhow::{Context, Result};
+use futures_util::future::BoxFuture;
+use futures_util::stream::{self, BoxStream, Stream, StreamExt};
 use reqwest::{Client, Response};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
 use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
-#[cfg(feature = "cli")]
 use crate::logging::{log_http_request, log_http_response};
 
 const API_KEY_HEADER: &str = "X-API-Key";
@@ -12,8 +26,19 @@ const API_KEY_HEADER: &str = "X-API-Key";
 // Re-export shared types
 pub use pali_types::*;
 
-// CLI-specific type aliases
-pub type ApiKey = ApiKeyInfo;
+/// An API key as listed by the server
+///
+/// Scopes and expiry are newer additions; servers that don't report them
+/// leave the key unrestricted.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiKey {
+    #[serde(flatten)]
+    pub info: ApiKeyInfo,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+}
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
 pub struct GenerateKeyResponse {
@@ -21,9 +46,337 @@ pub struct GenerateKeyResponse {
     pub id: String,
 }
 
+/// Options for a newly generated API key
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct GenerateKeyRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Permissions such as `todos:read`; empty leaves the server default (full access)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+    /// Unix timestamp after which the key stops working
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+/// A file attached to a todo
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Attachment {
+    pub id: String,
+    pub filename: String,
+    pub size: u64,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    pub created_at: i64,
+}
+
+/// Formats a byte count in human readable form (e.g. `512 B`, `1.5 KB`, `3.2 MB`)
+#[must_use]
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    #[allow(clippy::cast_precision_loss)] // Display only
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next;
+    }
+    format!("{size:.1} {unit}")
+}
+
+impl Attachment {
+    /// Returns the size in human readable form
+    #[must_use]
+    pub fn display_size(&self) -> String {
+        format_bytes(self.size)
+    }
+}
+
+/// A progress note left on a todo
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Comment {
+    pub id: String,
+    pub text: String,
+    pub created_at: i64,
+}
+
+/// Who last changed a todo, as reported by `/todos/modifiers`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TodoModifier {
+    pub todo_id: String,
+    pub modified_at: i64,
+    #[serde(default)]
+    pub key_id: Option<String>,
+    #[serde(default)]
+    pub key_name: Option<String>,
+    /// Client that made the change, as it named itself (e.g. `pacli`)
+    #[serde(default)]
+    pub client: Option<String>,
+}
+
+impl TodoModifier {
+    /// Who made the change: the key's name, else the client, else the key ID
+    #[must_use]
+    pub fn who(&self) -> &str {
+        self.key_name
+            .as_deref()
+            .or(self.client.as_deref())
+            .or(self.key_id.as_deref())
+            .unwrap_or("someone")
+    }
+}
+
+/// A todo as it was after one change, as listed by `/todos/{id}/revisions`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Revision {
+    pub changed_at: i64,
+    /// Name of the key or client that made the change
+    #[serde(default)]
+    pub changed_by: Option<String>,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub priority: i32,
+    #[serde(default)]
+    pub due_date: Option<i64>,
+    #[serde(default)]
+    pub completed: bool,
+}
+
+/// Request counts of an API key as reported by the server's usage endpoints
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KeyUsage {
+    pub key_id: String,
+    #[serde(default)]
+    pub client_name: Option<String>,
+    pub total_requests: u64,
+    /// Requests in the last 30 days
+    pub last_30_days: u64,
+    #[serde(default)]
+    pub last_used: Option<i64>,
+    /// Requests per route (e.g. `GET /todos`); only filled in for a single key
+    #[serde(default)]
+    pub routes: BTreeMap<String, u64>,
+}
+
+/// Deployment information reported by `/admin/status`
+///
+/// Everything except the version is optional, since older servers report less.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ServerStatus {
+    pub version: String,
+    #[serde(default)]
+    pub uptime_seconds: Option<i64>,
+    #[serde(default)]
+    pub todo_count: Option<u64>,
+    #[serde(default)]
+    pub storage: Option<StorageUsage>,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StorageUsage {
+    pub used_bytes: u64,
+    #[serde(default)]
+    pub limit_bytes: Option<u64>,
+}
+
+/// Optional features the server supports, as reported by `/capabilities`
+///
+/// Servers without the endpoint predate every optional feature, so they get
+/// [`Capabilities::default`] and only the core todo API is used with them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Capabilities {
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub features: BTreeSet<String>,
+}
+
+impl Capabilities {
+    /// `GET /todos/resolve/{prefix}` resolves ID prefixes on the server
+    pub const RESOLVE: &'static str = "resolve";
+    /// `GET /todos` takes `due_after` and `due_before` (see [`ApiClient::list_todos_due`])
+    pub const DUE_FILTER: &'static str = "due_filter";
+    /// `GET /todos/modifiers` reports who last changed each todo (see [`ApiClient::list_modifiers`])
+    pub const MODIFIERS: &'static str = "modifiers";
+    /// `GET /todos/{id}/revisions` lists a todo's past states (see [`ApiClient::list_revisions`])
+    pub const REVISIONS: &'static str = "revisions";
+
+    #[must_use]
+    pub fn supports(&self, feature: &str) -> bool {
+        self.features.contains(feature)
+    }
+}
+
+/// Requests allowed per key within a time window
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RateLimit {
+    pub requests: u64,
+    pub window_seconds: u64,
+}
+
+/// A request recorded in the server's audit log
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuditEvent {
+    pub id: String,
+    pub timestamp: i64,
+    #[serde(default)]
+    pub key_id: Option<String>,
+    #[serde(default)]
+    pub key_name: Option<String>,
+    /// What was done, e.g. `todo.create` or `key.revoke`
+    pub action: String,
+    #[serde(default)]
+    pub ip: Option<String>,
+}
+
+/// One page of audit events, oldest first
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuditPage {
+    pub events: Vec<AuditEvent>,
+    /// Pass to the next request to continue; `None` on the last page
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+/// A request was abandoned because its [`CancellationToken`] was cancelled
+///
+/// See [`ApiClient::with_cancellation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Request cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Prints the warning about disabled certificate checks once per process
+#[cfg(feature = "http-optimized")]
+fn warn_insecure() {
+    use colored::Colorize;
+    static WARNED: std::sync::Once = std::sync::Once::new();
+
+    WARNED.call_once(|| {
+        eprintln!(
+            "{} TLS certificate verification is DISABLED (insecure_skip_verify). \
+             Anyone on the network can read and change your requests.",
+            format!("{} WARNING:", term::symbols().warning).red().bold()
+        );
+    });
+}
+
+/// Scheme for endpoints served over a unix domain socket, e.g. `unix:///run/pali.sock`
+pub const UNIX_SOCKET_SCHEME: &str = "unix://";
+
+/// Returns the socket path if the endpoint is a `unix://` URL
+#[must_use]
+pub fn unix_socket_path(endpoint: &str) -> Option<&std::path::Path> {
+    endpoint
+        .strip_prefix(UNIX_SOCKET_SCHEME)
+        .map(std::path::Path::new)
+}
+
+/// Returns the base URL requests are made against
+///
+/// Socket endpoints use a placeholder host, since the socket replaces DNS and
+/// TCP entirely. Local addresses without a scheme (`localhost:8787`, `:8787`,
+/// `127.0.0.1:8787`) are shortcuts for plain HTTP.
+fn base_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if unix_socket_path(endpoint).is_some() {
+        return "http://localhost".to_string();
+    }
+    if endpoint.contains("://") {
+        return endpoint.to_string();
+    }
+
+    if let Some(port) = endpoint.strip_prefix(':') {
+        return format!("http://localhost:{port}");
+    }
+    let host = match endpoint.rsplit_once(':') {
+        Some((host, _)) if !endpoint.ends_with(']') => host,
+        _ => endpoint,
+    };
+    if matches!(host, "localhost" | "127.0.0.1" | "[::1]") {
+        format!("http://{endpoint}")
+    } else {
+        endpoint.to_string()
+    }
+}
+
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Splits a byte stream into lines and parses each non-empty line as JSON
+fn ndjson_lines<T, S, C, E>(bytes: S) -> impl Stream<Item = Result<T>>
+where
+    T: for<'de> Deserialize<'de>,
+    S: Stream<Item = std::result::Result<C, E>>,
+    C: AsRef<[u8]>,
+    E: Into<anyhow::Error>,
+{
+    let parse = |line: &[u8]| serde_json::from_slice(line).context("Invalid line in todo stream");
+
+    stream::unfold(
+        (Box::pin(bytes), Vec::new(), false),
+        move |(mut bytes, mut buf, mut done)| async move {
+            loop {
+                if let Some(end) = buf.iter().position(|&b| b == b'\n') {
+                    let line: Vec<u8> = buf.drain(..=end).collect();
+                    if line.trim_ascii().is_empty() {
+                        continue;
+                    }
+                    return Some((parse(line.trim_ascii()), (bytes, buf, done)));
+                }
+                if done {
+                    if buf.trim_ascii().is_empty() {
+                        return None;
+                    }
+                    let rest = std::mem::take(&mut buf);
+                    return Some((parse(rest.trim_ascii()), (bytes, buf, done)));
+                }
+
+                match bytes.next().await {
+                    Some(Ok(chunk)) => buf.extend_from_slice(chunk.as_ref()),
+                    Some(Err(err)) => {
+                        buf.clear();
+                        let err: anyhow::Error = err.into();
+                        return Some((
+                            Err(err.context("Todo stream was interrupted")),
+                            (bytes, buf, true),
+                        ));
+                    }
+                    None => done = true,
+                }
+            }
+        },
+    )
+}
+
+#[derive(Clone)]
 pub struct ApiClient {
     client: Client,
     config: Config,
+    /// Fetched on first use and shared between clones
+    capabilities: Arc<tokio::sync::OnceCell<Capabilities>>,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    /// Sends requests instead of `client` (see [`ApiClient::with_transport`])
+    transport: Option<Arc<dyn Transport>>,
+    /// Overrides the configured timeout (see [`ApiClient::with_timeout`])
+    timeout: Option<Duration>,
+    cancel: Option<CancellationToken>,
 }
 
 impl ApiClient {
@@ -42,24 +395,135 @@ impl ApiClient {
     /// - Configuration file format is invalid
     /// - HTTP client initialization fails
     pub fn new() -> Result<Self> {
-        let config = Config::load()?;
+        Self::with_config(Config::load()?)
+    }
+
+    /// Creates a client for the given configuration instead of the saved one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built
+    pub fn with_config(config: Config) -> Result<Self> {
+        if config.metrics {
+            metrics::enable();
+        }
 
         #[cfg(feature = "http-optimized")]
-        let client = Self::build_optimized_client()?;
+        let client = Self::build_optimized_client(&config)?;
 
         #[cfg(not(feature = "http-optimized"))]
-        let client = Self::build_standard_client()?;
+        let client = Self::build_standard_client(&config)?;
+
+        Ok(Self {
+            client,
+            config,
+            capabilities: Arc::default(),
+            interceptors: Vec::new(),
+            transport: transport::global(),
+            timeout: None,
+            cancel: None,
+        })
+    }
+
+    /// Returns a client whose requests give up after `timeout` instead of the configured timeout
+    ///
+    /// Cheap enough to call per request, e.g. `client.with_timeout(d).ping()`.
+    #[must_use]
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a client whose requests fail with [`Cancelled`] once `token` is cancelled
+    ///
+    /// Requests waiting for a response are abandoned right away, so a
+    /// superseded refresh doesn't keep the connection busy.
+    #[must_use]
+    pub fn with_cancellation(&self, token: CancellationToken) -> Self {
+        Self {
+            cancel: Some(token),
+            ..self.clone()
+        }
+    }
+
+    /// Runs `interceptor` around every request this client (and its clones) sends
+    ///
+    /// See [`crate::interceptor`].
+    #[must_use]
+    pub fn with_interceptor(mut self, interceptor: impl Interceptor + 'static) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
 
-        Ok(Self { client, config })
+    /// Sends every request through `transport` instead of the network
+    ///
+    /// Clients pick up the session of `--record` or `--replay` on their own;
+    /// see [`crate::transport`].
+    #[must_use]
+    pub fn with_transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Applies the proxy and certificate settings from the config
+    fn apply_network_settings(
+        mut builder: reqwest::ClientBuilder,
+        config: &Config,
+    ) -> Result<reqwest::ClientBuilder> {
+        if let Some(proxy) = &config.proxy {
+            builder = builder
+                .proxy(reqwest::Proxy::all(proxy).context(format!("Invalid proxy URL '{proxy}'"))?);
+        }
+
+        #[cfg(feature = "http-optimized")]
+        {
+            if let Some(path) = &config.ca_cert_path {
+                let pem = std::fs::read(path)
+                    .context(format!("Failed to read CA certificate {}", path.display()))?;
+                for cert in reqwest::Certificate::from_pem_bundle(&pem)
+                    .context(format!("Invalid CA certificate {}", path.display()))?
+                {
+                    builder = builder.add_root_certificate(cert);
+                }
+            }
+
+            if config.insecure_skip_verify {
+                warn_insecure();
+                builder = builder.danger_accept_invalid_certs(true);
+            }
+        }
+
+        #[cfg(not(feature = "http-optimized"))]
+        if config.ca_cert_path.is_some() || config.insecure_skip_verify {
+            log::warn!("TLS settings are ignored: built without TLS support (http-optimized)");
+        }
+
+        if let Some(socket) = unix_socket_path(&config.api_endpoint) {
+            #[cfg(all(unix, feature = "unix-socket"))]
+            {
+                log::debug!("Connecting through unix socket {}", socket.display());
+                builder = builder.unix_socket(socket);
+            }
+
+            #[cfg(not(all(unix, feature = "unix-socket")))]
+            anyhow::bail!(
+                "Endpoint {} needs unix socket support. Rebuild with --features unix-socket.",
+                socket.display()
+            );
+        }
+
+        Ok(builder)
     }
 
     #[cfg(feature = "http-optimized")]
-    fn build_optimized_client() -> Result<Client> {
+    fn build_optimized_client(config: &Config) -> Result<Client> {
         // Build an optimized HTTP client focused on reducing latency
-        let client = Client::builder()
+        let builder = Client::builder()
             // Connection and timeout optimizations to reduce latency
-            .timeout(Duration::from_secs(30)) // Total request timeout
-            .connect_timeout(Duration::from_secs(5)) // Faster connection timeout
+            .timeout(Duration::from_secs(config.timeout.unwrap_or(30))) // Total request timeout
+            .connect_timeout(Duration::from_secs(config.connect_timeout.unwrap_or(5))) // Faster connection timeout
             .tcp_nodelay(true) // Disable Nagle's algorithm for faster small requests
             .tcp_keepalive(Duration::from_secs(60)) // Keep TCP connections alive
             // Aggressive connection pool optimizations for connection reuse
@@ -75,7 +539,9 @@ impl ApiClient {
                 "pali-terminal/",
                 env!("CARGO_PKG_VERSION"),
                 " (http-optimized)"
-            ))
+            ));
+
+        let client = Self::apply_network_settings(builder, config)?
             .build()
             .context("Unable to initialize network client")?;
 
@@ -83,43 +549,151 @@ impl ApiClient {
     }
 
     #[cfg(not(feature = "http-optimized"))]
-    fn build_standard_client() -> Result<Client> {
+    fn build_standard_client(config: &Config) -> Result<Client> {
         // Build a standard HTTP client with default settings
-        let client = Client::builder()
+        let builder = Client::builder()
             // Basic timeout settings
-            .timeout(Duration::from_secs(30)) // Total request timeout
-            .connect_timeout(Duration::from_secs(10)) // Standard connection timeout
+            .timeout(Duration::from_secs(config.timeout.unwrap_or(30))) // Total request timeout
+            .connect_timeout(Duration::from_secs(config.connect_timeout.unwrap_or(10))) // Standard connection timeout
             // User agent for debugging/monitoring
             .user_agent(concat!(
                 "pali-terminal/",
                 env!("CARGO_PKG_VERSION"),
                 " (standard)"
-            ))
+            ));
+
+        let client = Self::apply_network_settings(builder, config)?
             .build()
             .context("Unable to initialize network client")?;
 
         Ok(client)
     }
 
+    /// Returns the configuration the client was created with
+    #[must_use]
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
     fn build_url(&self, path: &str) -> String {
-        format!("{}{}", self.config.api_endpoint.trim_end_matches('/'), path)
+        format!("{}{}", base_url(&self.config.api_endpoint), path)
     }
 
-    fn add_auth_header(&self, mut req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    /// Attaches credentials: an OAuth bearer token when configured, otherwise the API key
+    async fn add_auth_header(
+        &self,
+        mut req: reqwest::RequestBuilder,
+    ) -> Result<reqwest::RequestBuilder> {
+        if let Some(oauth) = &self.config.oauth {
+            let token = crate::auth::access_token(&self.client, oauth).await?;
+            return Ok(req.bearer_auth(token));
+        }
+
         if let Some(ref key) = self.config.api_key {
             req = req.header(API_KEY_HEADER, key);
         }
-        req
+        Ok(req)
+    }
+
+    /// Sends a request tagged with a fresh `X-Request-Id` through the
+    /// interceptors, logging it and printing it in full when `--trace` is on
+    async fn send(&self, req: reqwest::RequestBuilder) -> Result<Response> {
+        let request_id = trace::new_request_id();
+        let req = match self.timeout {
+            Some(timeout) => req.timeout(timeout),
+            None => req,
+        };
+        let mut request = req
+            .header(REQUEST_ID_HEADER, &request_id)
+            .build()
+            .context("Unable to build request")?;
+        for interceptor in &self.interceptors {
+            interceptor.on_request(&mut request);
+        }
+
+        log_http_request(
+            request.method().as_str(),
+            request.url().as_str(),
+            request.body().is_some(),
+            &request_id,
+        );
+
+        let probe = if trace::is_enabled() {
+            trace::print_request(&request, &request_id);
+            if unix_socket_path(&self.config.api_endpoint).is_some() {
+                trace::Probe::default()
+            } else {
+                trace::Probe::run(&request).await
+            }
+        } else {
+            trace::Probe::default()
+        };
+
+        let method = request.method().clone();
+        let url = request.url().clone();
+        let start = std::time::Instant::now();
+        let execute: BoxFuture<'_, Result<Response>> = match &self.transport {
+            Some(transport) => transport.execute(&self.client, request),
+            None => Box::pin(async { Ok(self.client.execute(request).await?) }),
+        };
+        let response = match &self.cancel {
+            Some(token) => tokio::select! {
+                response = execute => response,
+                () = token.cancelled() => {
+                    log::debug!("HTTP request {request_id} cancelled");
+                    return Err(Cancelled.into());
+                }
+            },
+            None => execute.await,
+        }
+        .inspect_err(|err| {
+            log::warn!("HTTP request {request_id} failed: {err}");
+            metrics::record(method.as_str(), url.path(), start.elapsed(), true);
+        })?;
+        let elapsed = start.elapsed();
+        let status = response.status();
+        metrics::record(
+            method.as_str(),
+            url.path(),
+            elapsed,
+            status.is_client_error() || status.is_server_error(),
+        );
+        for interceptor in &self.interceptors {
+            interceptor.on_response(&method, &url, &response, elapsed);
+        }
+
+        log_http_response(response.status().as_u16(), elapsed);
+        timings::record(
+            format!(
+                "{} {} ({})",
+                method,
+                response.url().path(),
+                response.status().as_u16()
+            ),
+            elapsed,
+        );
+
+        if trace::is_enabled() {
+            trace::print_response(&response, &probe, elapsed);
+        }
+
+        Ok(response)
     }
 
     async fn handle_response<T: for<'de> Deserialize<'de>>(response: Response) -> Result<T> {
         let status = response.status();
 
         if status.is_success() {
-            let api_response: ApiResponse<T> = response
-                .json()
+            let url = response.url().to_string();
+            let body = response
+                .text()
                 .await
-                .context("Unable to process server response")?;
+                .context("Unable to read server response")?;
+            let api_response: ApiResponse<T> = decode(&body).inspect_err(|_| {
+                if trace::dumps_bodies() {
+                    trace::print_body(&url, &body);
+                }
+            })?;
 
             if api_response.success {
                 api_response
@@ -158,20 +732,16 @@ impl ApiClient {
     pub async fn create_todo(&self, request: CreateTodoRequest) -> Result<Todo> {
         let url = self.build_url("/todos");
 
-        #[cfg(feature = "cli")]
-        log_http_request("POST", &url, true);
-
         let req = self.client.post(&url);
-        let req = self.add_auth_header(req);
+        let req = self.add_auth_header(req).await?;
 
-        let start = std::time::Instant::now();
-        let response = req.json(&request).send().await?;
-        let elapsed = start.elapsed();
+        let response = self.send(req.json(&request)).await?;
 
-        #[cfg(feature = "cli")]
-        log_http_response(response.status().as_u16(), elapsed);
-
-        Self::handle_response(response).await
+        let todo: Todo = Self::handle_response(response).await?;
+        IdIndex::update(|index| index.merge(std::slice::from_ref(&todo)));
+        TodoCache::upsert(&self.config.api_endpoint, &todo);
+        hooks::fire(&self.config, HookEvent::Create, &todo).await;
+        Ok(todo)
     }
 
     /// Lists todos with optional filtering by tag and priority
@@ -190,11 +760,10 @@ impl ApiClient {
     ) -> Result<Vec<Todo>> {
         let url = self.build_url("/todos");
 
-        #[cfg(feature = "cli")]
-        log_http_request("GET", &url, false);
-
         let req = self.client.get(&url);
-        let mut req = self.add_auth_header(req);
+        let mut req = self.add_auth_header(req).await?;
+        // Filtered lists only add to the ID index; unfiltered ones replace it
+        let complete = tag.is_none() && priority.is_none();
 
         if let Some(tag) = tag {
             req = req.query(&[("tag", tag)]);
@@ -204,14 +773,110 @@ impl ApiClient {
             req = req.query(&[("priority", priority)]);
         }
 
-        let start = std::time::Instant::now();
-        let response = req.send().await?;
-        let elapsed = start.elapsed();
+        let response = self.send(req).await?;
 
-        #[cfg(feature = "cli")]
-        log_http_response(response.status().as_u16(), elapsed);
+        let todos: Vec<Todo> = Self::handle_response(response).await?;
+        if complete {
+            TodoCache::store(&self.config.api_endpoint, &todos);
+        }
+        IdIndex::update(|index| {
+            if complete {
+                index.replace_all(&todos, chrono::Utc::now().timestamp());
+            } else {
+                index.merge(&todos);
+            }
+        });
+        Ok(todos)
+    }
 
-        Self::handle_response(response).await
+    /// Lists the todos due at or after `due_after` and before `due_before` (Unix timestamps)
+    ///
+    /// Only for servers reporting [`Capabilities::DUE_FILTER`]; others ignore
+    /// the parameters and return every todo.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Network request fails
+    /// - Server returns an error response
+    /// - Response parsing fails
+    /// - API key is missing or invalid
+    pub async fn list_todos_due(
+        &self,
+        due_after: Option<i64>,
+        due_before: Option<i64>,
+    ) -> Result<Vec<Todo>> {
+        let req = self.client.get(self.build_url("/todos"));
+        let mut req = self.add_auth_header(req).await?;
+        if let Some(after) = due_after {
+            req = req.query(&[("due_after", after)]);
+        }
+        if let Some(before) = due_before {
+            req = req.query(&[("due_before", before)]);
+        }
+
+        let response = self.send(req).await?;
+        let todos: Vec<Todo> = Self::handle_response(response).await?;
+        IdIndex::update(|index| index.merge(&todos));
+        Ok(todos)
+    }
+
+    /// Lists the todos created or changed after `updated_after` (Unix timestamp)
+    ///
+    /// Used by [`crate::cache::sync`] to refresh the cached list without
+    /// downloading every todo. Deleted todos are not reported. Servers that
+    /// don't support the filter return the complete list.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Network request fails
+    /// - Server returns an error response
+    /// - Response parsing fails
+    /// - API key is missing or invalid
+    pub async fn list_todos_since(&self, updated_after: i64) -> Result<Vec<Todo>> {
+        let req = self.client.get(self.build_url("/todos"));
+        let req = self
+            .add_auth_header(req)
+            .await?
+            .query(&[("updated_after", updated_after)]);
+
+        let response = self.send(req).await?;
+        let todos: Vec<Todo> = Self::handle_response(response).await?;
+        IdIndex::update(|index| index.merge(&todos));
+        Ok(todos)
+    }
+
+    /// Streams all todos one at a time, for accounts too large to hold in memory
+    ///
+    /// Asks the server for NDJSON (one todo per line) and parses it as it
+    /// arrives. Servers that don't support it answer with the regular JSON
+    /// list, which is then yielded item by item.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the server returns an error
+    /// response; the stream yields an error for lines that cannot be parsed
+    /// or if the connection breaks mid-way.
+    pub async fn list_todos_stream(&self) -> Result<BoxStream<'static, Result<Todo>>> {
+        let req = self.client.get(self.build_url("/todos"));
+        let req = self
+            .add_auth_header(req)
+            .await?
+            .header(reqwest::header::ACCEPT, NDJSON_CONTENT_TYPE);
+
+        let response = self.send(req).await?;
+        let is_ndjson = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with(NDJSON_CONTENT_TYPE));
+        if !response.status().is_success() || !is_ndjson {
+            let todos: Vec<Todo> = Self::handle_response(response).await?;
+            return Ok(stream::iter(todos.into_iter().map(Ok)).boxed());
+        }
+
+        Ok(ndjson_lines(response.bytes_stream()).boxed())
     }
 
     /// Retrieves a specific todo by ID
@@ -226,10 +891,12 @@ impl ApiClient {
     /// - API key is missing or invalid
     pub async fn get_todo(&self, id: &str) -> Result<Todo> {
         let req = self.client.get(self.build_url(&format!("/todos/{id}")));
-        let req = self.add_auth_header(req);
+        let req = self.add_auth_header(req).await?;
 
-        let response = req.send().await?;
-        Self::handle_response(response).await
+        let response = self.send(req).await?;
+        let todo: Todo = Self::handle_response(response).await?;
+        IdIndex::update(|index| index.merge(std::slice::from_ref(&todo)));
+        Ok(todo)
     }
 
     /// Updates an existing todo item
@@ -243,12 +910,19 @@ impl ApiClient {
     /// - Response parsing fails
     /// - API key is missing or invalid
     pub async fn update_todo(&self, id: &str, request: UpdateTodoRequest) -> Result<Todo> {
+        let completing = request.completed == Some(true);
         let req = self.client.put(self.build_url(&format!("/todos/{id}")));
-        let req = self.add_auth_header(req);
+        let req = self.add_auth_header(req).await?;
 
-        let response = req.json(&request).send().await?;
+        let response = self.send(req.json(&request)).await?;
 
-        Self::handle_response(response).await
+        let todo: Todo = Self::handle_response(response).await?;
+        IdIndex::update(|index| index.merge(std::slice::from_ref(&todo)));
+        TodoCache::upsert(&self.config.api_endpoint, &todo);
+        if completing && todo.completed {
+            hooks::fire(&self.config, HookEvent::Complete, &todo).await;
+        }
+        Ok(todo)
     }
 
     /// Deletes a todo item by ID
@@ -262,12 +936,14 @@ impl ApiClient {
     /// - API key is missing or invalid
     pub async fn delete_todo(&self, id: &str) -> Result<()> {
         let req = self.client.delete(self.build_url(&format!("/todos/{id}")));
-        let req = self.add_auth_header(req);
+        let req = self.add_auth_header(req).await?;
 
-        let response = req.send().await?;
+        let response = self.send(req).await?;
         let status = response.status();
 
         if status.is_success() {
+            IdIndex::update(|index| index.remove(id));
+            TodoCache::remove(&self.config.api_endpoint, id);
             Ok(())
         } else {
             let error_text = response.text().await.unwrap_or_else(|_| {
@@ -298,10 +974,15 @@ impl ApiClient {
         let req = self
             .client
             .patch(self.build_url(&format!("/todos/{id}/toggle")));
-        let req = self.add_auth_header(req);
+        let req = self.add_auth_header(req).await?;
 
-        let response = req.send().await?;
-        Self::handle_response(response).await
+        let response = self.send(req).await?;
+        let todo: Todo = Self::handle_response(response).await?;
+        TodoCache::upsert(&self.config.api_endpoint, &todo);
+        if todo.completed {
+            hooks::fire(&self.config, HookEvent::Complete, &todo).await;
+        }
+        Ok(todo)
     }
 
     /// Searches todos by query string
@@ -315,13 +996,184 @@ impl ApiClient {
     /// - API key is missing or invalid
     pub async fn search_todos(&self, query: &str) -> Result<Vec<Todo>> {
         let req = self.client.get(self.build_url("/todos/search"));
-        let req = self.add_auth_header(req);
+        let req = self.add_auth_header(req).await?;
+
+        let response = self.send(req.query(&[("q", query)])).await?;
+
+        Self::handle_response(response).await
+    }
+
+    /// Uploads a file as an attachment of a todo
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Network request fails
+    /// - Todo with the given ID is not found
+    /// - Server rejects the upload (e.g. file too large)
+    /// - Response parsing fails
+    /// - API key is missing or invalid
+    pub async fn upload_attachment(
+        &self,
+        todo_id: &str,
+        filename: &str,
+        content: Vec<u8>,
+    ) -> Result<Attachment> {
+        let url = self.build_url(&format!("/todos/{todo_id}/attachments"));
+
+        let part = reqwest::multipart::Part::bytes(content).file_name(filename.to_string());
+        let form = reqwest::multipart::Form::new().part("file", part);
 
-        let response = req.query(&[("q", query)]).send().await?;
+        let req = self.client.post(&url);
+        let req = self.add_auth_header(req).await?;
+
+        let response = self.send(req.multipart(form)).await?;
+
+        Self::handle_response(response).await
+    }
+
+    /// Lists the attachments of a todo
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Network request fails
+    /// - Todo with the given ID is not found
+    /// - Server returns an error response
+    /// - Response parsing fails
+    /// - API key is missing or invalid
+    pub async fn list_attachments(&self, todo_id: &str) -> Result<Vec<Attachment>> {
+        let req = self
+            .client
+            .get(self.build_url(&format!("/todos/{todo_id}/attachments")));
+        let req = self.add_auth_header(req).await?;
+
+        let response = self.send(req).await?;
+        Self::handle_response(response).await
+    }
+
+    /// Downloads the raw content of an attachment
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Network request fails
+    /// - Todo or attachment is not found
+    /// - Server returns an error response
+    /// - API key is missing or invalid
+    pub async fn download_attachment(&self, todo_id: &str, attachment_id: &str) -> Result<Vec<u8>> {
+        let req = self
+            .client
+            .get(self.build_url(&format!("/todos/{todo_id}/attachments/{attachment_id}")));
+        let req = self.add_auth_header(req).await?;
+
+        let response = self.send(req).await?;
+        let status = response.status();
+
+        if status.is_success() {
+            Ok(response
+                .bytes()
+                .await
+                .context("Unable to read attachment content")?
+                .to_vec())
+        } else {
+            let error_text = response.text().await.unwrap_or_else(|_| {
+                "Unable to connect to server. Please check your connection.".to_string()
+            });
+            anyhow::bail!(
+                "Server error: {}",
+                if error_text.trim().is_empty() {
+                    "Please try again later"
+                } else {
+                    &error_text
+                }
+            )
+        }
+    }
+
+    /// Adds a comment to a todo
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Network request fails
+    /// - Todo with the given ID is not found
+    /// - Server returns an error response
+    /// - Response parsing fails
+    /// - API key is missing or invalid
+    pub async fn add_comment(&self, todo_id: &str, text: &str) -> Result<Comment> {
+        #[derive(Serialize)]
+        struct CommentRequest<'a> {
+            text: &'a str,
+        }
+
+        let req = self
+            .client
+            .post(self.build_url(&format!("/todos/{todo_id}/comments")));
+        let req = self.add_auth_header(req).await?;
+
+        let response = self.send(req.json(&CommentRequest { text })).await?;
+        Self::handle_response(response).await
+    }
+
+    /// Lists the comments of a todo, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Network request fails
+    /// - Todo with the given ID is not found
+    /// - Server returns an error response
+    /// - Response parsing fails
+    /// - API key is missing or invalid
+    pub async fn list_comments(&self, todo_id: &str) -> Result<Vec<Comment>> {
+        let req = self
+            .client
+            .get(self.build_url(&format!("/todos/{todo_id}/comments")));
+        let req = self.add_auth_header(req).await?;
+
+        let response = self.send(req).await?;
+        let mut comments: Vec<Comment> = Self::handle_response(response).await?;
+        comments.sort_by_key(|comment| comment.created_at);
+        Ok(comments)
+    }
+
+    /// Returns who last changed each todo, on servers with [`Capabilities::MODIFIERS`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Network request fails
+    /// - Server returns an error response (e.g. it doesn't track modifiers)
+    /// - Response parsing fails
+    pub async fn list_modifiers(&self) -> Result<Vec<TodoModifier>> {
+        let req = self.client.get(self.build_url("/todos/modifiers"));
+        let req = self.add_auth_header(req).await?;
 
+        let response = self.send(req).await?;
         Self::handle_response(response).await
     }
 
+    /// Lists the revisions of a todo, oldest first, on servers with [`Capabilities::REVISIONS`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Network request fails
+    /// - Todo with the given ID is not found
+    /// - Server returns an error response (e.g. it doesn't keep revisions)
+    /// - Response parsing fails
+    pub async fn list_revisions(&self, todo_id: &str) -> Result<Vec<Revision>> {
+        let url = self.build_url(&format!("/todos/{todo_id}/revisions"));
+        let req = self.client.get(&url);
+        let req = self.add_auth_header(req).await?;
+
+        let response = self.send(req).await?;
+        let mut revisions: Vec<Revision> = Self::handle_response(response).await?;
+        revisions.sort_by_key(|revision| revision.changed_at);
+        Ok(revisions)
+    }
+
     /// Rotates the admin API key, generating a new key
     ///
     /// # Errors
@@ -338,14 +1190,15 @@ impl ApiClient {
         }
 
         let req = self.client.post(self.build_url("/admin/keys/rotate"));
-        let req = self.add_auth_header(req);
+        let req = self.add_auth_header(req).await?;
 
-        let response = req.send().await?;
+        let response = self.send(req).await?;
         let result: RotateResponse = Self::handle_response(response).await?;
+        redact::register(&result.new_key);
         Ok(result.new_key)
     }
 
-    /// Generates a new API key with optional name
+    /// Generates a new API key with optional name, scopes and expiry
     ///
     /// # Errors
     ///
@@ -354,22 +1207,21 @@ impl ApiClient {
     /// - Server returns an error response
     /// - Response parsing fails
     /// - Current API key lacks admin privileges
-    pub async fn generate_api_key(&self, name: Option<&str>) -> Result<GenerateKeyResponse> {
+    pub async fn generate_api_key(
+        &self,
+        request: &GenerateKeyRequest,
+    ) -> Result<GenerateKeyResponse> {
         let req = self.client.post(self.build_url("/admin/keys/generate"));
-        let mut req = self.add_auth_header(req);
+        let mut req = self.add_auth_header(req).await?;
 
-        if let Some(name) = name {
-            #[derive(Serialize)]
-            struct GenerateRequest {
-                name: String,
-            }
-            req = req.json(&GenerateRequest {
-                name: name.to_string(),
-            });
+        if *request != GenerateKeyRequest::default() {
+            req = req.json(request);
         }
 
-        let response = req.send().await?;
-        Self::handle_response(response).await
+        let response = self.send(req).await?;
+        let generated: GenerateKeyResponse = Self::handle_response(response).await?;
+        redact::register(&generated.key);
+        Ok(generated)
     }
 
     /// Lists all API keys (admin only)
@@ -383,9 +1235,156 @@ impl ApiClient {
     /// - Current API key lacks admin privileges
     pub async fn list_api_keys(&self) -> Result<Vec<ApiKey>> {
         let req = self.client.get(self.build_url("/admin/keys"));
-        let req = self.add_auth_header(req);
+        let req = self.add_auth_header(req).await?;
 
-        let response = req.send().await?;
+        let response = self.send(req).await?;
+        Self::handle_response(response).await
+    }
+
+    /// Returns version, uptime, storage and rate-limit information about the server (admin only)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Network request fails
+    /// - Server returns an error response (e.g. it has no status endpoint)
+    /// - Response parsing fails
+    /// - Current API key lacks admin privileges
+    pub async fn server_status(&self) -> Result<ServerStatus> {
+        let req = self.client.get(self.build_url("/admin/status"));
+        let req = self.add_auth_header(req).await?;
+
+        let response = self.send(req).await?;
+        Self::handle_response(response).await
+    }
+
+    /// Returns what the server supports, asking it once per client
+    ///
+    /// If the server doesn't answer (older servers have no `/capabilities`),
+    /// no optional features are assumed.
+    pub async fn capabilities(&self) -> &Capabilities {
+        self.capabilities
+            .get_or_init(|| async {
+                let result: Result<Capabilities> = async {
+                    let req = self.client.get(self.build_url("/capabilities"));
+                    let req = self.add_auth_header(req).await?;
+                    Self::handle_response(self.send(req).await?).await
+                }
+                .await;
+                result.unwrap_or_else(|err| {
+                    log::debug!("Server reports no capabilities ({err:#}), assuming none");
+                    Capabilities::default()
+                })
+            })
+            .await
+    }
+
+    /// Sends an unauthenticated `HEAD /health` and returns the round-trip time
+    ///
+    /// Any HTTP response counts: the point is to check that the server is
+    /// reachable and to leave a warm (resolved, connected, TLS-negotiated)
+    /// connection in the pool for the next request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server cannot be reached
+    pub async fn ping(&self) -> Result<Duration> {
+        let start = std::time::Instant::now();
+        self.send(self.client.head(self.build_url("/health")))
+            .await?;
+        Ok(start.elapsed())
+    }
+
+    /// Fetches a page of audit events at or after `since`, optionally for a single key (admin only)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Network request fails
+    /// - Server returns an error response (e.g. it has no audit log)
+    /// - Response parsing fails
+    /// - Current API key lacks admin privileges
+    pub async fn audit_events(
+        &self,
+        since: i64,
+        key_id: Option<&str>,
+        cursor: Option<&str>,
+    ) -> Result<AuditPage> {
+        let req = self.client.get(self.build_url("/admin/audit"));
+        let mut req = self.add_auth_header(req).await?.query(&[("since", since)]);
+
+        if let Some(key_id) = key_id {
+            req = req.query(&[("key", key_id)]);
+        }
+
+        if let Some(cursor) = cursor {
+            req = req.query(&[("cursor", cursor)]);
+        }
+
+        let response = self.send(req).await?;
+        Self::handle_response(response).await
+    }
+
+    /// Returns usage statistics for all API keys (admin only)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Network request fails
+    /// - Server returns an error response (e.g. it doesn't track usage)
+    /// - Response parsing fails
+    /// - Current API key lacks admin privileges
+    pub async fn list_key_usage(&self) -> Result<Vec<KeyUsage>> {
+        let req = self.client.get(self.build_url("/admin/keys/usage"));
+        let req = self.add_auth_header(req).await?;
+
+        let response = self.send(req).await?;
+        Self::handle_response(response).await
+    }
+
+    /// Returns usage statistics, including per-route counts, for one API key (admin only)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Network request fails
+    /// - API key with the given ID is not found
+    /// - Server returns an error response
+    /// - Response parsing fails
+    /// - Current API key lacks admin privileges
+    pub async fn get_key_usage(&self, id: &str) -> Result<KeyUsage> {
+        let req = self
+            .client
+            .get(self.build_url(&format!("/admin/keys/{id}/usage")));
+        let req = self.add_auth_header(req).await?;
+
+        let response = self.send(req).await?;
+        Self::handle_response(response).await
+    }
+
+    /// Changes the display name (`client_name`) of an API key without affecting the key itself (admin only)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Network request fails
+    /// - API key with the given ID is not found
+    /// - Server returns an error response
+    /// - Response parsing fails
+    /// - Current API key lacks admin privileges
+    pub async fn rename_api_key(&self, id: &str, name: &str) -> Result<ApiKey> {
+        #[derive(Serialize)]
+        struct RenameRequest<'a> {
+            name: &'a str,
+        }
+
+        let req = self
+            .client
+            .patch(self.build_url(&format!("/admin/keys/{id}")))
+            .json(&RenameRequest { name });
+        let req = self.add_auth_header(req).await?;
+
+        let response = self.send(req).await?;
         Self::handle_response(response).await
     }
 
@@ -402,9 +1401,9 @@ impl ApiClient {
         let req = self
             .client
             .delete(self.build_url(&format!("/admin/keys/{id}")));
-        let req = self.add_auth_header(req);
+        let req = self.add_auth_header(req).await?;
 
-        let response = req.send().await?;
+        let response = self.send(req).await?;
         let status = response.status();
 
         if status.is_success() {
@@ -439,7 +1438,7 @@ impl ApiClient {
         let req = self.client.post(self.build_url("/initialize"));
         // Note: No auth header for initialize - it's for first-time setup
 
-        let response = req.send().await?;
+        let response = self.send(req).await?;
         let result: ApiKeyResponse = Self::handle_response(response).await?;
         Ok(result.api_key)
     }
@@ -457,10 +1456,11 @@ impl ApiClient {
         use pali_types::ApiKeyResponse;
 
         let req = self.client.post(self.build_url("/reinitialize"));
-        let req = self.add_auth_header(req);
+        let req = self.add_auth_header(req).await?;
 
-        let response = req.send().await?;
+        let response = self.send(req).await?;
         let result: ApiKeyResponse = Self::handle_response(response).await?;
+        TodoCache::clear();
         Ok(result.api_key)
     }
 
@@ -495,24 +1495,36 @@ impl ApiClient {
 
         let url = self.build_url(&format!("/todos/resolve/{prefix}"));
 
-        #[cfg(feature = "cli")]
-        log_http_request("GET", &url, false);
-
         let req = self.client.get(&url);
-        let req = self.add_auth_header(req);
+        let req = self.add_auth_header(req).await?;
 
-        let start = std::time::Instant::now();
-        let response = req.send().await?;
-        let elapsed = start.elapsed();
-
-        #[cfg(feature = "cli")]
-        log_http_response(response.status().as_u16(), elapsed);
+        let response = self.send(req).await?;
 
         let result: ResolveResponse = Self::handle_response(response).await?;
         Ok(result.full_id)
     }
 }
 
+/// Parses a response body, naming the field that didn't match on failure
+///
+/// A field of the wrong type or a missing one usually means the server runs
+/// a newer or older API version than this client.
+fn decode<T: DeserializeOwned>(body: &str) -> Result<T> {
+    let deserializer = &mut serde_json::Deserializer::from_str(body);
+    serde_path_to_error::deserialize(deserializer).map_err(|err| {
+        let path = err.path().to_string();
+        let location = if path == "." {
+            String::new()
+        } else {
+            format!(" at '{path}'")
+        };
+        anyhow::Error::new(err.into_inner()).context(format!(
+            "Unexpected response from the server{location}. The server may run a different \
+             API version than this client; rerun with --debug-body to see the response"
+        ))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -522,10 +1534,16 @@ mod tests {
         let config = Config {
             api_endpoint: "http://localhost:8787".to_string(),
             api_key: None,
+            ..Config::default()
         };
         let client = ApiClient {
             client: Client::new(),
             config,
+            capabilities: Arc::default(),
+            interceptors: Vec::new(),
+            transport: None,
+            timeout: None,
+            cancel: None,
         };
 
         assert_eq!(client.build_url("/todos"), "http://localhost:8787/todos");
@@ -536,21 +1554,72 @@ mod tests {
         let config = Config {
             api_endpoint: "http://localhost:8787/".to_string(),
             api_key: None,
+            ..Config::default()
         };
         let client = ApiClient {
             client: Client::new(),
             config,
+            capabilities: Arc::default(),
+            interceptors: Vec::new(),
+            transport: None,
+            timeout: None,
+            cancel: None,
         };
 
         assert_eq!(client.build_url("/todos"), "http://localhost:8787/todos");
     }
 
+    #[test]
+    fn test_build_url_local_shortcuts() {
+        assert_eq!(base_url(":8787"), "http://localhost:8787");
+        assert_eq!(base_url("localhost:8787/"), "http://localhost:8787");
+        assert_eq!(base_url("127.0.0.1:9000"), "http://127.0.0.1:9000");
+        assert_eq!(base_url("unix:///run/pali.sock"), "http://localhost");
+        assert_eq!(
+            unix_socket_path("unix:///run/pali.sock"),
+            Some(std::path::Path::new("/run/pali.sock"))
+        );
+        assert_eq!(unix_socket_path("http://localhost:8787"), None);
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_lines() {
+        let chunks: Vec<std::result::Result<&[u8], std::io::Error>> =
+            vec![Ok(b"{\"n\":1}\n{\"n\""), Ok(b":2}\n\n{\"n\":3}")];
+        let values: Vec<serde_json::Value> = ndjson_lines(stream::iter(chunks))
+            .map(|item| item.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[1]["n"], 2);
+        assert_eq!(values[2]["n"], 3);
+    }
+
+    #[test]
+    fn test_decode_names_failing_field() {
+        let body = r#"{"success":true,"data":[{"id":"a","title":"x","completed":"no"}]}"#;
+        let err = decode::<ApiResponse<Vec<Todo>>>(body).unwrap_err();
+        assert!(err.to_string().contains("at 'data[0].completed'"));
+        assert!(format!("{err:#}").contains("expected a boolean"));
+
+        let err = decode::<ApiResponse<Todo>>("<html>").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Unexpected response from the server."));
+    }
+
     #[test]
     fn test_api_client_has_correct_fields() {
         let config = Config::default();
         let client = ApiClient {
             client: Client::new(),
             config: config.clone(),
+            capabilities: Arc::default(),
+            interceptors: Vec::new(),
+            transport: None,
+            timeout: None,
+            cancel: None,
         };
 
         // Verify the client was constructed properly
@@ -563,10 +1632,16 @@ mod tests {
         let config = Config {
             api_endpoint: "https://api.example.com".to_string(),
             api_key: Some("test-key".to_string()),
+            ..Config::default()
         };
         let client = ApiClient {
             client: Client::new(),
             config,
+            capabilities: Arc::default(),
+            interceptors: Vec::new(),
+            transport: None,
+            timeout: None,
+            cancel: None,
         };
 
         assert_eq!(client.build_url("/todos"), "https://api.example.com/todos");
@@ -576,4 +1651,39 @@ mod tests {
             "https://api.example.com/todos/123"
         );
     }
+
+    #[test]
+    fn test_generate_key_request_serialization() {
+        let request = GenerateKeyRequest {
+            name: Some("ci".to_string()),
+            scopes: vec!["todos:read".to_string()],
+            expires_at: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"name":"ci","scopes":["todos:read"]}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&GenerateKeyRequest::default()).unwrap(),
+            "{}"
+        );
+    }
+
+    #[test]
+    fn test_attachment_display_size() {
+        let mut attachment = Attachment {
+            id: "a".to_string(),
+            filename: "notes.md".to_string(),
+            size: 512,
+            content_type: None,
+            created_at: 0,
+        };
+        assert_eq!(attachment.display_size(), "512 B");
+
+        attachment.size = 1536;
+        assert_eq!(attachment.display_size(), "1.5 KB");
+
+        attachment.size = 5 * 1024 * 1024;
+        assert_eq!(attachment.display_size(), "5.0 MB");
+    }
 }
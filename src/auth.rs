@@ -0,0 +1,321 @@
+//! OAuth 2.0 device authorization flow (RFC 8628) for servers behind SSO
+//!
+//! `pacli login --oauth` obtains tokens with [`start_device_flow`] and
+//! [`poll_for_token`]; afterwards [`access_token`] hands out a valid access
+//! token, silently refreshing it when it is about to expire.
+
+use crate::config::{Config, OAuthSettings};
+use crate::redact;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Refresh tokens this many seconds before they actually expire
+const EXPIRY_LEEWAY_SECS: i64 = 60;
+
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Tokens saved after a successful login
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TokenSet {
+    pub access_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// Unix timestamp; `None` if the provider didn't say
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+/// What the user has to do to approve the login
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceAuthorization {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    #[serde(default)]
+    pub verification_uri_complete: Option<String>,
+    pub expires_in: u64,
+    #[serde(default)]
+    pub interval: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+impl TokenSet {
+    fn from_response(response: TokenResponse, now: i64) -> Self {
+        redact::register(&response.access_token);
+        if let Some(refresh_token) = &response.refresh_token {
+            redact::register(refresh_token);
+        }
+        Self {
+            access_token: response.access_token,
+            refresh_token: response.refresh_token,
+            expires_at: response.expires_in.map(|secs| now + secs),
+        }
+    }
+
+    /// Returns `true` if the access token expires within the leeway
+    #[must_use]
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at - EXPIRY_LEEWAY_SECS <= now)
+    }
+
+    /// Returns the path to the token file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data directory cannot be determined
+    pub fn path() -> Result<PathBuf> {
+        Ok(Config::data_dir()?.join("tokens.json"))
+    }
+
+    /// Loads saved tokens, returning `None` if the user hasn't logged in
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token file exists but cannot be read or parsed
+    pub fn load() -> Result<Option<Self>> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)?;
+        let tokens: Self = serde_json::from_str(&content)?;
+        redact::register(&tokens.access_token);
+        if let Some(refresh_token) = &tokens.refresh_token {
+            redact::register(refresh_token);
+        }
+        Ok(Some(tokens))
+    }
+
+    /// Saves the tokens, readable only by the current user where supported
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token file cannot be written
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&path)?;
+
+        // The mode above only applies to new files
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+
+        file.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+
+    /// Deletes saved tokens, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token file exists but cannot be removed
+    pub fn clear() -> Result<()> {
+        let path = Self::path()?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Asks the identity provider for a device and user code
+///
+/// # Errors
+///
+/// Returns an error if the request fails or the provider rejects the client
+pub async fn start_device_flow(
+    http: &Client,
+    settings: &OAuthSettings,
+) -> Result<DeviceAuthorization> {
+    let mut form = vec![("client_id", settings.client_id.as_str())];
+    if let Some(scope) = &settings.scope {
+        form.push(("scope", scope.as_str()));
+    }
+
+    let response = http
+        .post(&settings.device_authorization_url)
+        .form(&form)
+        .send()
+        .await
+        .context("Could not reach the identity provider")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Device authorization failed: {}",
+            error_text(response).await
+        );
+    }
+    Ok(response.json().await?)
+}
+
+/// Polls the token endpoint until the user approves (or denies) the login
+///
+/// # Errors
+///
+/// Returns an error if the user denies access, the code expires or the
+/// provider returns an unexpected error
+pub async fn poll_for_token(
+    http: &Client,
+    settings: &OAuthSettings,
+    authorization: &DeviceAuthorization,
+) -> Result<TokenSet> {
+    let mut interval = Duration::from_secs(authorization.interval.unwrap_or(5));
+    let deadline = std::time::Instant::now() + Duration::from_secs(authorization.expires_in);
+
+    loop {
+        if std::time::Instant::now() >= deadline {
+            anyhow::bail!("The login code expired. Run 'pacli login --oauth' again.");
+        }
+        tokio::time::sleep(interval).await;
+
+        let response = http
+            .post(&settings.token_url)
+            .form(&[
+                ("grant_type", DEVICE_CODE_GRANT),
+                ("device_code", authorization.device_code.as_str()),
+                ("client_id", settings.client_id.as_str()),
+            ])
+            .send()
+            .await
+            .context("Could not reach the identity provider")?;
+
+        if response.status().is_success() {
+            let tokens: TokenResponse = response.json().await?;
+            return Ok(TokenSet::from_response(tokens, Utc::now().timestamp()));
+        }
+
+        let error: ErrorResponse = response
+            .json()
+            .await
+            .context("Unexpected response from the identity provider")?;
+        match error.error.as_str() {
+            "authorization_pending" => {}
+            "slow_down" => interval += Duration::from_secs(5),
+            "access_denied" => anyhow::bail!("Login was denied"),
+            "expired_token" => {
+                anyhow::bail!("The login code expired. Run 'pacli login --oauth' again.")
+            }
+            other => anyhow::bail!(
+                "Login failed: {}",
+                error.error_description.as_deref().unwrap_or(other)
+            ),
+        }
+    }
+}
+
+async fn refresh(http: &Client, settings: &OAuthSettings, refresh_token: &str) -> Result<TokenSet> {
+    let response = http
+        .post(&settings.token_url)
+        .form(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", settings.client_id.as_str()),
+        ])
+        .send()
+        .await
+        .context("Could not reach the identity provider")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("Token refresh failed: {}", error_text(response).await);
+    }
+
+    let tokens: TokenResponse = response.json().await?;
+    let mut refreshed = TokenSet::from_response(tokens, Utc::now().timestamp());
+    // Providers may omit the refresh token when it stays the same
+    if refreshed.refresh_token.is_none() {
+        refreshed.refresh_token = Some(refresh_token.to_string());
+    }
+    Ok(refreshed)
+}
+
+/// Returns a valid access token, refreshing and saving it if it has expired
+///
+/// # Errors
+///
+/// Returns an error if the user hasn't logged in, or the token expired and
+/// cannot be refreshed
+pub async fn access_token(http: &Client, settings: &OAuthSettings) -> Result<String> {
+    let not_logged_in = || anyhow::anyhow!("Not logged in. Run 'pacli login --oauth' first.");
+    let tokens = TokenSet::load()?.ok_or_else(not_logged_in)?;
+
+    if !tokens.is_expired(Utc::now().timestamp()) {
+        return Ok(tokens.access_token);
+    }
+
+    let Some(refresh_token) = &tokens.refresh_token else {
+        anyhow::bail!("Your login has expired. Run 'pacli login --oauth' again.");
+    };
+    log::debug!("Access token expired, refreshing");
+    let refreshed = refresh(http, settings, refresh_token)
+        .await
+        .context("Your login has expired. Run 'pacli login --oauth' again.")?;
+    refreshed.save()?;
+
+    Ok(refreshed.access_token)
+}
+
+async fn error_text(response: reqwest::Response) -> String {
+    let status = response.status();
+    match response.json::<ErrorResponse>().await {
+        Ok(error) => error.error_description.unwrap_or(error.error),
+        Err(_) => status.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_expiry() {
+        let response = TokenResponse {
+            access_token: "abc".to_string(),
+            refresh_token: None,
+            expires_in: Some(3600),
+        };
+        let tokens = TokenSet::from_response(response, 1000);
+        assert_eq!(tokens.expires_at, Some(4600));
+        assert!(!tokens.is_expired(1000));
+        assert!(tokens.is_expired(4600 - EXPIRY_LEEWAY_SECS));
+
+        let forever = TokenSet {
+            expires_at: None,
+            ..tokens
+        };
+        assert!(!forever.is_expired(i64::MAX));
+    }
+}
@@ -4,18 +4,83 @@
 compile_error!("The 'cli' feature must be enabled to build pacli");
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use colored::Colorize;
 use pali_terminal::{
+    api::ApiClient,
     cli::{
-        commands,
-        types::{Cli, Commands},
+        commands::{self, todo::ListFilters},
+        types::{Cli, Commands, InternalAction},
+        utils::{
+            apply_default_command, config_arg, demo_arg, expand_command_alias, read_clipboard,
+            read_description, read_email,
+        },
     },
-    init_logging,
+    demo::{self, Demo},
+    init_logging, metrics,
+    project::ProjectConfig,
+    prompt::{self, set_no_input},
+    sort::SortSpec,
+    term, timings, trace, transport, Config,
 };
+use std::cell::OnceCell;
+use std::ffi::OsString;
+use std::process::ExitCode;
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+async fn main() -> ExitCode {
+    // Kept past the metrics flush, which writes to the demo's data directory
+    let mut demo_mode = None;
+    let result = run(&mut demo_mode).await;
+    metrics::flush();
+    drop(demo_mode);
+    // Also after a failed command, which is what bug reports are about
+    match transport::finish() {
+        Ok(Some(path)) => eprintln!(
+            "{} Session recorded to {}",
+            term::symbols().info.cyan(),
+            path.display()
+        ),
+        Ok(None) => {}
+        Err(err) => eprintln!("{} {err:#}", term::symbols().warning.yellow()),
+    }
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err:?}");
+            if prompt::is_input_required(&err) {
+                ExitCode::from(prompt::EXIT_CODE)
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
+
+/// Returns the client shared by the whole invocation, building it on first use
+///
+/// Commands that never reach the server, such as `config` or `help`, thus
+/// don't load (and possibly decrypt) the API key.
+fn client(shared: &OnceCell<ApiClient>) -> Result<&ApiClient> {
+    if let Some(client) = shared.get() {
+        return Ok(client);
+    }
+    let client = ApiClient::new()?;
+    Ok(shared.get_or_init(|| client))
+}
+
+async fn run(demo_mode: &mut Option<Demo>) -> Result<()> {
+    let args: Vec<OsString> = std::env::args_os().collect();
+    colored::control::set_override(term::capabilities().color.is_enabled());
+
+    // Before anything reads the config, including the aliases and the log_file setting
+    if demo_arg(&args) {
+        *demo_mode = Some(demo::start().await?);
+    } else if let Some(path) = config_arg(&args) {
+        Config::use_file(path);
+    }
+    let args = apply_default_command(args, Config::default_command_setting().as_deref())?;
+    let cli = Cli::parse_from(expand_command_alias(args, &Config::aliases_setting())?);
 
     // Handle version flag
     if cli.version {
@@ -24,60 +89,281 @@ async fn main() -> Result<()> {
     }
 
     // Initialize logging based on verbosity level
-    init_logging(cli.verbose)?;
+    let log_file = match &cli.log_file {
+        Some(path) => Some(Config::resolve_log_path(path)?),
+        None => Config::log_file_setting(),
+    };
+    init_logging(cli.verbose, log_file.as_deref())?;
+    if cli.debug_body {
+        trace::enable_body_dumps();
+    }
+    if cli.trace {
+        trace::enable();
+    }
+    let mut _replaying = None;
+    if let Some(path) = &cli.record {
+        transport::record(path.clone());
+    } else if let Some(path) = &cli.replay {
+        _replaying = Some(transport::replay(path)?);
+    }
+    set_no_input(cli.no_input);
+    // Prints the summary when dropped, after the command (even if it fails)
+    let _timings = cli.timings.then(timings::Report::start);
 
-    // Require a command if no version flag
+    // Without a command or a default_command, show what's available
     let Some(command) = cli.command else {
-        anyhow::bail!("A command is required. Use --help for usage information.");
+        Cli::command().print_help()?;
+        println!();
+        println!(
+            "{} Set a default command to run it here instead, e.g. 'pacli config default-command list'",
+            term::symbols().info.cyan()
+        );
+        return Ok(());
     };
 
+    // Settings from the nearest .pali.toml fill in options not given on the command line
+    let project = ProjectConfig::discover()?.unwrap_or_default();
+    // Every request of the command goes through one client (and its connection pool)
+    let shared = OnceCell::new();
+
     match command {
         Commands::Config { action } => {
             commands::config::handle(action).await?;
         }
+        Commands::Login {
+            endpoint,
+            oauth,
+            client_id,
+            device_url,
+            token_url,
+            scope,
+        } => {
+            if oauth {
+                commands::login::login_oauth(endpoint, client_id, device_url, token_url, scope)
+                    .await?;
+            } else {
+                commands::login::login(endpoint).await?;
+            }
+        }
         Commands::Add {
             title,
             description,
+            description_file,
             due,
             priority,
             tags,
+            from_clipboard,
+            from_email,
         } => {
-            commands::todo::add(title, description, due, priority, tags).await?;
+            let (title, description) = if from_clipboard {
+                read_clipboard()?
+            } else if from_email {
+                read_email()?
+            } else {
+                let description = read_description(description, description_file.as_deref())?;
+                (title.unwrap_or_default(), description)
+            };
+            let tags = project.with_project_tag(tags);
+            commands::todo::add(client(&shared)?, title, description, due, priority, tags).await?;
         }
-        Commands::List { all, tag, priority } => {
-            commands::todo::list(all, tag, priority).await?;
+        Commands::List {
+            all,
+            archived,
+            tag,
+            priority,
+            sort,
+            completed_last,
+            group_by,
+            dates,
+        } => {
+            let filters = project.filters;
+            // Checked here rather than by clap, since the spec may come from .pali.toml
+            let sort = SortSpec::parse(sort.or(filters.sort).as_deref().unwrap_or_default())?
+                .completed_last(completed_last);
+            let list_filters = ListFilters {
+                all: all || filters.all,
+                archived,
+                tag: tag.or(filters.tag),
+                priority: priority.or(filters.priority),
+                dates,
+            };
+            commands::todo::list(client(&shared)?, list_filters, &sort, group_by).await?;
+        }
+        Commands::Count {
+            all,
+            overdue,
+            due,
+            priority,
+            tag,
+            json,
+        } => {
+            let filters = project.filters;
+            commands::todo::count(
+                client(&shared)?,
+                all || filters.all,
+                overdue,
+                due,
+                priority.or(filters.priority),
+                tag.or(filters.tag),
+                json,
+            )
+            .await?;
         }
-        Commands::Get { id } => {
-            commands::todo::get(id).await?;
+        Commands::Get {
+            ids,
+            json,
+            markdown,
+        } => {
+            commands::todo::get(client(&shared)?, &ids, json, markdown).await?;
         }
         Commands::Update {
             id,
             title,
             description,
+            description_file,
             due,
             priority,
             tags,
+            filter,
+            set,
+            yes,
         } => {
-            commands::todo::update(id, title, description, due, priority, tags).await?;
+            if let Some(filter) = filter {
+                commands::todo::update_where(client(&shared)?, filter, set, yes).await?;
+            } else {
+                let id = id.unwrap_or_default();
+                let description = read_description(description, description_file.as_deref())?;
+                commands::todo::update(
+                    client(&shared)?,
+                    id,
+                    title,
+                    description,
+                    due,
+                    priority,
+                    tags,
+                )
+                .await?;
+            }
         }
         Commands::Delete { id } => {
-            commands::todo::delete(id).await?;
+            commands::todo::delete(client(&shared)?, id).await?;
         }
         Commands::Toggle { id } => {
-            commands::todo::toggle(id).await?;
+            commands::todo::toggle(client(&shared)?, id).await?;
         }
         Commands::Complete { id } => {
-            commands::todo::complete(id).await?;
+            commands::todo::complete(client(&shared)?, id).await?;
+        }
+        Commands::Alias { action } => {
+            commands::alias::handle(client(&shared)?, action).await?;
+        }
+        Commands::Link { id, blocks } => {
+            commands::link::link(client(&shared)?, id, blocks).await?;
+        }
+        Commands::Unlink { id, blocks } => {
+            commands::link::unlink(client(&shared)?, id, blocks).await?;
+        }
+        Commands::Start { id } => {
+            commands::timer::start(client(&shared)?, id).await?;
         }
-        Commands::Search { query } => {
-            commands::todo::search(query).await?;
+        Commands::Stop => {
+            commands::timer::stop(client(&shared)?).await?;
+        }
+        Commands::Timesheet { week } => {
+            commands::timer::timesheet(client(&shared)?, week).await?;
+        }
+        Commands::Board { width } => {
+            commands::board::board(client(&shared)?, width).await?;
+        }
+        Commands::Forecast { days } => {
+            commands::forecast::forecast(client(&shared)?, days).await?;
+        }
+        Commands::Report {
+            week,
+            format,
+            group_by,
+        } => {
+            commands::report::report(client(&shared)?, week, format, group_by).await?;
+        }
+        Commands::History { id: None, since } => {
+            commands::history::show(since).await?;
+        }
+        Commands::History {
+            id: Some(id),
+            since,
+        } => {
+            commands::history::show_todo(client(&shared)?, id, since).await?;
+        }
+        Commands::Open { id, number } => {
+            commands::open::open(client(&shared)?, id, number).await?;
+        }
+        Commands::Archive { id } => {
+            commands::archive::archive(client(&shared)?, id).await?;
+        }
+        Commands::Unarchive { id } => {
+            commands::archive::unarchive(client(&shared)?, id).await?;
+        }
+        Commands::Pin { id } => {
+            commands::pin::pin(client(&shared)?, id).await?;
+        }
+        Commands::Unpin { id } => {
+            commands::pin::unpin(client(&shared)?, id).await?;
+        }
+        Commands::Tags {
+            action: None,
+            names: true,
+        } => {
+            commands::tags::print_names()?;
+        }
+        Commands::Tags { action, .. } => {
+            commands::tags::handle(client(&shared)?, action).await?;
+        }
+        Commands::Comment { id, text } => {
+            commands::comment::comment(client(&shared)?, id, text).await?;
+        }
+        Commands::Attach { id, file } => {
+            commands::attachment::attach(client(&shared)?, id, file).await?;
+        }
+        Commands::Attachments { id } => {
+            commands::attachment::list(client(&shared)?, id).await?;
+        }
+        Commands::Download { id, number, output } => {
+            commands::attachment::download(client(&shared)?, id, number, output).await?;
+        }
+        Commands::Subtask { action } => {
+            commands::subtask::handle(client(&shared)?, action).await?;
+        }
+        Commands::Export { format, output } => {
+            commands::export::export(client(&shared)?, format, output).await?;
+        }
+        Commands::Bench { requests } => {
+            commands::bench::bench(client(&shared)?, requests).await?;
+        }
+        Commands::Debug { action } => {
+            commands::debug::handle(action)?;
+        }
+        Commands::Escalate { dry_run } => {
+            commands::escalate::escalate(client(&shared)?, dry_run).await?;
+        }
+        #[cfg(feature = "caldav")]
+        Commands::Caldav { action } => {
+            commands::caldav::handle(client(&shared)?, action).await?;
+        }
+        Commands::Search { query, regex } => {
+            commands::todo::search(client(&shared)?, query, regex).await?;
         }
         Commands::Init { url } => {
             commands::admin::initialize_with_url(url).await?;
         }
         Commands::Admin { action } => {
-            commands::admin::handle(action).await?;
+            commands::admin::handle(client(&shared)?, action).await?;
+        }
+        Commands::Help { topic } => {
+            commands::help::help(topic.as_deref())?;
         }
+        Commands::Internal { action } => match action {
+            InternalAction::GenMan { out_dir } => commands::help::gen_man(&out_dir)?,
+        },
     }
 
     Ok(())
@@ -4,84 +4,297 @@
 compile_error!("The 'tui' feature must be enabled to build patui");
 
 use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
 use crossterm::{
+    cursor,
     event::{self, DisableMouseCapture, EnableMouseCapture, Event},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use pali_terminal::tui::{app::App, ui};
+use pali_terminal::{
+    demo, editor,
+    logging::init_tui_logging,
+    term, transport,
+    tui::{
+        app::App,
+        input::{Input, Key},
+        ui,
+    },
+    Config,
+};
 use ratatui::{
     backend::{Backend, CrosstermBackend},
     Terminal,
 };
-use std::io;
+use std::io::{self, Write};
 use std::time::{Duration, Instant};
 
+/// Tick rate while something animates (spinner, timers)
+const TICK_RATE: Duration = Duration::from_millis(250);
+/// How long to wait for input when nothing changes on its own
+const IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    // stderr is hidden behind the alternate screen, so logs go to the F12 pane
+    // and, with `patui --log-file <path>` or `log_file` in the config, a file
+    let mut args = std::env::args().skip(1);
+    let mut log_path = None;
+    let mut config_path = None;
+    let mut demo = false;
+    let mut serve_ssh = None;
+    let mut authorized_keys = None;
+    // Removes the throwaway data directory of `--replay` on exit
+    let mut _replaying = None;
+    while let Some(arg) = args.next() {
+        let mut path = || {
+            args.next()
+                .ok_or_else(|| anyhow::anyhow!("{arg} needs a path"))
+        };
+        match arg.as_str() {
+            "--log-file" => log_path = Some(path()?),
+            "--config" => config_path = Some(path()?),
+            "--demo" => demo = true,
+            "--record" => transport::record(path()?.into()),
+            "--replay" => _replaying = Some(transport::replay(std::path::Path::new(&path()?))?),
+            "--serve-ssh" => {
+                serve_ssh = Some(
+                    args.next()
+                        .ok_or_else(|| anyhow::anyhow!("--serve-ssh needs an address, e.g. :2222"))?,
+                );
+            }
+            "--authorized-keys" => authorized_keys = Some(path()?),
+            _ => anyhow::bail!(
+                "Unknown argument '{arg}'. Usage: patui [--demo | --config <path>] [--record <path> | --replay <path>] [--log-file <path>] [--serve-ssh <address> --authorized-keys <path>]"
+            ),
+        }
+    }
+    // The demo brings its own config, so it must start before anything loads one
+    let demo_mode = match (demo, config_path) {
+        (true, Some(_)) => anyhow::bail!("--demo cannot be used with --config"),
+        (true, None) => Some(demo::start().await?),
+        (false, path) => {
+            if let Some(path) = path {
+                Config::use_file(path.into());
+            }
+            None
+        }
+    };
+    // After --config, which may hold a log_file setting
+    let log_file = match log_path {
+        Some(path) => Some(Config::resolve_log_path(std::path::Path::new(&path))?),
+        None => Config::log_file_setting(),
+    };
+    init_tui_logging(log_file.as_deref())?;
+
+    // Every SSH session gets an app of its own; this terminal isn't used
+    if let Some(address) = serve_ssh {
+        // Not ~/.ssh/authorized_keys: keys there may be meant for other uses only
+        let Some(authorized_keys) = authorized_keys else {
+            anyhow::bail!(
+                "--serve-ssh needs --authorized-keys <path> with the keys that may log in"
+            );
+        };
+        #[cfg(feature = "ssh")]
+        let result =
+            pali_terminal::tui::ssh::serve(&address, std::path::Path::new(&authorized_keys)).await;
+        #[cfg(not(feature = "ssh"))]
+        let _ = authorized_keys;
+        #[cfg(not(feature = "ssh"))]
+        let result = Err(anyhow::anyhow!(
+            "Cannot serve on {address}: patui was built without SSH support; rebuild it with `--features ssh`"
+        ));
+        drop(demo_mode);
+        return result;
+    }
+    if authorized_keys.is_some() {
+        anyhow::bail!("--authorized-keys is only used with --serve-ssh");
+    }
 
-    // Create app state
+    // Create app state before taking over the terminal, so an encrypted
+    // config can prompt for its passphrase
     let mut app = App::new()?;
 
-    // Run the TUI
-    let res = run_app(&mut terminal, &mut app).await;
+    // Setup terminal; the guard and panic hook restore it however we leave
+    install_panic_hook();
+    let guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
+    let mut terminal = Terminal::new(backend)?;
 
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    // Run the TUI
+    let res = run_app(&mut terminal, &mut app, &guard).await;
+    app.save_session();
+    pali_terminal::metrics::flush();
+    drop(demo_mode);
 
+    drop(guard);
     if let Err(err) = res {
         eprintln!("Error: {err}");
     }
+    if let Some(path) = transport::finish()? {
+        println!("Session recorded to {}", path.display());
+    }
 
     Ok(())
 }
 
-async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
-    // Load initial todos
-    app.load_todos().await?;
+/// Keeps the terminal in raw mode on the alternate screen while alive
+///
+/// Dropping it restores the terminal, also when `run_app` returns early with
+/// an error or unwinds from a panic.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        enable_raw_mode()?;
+        let guard = Self;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        Ok(guard)
+    }
+
+    /// Hands the terminal back to the shell and stops the process like a
+    /// job suspended with Ctrl+Z; takes the terminal over again on `fg`
+    ///
+    /// Raw mode turns Ctrl+Z into a key event instead of SIGTSTP, so the
+    /// event loop calls this and the signal is raised here.
+    #[cfg(unix)]
+    fn suspend<B: Backend>(&self, terminal: &mut Terminal<B>) -> io::Result<()> {
+        self.release(terminal, || {
+            // SAFETY: `raise` has no preconditions; the default SIGTSTP action
+            // stops the process until it receives SIGCONT
+            unsafe {
+                libc::raise(libc::SIGTSTP);
+            }
+        })
+    }
+
+    /// Job control doesn't exist on this platform
+    #[cfg(not(unix))]
+    fn suspend<B: Backend>(&self, _terminal: &mut Terminal<B>) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Gives the terminal back while `f` runs, e.g. an editor, then takes it over again
+    fn release<B: Backend, T>(
+        &self,
+        terminal: &mut Terminal<B>,
+        f: impl FnOnce() -> T,
+    ) -> io::Result<T> {
+        restore_terminal();
+        let result = f();
+
+        enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        // Something else has drawn over the screen; forget what ratatui thinks is on it
+        terminal.clear()?;
+        Ok(result)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+/// Leaves raw mode and the alternate screen, ignoring errors (there is
+/// nothing left to report them to)
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        cursor::Show
+    );
+}
+
+/// Restores the terminal before the panic message is printed
+///
+/// The default hook runs before unwinding reaches [`TerminalGuard`], so
+/// without this the message would be written to the alternate screen and
+/// lost, leaving the shell in raw mode.
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+    }));
+}
+
+async fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    guard: &TerminalGuard,
+) -> Result<()> {
+    // Cached todos are already shown; warm up the connection and fetch the
+    // current list without blocking the first frame
+    app.check_connection();
+    app.start_background_refresh();
 
-    let tick_rate = Duration::from_millis(250); // 4 FPS for spinner animation
     let mut last_tick = Instant::now();
+    // Only redraw when something changed; idle sessions then cost no CPU
+    let mut dirty = true;
 
     loop {
-        terminal.draw(|f| ui::render(f, app))?;
+        dirty |= app.poll_background_refresh();
+        dirty |= app.check_connection();
+        dirty |= app.check_reminders(chrono::Utc::now().timestamp());
+        if dirty {
+            terminal.draw(|f| ui::render(f, app))?;
+            dirty = false;
+        }
+        // After drawing, so the list shows while the detail view loads
+        dirty |= app.resume_detail().await;
 
-        // Handle events with timeout for spinner animation
-        let timeout = tick_rate.saturating_sub(last_tick.elapsed());
+        // Tick at 4 FPS while animating, otherwise block until input arrives
+        let timeout = if app.is_animating() {
+            TICK_RATE.saturating_sub(last_tick.elapsed())
+        } else {
+            IDLE_TIMEOUT
+        };
         if event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                // Handle Ctrl+C globally for quit confirmation
-                if key.code == crossterm::event::KeyCode::Char('c')
-                    && key
-                        .modifiers
-                        .contains(crossterm::event::KeyModifiers::CONTROL)
-                {
-                    app.handle_ctrl_c();
-                } else {
-                    app.handle_key(key.code).await?;
+            let event = event::read()?;
+            match Input::from_event(&event) {
+                // Job control belongs to the terminal, not the app
+                Some(Input::Key {
+                    key: Key::Char('z'),
+                    ctrl: true,
+                }) => {
+                    guard.suspend(terminal)?;
+                    dirty = true;
                 }
+                Some(input) => {
+                    app.handle_input(input).await?;
+                    dirty = true;
+                }
+                None => dirty |= matches!(event, Event::Resize(..)),
             }
         }
 
         // Update spinner animation and message timers
-        if last_tick.elapsed() >= tick_rate {
-            app.tick_spinner();
-            app.tick_messages();
+        if last_tick.elapsed() >= TICK_RATE {
+            if app.is_animating() {
+                app.tick_spinner();
+                app.tick_messages();
+                app.tick_pomodoro();
+                dirty = true;
+            }
             last_tick = Instant::now();
         }
 
+        if let Some(message) = app.take_notification() {
+            notify_terminal(&message)?;
+        }
+        if let Some(text) = app.take_clipboard() {
+            copy_to_clipboard(&text)?;
+        }
+        if let Some((text, name)) = app.take_editor() {
+            let edited = guard.release(terminal, || editor::edit(&text, &name))?;
+            app.finish_editor(edited).await?;
+            dirty = true;
+        }
+
         if app.should_quit {
             break;
         }
@@ -90,6 +303,23 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Resul
     Ok(())
 }
 
+/// Rings the terminal bell and requests a desktop notification
+fn notify_terminal(message: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "{}", term::notification(message))?;
+    stdout.flush()
+}
+
+/// Copies `text` to the clipboard of the terminal's machine
+///
+/// Uses the OSC 52 escape sequence, which also works over SSH; terminals
+/// without clipboard support silently ignore it.
+fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    stdout.flush()
+}
+
 // Fallback main for when tui feature is disabled
 #[cfg(not(feature = "tui"))]
 fn main() {
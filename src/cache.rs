@@ -0,0 +1,360 @@
+//! Todo list cache shared by `pacli` and `patui`
+//!
+//! The todo list is kept in `cache.json` in the data directory, together
+//! with the endpoint it came from and a checksum. `pacli` reuses it while it
+//! is younger than `cache_ttl` seconds; `patui` shows it immediately at
+//! startup (however old) while syncing in the background. Changes made
+//! through the API client are applied to it directly.
+//!
+//! Syncing is incremental: only todos updated since the newest one in the
+//! cache are requested (see [`ApiClient::list_todos_since`]) and merged in.
+//! Since that can't see todos deleted elsewhere, a full reload happens at
+//! least every [`FULL_SYNC_SECS`].
+//!
+//! Concurrent syncs with the same server (say, an auto-refresh and a key
+//! press in `patui`) share a single request.
+
+use crate::api::{ApiClient, Cancelled};
+use crate::config::Config;
+use anyhow::Result;
+use chrono::Utc;
+use futures_util::future::{BoxFuture, FutureExt, Shared};
+use pali_types::Todo;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard, PoisonError};
+
+/// Used when `cache_ttl` is not configured
+pub const DEFAULT_TTL_SECS: u64 = 30;
+
+/// Incremental syncs fall back to a full reload after this long
+pub const FULL_SYNC_SECS: i64 = 10 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoCache {
+    /// When the list was last synced with the server (Unix timestamp)
+    pub fetched_at: i64,
+    /// When the complete list was last fetched, see [`FULL_SYNC_SECS`]
+    #[serde(default)]
+    pub full_sync_at: i64,
+    /// Server the list came from; a cache for another server is ignored
+    pub endpoint: String,
+    /// FNV-1a hash of `todos`, to detect truncated or hand-edited files
+    pub checksum: String,
+    pub todos: Vec<Todo>,
+}
+
+/// FNV-1a over the serialized todos
+fn checksum(todos: &[Todo]) -> String {
+    let bytes = serde_json::to_vec(todos).unwrap_or_default();
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+impl TodoCache {
+    /// Returns the path to the cache file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data directory cannot be determined
+    pub fn path() -> Result<PathBuf> {
+        Ok(Config::data_dir()?.join("cache.json"))
+    }
+
+    /// Creates a cache for a complete todo list fetched at `now`
+    #[must_use]
+    pub fn new(endpoint: &str, todos: Vec<Todo>, now: i64) -> Self {
+        Self {
+            fetched_at: now,
+            full_sync_at: now,
+            endpoint: endpoint.to_string(),
+            checksum: checksum(&todos),
+            todos,
+        }
+    }
+
+    fn read() -> Option<Self> {
+        let content = fs::read_to_string(Self::path().ok()?).ok()?;
+        let cache: Self = serde_json::from_str(&content).ok()?;
+        if cache.checksum != checksum(&cache.todos) {
+            log::warn!("Ignoring todo cache with a bad checksum");
+            return None;
+        }
+        Some(cache)
+    }
+
+    /// Writes the cache, logging instead of failing (it is only an optimization)
+    fn write(&mut self) {
+        self.checksum = checksum(&self.todos);
+        let result = Self::path().and_then(|path| {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, serde_json::to_string(&*self)?)?;
+            Ok(())
+        });
+        if let Err(err) = result {
+            log::debug!("Could not write the todo cache: {err}");
+        }
+    }
+
+    /// Loads the cache for `endpoint`, ignoring missing, corrupt or foreign caches
+    #[must_use]
+    pub fn load(endpoint: &str) -> Option<Self> {
+        Self::read().filter(|cache| cache.endpoint == endpoint)
+    }
+
+    /// Saves a freshly fetched complete list
+    pub fn store(endpoint: &str, todos: &[Todo]) {
+        Self::new(endpoint, todos.to_vec(), Utc::now().timestamp()).write();
+    }
+
+    /// Adds or replaces a todo that was just created or changed on `endpoint`
+    ///
+    /// A cache for another server is left alone.
+    pub fn upsert(endpoint: &str, todo: &Todo) {
+        if let Some(mut cache) = Self::load(endpoint) {
+            cache.merge(std::slice::from_ref(todo));
+            cache.write();
+        }
+    }
+
+    /// Removes a todo that was just deleted on `endpoint`
+    ///
+    /// A cache for another server is left alone.
+    pub fn remove(endpoint: &str, id: &str) {
+        if let Some(mut cache) = Self::load(endpoint) {
+            cache.todos.retain(|todo| todo.id != id);
+            cache.write();
+        }
+    }
+
+    /// Deletes the cache, e.g. after the server was reset
+    pub fn clear() {
+        if let Ok(path) = Self::path() {
+            if path.exists() {
+                if let Err(err) = fs::remove_file(path) {
+                    log::debug!("Could not remove the todo cache: {err}");
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if the cache is younger than `ttl` seconds
+    #[must_use]
+    pub fn is_fresh(&self, ttl: u64, now: i64) -> bool {
+        i64::try_from(ttl).is_ok_and(|ttl| now - self.fetched_at < ttl)
+    }
+
+    /// Replaces todos with a newer version by ID, appending new ones
+    pub fn merge(&mut self, changed: &[Todo]) {
+        for todo in changed {
+            match self.todos.iter_mut().find(|t| t.id == todo.id) {
+                Some(existing) => *existing = todo.clone(),
+                None => self.todos.push(todo.clone()),
+            }
+        }
+    }
+
+    /// The `updated_after` value for the next incremental sync
+    ///
+    /// One second earlier than the newest todo, since timestamps have second
+    /// resolution; todos seen twice are simply merged again.
+    fn sync_cursor(&self) -> Option<i64> {
+        self.todos.iter().map(|todo| todo.updated_at - 1).max()
+    }
+}
+
+/// A sync whose result is shared by everyone waiting for it
+type Flight = Shared<BoxFuture<'static, Result<Vec<Todo>, Arc<anyhow::Error>>>>;
+
+/// Running syncs by endpoint
+static IN_FLIGHT: LazyLock<Mutex<HashMap<String, Flight>>> = LazyLock::new(Mutex::default);
+
+fn in_flight() -> MutexGuard<'static, HashMap<String, Flight>> {
+    IN_FLIGHT.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Brings the cached list up to date and returns it
+///
+/// Requests only the todos changed since the last sync when possible, and
+/// the complete list when there is no usable cache, the last full reload is
+/// older than [`FULL_SYNC_SECS`], or the server doesn't filter by update time.
+/// Joins a sync with the same server that is already running instead of
+/// starting another one.
+///
+/// # Errors
+///
+/// Returns an error if the request fails
+pub async fn sync(client: &ApiClient) -> Result<Vec<Todo>> {
+    let client = client.clone();
+    let endpoint = client.config().api_endpoint.clone();
+    coalesce(&endpoint, move || async move { fetch(&client).await }).await
+}
+
+/// Runs `fetch` unless a fetch for `key` is running, and returns its result either way
+async fn coalesce<F, Fut>(key: &str, fetch: F) -> Result<Vec<Todo>>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Vec<Todo>>> + Send + 'static,
+{
+    let running = in_flight().get(key).cloned();
+    if let Some(flight) = running {
+        log::debug!("Sharing the running sync with {key}");
+        match flight.await {
+            // Cancelled by whoever started it, which doesn't concern this caller
+            Err(err) if err.chain().any(|cause| cause.is::<Cancelled>()) => {}
+            result => return result.map_err(unshare),
+        }
+    }
+
+    let flight = in_flight()
+        .entry(key.to_string())
+        .or_insert_with(|| {
+            let key = key.to_string();
+            let fetch = fetch();
+            async move {
+                let result = fetch.await.map_err(Arc::new);
+                in_flight().remove(&key);
+                result
+            }
+            .boxed()
+            .shared()
+        })
+        .clone();
+    flight.await.map_err(unshare)
+}
+
+/// Returns the original error when no one else received it, and a copy of its message otherwise
+fn unshare(err: Arc<anyhow::Error>) -> anyhow::Error {
+    Arc::try_unwrap(err).unwrap_or_else(|err| anyhow::anyhow!("{err:#}"))
+}
+
+async fn fetch(client: &ApiClient) -> Result<Vec<Todo>> {
+    let now = Utc::now().timestamp();
+    let cache = TodoCache::load(&client.config().api_endpoint)
+        .filter(|cache| now - cache.full_sync_at < FULL_SYNC_SECS);
+    let Some((mut cache, since)) =
+        cache.and_then(|cache| cache.sync_cursor().map(|since| (cache, since)))
+    else {
+        return client.list_todos(None, None).await;
+    };
+
+    let changed = client.list_todos_since(since).await?;
+    if changed.iter().any(|todo| todo.updated_at < since) {
+        // The server ignored `updated_after` and sent everything
+        log::debug!("Server doesn't support incremental sync, reloading");
+        TodoCache::store(&client.config().api_endpoint, &changed);
+        return Ok(changed);
+    }
+
+    log::debug!("Incremental sync: {} changed todo(s)", changed.len());
+    cache.merge(&changed);
+    cache.fetched_at = now;
+    cache.write();
+    Ok(cache.todos)
+}
+
+/// Returns all todos, from the cache when fresh and otherwise synced with the server
+///
+/// `priority` is applied locally so every filter can share one cached list.
+///
+/// # Errors
+///
+/// Returns an error if the cache is stale and the request fails
+pub async fn list_todos(client: &ApiClient, priority: Option<i32>) -> Result<Vec<Todo>> {
+    let config = client.config();
+    let ttl = config.cache_ttl.unwrap_or(DEFAULT_TTL_SECS);
+    let cached = TodoCache::load(&config.api_endpoint)
+        .filter(|cache| cache.is_fresh(ttl, Utc::now().timestamp()));
+
+    let todos = match cached {
+        Some(cache) => {
+            log::debug!("Using cached todo list ({} todos)", cache.todos.len());
+            cache.todos
+        }
+        None => sync(client).await?,
+    };
+
+    Ok(match priority {
+        Some(priority) => todos
+            .into_iter()
+            .filter(|todo| todo.priority == priority)
+            .collect(),
+        None => todos,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn todo(id: &str, title: &str, updated_at: i64) -> Todo {
+        testing::todo(id).title(title).updated(updated_at).build()
+    }
+
+    #[test]
+    fn test_cache_freshness_and_checksum() {
+        let mut cache = TodoCache::new(
+            "http://localhost:8787",
+            vec![todo("abc", "Cached", 0)],
+            1000,
+        );
+
+        assert!(cache.is_fresh(30, 1029));
+        assert!(!cache.is_fresh(30, 1030));
+        assert!(!cache.is_fresh(0, 1000));
+
+        assert_eq!(cache.checksum, checksum(&cache.todos));
+        cache.todos[0].title = "Edited".to_string();
+        assert_ne!(cache.checksum, checksum(&cache.todos));
+    }
+
+    #[test]
+    fn test_merge_changed_todos() {
+        let mut cache = TodoCache::new(
+            "http://localhost:8787",
+            vec![todo("a", "First", 100), todo("b", "Second", 200)],
+            1000,
+        );
+        assert_eq!(cache.sync_cursor(), Some(199));
+
+        cache.merge(&[todo("b", "Second (edited)", 300), todo("c", "Third", 300)]);
+        let titles: Vec<&str> = cache.todos.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, ["First", "Second (edited)", "Third"]);
+        assert_eq!(cache.sync_cursor(), Some(299));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_syncs_share_one_fetch() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let fetch = {
+            let calls = calls.clone();
+            move || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok(vec![todo("a", "Shared", 1)])
+            }
+        };
+
+        let (first, second) = tokio::join!(
+            coalesce("test-endpoint", fetch.clone()),
+            coalesce("test-endpoint", fetch.clone())
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(first.unwrap()[0].title, "Shared");
+        assert_eq!(second.unwrap()[0].title, "Shared");
+
+        // Finished syncs aren't reused
+        coalesce("test-endpoint", fetch).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,841 @@
+//! Two-way sync of todos with a CalDAV task list (VTODO), such as Nextcloud Tasks
+//!
+//! The title, description, completion, priority and due date are synced.
+//! `caldav_state.json` in the data directory maps each todo to its task on
+//! the server, together with the fields as they were after the last sync;
+//! comparing both sides with that snapshot tells which side changed.
+//!
+//! - Changed on one side: the change is copied to the other side.
+//! - Changed on both sides: the preferred side wins (by default the one
+//!   modified last).
+//! - Deleted on one side: deleted on the other side too, unless it was
+//!   changed there, in which case it is recreated.
+//! - New on one side: created on the other side, if open.
+//!
+//! Only built with the `caldav` feature.
+
+use crate::config::{CalDavSettings, Config};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use pali_types::Todo;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Environment variable with the CalDAV password, preferred over `password` in the config
+pub const PASSWORD_ENV: &str = "PALI_CALDAV_PASSWORD";
+
+/// iCalendar content lines are folded at this many bytes
+const FOLD_WIDTH: usize = 75;
+
+/// The synced fields of a todo, in Pali's terms
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncFields {
+    pub title: String,
+    pub description: Option<String>,
+    pub completed: bool,
+    pub priority: i32,
+    pub due_date: Option<i64>,
+}
+
+impl SyncFields {
+    #[must_use]
+    pub fn from_todo(todo: &Todo) -> Self {
+        Self {
+            title: todo.title.clone(),
+            description: todo.description.clone().filter(|d| !d.is_empty()),
+            completed: todo.completed,
+            priority: todo.priority,
+            due_date: todo.due_date,
+        }
+    }
+
+    /// Formats the fields as an iCalendar object holding one VTODO
+    #[must_use]
+    pub fn to_ical(&self, uid: &str, now: i64) -> String {
+        let stamp = format_utc(now);
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//pali//pali-terminal//EN".to_string(),
+            "BEGIN:VTODO".to_string(),
+            format!("UID:{}", escape_text(uid)),
+            format!("DTSTAMP:{stamp}"),
+            format!("LAST-MODIFIED:{stamp}"),
+            format!("SUMMARY:{}", escape_text(&self.title)),
+        ];
+        if let Some(description) = &self.description {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        let status = if self.completed {
+            "COMPLETED"
+        } else {
+            "NEEDS-ACTION"
+        };
+        lines.push(format!("STATUS:{status}"));
+        lines.push(format!("PRIORITY:{}", ical_priority(self.priority)));
+        if let Some(due) = self.due_date {
+            lines.push(format!("DUE:{}", format_utc(due)));
+        }
+        lines.push("END:VTODO".to_string());
+        lines.push("END:VCALENDAR".to_string());
+
+        lines.iter().map(|line| fold_line(line) + "\r\n").collect()
+    }
+}
+
+/// A task as read from the CalDAV server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteTask {
+    /// Path of the task's resource on the server
+    pub href: String,
+    pub etag: Option<String>,
+    pub uid: String,
+    pub fields: SyncFields,
+    pub last_modified: Option<i64>,
+}
+
+/// Maps Pali's priorities (1-3) to iCalendar's (1 highest to 9 lowest)
+fn ical_priority(priority: i32) -> u8 {
+    match priority {
+        p if p >= 3 => 1,
+        2 => 5,
+        _ => 9,
+    }
+}
+
+/// Maps iCalendar's priorities to Pali's; 0 (undefined) counts as medium
+fn pali_priority(priority: u8) -> i32 {
+    match priority {
+        1..=4 => 3,
+        6..=9 => 1,
+        _ => 2,
+    }
+}
+
+fn format_utc(ts: i64) -> String {
+    DateTime::from_timestamp(ts, 0)
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Parses a DATE or DATE-TIME value; floating and TZID times are taken as local time
+fn parse_ical_time(value: &str, date_only: bool) -> Option<i64> {
+    if date_only || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Local
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+            .earliest()
+            .map(|dt| dt.timestamp());
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(naive.and_utc().timestamp());
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.timestamp())
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn unescape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Splits a content line into lines of at most [`FOLD_WIDTH`] bytes
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / FOLD_WIDTH * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > FOLD_WIDTH {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the next line
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+/// Reads the first VTODO of an iCalendar object: its UID, fields and last modification
+#[must_use]
+pub fn parse_vtodo(ical: &str) -> Option<(String, SyncFields, Option<i64>)> {
+    // Unfold continuation lines first
+    let mut lines: Vec<String> = Vec::new();
+    for line in ical.lines() {
+        match line.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => lines.last_mut()?.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut in_todo = false;
+    let mut uid = None;
+    let mut fields = SyncFields {
+        title: String::new(),
+        description: None,
+        completed: false,
+        priority: 2,
+        due_date: None,
+    };
+    let mut last_modified = None;
+    for line in &lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut params = name.split(';');
+        let name = params.next().unwrap_or_default().to_ascii_uppercase();
+        match (in_todo, name.as_str()) {
+            (false, "BEGIN") if value.eq_ignore_ascii_case("VTODO") => in_todo = true,
+            (true, "END") if value.eq_ignore_ascii_case("VTODO") => break,
+            (true, "UID") => uid = Some(unescape_text(value)),
+            (true, "SUMMARY") => fields.title = unescape_text(value),
+            (true, "DESCRIPTION") => {
+                fields.description = Some(unescape_text(value)).filter(|d| !d.is_empty());
+            }
+            (true, "STATUS") => fields.completed = value.eq_ignore_ascii_case("COMPLETED"),
+            (true, "PRIORITY") => fields.priority = pali_priority(value.trim().parse().ok()?),
+            (true, "DUE") => {
+                let date_only = params.any(|param| param.eq_ignore_ascii_case("VALUE=DATE"));
+                fields.due_date = parse_ical_time(value.trim(), date_only);
+            }
+            (true, "LAST-MODIFIED") => last_modified = parse_ical_time(value.trim(), false),
+            _ => {}
+        }
+    }
+
+    Some((uid?, fields, last_modified))
+}
+
+/// Which side wins when a todo changed on both sides since the last sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Prefer {
+    /// Keep the local todo
+    Local,
+    /// Keep the server's task
+    #[cfg_attr(feature = "cli", value(alias = "server"))]
+    Remote,
+    /// The side modified last; local if the server doesn't say when
+    Newer,
+}
+
+/// What the last sync left a todo and its task like
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mapping {
+    pub href: String,
+    pub uid: String,
+    /// Fields as they were on both sides after the last sync
+    pub synced: SyncFields,
+}
+
+/// Mappings between todos and CalDAV tasks, saved in `caldav_state.json`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncState {
+    /// Task list the mappings belong to; a different URL starts over
+    #[serde(default)]
+    pub url: String,
+    /// Mappings by todo ID
+    #[serde(default)]
+    pub todos: BTreeMap<String, Mapping>,
+}
+
+impl SyncState {
+    /// Returns the path to the state file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data directory cannot be determined
+    pub fn path() -> Result<PathBuf> {
+        Ok(Config::data_dir()?.join("caldav_state.json"))
+    }
+
+    /// Loads the mappings for `url`; a missing file, or one for another URL, gives none
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state file exists but cannot be read or parsed
+    pub fn load(url: &str) -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self {
+                url: url.to_string(),
+                ..Self::default()
+            });
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let state: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid CalDAV sync state in {}", path.display()))?;
+        if state.url == url {
+            Ok(state)
+        } else {
+            log::info!(
+                "CalDAV URL changed from {}; starting a fresh sync",
+                state.url
+            );
+            Ok(Self {
+                url: url.to_string(),
+                ..Self::default()
+            })
+        }
+    }
+
+    /// Saves the mappings
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state file cannot be written
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// One change a sync makes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// Create a task for a todo
+    PushNew { todo_id: String },
+    /// Update a task from its todo
+    Push { todo_id: String, conflict: bool },
+    /// Update a todo from its task
+    Pull { todo_id: String, conflict: bool },
+    /// Create a todo for a task
+    PullNew { href: String },
+    /// Delete a task whose todo was deleted
+    DeleteRemote { todo_id: String },
+    /// Delete a todo whose task was deleted
+    DeleteLocal { todo_id: String },
+}
+
+/// Works out the changes that bring both sides in line
+///
+/// `skip_local` lists todos that are never pushed as new tasks (archived
+/// ones); their existing mappings are still synced.
+#[must_use]
+pub fn plan(
+    todos: &[Todo],
+    remote: &[RemoteTask],
+    state: &SyncState,
+    skip_local: &HashSet<String>,
+    prefer: Prefer,
+) -> Vec<Step> {
+    let local: HashMap<&str, &Todo> = todos.iter().map(|t| (t.id.as_str(), t)).collect();
+    let by_href: HashMap<&str, &RemoteTask> = remote
+        .iter()
+        .map(|task| (task.href.as_str(), task))
+        .collect();
+
+    let mut steps = Vec::new();
+    for (todo_id, mapping) in &state.todos {
+        let todo = local.get(todo_id.as_str());
+        let task = by_href.get(mapping.href.as_str());
+        let local_changed = todo.is_some_and(|t| SyncFields::from_todo(t) != mapping.synced);
+        let remote_changed = task.is_some_and(|t| t.fields != mapping.synced);
+        let todo_id = todo_id.clone();
+
+        let step = match (todo, task) {
+            // Gone from both sides: the mapping is simply dropped
+            (None, None) => None,
+            (None, Some(_)) if remote_changed => Some(Step::PullNew {
+                href: mapping.href.clone(),
+            }),
+            (None, Some(_)) => Some(Step::DeleteRemote { todo_id }),
+            (Some(_), None) if local_changed => Some(Step::PushNew { todo_id }),
+            (Some(_), None) => Some(Step::DeleteLocal { todo_id }),
+            (Some(todo), Some(task)) => match (local_changed, remote_changed) {
+                (false, false) => None,
+                (true, false) => Some(Step::Push {
+                    todo_id,
+                    conflict: false,
+                }),
+                (false, true) => Some(Step::Pull {
+                    todo_id,
+                    conflict: false,
+                }),
+                (true, true) if SyncFields::from_todo(todo) == task.fields => None,
+                (true, true) => {
+                    let local_wins = match prefer {
+                        Prefer::Local => true,
+                        Prefer::Remote => false,
+                        Prefer::Newer => task
+                            .last_modified
+                            .is_none_or(|remote| todo.updated_at >= remote),
+                    };
+                    Some(if local_wins {
+                        Step::Push {
+                            todo_id,
+                            conflict: true,
+                        }
+                    } else {
+                        Step::Pull {
+                            todo_id,
+                            conflict: true,
+                        }
+                    })
+                }
+            },
+        };
+        steps.extend(step);
+    }
+
+    let mapped_hrefs: HashSet<&str> = state.todos.values().map(|m| m.href.as_str()).collect();
+    steps.extend(
+        todos
+            .iter()
+            .filter(|todo| !state.todos.contains_key(&todo.id))
+            .filter(|todo| !todo.completed && !skip_local.contains(&todo.id))
+            .map(|todo| Step::PushNew {
+                todo_id: todo.id.clone(),
+            }),
+    );
+    steps.extend(
+        remote
+            .iter()
+            .filter(|task| !mapped_hrefs.contains(task.href.as_str()))
+            .filter(|task| !task.fields.completed)
+            .map(|task| Step::PullNew {
+                href: task.href.clone(),
+            }),
+    );
+    steps
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PushNew { .. } => f.write_str("create on server"),
+            Self::Push { conflict, .. } => {
+                f.write_str("update on server")?;
+                if *conflict {
+                    f.write_str(" (conflict, kept local)")?;
+                }
+                Ok(())
+            }
+            Self::Pull { conflict, .. } => {
+                f.write_str("update locally")?;
+                if *conflict {
+                    f.write_str(" (conflict, kept server)")?;
+                }
+                Ok(())
+            }
+            Self::PullNew { .. } => f.write_str("create locally"),
+            Self::DeleteRemote { .. } => f.write_str("delete on server"),
+            Self::DeleteLocal { .. } => f.write_str("delete locally"),
+        }
+    }
+}
+
+/// Minimal WebDAV client for one CalDAV task list
+pub struct CalDavClient {
+    client: reqwest::Client,
+    /// Collection URL, ending in `/`
+    url: reqwest::Url,
+    username: String,
+    password: Option<String>,
+}
+
+impl CalDavClient {
+    /// Creates a client for the configured task list
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL is invalid or the HTTP client cannot be built
+    pub fn new(settings: &CalDavSettings, config: &Config) -> Result<Self> {
+        let mut url = settings.url.clone();
+        if !url.ends_with('/') {
+            url.push('/');
+        }
+        let url = reqwest::Url::parse(&url)
+            .with_context(|| format!("Invalid CalDAV URL '{}'", settings.url))?;
+
+        let mut builder = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout.unwrap_or(30)))
+            .user_agent(concat!("pali-terminal/", env!("CARGO_PKG_VERSION")));
+        if let Some(proxy) = &config.proxy {
+            builder = builder.proxy(
+                reqwest::Proxy::all(proxy)
+                    .with_context(|| format!("Invalid proxy URL '{proxy}'"))?,
+            );
+        }
+
+        Ok(Self {
+            client: builder
+                .build()
+                .context("Unable to initialize network client")?,
+            url,
+            username: settings.username.clone(),
+            password: std::env::var(PASSWORD_ENV)
+                .ok()
+                .or_else(|| settings.password.clone()),
+        })
+    }
+
+    /// The task list URL
+    #[must_use]
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    fn request(&self, method: reqwest::Method, url: reqwest::Url) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, url)
+            .basic_auth(&self.username, self.password.as_deref())
+    }
+
+    fn resolve(&self, href: &str) -> Result<reqwest::Url> {
+        self.url
+            .join(href)
+            .with_context(|| format!("Invalid task path '{href}'"))
+    }
+
+    /// Lists the VTODOs of the task list
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the response cannot be parsed
+    pub async fn list_tasks(&self) -> Result<Vec<RemoteTask>> {
+        const QUERY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><d:getetag/><c:calendar-data/></d:prop>
+  <c:filter><c:comp-filter name="VCALENDAR"><c:comp-filter name="VTODO"/></c:comp-filter></c:filter>
+</c:calendar-query>"#;
+
+        let method = reqwest::Method::from_bytes(b"REPORT").expect("valid method");
+        let response = self
+            .request(method, self.url.clone())
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(QUERY)
+            .send()
+            .await
+            .context("Failed to reach the CalDAV server")?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("CalDAV server answered {status} when listing tasks");
+        }
+        let body = response.text().await?;
+
+        Ok(parse_multistatus(&body)?
+            .into_iter()
+            .filter_map(|(href, etag, data)| {
+                let (uid, fields, last_modified) = parse_vtodo(&data)?;
+                Some(RemoteTask {
+                    href,
+                    etag,
+                    uid,
+                    fields,
+                    last_modified,
+                })
+            })
+            .collect())
+    }
+
+    /// Creates (`etag` is `None`) or replaces a task
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or the task changed since `etag`
+    pub async fn put_task(
+        &self,
+        href: &str,
+        uid: &str,
+        fields: &SyncFields,
+        etag: Option<&str>,
+    ) -> Result<()> {
+        let mut request = self
+            .request(reqwest::Method::PUT, self.resolve(href)?)
+            .header("Content-Type", "text/calendar; charset=utf-8")
+            .body(fields.to_ical(uid, Utc::now().timestamp()));
+        request = match etag {
+            Some(etag) => request.header("If-Match", etag),
+            None => request.header("If-None-Match", "*"),
+        };
+        let response = request
+            .send()
+            .await
+            .context("Failed to reach the CalDAV server")?;
+        let status = response.status();
+        if status == reqwest::StatusCode::PRECONDITION_FAILED {
+            anyhow::bail!("'{}' changed on the server during the sync", fields.title);
+        }
+        if !status.is_success() {
+            anyhow::bail!(
+                "CalDAV server answered {status} when saving '{}'",
+                fields.title
+            );
+        }
+        Ok(())
+    }
+
+    /// Deletes a task
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails; a task that is already gone is fine
+    pub async fn delete_task(&self, href: &str, etag: Option<&str>) -> Result<()> {
+        let mut request = self.request(reqwest::Method::DELETE, self.resolve(href)?);
+        if let Some(etag) = etag {
+            request = request.header("If-Match", etag);
+        }
+        let response = request
+            .send()
+            .await
+            .context("Failed to reach the CalDAV server")?;
+        let status = response.status();
+        if status.is_success() || status == reqwest::StatusCode::NOT_FOUND {
+            Ok(())
+        } else {
+            anyhow::bail!("CalDAV server answered {status} when deleting {href}")
+        }
+    }
+}
+
+/// Path of a new task for a todo, relative to the task list
+#[must_use]
+pub fn new_task_href(uid: &str) -> String {
+    format!("{uid}.ics")
+}
+
+/// UID of the task created for a todo
+#[must_use]
+pub fn task_uid(todo_id: &str) -> String {
+    format!("pali-{todo_id}")
+}
+
+/// Reads the href, ETag and calendar data of every response in a WebDAV multistatus
+fn parse_multistatus(xml: &str) -> Result<Vec<(String, Option<String>, String)>> {
+    use quick_xml::events::Event;
+
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut results = Vec::new();
+    let (mut href, mut etag, mut data) = (None, None, None);
+    // Text of the element being read, if it is one of interest
+    let mut text: Option<String> = None;
+    loop {
+        match reader
+            .read_event()
+            .context("Invalid response from the CalDAV server")?
+        {
+            Event::Start(start) => {
+                if matches!(
+                    start.local_name().as_ref(),
+                    b"href" | b"getetag" | b"calendar-data"
+                ) {
+                    text = Some(String::new());
+                }
+            }
+            Event::Text(content) => {
+                if let Some(text) = &mut text {
+                    text.push_str(&content.unescape()?);
+                }
+            }
+            Event::CData(content) => {
+                if let Some(text) = &mut text {
+                    text.push_str(&String::from_utf8_lossy(&content.into_inner()));
+                }
+            }
+            Event::End(end) => match end.local_name().as_ref() {
+                b"href" => href = text.take().map(|t| t.trim().to_string()),
+                b"getetag" => etag = text.take().map(|t| t.trim().to_string()),
+                b"calendar-data" => data = text.take(),
+                b"response" => {
+                    if let (Some(href), Some(data)) = (href.take(), data.take()) {
+                        results.push((href, etag.take(), data));
+                    }
+                    etag = None;
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn fields(title: &str) -> SyncFields {
+        SyncFields {
+            title: title.to_string(),
+            description: None,
+            completed: false,
+            priority: 2,
+            due_date: None,
+        }
+    }
+
+    fn todo(id: &str, title: &str, updated_at: i64) -> Todo {
+        testing::todo(id).title(title).updated(updated_at).build()
+    }
+
+    fn task(href: &str, title: &str, last_modified: i64) -> RemoteTask {
+        RemoteTask {
+            href: href.to_string(),
+            etag: None,
+            uid: href.to_string(),
+            fields: fields(title),
+            last_modified: Some(last_modified),
+        }
+    }
+
+    #[test]
+    fn test_ical_roundtrip() {
+        let original = SyncFields {
+            title: "Call Ada; bring notes, and the \\ key".to_string(),
+            description: Some(format!("Line one\nLine two {}", "long ".repeat(30))),
+            completed: true,
+            priority: 3,
+            due_date: Some(1_760_000_000),
+        };
+        let ical = original.to_ical("pali-1", 0);
+        assert!(ical.lines().all(|line| line.len() <= FOLD_WIDTH));
+
+        let (uid, parsed, last_modified) = parse_vtodo(&ical).unwrap();
+        assert_eq!(uid, "pali-1");
+        assert_eq!(parsed, original);
+        assert_eq!(last_modified, Some(0));
+    }
+
+    #[test]
+    fn test_parse_foreign_vtodo() {
+        let ical = "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nUID:abc\r\nSUMMARY:Water the plants\r\n\
+                    PRIORITY:7\r\nDUE;VALUE=DATE:20261020\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+        let (uid, parsed, _) = parse_vtodo(ical).unwrap();
+        assert_eq!(uid, "abc");
+        assert_eq!(parsed.priority, 1);
+        assert!(!parsed.completed);
+        let due = parsed.due_date.unwrap();
+        let local = Local.timestamp_opt(due, 0).unwrap();
+        assert_eq!(
+            local.date_naive(),
+            NaiveDate::from_ymd_opt(2026, 10, 20).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_multistatus() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">
+  <d:response>
+    <d:href>/dav/tasks/abc.ics</d:href>
+    <d:propstat><d:prop>
+      <d:getetag>"1-2"</d:getetag>
+      <cal:calendar-data>BEGIN:VCALENDAR&#13;
+BEGIN:VTODO&#13;
+UID:abc&#13;
+SUMMARY:Fish &amp; chips&#13;
+END:VTODO&#13;
+END:VCALENDAR&#13;
+</cal:calendar-data>
+    </d:prop></d:propstat>
+  </d:response>
+</d:multistatus>"#;
+        let results = parse_multistatus(xml).unwrap();
+        assert_eq!(results.len(), 1);
+        let (href, etag, data) = &results[0];
+        assert_eq!(href, "/dav/tasks/abc.ics");
+        assert_eq!(etag.as_deref(), Some("\"1-2\""));
+        assert_eq!(parse_vtodo(data).unwrap().1.title, "Fish & chips");
+    }
+
+    #[test]
+    fn test_plan() {
+        let mut state = SyncState::default();
+        for (id, href) in [
+            ("a", "a.ics"),
+            ("b", "b.ics"),
+            ("c", "c.ics"),
+            ("d", "d.ics"),
+        ] {
+            state.todos.insert(
+                id.to_string(),
+                Mapping {
+                    href: href.to_string(),
+                    uid: id.to_string(),
+                    synced: fields(id),
+                },
+            );
+        }
+        let todos = [
+            todo("a", "a edited", 200),
+            todo("b", "b", 0),
+            todo("c", "c edited here", 100),
+            todo("new", "new", 0),
+        ];
+        let remote = [
+            task("a.ics", "a", 0),
+            task("b.ics", "b edited", 0),
+            task("c.ics", "c edited there", 300),
+            task("fresh.ics", "fresh", 0),
+        ];
+
+        let steps = plan(&todos, &remote, &state, &HashSet::new(), Prefer::Newer);
+        assert_eq!(
+            steps,
+            [
+                Step::Push {
+                    todo_id: "a".to_string(),
+                    conflict: false
+                },
+                Step::Pull {
+                    todo_id: "b".to_string(),
+                    conflict: false
+                },
+                Step::Pull {
+                    todo_id: "c".to_string(),
+                    conflict: true
+                },
+                // "d" is gone from both sides
+                Step::PushNew {
+                    todo_id: "new".to_string()
+                },
+                Step::PullNew {
+                    href: "fresh.ics".to_string()
+                },
+            ]
+        );
+
+        let steps = plan(&todos, &remote, &state, &HashSet::new(), Prefer::Local);
+        assert!(steps.contains(&Step::Push {
+            todo_id: "c".to_string(),
+            conflict: true
+        }));
+    }
+}
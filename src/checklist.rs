@@ -0,0 +1,169 @@
+//! Checklist (subtask) support for todos
+//!
+//! Checklist items live in the todo description using the markdown task-list
+//! convention, one item per line:
+//!
+//! ```text
+//! - [ ] pending item
+//! - [x] finished item
+//! ```
+//!
+//! Keeping them in the description means they sync through the existing
+//! server fields and stay readable in any client.
+
+const PENDING_MARKER: &str = "- [ ] ";
+const DONE_MARKER: &str = "- [x] ";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecklistItem {
+    pub text: String,
+    pub done: bool,
+}
+
+/// Parses a single description line, returning `(done, text)` for checklist items
+fn parse_line(line: &str) -> Option<(bool, &str)> {
+    let trimmed = line.trim_start();
+    let rest = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))?;
+
+    if let Some(text) = rest.strip_prefix("[ ] ") {
+        Some((false, text.trim()))
+    } else {
+        rest.strip_prefix("[x] ")
+            .or_else(|| rest.strip_prefix("[X] "))
+            .map(|text| (true, text.trim()))
+    }
+}
+
+/// Returns all checklist items found in a description, in order
+#[must_use]
+pub fn items(description: &str) -> Vec<ChecklistItem> {
+    description
+        .lines()
+        .filter_map(parse_line)
+        .map(|(done, text)| ChecklistItem {
+            text: text.to_string(),
+            done,
+        })
+        .collect()
+}
+
+/// Returns `(done, total)` checklist counts, or `None` if there is no checklist
+#[must_use]
+pub fn progress(description: &str) -> Option<(usize, usize)> {
+    let items = items(description);
+    if items.is_empty() {
+        None
+    } else {
+        Some((items.iter().filter(|item| item.done).count(), items.len()))
+    }
+}
+
+/// Returns the description with all checklist lines removed
+#[must_use]
+pub fn strip_items(description: &str) -> String {
+    description
+        .lines()
+        .filter(|line| parse_line(line).is_none())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
+/// Appends a new pending checklist item to a description
+#[must_use]
+pub fn add_item(description: Option<&str>, text: &str) -> String {
+    let item = format!("{PENDING_MARKER}{}", text.trim());
+    match description.map(str::trim_end) {
+        Some(existing) if !existing.is_empty() => format!("{existing}\n{item}"),
+        _ => item,
+    }
+}
+
+/// Marks the checklist item at `index` (0-based) as done or pending
+///
+/// Returns the updated description, or `None` if there is no such item.
+#[must_use]
+pub fn set_item_done(description: &str, index: usize, done: bool) -> Option<String> {
+    let mut seen = 0;
+    let mut found = false;
+
+    let lines: Vec<String> = description
+        .lines()
+        .map(|line| {
+            if let Some((_, text)) = parse_line(line) {
+                let current = seen;
+                seen += 1;
+                if current == index {
+                    found = true;
+                    let indent = &line[..line.len() - line.trim_start().len()];
+                    let marker = if done { DONE_MARKER } else { PENDING_MARKER };
+                    return format!("{indent}{marker}{text}");
+                }
+            }
+            line.to_string()
+        })
+        .collect();
+
+    found.then(|| lines.join("\n"))
+}
+
+/// Flips the state of the checklist item at `index` (0-based)
+///
+/// Returns the updated description, or `None` if there is no such item.
+#[must_use]
+pub fn toggle_item(description: &str, index: usize) -> Option<String> {
+    let item = items(description).into_iter().nth(index)?;
+    set_item_done(description, index, !item.done)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIPTION: &str = "Groceries for the week\n- [ ] milk\n- [x] eggs\n  * [ ] bread";
+
+    #[test]
+    fn test_items_and_progress() {
+        let items = items(DESCRIPTION);
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].text, "milk");
+        assert!(!items[0].done);
+        assert!(items[1].done);
+        assert_eq!(items[2].text, "bread");
+
+        assert_eq!(progress(DESCRIPTION), Some((1, 3)));
+        assert_eq!(progress("No checklist here"), None);
+    }
+
+    #[test]
+    fn test_strip_items() {
+        assert_eq!(strip_items(DESCRIPTION), "Groceries for the week");
+        assert_eq!(strip_items("- [ ] only item"), "");
+    }
+
+    #[test]
+    fn test_add_item() {
+        assert_eq!(add_item(None, "milk"), "- [ ] milk");
+        assert_eq!(add_item(Some(""), " milk "), "- [ ] milk");
+        assert_eq!(
+            add_item(Some("Notes\n"), "milk"),
+            "Notes\n- [ ] milk".to_string()
+        );
+    }
+
+    #[test]
+    fn test_set_and_toggle_item() {
+        let updated = set_item_done(DESCRIPTION, 0, true).unwrap();
+        assert_eq!(progress(&updated), Some((2, 3)));
+
+        let updated = toggle_item(&updated, 2).unwrap();
+        assert!(updated.ends_with("  - [x] bread"));
+        assert_eq!(progress(&updated), Some((3, 3)));
+
+        assert!(set_item_done(DESCRIPTION, 3, true).is_none());
+        assert!(toggle_item("no items", 0).is_none());
+    }
+}
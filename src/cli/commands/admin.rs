@@ -1,8 +1,55 @@
 use crate::cli::types::AdminAction;
-use crate::{api::ApiClient, config::Config, ID_DISPLAY_LENGTH};
+use crate::{
+    activity::{self, ActivityEntry, Source},
+    api::{format_bytes, ApiClient, ApiKey, GenerateKeyRequest, KeyUsage},
+    cli::utils::{confirm, parse_duration_secs, wildcard_match},
+    config::Config,
+    state::format_duration,
+    term, ID_DISPLAY_LENGTH,
+};
 use anyhow::Result;
 use chrono::TimeZone;
 use colored::Colorize;
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+/// Scope granted by `--read-only`
+const READ_ONLY_SCOPE: &str = "todos:read";
+
+/// Formats a Unix timestamp as local date and time
+fn format_timestamp(ts: i64) -> String {
+    chrono::Utc
+        .timestamp_opt(ts, 0)
+        .latest()
+        .map(|dt| dt.with_timezone(&chrono::Local))
+        .map_or_else(
+            || "Invalid date".to_string(),
+            |dt| dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+        )
+}
+
+/// Parses a comma-separated `--scope` value such as `todos:read,todos:write`
+fn parse_scopes(spec: &str) -> Result<Vec<String>> {
+    let mut scopes: Vec<String> = Vec::new();
+    for scope in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let valid = scope
+            .split_once(':')
+            .is_some_and(|(resource, action)| !resource.is_empty() && !action.is_empty());
+        if !valid {
+            anyhow::bail!("Invalid scope '{scope}'. Use <resource>:<action>, e.g. todos:read");
+        }
+        let scope = scope.to_lowercase();
+        if !scopes.contains(&scope) {
+            scopes.push(scope);
+        }
+    }
+
+    if scopes.is_empty() {
+        anyhow::bail!("No scopes given");
+    }
+    Ok(scopes)
+}
 
 /// Handles admin actions (key rotation, generation, listing, revocation)
 ///
@@ -13,70 +60,232 @@ use colored::Colorize;
 /// - API key is invalid or lacks admin privileges
 /// - Server returns an error response
 /// - Configuration cannot be saved (for key operations)
-pub async fn handle(action: AdminAction) -> Result<()> {
+pub async fn handle(client: &ApiClient, action: AdminAction) -> Result<()> {
     match action {
-        AdminAction::RotateKey => rotate_key().await,
-        AdminAction::GenerateKey { name } => generate_key(name).await,
-        AdminAction::ListKeys => list_keys().await,
-        AdminAction::RevokeKey { id } => revoke_key(id).await,
-        AdminAction::Reinitialize => reinitialize().await,
+        AdminAction::Status { json } => status(client, json).await,
+        AdminAction::Audit {
+            since,
+            key,
+            action,
+            follow,
+        } => super::audit::audit(client, since, key, action, follow).await,
+        AdminAction::RotateKey => rotate_key(client).await,
+        AdminAction::GenerateKey {
+            name,
+            read_only,
+            scope,
+            expires,
+            qr,
+        } => generate_key(client, name, read_only, scope, expires, qr).await,
+        AdminAction::ListKeys => list_keys(client).await,
+        AdminAction::KeyStats { id, json } => key_stats(client, id, json).await,
+        AdminAction::RenameKey { id, name } => rename_key(client, id, name).await,
+        AdminAction::RevokeKey {
+            ids,
+            name,
+            inactive,
+            yes,
+        } => revoke_keys(client, ids, name, inactive, yes).await,
+        AdminAction::Reinitialize { force } => reinitialize(client, force).await,
     }
 }
 
-async fn rotate_key() -> Result<()> {
-    let client = ApiClient::new()?;
-    let new_key = client.rotate_admin_key().await?;
+async fn status(client: &ApiClient, json: bool) -> Result<()> {
+    let status = client.server_status().await?;
 
-    let mut config = Config::load()?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&status)?);
+        return Ok(());
+    }
+
+    let unknown = || "unknown".dimmed().to_string();
+
+    println!("{}", "Server status".bold());
+    println!("  {} {}", "Endpoint:".cyan(), client.config().api_endpoint);
+    println!("  {} {}", "Version:".cyan(), status.version);
+    println!(
+        "  {} {}",
+        "Uptime:".cyan(),
+        status.uptime_seconds.map_or_else(unknown, |secs| {
+            let days = secs / 86_400;
+            if days > 0 {
+                format!("{days}d {}", format_duration(secs % 86_400))
+            } else {
+                format_duration(secs)
+            }
+        })
+    );
+    println!(
+        "  {} {}",
+        "Todos:".cyan(),
+        status.todo_count.map_or_else(unknown, |n| n.to_string())
+    );
+    println!(
+        "  {} {}",
+        "Storage:".cyan(),
+        status
+            .storage
+            .as_ref()
+            .map_or_else(unknown, |storage| match storage.limit_bytes {
+                Some(limit) => format!(
+                    "{} of {}",
+                    format_bytes(storage.used_bytes),
+                    format_bytes(limit)
+                ),
+                None => format_bytes(storage.used_bytes),
+            })
+    );
+    println!(
+        "  {} {}",
+        "Rate limit:".cyan(),
+        status
+            .rate_limit
+            .as_ref()
+            .map_or_else(unknown, |limit| format!(
+                "{} requests per {} per key",
+                limit.requests,
+                format_duration(i64::try_from(limit.window_seconds).unwrap_or(i64::MAX))
+            ))
+    );
+    let capabilities = client.capabilities().await;
+    println!(
+        "  {} {}",
+        "Features:".cyan(),
+        if capabilities.features.is_empty() {
+            "none reported".dimmed().to_string()
+        } else {
+            capabilities
+                .features
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    );
+
+    Ok(())
+}
+
+async fn rotate_key(client: &ApiClient) -> Result<()> {
+    let new_key = activity::track(
+        ActivityEntry::new(Source::Cli, "admin rotate-key"),
+        client.rotate_admin_key().await,
+    )?;
+
+    let mut config = client.config().clone();
     config.set_api_key(&new_key);
     config.save()?;
 
-    println!("{} Admin key rotated successfully", "✓".green());
-    println!("{} New key has been saved to config", "✓".green());
+    println!(
+        "{} Admin key rotated successfully",
+        term::symbols().ok.green()
+    );
+    println!(
+        "{} New key has been saved to config",
+        term::symbols().ok.green()
+    );
     println!();
     println!("{} {}", "New API Key:".yellow().bold(), new_key.cyan());
     println!();
     println!(
         "{} Store this key securely - it won't be shown again!",
-        "⚠".yellow()
+        term::symbols().warning.yellow()
     );
     println!(
         "{} API key is stored in plain text at: {}",
-        "⚠".yellow(),
+        term::symbols().warning.yellow(),
         Config::config_path()?.display().to_string().dimmed()
     );
 
     Ok(())
 }
 
-async fn generate_key(name: Option<String>) -> Result<()> {
-    let client = ApiClient::new()?;
-    let response = client.generate_api_key(name.as_deref()).await?;
-
-    println!("{} Generated new API key", "✓".green());
+/// Renders the endpoint and key as a QR code of JSON, in the config's field names
+fn pairing_qr(endpoint: &str, key: &str) -> Result<String> {
+    use qrcode::render::unicode::Dense1x2;
+
+    let payload = serde_json::json!({ "api_endpoint": endpoint, "api_key": key }).to_string();
+    let code = qrcode::QrCode::new(payload.as_bytes())
+        .map_err(|err| anyhow::anyhow!("Could not encode the QR code: {err}"))?;
+    // Inverted, as most terminals draw light text on a dark background
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .quiet_zone(true)
+        .build())
+}
 
-    if let Some(n) = name {
+async fn generate_key(
+    client: &ApiClient,
+    name: Option<String>,
+    read_only: bool,
+    scope: Option<String>,
+    expires: Option<String>,
+    qr: bool,
+) -> Result<()> {
+    let scopes = match scope {
+        Some(spec) => parse_scopes(&spec)?,
+        None if read_only => vec![READ_ONLY_SCOPE.to_string()],
+        None => Vec::new(),
+    };
+    let expires_at = match expires {
+        Some(spec) => {
+            let secs = parse_duration_secs(&spec)?;
+            if secs <= 0 {
+                anyhow::bail!("Expiry must be in the future");
+            }
+            Some(chrono::Utc::now().timestamp() + secs)
+        }
+        None => None,
+    };
+    let request = GenerateKeyRequest {
+        name,
+        scopes,
+        expires_at,
+    };
+
+    let response = activity::track(
+        ActivityEntry::new(Source::Cli, "admin generate-key"),
+        client.generate_api_key(&request).await,
+    )?;
+
+    println!("{} Generated new API key", term::symbols().ok.green());
+
+    if let Some(n) = &request.name {
         println!("  {} {}", "Name:".cyan(), n);
     }
 
     println!("  {} {}", "ID:".cyan(), response.id);
+    if !request.scopes.is_empty() {
+        println!("  {} {}", "Scopes:".cyan(), request.scopes.join(", "));
+    }
+    if let Some(expires_at) = request.expires_at {
+        println!("  {} {}", "Expires:".cyan(), format_timestamp(expires_at));
+    }
     println!();
     println!("{} {}", "API Key:".yellow().bold(), response.key.cyan());
     println!();
+    if qr {
+        println!("{}", "Scan to pair a device:".bold());
+        println!(
+            "{}",
+            pairing_qr(&client.config().api_endpoint, &response.key)?
+        );
+    }
     println!(
         "{} Store this key securely - it won't be shown again!",
-        "⚠".yellow()
+        term::symbols().warning.yellow()
     );
     println!(
         "{} API keys are stored in plain text in your config file",
-        "⚠".yellow()
+        term::symbols().warning.yellow()
     );
 
     Ok(())
 }
 
-async fn list_keys() -> Result<()> {
-    let client = ApiClient::new()?;
+async fn list_keys(client: &ApiClient) -> Result<()> {
     let keys = client.list_api_keys().await?;
 
     if keys.is_empty() {
@@ -87,17 +296,15 @@ async fn list_keys() -> Result<()> {
     println!("{}", format!("Found {} API key(s):", keys.len()).bold());
     println!();
 
-    for key in keys {
-        let created_dt = chrono::Utc
-            .timestamp_opt(key.created_at, 0)
-            .latest()
-            .map(|dt| dt.with_timezone(&chrono::Local))
-            .map_or_else(
-                || "Invalid date".to_string(),
-                |dt| dt.format("%Y-%m-%d %H:%M:%S").to_string(),
-            );
+    let now = chrono::Utc::now().timestamp();
+    for api_key in keys {
+        let key = &api_key.info;
+        let created_dt = format_timestamp(key.created_at);
+        let expired = api_key.expires_at.is_some_and(|ts| ts <= now);
 
-        let status = if key.active {
+        let status = if expired {
+            "expired".red()
+        } else if key.active {
             "active".green()
         } else {
             "inactive".red()
@@ -117,29 +324,251 @@ async fn list_keys() -> Result<()> {
         );
 
         if let Some(last_used) = key.last_used {
-            let last_used_dt = chrono::Utc
-                .timestamp_opt(last_used, 0)
-                .latest()
-                .map(|dt| dt.with_timezone(&chrono::Local))
-                .map_or_else(
-                    || "Invalid date".to_string(),
-                    |dt| dt.format("%Y-%m-%d %H:%M:%S").to_string(),
-                );
-            print!(" [Last used: {}]", last_used_dt.dimmed());
+            print!(" [Last used: {}]", format_timestamp(last_used).dimmed());
         }
 
         println!();
         println!("    Created: {}", created_dt.dimmed());
+        if !api_key.scopes.is_empty() {
+            println!("    Scopes:  {}", api_key.scopes.join(", ").dimmed());
+        }
+        if let Some(expires_at) = api_key.expires_at {
+            let label = if expired { "Expired:" } else { "Expires:" };
+            println!("    {label} {}", format_timestamp(expires_at).dimmed());
+        }
     }
 
     Ok(())
 }
 
-async fn revoke_key(id: String) -> Result<()> {
-    let client = ApiClient::new()?;
-    client.revoke_api_key(&id).await?;
+/// Finds the key whose ID starts with `id` (as shown by `list-keys`)
+fn find_key<'a>(keys: &'a [ApiKey], id: &str) -> Result<&'a ApiKey> {
+    let matches: Vec<_> = keys
+        .iter()
+        .filter(|key| key.info.id.starts_with(id))
+        .collect();
+
+    match matches.as_slice() {
+        [key] => Ok(key),
+        [] => anyhow::bail!("No API key found with ID '{id}'"),
+        _ => anyhow::bail!(
+            "ID '{id}' matches {} API keys. Use a longer prefix.",
+            matches.len()
+        ),
+    }
+}
+
+/// Resolves a key ID prefix to the full key ID
+///
+/// # Errors
+///
+/// Returns an error if the keys cannot be listed or the prefix doesn't match exactly one key
+pub async fn resolve_key_id(client: &ApiClient, id: &str) -> Result<String> {
+    let keys = client.list_api_keys().await?;
+    Ok(find_key(&keys, id)?.info.id.clone())
+}
+
+fn print_usage_table(usage: &[KeyUsage]) {
+    println!(
+        "{}",
+        format!(
+            "{:<10} {:<24} {:>10} {:>10}  {}",
+            "ID", "NAME", "LAST 30D", "TOTAL", "LAST USED"
+        )
+        .bold()
+    );
+
+    for key in usage {
+        let name = key.client_name.as_deref().unwrap_or("-");
+        let name = if name.chars().count() > 24 {
+            format!("{}…", name.chars().take(23).collect::<String>())
+        } else {
+            name.to_string()
+        };
+        let last_used = key
+            .last_used
+            .map_or_else(|| "never".to_string(), format_timestamp);
+
+        println!(
+            "{} {:<24} {:>10} {:>10}  {}",
+            format!(
+                "{:<10}",
+                &key.key_id[..ID_DISPLAY_LENGTH.min(key.key_id.len())]
+            )
+            .cyan(),
+            name,
+            key.last_30_days,
+            key.total_requests,
+            last_used.dimmed()
+        );
+    }
+}
+
+fn print_route_counts(usage: &KeyUsage) {
+    if usage.routes.is_empty() {
+        return;
+    }
+
+    // Busiest routes first
+    let mut routes: Vec<_> = usage.routes.iter().collect();
+    routes.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+    println!();
+    println!("{}", "Requests per route:".bold());
+    for (route, count) in routes {
+        println!("  {count:>10}  {route}");
+    }
+}
+
+async fn key_stats(client: &ApiClient, id: Option<String>, json: bool) -> Result<()> {
+    if let Some(id) = id {
+        let full_id = resolve_key_id(client, &id).await?;
+        let usage = client.get_key_usage(&full_id).await?;
+        if json {
+            println!("{}", serde_json::to_string_pretty(&usage)?);
+        } else {
+            print_usage_table(std::slice::from_ref(&usage));
+            print_route_counts(&usage);
+        }
+        return Ok(());
+    }
+
+    let mut usage = client.list_key_usage().await?;
+    // Busiest keys first, so the automation hammering the API stands out
+    usage.sort_by(|a, b| {
+        b.last_30_days
+            .cmp(&a.last_30_days)
+            .then_with(|| b.total_requests.cmp(&a.total_requests))
+    });
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&usage)?);
+    } else if usage.is_empty() {
+        println!("{}", "No API key usage recorded".yellow());
+    } else {
+        print_usage_table(&usage);
+    }
+
+    Ok(())
+}
+
+async fn rename_key(client: &ApiClient, id: String, name: String) -> Result<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        anyhow::bail!("Key name cannot be empty");
+    }
+
+    let full_id = resolve_key_id(client, &id).await?;
+    let key = activity::track(
+        ActivityEntry::new(Source::Cli, format!("admin rename-key {full_id}")).with_title(name),
+        client.rename_api_key(&full_id, name).await,
+    )?;
+
+    println!(
+        "{} Renamed API key {} to '{}'",
+        term::symbols().ok.green(),
+        full_id[..ID_DISPLAY_LENGTH.min(full_id.len())].cyan(),
+        key.info.client_name.bold()
+    );
+
+    Ok(())
+}
+
+/// Picks the keys to revoke from explicit IDs or the `--name` / `--inactive` selectors
+///
+/// Both selectors must match when given together.
+fn select_keys<'a>(
+    keys: &'a [ApiKey],
+    ids: &[String],
+    name: Option<&str>,
+    inactive: bool,
+) -> Result<Vec<&'a ApiKey>> {
+    if !ids.is_empty() {
+        let mut selected: Vec<&ApiKey> = Vec::new();
+        for id in ids {
+            let key = find_key(keys, id)?;
+            if !selected.iter().any(|k| k.info.id == key.info.id) {
+                selected.push(key);
+            }
+        }
+        return Ok(selected);
+    }
+
+    Ok(keys
+        .iter()
+        .filter(|key| name.is_none_or(|pattern| wildcard_match(pattern, &key.info.client_name)))
+        .filter(|key| !inactive || !key.info.active)
+        .collect())
+}
+
+async fn revoke_keys(
+    client: &ApiClient,
+    ids: Vec<String>,
+    name: Option<String>,
+    inactive: bool,
+    yes: bool,
+) -> Result<()> {
+    let keys = client.list_api_keys().await?;
+    let selected = select_keys(&keys, &ids, name.as_deref(), inactive)?;
+
+    if selected.is_empty() {
+        println!("{}", "No API keys match".yellow());
+        return Ok(());
+    }
+
+    // A single key named by ID needs no confirmation; anything broader does
+    let selector_used = name.is_some() || inactive;
+    if !yes && (selector_used || selected.len() > 1) {
+        println!(
+            "{}",
+            format!("{} API key(s) will be revoked:", selected.len()).bold()
+        );
+        for key in &selected {
+            println!(
+                "  {} {}",
+                format!(
+                    "[{}]",
+                    &key.info.id[..ID_DISPLAY_LENGTH.min(key.info.id.len())]
+                )
+                .cyan(),
+                key.info.client_name
+            );
+        }
+        println!();
+        if !confirm("Revoke these keys?")? {
+            println!("{} Nothing revoked", term::symbols().info.cyan());
+            return Ok(());
+        }
+    }
+
+    let mut failed = 0;
+    for key in selected {
+        let id = &key.info.id;
+        let result = activity::track(
+            ActivityEntry::new(Source::Cli, format!("admin revoke-key {id}"))
+                .with_title(&key.info.client_name),
+            client.revoke_api_key(id).await,
+        );
+        match result {
+            Ok(()) => println!(
+                "{} Revoked API key: {}",
+                term::symbols().ok.green(),
+                id.cyan()
+            ),
+            Err(err) => {
+                failed += 1;
+                println!(
+                    "{} Failed to revoke {}: {err}",
+                    term::symbols().warning.yellow(),
+                    id.cyan()
+                );
+            }
+        }
+    }
 
-    println!("{} Revoked API key: {}", "✓".green(), id.cyan());
+    if failed > 0 {
+        anyhow::bail!("{failed} API key(s) could not be revoked");
+    }
 
     Ok(())
 }
@@ -149,78 +578,171 @@ async fn revoke_key(id: String) -> Result<()> {
 /// # Errors
 ///
 /// Returns an error if:
-/// - Configuration cannot be saved to disk
+/// - Configuration cannot be loaded or saved to disk
 /// - Network request to server fails
 /// - Server is already initialized
 /// - Server returns an error response
 /// - API key cannot be saved to configuration
 pub async fn initialize_with_url(url: String) -> Result<()> {
     // Create config with the provided URL first
-    let mut config = Config::load().unwrap_or_default();
+    let mut config = Config::load()?;
     config.set_endpoint(&url);
     config.save()?;
 
-    println!("{} Set API endpoint to: {}", "✓".green(), url.cyan());
+    println!(
+        "{} Set API endpoint to: {}",
+        term::symbols().ok.green(),
+        url.cyan()
+    );
 
     // Now create client with the new config and initialize
     let client = ApiClient::new()?;
-    let admin_key = client.initialize().await?;
+    let admin_key = activity::track(
+        ActivityEntry::new(Source::Cli, "init"),
+        client.initialize().await,
+    )?;
 
     // Save the admin key to config
     config.set_api_key(&admin_key);
     config.save()?;
 
-    println!("{} Server initialized successfully", "✓".green());
+    println!(
+        "{} Server initialized successfully",
+        term::symbols().ok.green()
+    );
     println!(
         "{} First admin key generated and saved to config",
-        "✓".green()
+        term::symbols().ok.green()
     );
     println!();
     println!("{} {}", "Admin Key:".yellow().bold(), admin_key.cyan());
     println!();
     println!(
         "{} Setup complete! You can now use all CLI commands.",
-        "🚀".green()
+        term::glyph("🚀", "*").green()
     );
     println!(
         "{} API key is stored in plain text at: {}",
-        "⚠".yellow(),
+        term::symbols().warning.yellow(),
         Config::config_path()?.display().to_string().dimmed()
     );
 
     Ok(())
 }
 
-async fn reinitialize() -> Result<()> {
-    let client = ApiClient::new()?;
-    let admin_key = client.reinitialize().await?;
+/// Writes all todos to a timestamped JSON file in the data directory's `backups` folder
+async fn backup_todos(client: &ApiClient) -> Result<PathBuf> {
+    let todos = client.list_todos(None, None).await?;
+
+    let dir = Config::data_dir()?.join("backups");
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!(
+        "todos-{}.json",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    fs::write(&path, serde_json::to_string_pretty(&todos)?)?;
+
+    Ok(path)
+}
+
+/// Asks the user to type the server hostname before a destructive operation
+fn confirm_hostname(endpoint: &str) -> Result<bool> {
+    let host = reqwest::Url::parse(endpoint)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| endpoint.to_string());
+
+    crate::prompt::require("type the server hostname (pass --force to skip it)")?;
+    print!(
+        "Type the server hostname ({}) to confirm: ",
+        host.as_str().bold()
+    );
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(answer.trim().eq_ignore_ascii_case(&host))
+}
+
+async fn reinitialize(client: &ApiClient, force: bool) -> Result<()> {
+    let mut config = client.config().clone();
+
+    println!(
+        "{} This deactivates ALL admin keys on {}",
+        term::glyph("🚨", "!!").red(),
+        config.api_endpoint.cyan()
+    );
+
+    match backup_todos(client).await {
+        Ok(path) => println!(
+            "{} Backed up todos to {}",
+            term::symbols().ok.green(),
+            path.display().to_string().dimmed()
+        ),
+        Err(err) => {
+            println!(
+                "{} Could not back up todos: {err}",
+                term::symbols().warning.yellow()
+            );
+            if !force && !confirm("Continue without a backup?")? {
+                anyhow::bail!("Reinitialization cancelled");
+            }
+        }
+    }
+
+    if !force && !confirm_hostname(&config.api_endpoint)? {
+        anyhow::bail!("Hostname did not match, reinitialization cancelled");
+    }
+
+    let admin_key = activity::track(
+        ActivityEntry::new(Source::Cli, "admin reinitialize"),
+        client.reinitialize().await,
+    )?;
 
     // Save the new admin key to config
-    let mut config = Config::load()?;
     config.set_api_key(&admin_key);
     config.save()?;
 
-    println!("{} Server reinitialized successfully", "✓".green());
+    println!(
+        "{} Server reinitialized successfully",
+        term::symbols().ok.green()
+    );
     println!(
         "{} ALL previous admin keys have been deactivated",
-        "⚠".yellow()
+        term::symbols().warning.yellow()
     );
     println!(
         "{} New admin key generated and saved to config",
-        "✓".green()
+        term::symbols().ok.green()
     );
     println!();
     println!("{} {}", "New Admin Key:".yellow().bold(), admin_key.cyan());
     println!();
     println!(
         "{} This emergency reset invalidated ALL previous admin keys!",
-        "🚨".red()
+        term::glyph("🚨", "!!").red()
     );
     println!(
         "{} API key is stored in plain text at: {}",
-        "⚠".yellow(),
+        term::symbols().warning.yellow(),
         Config::config_path()?.display().to_string().dimmed()
     );
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scopes() {
+        assert_eq!(
+            parse_scopes("todos:read, Todos:Write,todos:read").unwrap(),
+            vec!["todos:read".to_string(), "todos:write".to_string()]
+        );
+        assert!(parse_scopes("todos").is_err());
+        assert!(parse_scopes(":read").is_err());
+        assert!(parse_scopes(" , ").is_err());
+    }
+}
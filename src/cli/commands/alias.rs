@@ -0,0 +1,109 @@
+use crate::{
+    activity::{self, ActivityEntry, Source},
+    api::ApiClient,
+    cache,
+    cli::{types::AliasAction, utils::resolve_partial_id},
+    state::{validate_alias, LocalState},
+    term, time_operation, ID_DISPLAY_LENGTH,
+};
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+/// Handles alias actions, listing all aliases when no action is given
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The alias is invalid or unknown
+/// - The todo ID cannot be resolved
+/// - Network request fails
+/// - Local state cannot be loaded or saved
+pub async fn handle(client: &ApiClient, action: Option<AliasAction>) -> Result<()> {
+    match action {
+        None | Some(AliasAction::List) => list(client).await,
+        Some(AliasAction::Set { alias, id }) => set(client, &alias, &id).await,
+        Some(AliasAction::Remove { alias }) => remove(&alias),
+    }
+}
+
+async fn list(client: &ApiClient) -> Result<()> {
+    let state = LocalState::load()?;
+    let aliases = state.aliases();
+    if aliases.is_empty() {
+        println!(
+            "{} No aliases yet. Create one with 'pacli alias set <alias> <id>'",
+            term::symbols().info.cyan()
+        );
+        return Ok(());
+    }
+
+    let todos = time_operation!(cache::list_todos(client, None).await?, "Fetch todos");
+    let width = aliases
+        .iter()
+        .map(|(alias, _)| alias.len())
+        .max()
+        .unwrap_or(0);
+
+    println!("{}", format!("Found {} alias(es):", aliases.len()).bold());
+    println!();
+    for (alias, id) in aliases {
+        let title = todos.iter().find(|todo| todo.id == id).map_or_else(
+            || "(deleted)".red().to_string(),
+            |todo| todo.title.bold().to_string(),
+        );
+        println!(
+            "  {} {} {title}",
+            format!("@{alias:<width$}").blue(),
+            format!("[{}]", &id[..ID_DISPLAY_LENGTH.min(id.len())]).cyan(),
+        );
+    }
+
+    Ok(())
+}
+
+async fn set(client: &ApiClient, alias: &str, id: &str) -> Result<()> {
+    let alias = validate_alias(alias)?;
+    let full_id = resolve_partial_id(id, client)
+        .await
+        .context(format!("Failed to resolve ID '{id}'"))?;
+    let todo = client.get_todo(&full_id).await?;
+
+    let mut state = LocalState::load()?;
+    let replaced = state.alias_of(&full_id).map(str::to_string);
+    let previous = state.set_alias(&full_id, &alias);
+    state.save()?;
+    activity::record(&ActivityEntry::new(Source::Cli, format!("alias {alias}")).with_todo(&todo));
+
+    if let Some(other) = previous {
+        println!(
+            "{} '{}' no longer names {}",
+            term::symbols().warning.yellow(),
+            alias,
+            other[..ID_DISPLAY_LENGTH.min(other.len())].cyan()
+        );
+    }
+    if let Some(old) = replaced.filter(|old| *old != alias) {
+        println!("{} Replaced the alias '{old}'", term::symbols().info.cyan());
+    }
+    println!(
+        "{} '{}' is now {}",
+        term::symbols().ok.green(),
+        todo.title.bold(),
+        format!("@{alias}").blue()
+    );
+
+    Ok(())
+}
+
+fn remove(alias: &str) -> Result<()> {
+    let mut state = LocalState::load()?;
+    let Some(id) = state.remove_alias(alias) else {
+        anyhow::bail!("No alias named '{alias}'. See 'pacli alias list'");
+    };
+    state.save()?;
+    activity::record(&ActivityEntry::new(Source::Cli, format!("unalias {alias}")).with_id(&id));
+
+    println!("{} Removed the alias '{alias}'", term::symbols().ok.green());
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use crate::{
+    activity::{self, ActivityEntry, Source},
+    api::ApiClient,
+    cli::utils::resolve_partial_id,
+    state::LocalState,
+    term,
+};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use colored::Colorize;
+
+/// Archives a todo, hiding it from default listings without deleting it
+///
+/// Archiving is local to this machine; the todo stays on the server
+/// unchanged and can be listed with `pacli list --archived`.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The todo ID cannot be resolved
+/// - Network request fails
+/// - Local state cannot be loaded or saved
+pub async fn archive(client: &ApiClient, id: String) -> Result<()> {
+    let full_id = resolve_partial_id(&id, client)
+        .await
+        .context(format!("Failed to resolve ID '{id}'"))?;
+    let todo = client.get_todo(&full_id).await?;
+
+    let mut state = LocalState::load()?;
+    if !state.archive(&full_id, Utc::now().timestamp()) {
+        println!(
+            "{} '{}' is already archived",
+            term::symbols().info.cyan(),
+            todo.title.bold()
+        );
+        return Ok(());
+    }
+    state.save()?;
+    activity::record(&ActivityEntry::new(Source::Cli, "archive").with_todo(&todo));
+
+    println!(
+        "{} Archived '{}'",
+        term::symbols().ok.green(),
+        todo.title.bold()
+    );
+
+    Ok(())
+}
+
+/// Restores an archived todo to the default listings
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The todo ID cannot be resolved
+/// - Network request fails
+/// - Local state cannot be loaded or saved
+pub async fn unarchive(client: &ApiClient, id: String) -> Result<()> {
+    let full_id = resolve_partial_id(&id, client)
+        .await
+        .context(format!("Failed to resolve ID '{id}'"))?;
+    let todo = client.get_todo(&full_id).await?;
+
+    let mut state = LocalState::load()?;
+    if !state.unarchive(&full_id) {
+        println!(
+            "{} '{}' is not archived",
+            term::symbols().info.cyan(),
+            todo.title.bold()
+        );
+        return Ok(());
+    }
+    state.save()?;
+    activity::record(&ActivityEntry::new(Source::Cli, "unarchive").with_todo(&todo));
+
+    println!(
+        "{} Restored '{}'",
+        term::symbols().ok.green(),
+        todo.title.bold()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use crate::{
+    activity::{self, ActivityEntry, Source},
+    api::{ApiClient, Attachment},
+    cli::utils::resolve_partial_id,
+    term,
+};
+use anyhow::{Context, Result};
+use chrono::{Local, TimeZone, Utc};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+/// Uploads a file as an attachment of a todo
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The file cannot be read
+/// - The todo ID cannot be resolved
+/// - Network request fails
+/// - Server rejects the upload
+pub async fn attach(client: &ApiClient, id: String, file: PathBuf) -> Result<()> {
+    let content =
+        std::fs::read(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let filename = file
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not a file", file.display()))?;
+
+    let full_id = resolve_partial_id(&id, client)
+        .await
+        .context(format!("Failed to resolve ID '{id}'"))?;
+
+    let attachment = activity::track(
+        ActivityEntry::new(Source::Cli, format!("attach {filename}")).with_id(&full_id),
+        client.upload_attachment(&full_id, &filename, content).await,
+    )?;
+
+    println!(
+        "{} Attached {} ({})",
+        term::symbols().ok.green(),
+        attachment.filename.bold(),
+        attachment.display_size()
+    );
+
+    Ok(())
+}
+
+/// Lists the attachments of a todo
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The todo ID cannot be resolved
+/// - Network request fails
+/// - Server returns an error response
+pub async fn list(client: &ApiClient, id: String) -> Result<()> {
+    let full_id = resolve_partial_id(&id, client)
+        .await
+        .context(format!("Failed to resolve ID '{id}'"))?;
+
+    let attachments = client.list_attachments(&full_id).await?;
+    if attachments.is_empty() {
+        println!("{}", "No attachments".yellow());
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("Found {} attachment(s):", attachments.len()).bold()
+    );
+    println!();
+    print_attachments(&attachments);
+
+    Ok(())
+}
+
+/// Prints a numbered attachment list (numbers are used by `pacli download`)
+pub fn print_attachments(attachments: &[Attachment]) {
+    for (i, attachment) in attachments.iter().enumerate() {
+        let uploaded = Utc
+            .timestamp_opt(attachment.created_at, 0)
+            .latest()
+            .map(|dt| dt.with_timezone(&Local))
+            .map_or_else(
+                || "Invalid date".to_string(),
+                |dt| dt.format("%Y-%m-%d %H:%M").to_string(),
+            );
+
+        println!(
+            "  {} {} {} {}",
+            format!("{}.", i + 1).cyan(),
+            attachment.filename.bold(),
+            format!("({})", attachment.display_size()).dimmed(),
+            uploaded.dimmed()
+        );
+    }
+}
+
+/// Downloads attachment number `number` (1-based, as listed by `pacli attachments`)
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The todo ID cannot be resolved
+/// - No attachment with that number exists
+/// - The target file already exists
+/// - Network request fails
+/// - The file cannot be written
+pub async fn download(
+    client: &ApiClient,
+    id: String,
+    number: usize,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let full_id = resolve_partial_id(&id, client)
+        .await
+        .context(format!("Failed to resolve ID '{id}'"))?;
+
+    let attachments = client.list_attachments(&full_id).await?;
+    let Some(attachment) = number
+        .checked_sub(1)
+        .and_then(|index| attachments.get(index))
+    else {
+        anyhow::bail!(
+            "Attachment {number} does not exist (this todo has {} attachment(s))",
+            attachments.len()
+        );
+    };
+
+    // Never let a server-provided name escape the current directory
+    let target = output.unwrap_or_else(|| {
+        Path::new(&attachment.filename)
+            .file_name()
+            .map_or_else(|| PathBuf::from("attachment"), PathBuf::from)
+    });
+    if target.exists() {
+        anyhow::bail!(
+            "{} already exists. Use --output to choose another path.",
+            target.display()
+        );
+    }
+
+    let content = client.download_attachment(&full_id, &attachment.id).await?;
+    std::fs::write(&target, content)
+        .with_context(|| format!("Failed to write {}", target.display()))?;
+
+    println!(
+        "{} Saved {} to {}",
+        term::symbols().ok.green(),
+        attachment.filename.bold(),
+        target.display().to_string().cyan()
+    );
+
+    Ok(())
+}
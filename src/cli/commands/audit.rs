@@ -0,0 +1,144 @@
+use crate::{
+    api::{ApiClient, AuditEvent},
+    cli::{commands::admin::resolve_key_id, utils::parse_since},
+    prompt, ID_DISPLAY_LENGTH,
+};
+use anyhow::Result;
+use chrono::{Local, TimeZone, Utc};
+use colored::Colorize;
+use std::collections::HashSet;
+use std::io::{BufRead, IsTerminal, Write};
+use std::time::Duration;
+
+/// How often `--follow` asks the server for new events
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(5);
+
+fn print_header() {
+    println!(
+        "{}",
+        format!("{:<19}  {:<24}  {:<20}  {}", "TIME", "KEY", "ACTION", "IP").bold()
+    );
+}
+
+fn print_event(event: &AuditEvent) {
+    let when = Utc
+        .timestamp_opt(event.timestamp, 0)
+        .latest()
+        .map(|dt| dt.with_timezone(&Local))
+        .map_or_else(
+            || "Invalid date".to_string(),
+            |dt| dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+        );
+    let key = match (&event.key_id, &event.key_name) {
+        (Some(id), Some(name)) => format!("{} {name}", &id[..ID_DISPLAY_LENGTH.min(id.len())]),
+        (Some(id), None) => id[..ID_DISPLAY_LENGTH.min(id.len())].to_string(),
+        (None, Some(name)) => name.clone(),
+        (None, None) => "-".to_string(),
+    };
+    let key: String = if key.chars().count() > 24 {
+        format!("{}…", key.chars().take(23).collect::<String>())
+    } else {
+        key
+    };
+
+    println!(
+        "{}  {:<24}  {}  {}",
+        when.dimmed(),
+        key,
+        format!("{:<20}", event.action).cyan(),
+        event.ip.as_deref().unwrap_or("-").dimmed()
+    );
+}
+
+/// Waits for the user between pages; returns `false` if they want to stop
+fn next_page_prompt() -> Result<bool> {
+    print!("{}", "-- more (Enter to continue, q to quit) --".dimmed());
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(!answer.trim().eq_ignore_ascii_case("q"))
+}
+
+/// Prints the server's audit log as a table, one page at a time
+///
+/// Output pauses between pages when it goes to a terminal and `--follow` is
+/// not set. With `--follow`, the server is polled for new events until the
+/// process is interrupted.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The `--since` value or key ID is invalid
+/// - Network request fails
+/// - Server returns an error response (e.g. it has no audit log)
+/// - API key lacks admin privileges
+pub async fn audit(
+    client: &ApiClient,
+    since: String,
+    key: Option<String>,
+    action: Option<String>,
+    follow: bool,
+) -> Result<()> {
+    let mut since = parse_since(&since)?;
+    let mut newest = since;
+    let key_id = match key {
+        Some(id) => Some(resolve_key_id(client, &id).await?),
+        None => None,
+    };
+    let action = action.map(|a| a.to_lowercase());
+    let matches = |event: &AuditEvent| {
+        action
+            .as_deref()
+            .is_none_or(|a| event.action.to_lowercase().contains(a))
+    };
+    let interactive = !follow && std::io::stdout().is_terminal() && prompt::can_prompt();
+
+    print_header();
+
+    let mut shown = 0;
+    let mut cursor: Option<String> = None;
+    // Events at the newest timestamp seen, so polling with `since` doesn't repeat them
+    let mut latest_ids: HashSet<String> = HashSet::new();
+    loop {
+        let page = client
+            .audit_events(since, key_id.as_deref(), cursor.as_deref())
+            .await?;
+
+        for event in &page.events {
+            if latest_ids.contains(&event.id) {
+                continue;
+            }
+            if event.timestamp > newest {
+                newest = event.timestamp;
+                latest_ids.clear();
+            }
+            latest_ids.insert(event.id.clone());
+
+            if matches(event) {
+                print_event(event);
+                shown += 1;
+            }
+        }
+
+        cursor = page.next_cursor;
+        if cursor.is_some() {
+            if interactive && !next_page_prompt()? {
+                return Ok(());
+            }
+            continue;
+        }
+
+        if !follow {
+            break;
+        }
+        since = newest;
+        tokio::time::sleep(FOLLOW_INTERVAL).await;
+    }
+
+    if shown == 0 {
+        println!("{}", "No audit events in this period".yellow());
+    }
+
+    Ok(())
+}
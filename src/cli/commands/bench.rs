@@ -0,0 +1,171 @@
+use crate::api::ApiClient;
+use crate::term;
+use anyhow::Result;
+use colored::Colorize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// A warm request this much faster than the first one means the connection
+/// (and TLS session) was reused
+const REUSE_RATIO: f64 = 0.7;
+
+/// Latency statistics of one benchmarked request type
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Stats {
+    first: Duration,
+    min: Duration,
+    p50: Duration,
+    p95: Duration,
+    max: Duration,
+}
+
+impl Stats {
+    /// Computes statistics, or `None` for no samples
+    ///
+    /// Percentiles use the nearest-rank method.
+    fn from_samples(samples: &[Duration]) -> Option<Self> {
+        let first = *samples.first()?;
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let rank = |p: usize| sorted[(p * sorted.len()).div_ceil(100).max(1) - 1];
+
+        Some(Self {
+            first,
+            min: sorted[0],
+            p50: rank(50),
+            p95: rank(95),
+            max: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+fn ms(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+async fn measure<F, Fut, T>(requests: usize, mut call: F) -> Result<Vec<Duration>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut samples = Vec::with_capacity(requests);
+    for _ in 0..requests {
+        let start = Instant::now();
+        call().await?;
+        samples.push(start.elapsed());
+    }
+    Ok(samples)
+}
+
+fn print_row(name: &str, stats: &Stats) {
+    println!(
+        "{:<6} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        name,
+        ms(stats.first),
+        ms(stats.min),
+        ms(stats.p50),
+        ms(stats.p95),
+        ms(stats.max)
+    );
+}
+
+/// Measures API latency with repeated list and get requests
+///
+/// The first request pays for DNS, connecting and the TLS handshake; if the
+/// following ones are clearly faster, the connection is being reused.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - `requests` is zero
+/// - Any request fails
+pub async fn bench(client: &ApiClient, requests: usize) -> Result<()> {
+    if requests == 0 {
+        anyhow::bail!("--requests must be at least 1");
+    }
+
+    let mode = if cfg!(feature = "http-optimized") {
+        "http-optimized"
+    } else {
+        "standard"
+    };
+    println!(
+        "{} {requests} requests per endpoint ({mode} client)",
+        term::symbols().info.cyan()
+    );
+
+    let list = measure(requests, || client.list_todos(None, None)).await?;
+    let todo_id = client
+        .list_todos(None, None)
+        .await?
+        .first()
+        .map(|todo| todo.id.clone());
+    let get = match &todo_id {
+        Some(id) => Some(measure(requests, || client.get_todo(id)).await?),
+        None => None,
+    };
+
+    println!();
+    println!(
+        "{}",
+        format!(
+            "{:<6} {:>10} {:>10} {:>10} {:>10} {:>10}",
+            "", "first", "min", "p50", "p95", "max"
+        )
+        .bold()
+    );
+    let Some(list_stats) = Stats::from_samples(&list) else {
+        return Ok(());
+    };
+    print_row("list", &list_stats);
+    match get.as_deref().and_then(Stats::from_samples) {
+        Some(stats) => print_row("get", &stats),
+        None => println!("{}", "get    skipped (no todos to fetch)".dimmed()),
+    }
+
+    println!();
+    if requests < 2 {
+        println!(
+            "{} Run at least 2 requests to check connection reuse",
+            term::symbols().info.cyan()
+        );
+    } else if list_stats.p50.as_secs_f64() < list_stats.first.as_secs_f64() * REUSE_RATIO {
+        println!(
+            "{} Connection reuse: yes (warm requests {} vs {} for the first)",
+            term::symbols().ok.green(),
+            ms(list_stats.p50),
+            ms(list_stats.first)
+        );
+    } else {
+        println!(
+            "{} Connection reuse: not detected (warm requests {} vs {} for the first). \
+             A proxy or server closing connections may be forcing new handshakes.",
+            term::symbols().warning.yellow(),
+            ms(list_stats.p50),
+            ms(list_stats.first)
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_percentiles() {
+        let samples: Vec<Duration> = [50, 10, 20, 30, 40, 60, 70, 80, 90, 100]
+            .into_iter()
+            .map(Duration::from_millis)
+            .collect();
+        let stats = Stats::from_samples(&samples).unwrap();
+
+        assert_eq!(stats.first, Duration::from_millis(50));
+        assert_eq!(stats.min, Duration::from_millis(10));
+        assert_eq!(stats.p50, Duration::from_millis(50));
+        assert_eq!(stats.p95, Duration::from_millis(100));
+        assert_eq!(stats.max, Duration::from_millis(100));
+        assert!(Stats::from_samples(&[]).is_none());
+    }
+}
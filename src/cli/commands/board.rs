@@ -0,0 +1,193 @@
+use crate::{
+    api::{ApiClient, Todo},
+    state::LocalState,
+    term, time_operation,
+};
+use anyhow::Result;
+use colored::Colorize;
+use pali_types::priority;
+
+/// Width used when neither `--width` is given nor the terminal width known
+const DEFAULT_WIDTH: usize = 100;
+/// Narrowest column that still shows something useful
+const MIN_COLUMN_WIDTH: usize = 8;
+
+struct Column<'a> {
+    name: &'a str,
+    todos: Vec<&'a Todo>,
+}
+
+/// Pads or truncates `text` to exactly `width` characters
+fn fit(text: &str, width: usize) -> String {
+    let len = text.chars().count();
+    if len <= width {
+        format!("{text}{}", " ".repeat(width - len))
+    } else if width == 0 {
+        String::new()
+    } else {
+        let truncated: String = text.chars().take(width - 1).collect();
+        format!("{truncated}…")
+    }
+}
+
+fn border(left: char, mid: char, right: char, column_width: usize, columns: usize) -> String {
+    let segment = "─".repeat(column_width + 2);
+    let mut line = String::from(left);
+    line.push_str(&vec![segment; columns].join(&mid.to_string()));
+    line.push(right);
+    line
+}
+
+fn row(cells: &[String]) -> String {
+    format!("│ {} │", cells.join(" │ "))
+}
+
+/// Renders the columns side by side, using at most `width` characters per line
+fn render(columns: &[Column], width: usize) -> Result<Vec<String>> {
+    let count = columns.len();
+    // Each column takes its content plus one space of padding on each side and a border
+    let column_width = width.saturating_sub(1) / count;
+    let column_width = column_width.saturating_sub(3);
+    if column_width < MIN_COLUMN_WIDTH {
+        anyhow::bail!(
+            "Width {width} is too narrow for the board (need at least {})",
+            (MIN_COLUMN_WIDTH + 3) * count + 1
+        );
+    }
+
+    let mut lines = vec![border('┌', '┬', '┐', column_width, count)];
+    let headers: Vec<_> = columns
+        .iter()
+        .map(|c| fit(&format!("{} ({})", c.name, c.todos.len()), column_width))
+        .collect();
+    lines.push(row(&headers));
+    lines.push(border('├', '┼', '┤', column_width, count));
+
+    let height = columns.iter().map(|c| c.todos.len()).max().unwrap_or(0);
+    for i in 0..height.max(1) {
+        let cells: Vec<_> = columns
+            .iter()
+            .map(|c| {
+                c.todos.get(i).map_or_else(
+                    || " ".repeat(column_width),
+                    |todo| {
+                        let mark = if todo.completed { '✓' } else { '○' };
+                        fit(&format!("{mark} {}", todo.title), column_width)
+                    },
+                )
+            })
+            .collect();
+        lines.push(row(&cells));
+    }
+
+    lines.push(border('└', '┴', '┘', column_width, count));
+    Ok(lines)
+}
+
+/// Prints a kanban-style overview: pending todos by priority, then completed todos
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The width is too narrow to fit all columns
+/// - Network request fails
+/// - Server returns an error response
+/// - API key is missing or invalid
+pub async fn board(client: &ApiClient, width: Option<usize>) -> Result<()> {
+    let width = width.unwrap_or_else(|| term::width_or(DEFAULT_WIDTH));
+
+    let labels = client.config().priority_labels.clone();
+    let mut todos = time_operation!(
+        client.list_todos(None, None).await?,
+        "Fetch todos from server"
+    );
+    let state = LocalState::load().unwrap_or_default();
+    todos.retain(|t| !state.is_archived(&t.id));
+
+    let pending_with = |p: i32| -> Vec<&Todo> {
+        todos
+            .iter()
+            .filter(|t| !t.completed && t.priority == p)
+            .collect()
+    };
+    let columns = [
+        Column {
+            name: labels.label(priority::HIGH),
+            todos: pending_with(priority::HIGH),
+        },
+        Column {
+            name: labels.label(priority::MEDIUM),
+            todos: todos
+                .iter()
+                .filter(|t| {
+                    !t.completed && t.priority != priority::HIGH && t.priority != priority::LOW
+                })
+                .collect(),
+        },
+        Column {
+            name: labels.label(priority::LOW),
+            todos: pending_with(priority::LOW),
+        },
+        Column {
+            name: "Completed",
+            todos: todos.iter().filter(|t| t.completed).collect(),
+        },
+    ];
+
+    for (i, line) in render(&columns, width)?.into_iter().enumerate() {
+        // Only the header row gets emphasis; colouring cells would break alignment math
+        if i == 1 {
+            println!("{}", line.bold());
+        } else {
+            println!("{line}");
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit() {
+        assert_eq!(fit("abc", 5), "abc  ");
+        assert_eq!(fit("abcdef", 4), "abc…");
+        assert_eq!(fit("abcd", 4), "abcd");
+    }
+
+    #[test]
+    fn test_render_board() {
+        let todo = Todo {
+            id: "a".to_string(),
+            title: "Write the quarterly report".to_string(),
+            description: None,
+            completed: false,
+            priority: priority::HIGH,
+            due_date: None,
+            created_at: 0,
+            updated_at: 0,
+        };
+        let columns = [
+            Column {
+                name: "High",
+                todos: vec![&todo],
+            },
+            Column {
+                name: "Done",
+                todos: vec![],
+            },
+        ];
+
+        let lines = render(&columns, 31).unwrap();
+        assert_eq!(lines.len(), 5);
+        for line in &lines {
+            assert_eq!(line.chars().count(), 31);
+        }
+        assert_eq!(lines[1], "│ High (1)     │ Done (0)     │");
+        assert_eq!(lines[3], "│ ○ Write the… │              │");
+
+        assert!(render(&columns, 20).is_err());
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{
+    activity::{self, ActivityEntry, Source},
+    api::{ApiClient, CreateTodoRequest, Todo, UpdateTodoRequest},
+    cache,
+    caldav::{self, CalDavClient, Mapping, Prefer, RemoteTask, Step, SyncFields, SyncState},
+    cli::types::CaldavAction,
+    state::LocalState,
+    term, ID_DISPLAY_LENGTH,
+};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::collections::{HashMap, HashSet};
+
+/// Handles CalDAV actions
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - No CalDAV task list is configured
+/// - Either server cannot be reached or returns an error
+/// - The sync state cannot be read or saved
+pub async fn handle(client: &ApiClient, action: CaldavAction) -> Result<()> {
+    match action {
+        CaldavAction::Sync { dry_run, prefer } => {
+            sync(client, dry_run, prefer.unwrap_or(Prefer::Newer)).await
+        }
+    }
+}
+
+async fn sync(client: &ApiClient, dry_run: bool, prefer: Prefer) -> Result<()> {
+    let config = client.config();
+    let Some(settings) = config.caldav.clone() else {
+        anyhow::bail!(
+            "No CalDAV task list configured. Add a [caldav] section with url and username to the config file"
+        );
+    };
+    let dav = CalDavClient::new(&settings, config)?;
+
+    let todos = cache::sync(client).await?;
+    let remote = dav.list_tasks().await?;
+    let mut state = SyncState::load(dav.url())?;
+    let local_state = LocalState::load()?;
+    let archived: HashSet<String> = todos
+        .iter()
+        .filter(|todo| local_state.is_archived(&todo.id))
+        .map(|todo| todo.id.clone())
+        .collect();
+
+    let steps = caldav::plan(&todos, &remote, &state, &archived, prefer);
+    let local: HashMap<&str, &Todo> = todos.iter().map(|t| (t.id.as_str(), t)).collect();
+    let by_href: HashMap<&str, &RemoteTask> = remote
+        .iter()
+        .map(|task| (task.href.as_str(), task))
+        .collect();
+
+    if dry_run {
+        if steps.is_empty() {
+            println!("{} Already in sync", term::symbols().ok.green());
+        } else {
+            println!(
+                "{}",
+                format!("Would make {} change(s):", steps.len()).bold()
+            );
+            for step in &steps {
+                print_step(step, &describe(step, &local, &by_href, &state), None);
+            }
+        }
+        return Ok(());
+    }
+
+    // Mappings whose todo and task are both gone are of no use any more
+    state.todos.retain(|id, mapping| {
+        local.contains_key(id.as_str()) || by_href.contains_key(mapping.href.as_str())
+    });
+
+    let mut failed = 0;
+    for step in &steps {
+        // Described first, as applying the step may remove the mapping or todo
+        let label = describe(step, &local, &by_href, &state);
+        let result = apply(client, &dav, step, &local, &by_href, &mut state).await;
+        if result.is_err() {
+            failed += 1;
+        }
+        print_step(step, &label, result.err());
+    }
+    state.save()?;
+
+    let done = steps.len() - failed;
+    if failed == 0 {
+        println!(
+            "{} Synced with {} ({done} change(s))",
+            term::symbols().ok.green(),
+            dav.url().cyan()
+        );
+        Ok(())
+    } else {
+        anyhow::bail!(
+            "{failed} of {} change(s) failed; run the sync again to retry",
+            steps.len()
+        )
+    }
+}
+
+/// Makes one change and records it in the sync state
+async fn apply(
+    client: &ApiClient,
+    dav: &CalDavClient,
+    step: &Step,
+    local: &HashMap<&str, &Todo>,
+    remote: &HashMap<&str, &RemoteTask>,
+    state: &mut SyncState,
+) -> Result<()> {
+    match step {
+        Step::PushNew { todo_id } => {
+            let todo = local[todo_id.as_str()];
+            let fields = SyncFields::from_todo(todo);
+            let uid = caldav::task_uid(todo_id);
+            let href = caldav::new_task_href(&uid);
+            dav.put_task(&href, &uid, &fields, None).await?;
+            state.todos.insert(
+                todo_id.clone(),
+                Mapping {
+                    href,
+                    uid,
+                    synced: fields,
+                },
+            );
+        }
+        Step::Push { todo_id, .. } => {
+            let fields = SyncFields::from_todo(local[todo_id.as_str()]);
+            let mapping = mapping(state, todo_id)?;
+            let task = remote[mapping.href.as_str()];
+            dav.put_task(&task.href, &task.uid, &fields, task.etag.as_deref())
+                .await?;
+            mapping.synced = fields;
+        }
+        Step::Pull { todo_id, .. } => {
+            let task = remote[mapping(state, todo_id)?.href.as_str()];
+            let request = UpdateTodoRequest {
+                title: Some(task.fields.title.clone()),
+                description: task.fields.description.clone(),
+                completed: Some(task.fields.completed),
+                priority: Some(task.fields.priority),
+                due_date: task.fields.due_date,
+            };
+            let todo = activity::track_todo(
+                ActivityEntry::new(Source::Cli, "caldav-sync").with_id(todo_id),
+                client.update_todo(todo_id, request).await,
+            )?;
+            let mapping = settle(dav, &todo, task).await?;
+            state.todos.insert(todo_id.clone(), mapping);
+        }
+        Step::PullNew { href } => {
+            let task = remote[href.as_str()];
+            let request = CreateTodoRequest {
+                title: task.fields.title.clone(),
+                description: task.fields.description.clone(),
+                priority: Some(task.fields.priority),
+                due_date: task.fields.due_date,
+            };
+            let entry = ActivityEntry::new(Source::Cli, "caldav-sync").with_title(&request.title);
+            let mut todo = activity::track_todo(entry, client.create_todo(request).await)?;
+            if task.fields.completed {
+                todo = client.toggle_todo(&todo.id).await?;
+            }
+            // A task recreated after its todo was deleted replaces the old mapping
+            state.todos.retain(|_, mapping| mapping.href != *href);
+            let mapping = settle(dav, &todo, task).await?;
+            state.todos.insert(todo.id, mapping);
+        }
+        Step::DeleteRemote { todo_id } => {
+            let href = mapping(state, todo_id)?.href.clone();
+            let etag = remote
+                .get(href.as_str())
+                .and_then(|task| task.etag.as_deref());
+            dav.delete_task(&href, etag).await?;
+            state.todos.remove(todo_id);
+        }
+        Step::DeleteLocal { todo_id } => {
+            activity::track(
+                ActivityEntry::new(Source::Cli, "caldav-sync").with_id(todo_id),
+                client.delete_todo(todo_id).await,
+            )?;
+            state.todos.remove(todo_id);
+        }
+    }
+    Ok(())
+}
+
+fn mapping<'a>(state: &'a mut SyncState, todo_id: &str) -> Result<&'a mut Mapping> {
+    state
+        .todos
+        .get_mut(todo_id)
+        .with_context(|| format!("No CalDAV mapping for todo {todo_id}"))
+}
+
+/// Maps a todo to the task it was pulled from
+///
+/// The Pali API can't clear a description or due date, so when the todo
+/// still differs from the task it is pushed back, leaving both sides equal.
+async fn settle(dav: &CalDavClient, todo: &Todo, task: &RemoteTask) -> Result<Mapping> {
+    let fields = SyncFields::from_todo(todo);
+    if fields != task.fields {
+        log::info!("Pushing '{}' back to the CalDAV server", todo.title);
+        dav.put_task(&task.href, &task.uid, &fields, task.etag.as_deref())
+            .await?;
+    }
+    Ok(Mapping {
+        href: task.href.clone(),
+        uid: task.uid.clone(),
+        synced: fields,
+    })
+}
+
+/// The short ID (or "new") and title a step is about
+fn describe(
+    step: &Step,
+    local: &HashMap<&str, &Todo>,
+    remote: &HashMap<&str, &RemoteTask>,
+    state: &SyncState,
+) -> (String, String) {
+    let (id, title) = match step {
+        Step::PullNew { href } => (
+            "new".to_string(),
+            remote
+                .get(href.as_str())
+                .map_or(href.as_str(), |task| task.fields.title.as_str()),
+        ),
+        Step::PushNew { todo_id }
+        | Step::Push { todo_id, .. }
+        | Step::Pull { todo_id, .. }
+        | Step::DeleteRemote { todo_id }
+        | Step::DeleteLocal { todo_id } => {
+            let title = local.get(todo_id.as_str()).map_or_else(
+                || {
+                    state
+                        .todos
+                        .get(todo_id)
+                        .map_or("", |mapping| mapping.synced.title.as_str())
+                },
+                |todo| todo.title.as_str(),
+            );
+            (
+                todo_id[..ID_DISPLAY_LENGTH.min(todo_id.len())].to_string(),
+                title,
+            )
+        }
+    };
+    (id, title.to_string())
+}
+
+fn print_step(step: &Step, (id, title): &(String, String), error: Option<anyhow::Error>) {
+    let action = step.to_string();
+    let action = match step {
+        Step::DeleteRemote { .. } | Step::DeleteLocal { .. } => action.red(),
+        Step::Push { conflict: true, .. } | Step::Pull { conflict: true, .. } => action.yellow(),
+        _ => action.cyan(),
+    };
+    match error {
+        None => println!("  {} {} {action}", id.dimmed(), title.bold()),
+        Some(err) => println!(
+            "  {} {} {} {action}: {err:#}",
+            term::symbols().warning.yellow(),
+            id.dimmed(),
+            title.bold()
+        ),
+    }
+}
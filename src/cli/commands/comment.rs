@@ -0,0 +1,56 @@
+use crate::{
+    activity::{self, ActivityEntry, Source},
+    api::{ApiClient, Comment},
+    cli::utils::resolve_partial_id,
+    term,
+};
+use anyhow::{Context, Result};
+use chrono::{Local, TimeZone, Utc};
+use colored::Colorize;
+
+/// Adds a comment (progress note) to a todo
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The comment text is empty
+/// - The todo ID cannot be resolved
+/// - Network request fails
+/// - Server returns an error response
+pub async fn comment(client: &ApiClient, id: String, text: String) -> Result<()> {
+    if text.trim().is_empty() {
+        anyhow::bail!("Comment text cannot be empty");
+    }
+
+    let full_id = resolve_partial_id(&id, client)
+        .await
+        .context(format!("Failed to resolve ID '{id}'"))?;
+
+    activity::track(
+        ActivityEntry::new(Source::Cli, "comment").with_id(&full_id),
+        client.add_comment(&full_id, text.trim()).await,
+    )?;
+
+    println!("{} Comment added", term::symbols().ok.green());
+
+    Ok(())
+}
+
+/// Prints comments with their timestamps, oldest first
+pub fn print_comments(comments: &[Comment]) {
+    for comment in comments {
+        let when = Utc
+            .timestamp_opt(comment.created_at, 0)
+            .latest()
+            .map(|dt| dt.with_timezone(&Local))
+            .map_or_else(
+                || "Invalid date".to_string(),
+                |dt| dt.format("%Y-%m-%d %H:%M").to_string(),
+            );
+
+        println!("    {}", when.dimmed());
+        for line in comment.text.lines() {
+            println!("      {line}");
+        }
+    }
+}
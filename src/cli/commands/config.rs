@@ -1,9 +1,16 @@
-use crate::cli::types::ConfigAction;
-use crate::config::Config;
-use anyhow::Result;
+use crate::cli::types::{Cli, ConfigAction, PriorityLevel, UnsetSetting};
+use crate::cli::utils::split_words;
+use crate::config::{key_fingerprint, Config, PriorityLabels};
+use crate::escalate::EscalationRule;
+use crate::highlight::ColorRule;
+use crate::secret::{KeySource, PASSPHRASE_ENV};
+use crate::term;
+use anyhow::{Context, Result};
+use clap::CommandFactory;
 use colored::Colorize;
+use pali_types::priority;
 
-/// Handles configuration actions (set endpoint, set key, show config)
+/// Handles configuration actions (set endpoint, set key, color rules, show config)
 ///
 /// # Errors
 ///
@@ -11,21 +18,208 @@ use colored::Colorize;
 /// - Configuration cannot be loaded or saved
 /// - File I/O operations fail
 /// - Configuration format is invalid
+/// - A color or escalation rule cannot be parsed
+/// - A command alias would shadow a built-in command
 #[allow(clippy::unused_async)] // Function is async to match CLI command pattern
 pub async fn handle(action: ConfigAction) -> Result<()> {
     match action {
         ConfigAction::Endpoint { url } => set_endpoint(&url),
         ConfigAction::Key { key } => set_key(key),
-        ConfigAction::Show => show_config(),
+        ConfigAction::Show { reveal } => show_config(reveal),
+        ConfigAction::Unset { setting } => unset(setting),
+        ConfigAction::Migrate => migrate(),
+        ConfigAction::Encrypt { machine } => encrypt(machine),
+        ConfigAction::Decrypt => decrypt(),
+        ConfigAction::AddColorRule { rule } => add_color_rule(rule),
+        ConfigAction::ClearColorRules => clear_color_rules(),
+        ConfigAction::AddEscalationRule { rule } => add_escalation_rule(rule),
+        ConfigAction::ClearEscalationRules => clear_escalation_rules(),
+        ConfigAction::PriorityLabel { level, label } => set_priority_label(level, label),
+        ConfigAction::PriorityAlias { alias, level } => set_priority_alias(alias, level),
+        ConfigAction::DefaultCommand { command } => set_default_command(&command),
+        ConfigAction::CommandAlias { name, command } => set_command_alias(&name, &command),
+        ConfigAction::RemoveCommandAlias { name } => remove_command_alias(&name),
     }
 }
 
+// Levels are given by built-in name only, so labels can always be reassigned
+fn set_priority_label(level: PriorityLevel, label: String) -> Result<()> {
+    let level = level.level();
+    let label = label.trim().to_string();
+    if label.is_empty() {
+        anyhow::bail!("Priority label cannot be empty");
+    }
+
+    let mut config = Config::load()?;
+    let labels = &mut config.priority_labels;
+    match level {
+        l if l == priority::HIGH => labels.high.clone_from(&label),
+        l if l == priority::LOW => labels.low.clone_from(&label),
+        _ => labels.medium.clone_from(&label),
+    }
+    config.save()?;
+
+    println!(
+        "{} Priority {level} is now shown as {}",
+        term::symbols().ok.green(),
+        label.cyan()
+    );
+    Ok(())
+}
+
+fn set_priority_alias(alias: String, level: PriorityLevel) -> Result<()> {
+    let level = level.level();
+    let alias = alias.trim().to_lowercase();
+    if alias.is_empty() {
+        anyhow::bail!("Priority alias cannot be empty");
+    }
+
+    let mut config = Config::load()?;
+    config.priority_labels.aliases.insert(alias.clone(), level);
+    config.save()?;
+
+    println!(
+        "{} '{}' now means priority {level} ({})",
+        term::symbols().ok.green(),
+        alias.cyan(),
+        config.priority_labels.label(level)
+    );
+    Ok(())
+}
+
+fn set_default_command(command: &str) -> Result<()> {
+    let words = split_words(command).context("Invalid default command")?;
+    let Some(first) = words.first() else {
+        anyhow::bail!("The default command cannot be empty");
+    };
+
+    let mut config = Config::load()?;
+    // Catch typos now rather than on every bare `pacli`
+    if Cli::command().find_subcommand(first).is_none() && !config.aliases.contains_key(first) {
+        anyhow::bail!("'{first}' is neither a pacli command nor a command alias");
+    }
+    config.default_command = Some(command.trim().to_string());
+    config.save()?;
+
+    println!(
+        "{} A bare 'pacli' now runs 'pacli {}'",
+        term::symbols().ok.green(),
+        command.trim().cyan()
+    );
+    Ok(())
+}
+
+fn set_command_alias(name: &str, command: &str) -> Result<()> {
+    let name = name.trim();
+    if name.is_empty() || name.starts_with('-') || name.contains(char::is_whitespace) {
+        anyhow::bail!("'{name}' cannot be a command name");
+    }
+    // Built-in commands always win, so such an alias would never run
+    if Cli::command().find_subcommand(name).is_some() {
+        anyhow::bail!("'{name}' is a built-in command");
+    }
+    let words = split_words(command).with_context(|| format!("Invalid command for '{name}'"))?;
+    if words.is_empty() {
+        anyhow::bail!("The command for '{name}' cannot be empty");
+    }
+
+    let mut config = Config::load()?;
+    config
+        .aliases
+        .insert(name.to_string(), command.trim().to_string());
+    config.save()?;
+
+    println!(
+        "{} 'pacli {}' now runs 'pacli {}'",
+        term::symbols().ok.green(),
+        name.cyan(),
+        command.trim()
+    );
+    Ok(())
+}
+
+fn remove_command_alias(name: &str) -> Result<()> {
+    let mut config = Config::load()?;
+    if config.aliases.remove(name).is_none() {
+        anyhow::bail!("No command alias named '{name}'");
+    }
+    config.save()?;
+
+    println!(
+        "{} Removed the command alias '{}'",
+        term::symbols().ok.green(),
+        name.cyan()
+    );
+    Ok(())
+}
+
+fn add_color_rule(rule: String) -> Result<()> {
+    // Validate before saving so a typo doesn't silently do nothing
+    rule.parse::<ColorRule>()?;
+
+    let mut config = Config::load()?;
+    config.color_rules.push(rule.trim().to_string());
+    config.save()?;
+
+    println!(
+        "{} Added color rule: {}",
+        term::symbols().ok.green(),
+        rule.trim().cyan()
+    );
+    Ok(())
+}
+
+fn clear_color_rules() -> Result<()> {
+    let mut config = Config::load()?;
+    let removed = config.color_rules.len();
+    config.color_rules.clear();
+    config.save()?;
+
+    println!(
+        "{} Removed {removed} color rule(s)",
+        term::symbols().ok.green()
+    );
+    Ok(())
+}
+
+fn add_escalation_rule(rule: String) -> Result<()> {
+    rule.parse::<EscalationRule>()?;
+
+    let mut config = Config::load()?;
+    config.escalation_rules.push(rule.trim().to_string());
+    config.save()?;
+
+    println!(
+        "{} Added escalation rule: {}",
+        term::symbols().ok.green(),
+        rule.trim().cyan()
+    );
+    Ok(())
+}
+
+fn clear_escalation_rules() -> Result<()> {
+    let mut config = Config::load()?;
+    let removed = config.escalation_rules.len();
+    config.escalation_rules.clear();
+    config.save()?;
+
+    println!(
+        "{} Removed {removed} escalation rule(s)",
+        term::symbols().ok.green()
+    );
+    Ok(())
+}
+
 fn set_endpoint(url: &str) -> Result<()> {
     let mut config = Config::load()?;
     config.set_endpoint(url);
     config.save()?;
 
-    println!("{} API endpoint set to: {}", "✓".green(), url.cyan());
+    println!(
+        "{} API endpoint set to: {}",
+        term::symbols().ok.green(),
+        url.cyan()
+    );
     Ok(())
 }
 
@@ -34,16 +228,117 @@ fn set_key(key: String) -> Result<()> {
     config.set_api_key(key);
     config.save()?;
 
-    println!("{} API key configured successfully", "✓".green());
     println!(
-        "{} API key is stored in plain text at: {}",
-        "⚠".yellow(),
-        Config::config_path()?.display().to_string().dimmed()
+        "{} API key configured successfully",
+        term::symbols().ok.green()
     );
+    if !config.is_encrypted() {
+        println!(
+            "{} API key is stored in plain text at: {}",
+            term::symbols().warning.yellow(),
+            Config::config_path()?.display().to_string().dimmed()
+        );
+    }
     Ok(())
 }
 
-fn show_config() -> Result<()> {
+fn migrate() -> Result<()> {
+    let path = Config::migrate_to_toml()?;
+
+    println!(
+        "{} Configuration converted to {}",
+        term::symbols().ok.green(),
+        path.display().to_string().cyan()
+    );
+    println!(
+        "{} The old file was kept as config.json.bak; available settings are listed as comments",
+        term::symbols().info.cyan()
+    );
+    Ok(())
+}
+
+fn encrypt(machine: bool) -> Result<()> {
+    let mut config = Config::load()?;
+    if config.is_encrypted() {
+        println!(
+            "{} API key is already encrypted",
+            term::symbols().info.cyan()
+        );
+        return Ok(());
+    }
+
+    let source = if machine {
+        KeySource::Machine
+    } else {
+        KeySource::Passphrase
+    };
+    config.encrypt_api_key(source)?;
+    config.save()?;
+
+    println!("{} API key encrypted", term::symbols().ok.green());
+    if source == KeySource::Passphrase {
+        println!(
+            "{} You will be asked for the passphrase when it is needed (or set {})",
+            term::symbols().info.cyan(),
+            PASSPHRASE_ENV.cyan()
+        );
+    }
+    Ok(())
+}
+
+fn decrypt() -> Result<()> {
+    let mut config = Config::load()?;
+    if !config.is_encrypted() {
+        println!("{} API key is not encrypted", term::symbols().info.cyan());
+        return Ok(());
+    }
+
+    config.decrypt_api_key();
+    config.save()?;
+
+    println!(
+        "{} API key is stored in plain text again",
+        term::symbols().ok.green()
+    );
+    Ok(())
+}
+
+fn unset(setting: UnsetSetting) -> Result<()> {
+    let mut config = Config::load()?;
+    match setting {
+        UnsetSetting::Key => {
+            if config.api_key.take().is_none() {
+                println!("{} No API key is set", term::symbols().info.cyan());
+                return Ok(());
+            }
+            config.save()?;
+            println!("{} API key removed from config", term::symbols().ok.green());
+        }
+        UnsetSetting::Endpoint => {
+            config.api_endpoint = Config::default().api_endpoint;
+            config.save()?;
+            println!(
+                "{} API endpoint reset to: {}",
+                term::symbols().ok.green(),
+                config.api_endpoint.cyan()
+            );
+        }
+        UnsetSetting::DefaultCommand => {
+            if config.default_command.take().is_none() {
+                println!("{} No default command is set", term::symbols().info.cyan());
+                return Ok(());
+            }
+            config.save()?;
+            println!(
+                "{} A bare 'pacli' shows the help again",
+                term::symbols().ok.green()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn show_config(reveal: bool) -> Result<()> {
     let config = Config::load()?;
 
     println!("{}", "Current Configuration:".bold());
@@ -51,22 +346,92 @@ fn show_config() -> Result<()> {
     println!(
         "  {} {}",
         "API Key:".cyan(),
-        if config.api_key.is_some() {
-            "[configured]".green().to_string()
-        } else {
-            "[not set]".yellow().to_string()
+        match &config.api_key {
+            Some(key) if reveal => key.clone(),
+            Some(key) => key_fingerprint(key).green().to_string(),
+            None => "[not set]".yellow().to_string(),
         }
     );
 
+    if let Some(oauth) = &config.oauth {
+        println!(
+            "  {} OAuth ({}, client {})",
+            "Auth:".cyan(),
+            oauth.token_url,
+            oauth.client_id
+        );
+    }
+
     if let Ok(path) = Config::config_path() {
         println!("  {} {}", "Config file:".cyan(), path.display());
     }
 
-    if config.api_key.is_some() {
+    if let Some(timeout) = config.timeout {
+        println!("  {} {timeout}s", "Timeout:".cyan());
+    }
+    if let Some(timeout) = config.connect_timeout {
+        println!("  {} {timeout}s", "Connect timeout:".cyan());
+    }
+    if let Some(proxy) = &config.proxy {
+        println!("  {} {proxy}", "Proxy:".cyan());
+    }
+    if let Some(path) = &config.ca_cert_path {
+        println!("  {} {}", "CA certificate:".cyan(), path.display());
+    }
+    if config.insecure_skip_verify {
+        println!(
+            "  {} {}",
+            "TLS verification:".cyan(),
+            "DISABLED (insecure_skip_verify)".red().bold()
+        );
+    }
+
+    if config.priority_labels != PriorityLabels::default() {
+        let labels = &config.priority_labels;
+        println!(
+            "  {} {} / {} / {}",
+            "Priority labels:".cyan(),
+            labels.low,
+            labels.medium,
+            labels.high
+        );
+        for (alias, level) in &labels.aliases {
+            println!(
+                "    {alias} {} {}",
+                term::symbols().arrow,
+                labels.label(*level)
+            );
+        }
+    }
+
+    if let Some(command) = &config.default_command {
+        println!("  {} {command}", "Default command:".cyan());
+    }
+    if !config.aliases.is_empty() {
+        println!("  {}", "Command aliases:".cyan());
+        for (name, command) in &config.aliases {
+            println!("    {name} {} {command}", term::symbols().arrow);
+        }
+    }
+
+    if !config.color_rules.is_empty() {
+        println!("  {}", "Color rules:".cyan());
+        for rule in &config.color_rules {
+            let valid = rule.parse::<ColorRule>().map_or_else(
+                |err| format!(" {}", format!("(invalid: {err})").red()),
+                |_| String::new(),
+            );
+            println!("    {rule}{valid}");
+        }
+    }
+
+    if config.is_encrypted() {
+        println!("  {} {}", "Encryption:".cyan(), "on".green());
+    } else if config.api_key.is_some() {
         println!();
         println!(
-            "{} API key is stored in plain text in the config file",
-            "⚠".yellow()
+            "{} API key is stored in plain text in the config file (see 'pacli config encrypt')",
+            term::symbols().warning.yellow()
         );
     }
 
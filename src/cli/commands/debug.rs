@@ -0,0 +1,90 @@
+use crate::{cli::types::DebugAction, config::Config, metrics::Metrics, term};
+use anyhow::Result;
+use chrono::{Local, TimeZone};
+use colored::Colorize;
+
+/// Handles debugging actions
+///
+/// # Errors
+///
+/// Returns an error if the metrics file cannot be removed
+pub fn handle(action: DebugAction) -> Result<()> {
+    match action {
+        DebugAction::Metrics { json, reset } => metrics(json, reset),
+    }
+}
+
+fn metrics(json: bool, reset: bool) -> Result<()> {
+    let metrics = Metrics::load();
+
+    if reset {
+        Metrics::reset()?;
+        println!("{} Metrics cleared", term::symbols().ok.green());
+        return Ok(());
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&metrics)?);
+        return Ok(());
+    }
+
+    if metrics.endpoints.is_empty() {
+        if Config::load().is_ok_and(|config| config.metrics) {
+            println!("{} No API calls recorded yet", term::symbols().info.cyan());
+        } else {
+            println!(
+                "{} Metrics are off. Set {} in the config to start counting API calls",
+                term::symbols().info.cyan(),
+                "metrics = true".bold()
+            );
+        }
+        return Ok(());
+    }
+
+    let since = Local
+        .timestamp_opt(metrics.since, 0)
+        .single()
+        .map_or_else(String::new, |since| {
+            since.format(" since %Y-%m-%d %H:%M").to_string()
+        });
+    println!("{}", format!("API calls{since}").bold());
+    let width = metrics
+        .endpoints
+        .keys()
+        .map(|endpoint| endpoint.chars().count())
+        .max()
+        .unwrap_or(0);
+    println!(
+        "  {:<width$}  {:>7} {:>6} {:>8} {:>8} {:>8} {:>8}",
+        "endpoint".dimmed(),
+        "calls".dimmed(),
+        "errors".dimmed(),
+        "avg".dimmed(),
+        "p50".dimmed(),
+        "p95".dimmed(),
+        "max".dimmed()
+    );
+
+    let ms = |value: Option<u64>| value.map_or_else(|| "-".to_string(), |ms| format!("{ms}ms"));
+    for (endpoint, stats) in &metrics.endpoints {
+        let errors = stats.errors.to_string();
+        println!(
+            "  {endpoint:<width$}  {:>7} {:>6} {:>8} {:>8} {:>8} {:>8}",
+            stats.calls,
+            if stats.errors > 0 {
+                errors.red()
+            } else {
+                errors.normal()
+            },
+            ms(Some(stats.average_ms())),
+            ms(stats.percentile_ms(50)),
+            ms(stats.percentile_ms(95)),
+            ms(Some(stats.max_ms))
+        );
+    }
+    println!();
+    println!(
+        "{} Percentiles are the upper bounds of latency buckets",
+        term::symbols().info.cyan()
+    );
+    Ok(())
+}
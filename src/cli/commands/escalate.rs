@@ -0,0 +1,146 @@
+use crate::{
+    activity::{self, ActivityEntry, Source},
+    api::ApiClient,
+    cache,
+    config::PriorityLabels,
+    escalate::{self, Escalation},
+    state::LocalState,
+    term, ID_DISPLAY_LENGTH,
+};
+use anyhow::Result;
+use chrono::Local;
+use colored::Colorize;
+
+/// Applies the configured escalation rules, or only shows what they would change
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Configuration or local state cannot be loaded
+/// - Network request fails
+/// - Local state cannot be saved
+pub async fn escalate(client: &ApiClient, dry_run: bool) -> Result<()> {
+    let config = client.config();
+    let rules = escalate::parse_rules(&config.escalation_rules);
+    if rules.is_empty() {
+        println!(
+            "{} No escalation rules configured. Add one with: pacli config add-escalation-rule 'overdue > 3d -> priority:high'",
+            term::symbols().info.cyan()
+        );
+        return Ok(());
+    }
+
+    let todos = cache::list_todos(client, None).await?;
+    let state = LocalState::load()?;
+    let planned = escalate::plan(&rules, &todos, &state, Local::now().date_naive());
+    if planned.is_empty() {
+        println!("{} Nothing to escalate", term::symbols().ok.green());
+        return Ok(());
+    }
+
+    if dry_run {
+        println!(
+            "{}",
+            format!("Would escalate {} todo(s):", planned.len()).bold()
+        );
+        print_report(&planned, &config.priority_labels);
+        return Ok(());
+    }
+
+    apply(client, &planned, state).await?;
+    println!(
+        "{} Escalated {} todo(s):",
+        term::symbols().ok.green(),
+        planned.len()
+    );
+    print_report(&planned, &config.priority_labels);
+    Ok(())
+}
+
+/// Applies the escalation rules before a listing, printing what changed
+///
+/// Escalation shouldn't stop the listing itself, so failures are only logged.
+pub async fn escalate_quietly(client: &ApiClient) {
+    let config = client.config();
+    let rules = escalate::parse_rules(&config.escalation_rules);
+    if rules.is_empty() {
+        return;
+    }
+
+    let result = async {
+        let todos = cache::list_todos(client, None).await?;
+        let state = LocalState::load()?;
+        let planned = escalate::plan(&rules, &todos, &state, Local::now().date_naive());
+        if !planned.is_empty() {
+            apply(client, &planned, state).await?;
+        }
+        anyhow::Ok(planned)
+    }
+    .await;
+
+    match result {
+        Ok(planned) if planned.is_empty() => {}
+        Ok(planned) => {
+            println!(
+                "{} Escalated {} todo(s):",
+                term::symbols().warning.yellow(),
+                planned.len()
+            );
+            print_report(&planned, &config.priority_labels);
+            println!();
+        }
+        Err(err) => log::warn!("Could not apply escalation rules: {err:#}"),
+    }
+}
+
+/// Raises priorities on the server and adds tags to local state
+async fn apply(client: &ApiClient, planned: &[Escalation], mut state: LocalState) -> Result<()> {
+    for escalation in planned {
+        let todo = &escalation.todo;
+        if let Some(priority) = escalation.priority {
+            let request = pali_types::UpdateTodoRequest {
+                title: None,
+                description: None,
+                completed: None,
+                priority: Some(priority),
+                due_date: None,
+            };
+            let result = client.update_todo(&todo.id, request).await;
+            activity::track_todo(ActivityEntry::new(Source::Cli, "escalate"), result)?;
+        }
+        if !escalation.tags.is_empty() {
+            let mut tags = state.tags_of(&todo.id).to_vec();
+            tags.extend(escalation.tags.iter().cloned());
+            state.set_tags(&todo.id, tags);
+            if escalation.priority.is_none() {
+                activity::record(&ActivityEntry::new(Source::Cli, "escalate").with_todo(todo));
+            }
+        }
+    }
+    state.save()
+}
+
+fn print_report(planned: &[Escalation], labels: &PriorityLabels) {
+    for escalation in planned {
+        let todo = &escalation.todo;
+        let mut changes = Vec::new();
+        if let Some(priority) = escalation.priority {
+            changes.push(format!(
+                "priority {} {} {}",
+                labels.label(todo.priority),
+                term::symbols().arrow,
+                labels.label(priority)
+            ));
+        }
+        for tag in &escalation.tags {
+            changes.push(format!("+#{tag}"));
+        }
+        println!(
+            "  {} {} {} {}",
+            todo.id[..ID_DISPLAY_LENGTH.min(todo.id.len())].dimmed(),
+            todo.title.bold(),
+            changes.join(", ").yellow(),
+            format!("(overdue {} day(s))", escalation.overdue_days).dimmed()
+        );
+    }
+}
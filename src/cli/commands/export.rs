@@ -0,0 +1,61 @@
+use crate::{
+    api::ApiClient,
+    cli::types::ExportFormat,
+    export::{Exporter, Format},
+    term,
+};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use futures_util::StreamExt;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+/// Writes every todo to a file or stdout as the server streams them
+///
+/// Todos are written one at a time, so even very large accounts never have
+/// to fit in memory. `ndjson` writes one todo per line, `json` a single
+/// array, `md` a task list and `csv` one row per todo.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Network request fails or the stream is interrupted
+/// - The output file cannot be written
+pub async fn export(
+    client: &ApiClient,
+    format: ExportFormat,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let format = match format {
+        ExportFormat::Ndjson => Format::Ndjson,
+        ExportFormat::Json => Format::Json,
+        ExportFormat::Md => Format::Markdown,
+        ExportFormat::Csv => Format::Csv,
+    };
+
+    let mut todos = client.list_todos_stream().await?;
+
+    let writer: Box<dyn Write> = match &output {
+        Some(path) => Box::new(
+            std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?,
+        ),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    let labels = client.config().priority_labels.clone();
+    let mut exporter = Exporter::new(BufWriter::new(writer), format, labels)?;
+    while let Some(todo) = todos.next().await {
+        exporter.write(&todo?)?;
+    }
+    let count = exporter.finish()?;
+
+    if let Some(path) = output {
+        println!(
+            "{} Exported {count} todos to {}",
+            term::symbols().ok.green(),
+            path.display()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,202 @@
+use crate::{
+    api::{ApiClient, Todo},
+    cache,
+    due::{is_overdue, local_date, week_start},
+    state::LocalState,
+    time_operation,
+};
+use anyhow::Result;
+use chrono::{Datelike, Local, NaiveDate};
+use colored::Colorize;
+use std::collections::BTreeMap;
+
+/// Days with at least this many todos due are shown in yellow
+const BUSY_DAY: usize = 3;
+/// Days with at least this many todos due are shown in red
+const HEAVY_DAY: usize = 5;
+/// Characters per day in the calendar
+const CELL_WIDTH: usize = 4;
+
+/// Counts the open todos due on each of the `days` days from `start`
+fn day_counts(todos: &[&Todo], start: NaiveDate, days: u32) -> BTreeMap<NaiveDate, usize> {
+    let end = start + chrono::Days::new(u64::from(days));
+    let mut counts = BTreeMap::new();
+    for todo in todos.iter().filter(|todo| !todo.completed) {
+        if let Some(day) = todo.due_date.and_then(local_date) {
+            if (start..end).contains(&day) {
+                *counts.entry(day).or_insert(0) += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// Lays the days from `start` out in Monday-first weeks, leaving days outside the range empty
+fn weeks(start: NaiveDate, days: u32) -> Vec<[Option<NaiveDate>; 7]> {
+    let end = start + chrono::Days::new(u64::from(days));
+    (0u64..)
+        .map(|week| week_start(start) + chrono::Days::new(7 * week))
+        .take_while(|monday| *monday < end)
+        .map(|monday| {
+            let mut week = [None; 7];
+            for (offset, cell) in (0u64..).zip(week.iter_mut()) {
+                let day = monday + chrono::Days::new(offset);
+                *cell = (start..end).contains(&day).then_some(day);
+            }
+            week
+        })
+        .collect()
+}
+
+fn count_cell(count: usize) -> String {
+    let text = format!(
+        "{:>CELL_WIDTH$}",
+        if count == 0 {
+            "·".to_string()
+        } else {
+            count.to_string()
+        }
+    );
+    match count {
+        0 => text.dimmed().to_string(),
+        n if n >= HEAVY_DAY => text.red().bold().to_string(),
+        n if n >= BUSY_DAY => text.yellow().to_string(),
+        _ => text,
+    }
+}
+
+/// Prints how many todos are due on each of the next `days` days as a calendar
+///
+/// Open, unarchived todos count on their due day; busy days are yellow and
+/// heavy ones red. Overdue todos and todos without a due date are summed up
+/// below.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Network request fails
+/// - Server returns an error response
+/// - API key is missing or invalid
+pub async fn forecast(client: &ApiClient, days: u32) -> Result<()> {
+    let todos = time_operation!(cache::list_todos(client, None).await?, "Fetch todos");
+    let state = LocalState::load().unwrap_or_default();
+    let open: Vec<&Todo> = todos
+        .iter()
+        .filter(|todo| !todo.completed && !state.is_archived(&todo.id))
+        .collect();
+
+    let today = Local::now().date_naive();
+    let counts = day_counts(&open, today, days);
+    let last = today + chrono::Days::new(u64::from(days - 1));
+    println!(
+        "{}",
+        format!(
+            "Due in the next {days} day(s) ({} – {})",
+            today.format("%b %-d"),
+            last.format("%b %-d")
+        )
+        .bold()
+    );
+    println!();
+
+    let header: String = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"]
+        .iter()
+        .map(|day| format!("{day:>CELL_WIDTH$}"))
+        .collect();
+    println!("{:<7}{}", "", header.dimmed());
+    for week in weeks(today, days) {
+        let first = week.iter().flatten().next().copied().unwrap_or(today);
+        let month = first.format("%b").to_string();
+        let mut dates = String::new();
+        let mut cells = String::new();
+        for day in week {
+            match day {
+                Some(day) => {
+                    let number = format!("{:>CELL_WIDTH$}", day.day());
+                    dates.push_str(&if day == today {
+                        number.cyan().bold().to_string()
+                    } else {
+                        number.dimmed().to_string()
+                    });
+                    cells.push_str(&count_cell(counts.get(&day).copied().unwrap_or(0)));
+                }
+                None => {
+                    dates.push_str(&" ".repeat(CELL_WIDTH));
+                    cells.push_str(&" ".repeat(CELL_WIDTH));
+                }
+            }
+        }
+        println!("{month:<7}{dates}");
+        println!("{:<7}{cells}", "");
+    }
+    println!();
+
+    let total: usize = counts.values().sum();
+    let overdue = open.iter().filter(|todo| is_overdue(todo, today)).count();
+    let undated = open.iter().filter(|todo| todo.due_date.is_none()).count();
+    println!("  {} {total}", "Due in this period:".cyan());
+    if overdue > 0 {
+        println!(
+            "  {} {}",
+            "Overdue:".cyan(),
+            overdue.to_string().red().bold()
+        );
+    }
+    println!("  {} {undated}", "No due date:".cyan());
+    if let Some((day, count)) = counts
+        .iter()
+        .max_by_key(|(day, count)| (**count, std::cmp::Reverse(**day)))
+    {
+        if *count >= BUSY_DAY {
+            println!(
+                "  {} {} ({count} due)",
+                "Busiest day:".cyan(),
+                day.format("%a %b %-d")
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_forecast_layout() {
+        // A Thursday
+        let start = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        let weeks = weeks(start, 7);
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0][2], None);
+        assert_eq!(weeks[0][3], Some(start));
+        assert_eq!(weeks[1][2], NaiveDate::from_ymd_opt(2026, 10, 21));
+        assert_eq!(weeks[1][3], None);
+
+        let due = |day: u32, completed: bool| Todo {
+            id: format!("{day}-{completed}"),
+            title: "Test".to_string(),
+            description: None,
+            completed,
+            priority: 2,
+            due_date: Local
+                .with_ymd_and_hms(2026, 10, day, 12, 0, 0)
+                .single()
+                .map(|due| due.timestamp()),
+            created_at: 0,
+            updated_at: 0,
+        };
+        let todos = [
+            due(15, false),
+            due(15, true),
+            due(21, false),
+            due(22, false),
+        ];
+        let todos: Vec<&Todo> = todos.iter().collect();
+        let counts = day_counts(&todos, start, 7);
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[&start], 1);
+        assert_eq!(counts.values().sum::<usize>(), 2);
+    }
+}
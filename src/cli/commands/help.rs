@@ -0,0 +1,251 @@
+use crate::cli::types::Cli;
+use crate::term;
+use anyhow::{Context, Result};
+use clap::CommandFactory;
+use colored::Colorize;
+use std::fs;
+use std::path::Path;
+
+/// A help topic: name, one-line summary and text
+struct Topic {
+    name: &'static str,
+    summary: &'static str,
+    text: &'static str,
+}
+
+const TOPICS: [Topic; 5] = [
+    Topic {
+        name: "dates",
+        summary: "Due dates, days and durations",
+        text: "\
+Due dates (add --due, update --due, --set due=...):
+  2026-10-20                 that day at midnight
+  2026-10-20 14:30:00        that day and time
+
+Days (count --due, list --due-before/--due-after/--completed-after, --where due:...):
+  today, tomorrow, yesterday or YYYY-MM-DD
+
+Durations (--since, --expires, escalation rules, --set due=+...):
+  A number and a unit: 90s, 30m, 24h, 7d or 2w
+
+  --since also takes a date, meaning midnight at its start:
+    pacli admin audit --since 2026-10-01
+
+  --set due=+2d and due=-1w move due dates relative to their current value:
+    pacli update --where tag:chores --set due=+1w",
+    },
+    Topic {
+        name: "query",
+        summary: "Search queries",
+        text: "\
+pacli search <query> matches titles and descriptions:
+
+  pacli search invoice       case-insensitive text, searched by the server
+  pacli search --regex 'INV-\\d{4}'
+                             a regular expression, matched locally;
+                             case-sensitive unless it starts with (?i)
+
+Results show the matching part of the title or description.
+In patui, / searches the list the same way as plain text.",
+    },
+    Topic {
+        name: "where",
+        summary: "Conditions for bulk updates (--where)",
+        text: "\
+pacli update --where '<conditions>' --set <field>=<value> changes every
+matching todo. Conditions are separated by spaces and must all match:
+
+  tag:<name>                 carries the tag
+  priority:<level>           has the priority (low, medium, high, 1-3 or a label)
+  due:<day>                  is due that day (see 'pacli help dates')
+  title:<text>               title contains the text (case-insensitive)
+  overdue                    open and past its due date
+  open, pending              not completed
+  completed, done            completed
+
+--set takes title, description, priority, due, completed or tags, e.g.
+
+  pacli update --where 'tag:work overdue' --set priority=high --set due=+2d
+
+Matching todos are listed and confirmed before anything changes; -y skips
+the confirmation.",
+    },
+    Topic {
+        name: "rules",
+        summary: "Color and escalation rules",
+        text: "\
+Rules are written '<condition> -> <action>' and stored in the config.
+
+Color rules (pacli config add-color-rule) highlight todos in pacli and patui:
+
+  tag:urgent -> red bold
+  title~\"waiting\" -> dim
+
+  Colors: red, green, yellow, blue, magenta, cyan, white, gray
+  Styles: bold, dim, italic, underline
+  Later rules override the color of earlier ones; styles add up.
+
+Escalation rules (pacli config add-escalation-rule) act on overdue todos:
+
+  overdue > 3d -> priority:high
+  overdue > 7d -> tag:stale
+
+  They only raise priorities and add tags. pacli list applies them before
+  listing; pacli escalate [--dry-run] applies or previews them.",
+    },
+    Topic {
+        name: "projects",
+        summary: "Per-project settings in .pali.toml",
+        text: "\
+A .pali.toml in the current directory or any parent applies defaults while
+working inside it:
+
+  tag = \"website\"            # added to todos created here
+
+  [filters]                  # defaults for pacli list and pacli count
+  tag = \"website\"
+  priority = \"high\"
+  sort = \"priority,due\"      # pacli list only; see below
+  all = false
+
+Options given on the command line win over the file.
+
+Sort keys are priority, due, created, updated and title, comma-separated
+and prefixed with - to reverse them.",
+    },
+];
+
+fn find_topic(name: &str) -> Option<&'static Topic> {
+    TOPICS
+        .iter()
+        .find(|topic| topic.name.eq_ignore_ascii_case(name))
+}
+
+/// Prints help on a topic or command, or the overview with the list of topics
+///
+/// # Errors
+///
+/// Returns an error if `topic` is neither a help topic nor a command
+pub fn help(topic: Option<&str>) -> Result<()> {
+    let mut command = Cli::command();
+    let Some(name) = topic else {
+        command.print_help()?;
+        println!();
+        print_topics();
+        return Ok(());
+    };
+
+    if let Some(topic) = find_topic(name) {
+        println!("{}", topic.summary.bold());
+        println!();
+        println!("{}", topic.text);
+        return Ok(());
+    }
+
+    if let Some(subcommand) = command.find_subcommand_mut(name) {
+        subcommand.print_long_help()?;
+        return Ok(());
+    }
+
+    print_topics();
+    anyhow::bail!("No help topic or command named '{name}'")
+}
+
+fn print_topics() {
+    println!("{}", "Help topics (pacli help <topic>):".bold());
+    for topic in &TOPICS {
+        println!("  {:<10} {}", topic.name.cyan(), topic.summary);
+    }
+}
+
+/// Writes man pages for pacli, its commands and the help topics to `out_dir`
+///
+/// # Errors
+///
+/// Returns an error if the directory or a page cannot be written
+pub fn gen_man(out_dir: &Path) -> Result<()> {
+    fs::create_dir_all(out_dir)
+        .with_context(|| format!("Failed to create {}", out_dir.display()))?;
+
+    let mut command = Cli::command();
+    // Names subcommands `pacli-<command>`, as their pages are called
+    command.build();
+    let mut pages = 0;
+    write_command_pages(&command, out_dir, &mut pages)?;
+
+    for topic in &TOPICS {
+        let path = out_dir.join(format!("pacli-{}.7", topic.name));
+        fs::write(&path, topic_page(topic))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        pages += 1;
+    }
+
+    println!(
+        "{} Wrote {pages} man page(s) to {}",
+        term::symbols().ok.green(),
+        out_dir.display()
+    );
+    Ok(())
+}
+
+/// Writes the page of `command`, then those of its visible subcommands
+fn write_command_pages(command: &clap::Command, out_dir: &Path, pages: &mut usize) -> Result<()> {
+    let name = command
+        .get_display_name()
+        .unwrap_or_else(|| command.get_name());
+    let mut page = Vec::new();
+    clap_mangen::Man::new(command.clone()).render(&mut page)?;
+    let path = out_dir.join(format!("{name}.1"));
+    fs::write(&path, page).with_context(|| format!("Failed to write {}", path.display()))?;
+    *pages += 1;
+
+    for subcommand in command.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        write_command_pages(subcommand, out_dir, pages)?;
+    }
+    Ok(())
+}
+
+/// Formats a help topic as a section 7 man page, keeping its layout
+fn topic_page(topic: &Topic) -> String {
+    let mut page = format!(
+        ".TH PACLI-{} 7\n.SH NAME\npacli-{} \\- {}\n.SH DESCRIPTION\n.nf\n",
+        topic.name.to_uppercase(),
+        topic.name,
+        topic.summary
+    );
+    for line in topic.text.lines() {
+        let line = line.replace('\\', "\\e");
+        // Lines starting with a control character would be read as requests
+        if line.starts_with(['.', '\'']) {
+            page.push_str("\\&");
+        }
+        page.push_str(&line);
+        page.push('\n');
+    }
+    page.push_str(".fi\n");
+    page
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topics() {
+        assert!(find_topic("Dates").is_some());
+        assert!(find_topic("list").is_none());
+        // Topics mustn't hide commands of the same name
+        let command = Cli::command();
+        for topic in &TOPICS {
+            assert!(command.find_subcommand(topic.name).is_none());
+        }
+
+        let page = topic_page(&Topic {
+            name: "test",
+            summary: "Test",
+            text: ".hidden\nC:\\path",
+        });
+        assert!(page.starts_with(".TH PACLI-TEST 7\n"));
+        assert!(page.contains("\n\\&.hidden\nC:\\epath\n"));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{
+    activity,
+    api::{ApiClient, Capabilities},
+    cli::utils::{parse_since, resolve_partial_id},
+    revisions, term, ID_DISPLAY_LENGTH,
+};
+use anyhow::{Context, Result};
+use chrono::{Local, TimeZone, Utc};
+use colored::Colorize;
+
+/// Default window for `pacli history` when `--since` isn't given
+const DEFAULT_SINCE: &str = "7d";
+
+/// Shows locally recorded operations since the given time
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The `--since` value is invalid
+/// - The activity log cannot be read
+#[allow(clippy::unused_async)] // Function is async to match CLI command pattern
+pub async fn show(since: Option<String>) -> Result<()> {
+    let since_spec = since.as_deref().unwrap_or(DEFAULT_SINCE);
+    let since_ts = parse_since(since_spec)?;
+    let entries = activity::read_since(since_ts)?;
+
+    if entries.is_empty() {
+        println!(
+            "{}",
+            format!("No activity recorded since {since_spec}").yellow()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("{} operation(s) since {since_spec}:", entries.len()).bold()
+    );
+    println!();
+
+    for entry in entries {
+        let when = Utc
+            .timestamp_opt(entry.timestamp, 0)
+            .latest()
+            .map(|dt| dt.with_timezone(&Local))
+            .map_or_else(
+                || "Invalid date".to_string(),
+                |dt| dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+            );
+
+        let outcome = if entry.succeeded() {
+            term::symbols().ok.green()
+        } else {
+            term::symbols().error.red()
+        };
+
+        print!(
+            "{} {} {} {:<12}",
+            when.dimmed(),
+            outcome,
+            entry.source.to_string().dimmed(),
+            entry.operation
+        );
+
+        if let Some(id) = &entry.todo_id {
+            print!(
+                " {}",
+                format!("[{}]", &id[..ID_DISPLAY_LENGTH.min(id.len())]).cyan()
+            );
+        }
+        if let Some(title) = &entry.title {
+            print!(" {}", title.bold());
+        }
+        println!();
+
+        if let Some(error) = &entry.error {
+            println!("    {}", error.red());
+        }
+    }
+
+    Ok(())
+}
+
+/// Shows the revisions of a todo with what each one changed, oldest first
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The `--since` value is invalid
+/// - The server doesn't keep revisions
+/// - The todo ID cannot be resolved
+/// - Network request fails
+pub async fn show_todo(client: &ApiClient, id: String, since: Option<String>) -> Result<()> {
+    let since_ts = since.as_deref().map(parse_since).transpose()?;
+    if !client
+        .capabilities()
+        .await
+        .supports(Capabilities::REVISIONS)
+    {
+        anyhow::bail!("The server doesn't keep the history of todos");
+    }
+
+    let full_id = resolve_partial_id(&id, client)
+        .await
+        .context(format!("Failed to resolve ID '{id}'"))?;
+    let revisions = client.list_revisions(&full_id).await?;
+    let Some(latest) = revisions.last() else {
+        println!("{}", "No revisions recorded for this todo".yellow());
+        return Ok(());
+    };
+
+    println!(
+        "{} {}",
+        format!("[{}]", &full_id[..ID_DISPLAY_LENGTH.min(full_id.len())]).cyan(),
+        latest.title.bold()
+    );
+    println!();
+
+    let mut shown = 0;
+    for (i, revision) in revisions.iter().enumerate() {
+        if since_ts.is_some_and(|since_ts| revision.changed_at < since_ts) {
+            continue;
+        }
+        shown += 1;
+        let Some(previous) = i.checked_sub(1).map(|previous| &revisions[previous]) else {
+            println!(
+                "{} {}",
+                revisions::describe(revision).dimmed(),
+                "created".green()
+            );
+            continue;
+        };
+
+        println!("{}", revisions::describe(revision).dimmed());
+        let changes = revisions::changes(previous, revision);
+        if changes.is_empty() {
+            println!("    {}", "(no visible changes)".dimmed());
+        }
+        for change in changes {
+            println!("    {}", change.field.bold());
+            for line in &change.removed {
+                println!("      {}", format!("- {line}").red());
+            }
+            for line in &change.added {
+                println!("      {}", format!("+ {line}").green());
+            }
+        }
+    }
+
+    if shown == 0 {
+        if let Some(since) = since {
+            println!("{}", format!("No changes since {since}").yellow());
+        }
+    }
+
+    Ok(())
+}
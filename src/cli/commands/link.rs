@@ -0,0 +1,90 @@
+use crate::{
+    activity::{self, ActivityEntry, Source},
+    api::ApiClient,
+    cli::utils::resolve_partial_id,
+    state::LocalState,
+    term,
+};
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+/// Records that the todo `id` must be completed before `blocks`
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Either ID cannot be resolved
+/// - The link would create a dependency cycle
+/// - Local state cannot be loaded or saved
+pub async fn link(client: &ApiClient, id: String, blocks: String) -> Result<()> {
+    let blocker_id = resolve_partial_id(&id, client)
+        .await
+        .context(format!("Failed to resolve ID '{id}'"))?;
+    let blocked_id = resolve_partial_id(&blocks, client)
+        .await
+        .context(format!("Failed to resolve ID '{blocks}'"))?;
+
+    let mut state = LocalState::load()?;
+    if state.would_create_cycle(&blocker_id, &blocked_id) {
+        anyhow::bail!("Cannot link '{id}' -> '{blocks}': this would create a dependency cycle");
+    }
+
+    let blocker = client.get_todo(&blocker_id).await?;
+    let blocked = client.get_todo(&blocked_id).await?;
+
+    if state.add_block(&blocker_id, &blocked_id) {
+        state.save()?;
+        activity::record(
+            &ActivityEntry::new(Source::Cli, format!("link (blocks {})", blocked.title))
+                .with_todo(&blocker),
+        );
+        println!(
+            "{} '{}' now blocks '{}'",
+            term::symbols().ok.green(),
+            blocker.title.bold(),
+            blocked.title.bold()
+        );
+    } else {
+        println!(
+            "{} '{}' already blocks '{}'",
+            term::symbols().info.cyan(),
+            blocker.title.bold(),
+            blocked.title.bold()
+        );
+    }
+
+    Ok(())
+}
+
+/// Removes a dependency link created with [`link`]
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Either ID cannot be resolved
+/// - No such link exists
+/// - Local state cannot be loaded or saved
+pub async fn unlink(client: &ApiClient, id: String, blocks: String) -> Result<()> {
+    let blocker_id = resolve_partial_id(&id, client)
+        .await
+        .context(format!("Failed to resolve ID '{id}'"))?;
+    let blocked_id = resolve_partial_id(&blocks, client)
+        .await
+        .context(format!("Failed to resolve ID '{blocks}'"))?;
+
+    let mut state = LocalState::load()?;
+    if !state.remove_block(&blocker_id, &blocked_id) {
+        anyhow::bail!("'{id}' does not block '{blocks}'");
+    }
+    state.save()?;
+    activity::record(&ActivityEntry::new(Source::Cli, "unlink").with_id(&blocker_id));
+
+    println!(
+        "{} Removed dependency {} -> {}",
+        term::symbols().ok.green(),
+        id.cyan(),
+        blocks.cyan()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use crate::{
+    activity::{self, ActivityEntry, Source},
+    api::ApiClient,
+    auth::{self, TokenSet},
+    config::{Config, OAuthSettings},
+    interceptor::HeaderInterceptor,
+    prompt, term,
+};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use std::io::{BufRead, Write};
+
+/// Prompts for a line of input, returning `default` when the answer is empty
+fn ask(label: &str, default: &str) -> Result<String> {
+    print!("{label} [{}]: ", default.dimmed());
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok(if answer.is_empty() {
+        default.to_string()
+    } else {
+        answer.to_string()
+    })
+}
+
+/// Interactively configures the endpoint and API key
+///
+/// The key is read with echo disabled so it never ends up in shell history
+/// or on screen, and is only saved after a test request succeeds with it.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Configuration cannot be loaded or saved
+/// - The key cannot be read from the terminal, or prompting is disabled
+/// - The server rejects the key or cannot be reached
+pub async fn login(endpoint: Option<String>) -> Result<()> {
+    let mut config = Config::load()?;
+
+    let endpoint = match endpoint {
+        Some(endpoint) => endpoint,
+        None => {
+            prompt::require("enter the server URL (pass it as an argument instead)")?;
+            ask("Server URL", &config.api_endpoint)?
+        }
+    };
+    config.set_endpoint(endpoint.trim_end_matches('/'));
+
+    prompt::require("enter the API key")?;
+    let key = rpassword::prompt_password("API key: ").context("Failed to read the API key")?;
+    let key = key.trim();
+    if key.is_empty() {
+        anyhow::bail!("API key cannot be empty");
+    }
+    config.set_api_key(key);
+    // Logging in with a key switches away from SSO
+    config.oauth = None;
+
+    // Validate before saving so a typo doesn't replace a working key
+    let client = ApiClient::with_config(config.clone())?;
+    activity::track(
+        ActivityEntry::new(Source::Cli, "login"),
+        client.list_todos(None, None).await,
+    )
+    .context(format!("Login to {} failed", config.api_endpoint))?;
+
+    config.save()?;
+    TokenSet::clear()?;
+
+    println!(
+        "{} Logged in to {}",
+        term::symbols().ok.green(),
+        config.api_endpoint.cyan()
+    );
+    if !config.is_encrypted() {
+        println!(
+            "{} API key is stored in plain text at: {}",
+            term::symbols().warning.yellow(),
+            Config::config_path()?.display().to_string().dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// Merges OAuth settings given on the command line with the saved ones
+fn oauth_settings(
+    saved: Option<OAuthSettings>,
+    client_id: Option<String>,
+    device_url: Option<String>,
+    token_url: Option<String>,
+    scope: Option<String>,
+) -> Result<OAuthSettings> {
+    let missing = |flag: &str| {
+        anyhow::anyhow!(
+            "No OAuth provider configured. Pass --client-id, --device-url and --token-url \
+             (missing {flag})"
+        )
+    };
+
+    Ok(OAuthSettings {
+        client_id: client_id
+            .or_else(|| saved.as_ref().map(|s| s.client_id.clone()))
+            .ok_or_else(|| missing("--client-id"))?,
+        device_authorization_url: device_url
+            .or_else(|| saved.as_ref().map(|s| s.device_authorization_url.clone()))
+            .ok_or_else(|| missing("--device-url"))?,
+        token_url: token_url
+            .or_else(|| saved.as_ref().map(|s| s.token_url.clone()))
+            .ok_or_else(|| missing("--token-url"))?,
+        scope: scope.or_else(|| saved.and_then(|s| s.scope)),
+    })
+}
+
+/// Signs in through SSO using the OAuth device-code flow
+///
+/// The provider settings are remembered, so later logins only need
+/// `pacli login --oauth`. Tokens are stored in the data directory and
+/// refreshed automatically by the API client.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - No provider is configured and none was given
+/// - The identity provider cannot be reached or denies the login
+/// - The server rejects the token
+/// - Configuration cannot be loaded, or it or the tokens cannot be saved
+pub async fn login_oauth(
+    endpoint: Option<String>,
+    client_id: Option<String>,
+    device_url: Option<String>,
+    token_url: Option<String>,
+    scope: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    if let Some(endpoint) = endpoint {
+        config.set_endpoint(endpoint.trim_end_matches('/'));
+    }
+    let settings = oauth_settings(config.oauth.take(), client_id, device_url, token_url, scope)?;
+
+    let http = reqwest::Client::new();
+    let authorization = auth::start_device_flow(&http, &settings).await?;
+
+    println!(
+        "Open {} and enter the code {}",
+        authorization.verification_uri.cyan(),
+        authorization.user_code.yellow().bold()
+    );
+    if let Some(uri) = &authorization.verification_uri_complete {
+        println!(
+            "{} Or open {} directly",
+            term::symbols().info.cyan(),
+            uri.dimmed()
+        );
+    }
+    println!("{}", "Waiting for approval...".dimmed());
+
+    let tokens = auth::poll_for_token(&http, &settings, &authorization).await?;
+
+    // Validate before saving anything, so a rejected token doesn't replace a
+    // working login; the new token isn't on disk yet, so it's sent directly
+    let mut bearer = HeaderValue::from_str(&format!("Bearer {}", tokens.access_token))
+        .context("The identity provider returned an invalid access token")?;
+    bearer.set_sensitive(true);
+    let mut unsaved = config.clone();
+    unsaved.oauth = None;
+    unsaved.api_key = None;
+    let client = ApiClient::with_config(unsaved)?
+        .with_interceptor(HeaderInterceptor::new(AUTHORIZATION, bearer));
+    activity::track(
+        ActivityEntry::new(Source::Cli, "login --oauth"),
+        client.list_todos(None, None).await,
+    )
+    .context(format!(
+        "Signed in, but {} rejected the token",
+        config.api_endpoint
+    ))?;
+
+    config.oauth = Some(settings);
+    tokens.save()?;
+    config.save()?;
+
+    println!(
+        "{} Logged in to {} via SSO",
+        term::symbols().ok.green(),
+        config.api_endpoint.cyan()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::{api::ApiClient, cli::utils::resolve_partial_id, links, prompt::can_prompt, term};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::io::{BufRead, Write};
+
+/// Opens a URL from a todo's title or description in the default browser
+///
+/// With several URLs, `number` picks one (1-based); otherwise the user is
+/// asked, or the first one is opened when prompting isn't possible.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The todo ID cannot be resolved
+/// - Network request fails
+/// - The todo mentions no URL, or `number` is out of range
+/// - The browser cannot be launched
+pub async fn open(client: &ApiClient, id: String, number: Option<usize>) -> Result<()> {
+    let full_id = resolve_partial_id(&id, client)
+        .await
+        .context(format!("Failed to resolve ID '{id}'"))?;
+    let todo = client.get_todo(&full_id).await?;
+
+    let urls = links::todo_urls(&todo);
+    let url = match (urls.as_slice(), number) {
+        ([], _) => anyhow::bail!("'{}' contains no URL", todo.title),
+        (urls, Some(n)) => urls.get(n.wrapping_sub(1)).ok_or_else(|| {
+            anyhow::anyhow!(
+                "'{}' has {} URL(s); pick 1-{}",
+                todo.title,
+                urls.len(),
+                urls.len()
+            )
+        })?,
+        ([url], None) => url,
+        (urls, None) if can_prompt() => pick_url(urls)?,
+        ([first, ..], None) => first,
+    };
+
+    ::open::that_detached(url).with_context(|| format!("Failed to open {url}"))?;
+    println!("{} Opened {}", term::symbols().ok.green(), url.cyan());
+    Ok(())
+}
+
+/// Asks the user which of several URLs to open
+fn pick_url(urls: &[String]) -> Result<&String> {
+    eprintln!("{} Several URLs found:", "?".yellow());
+    for (i, url) in urls.iter().enumerate() {
+        eprintln!("  {:>2}) {url}", i + 1);
+    }
+
+    loop {
+        eprint!("Select 1-{} (Enter to cancel): ", urls.len());
+        std::io::stderr().flush()?;
+
+        let mut answer = String::new();
+        std::io::stdin().lock().read_line(&mut answer)?;
+        let answer = answer.trim();
+        if answer.is_empty() || answer.eq_ignore_ascii_case("q") {
+            anyhow::bail!("Cancelled");
+        }
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=urls.len()).contains(&n) => return Ok(&urls[n - 1]),
+            _ => eprintln!(
+                "{} Enter a number from the list",
+                term::symbols().warning.yellow()
+            ),
+        }
+    }
+}
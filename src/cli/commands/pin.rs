@@ -0,0 +1,83 @@
+use crate::{
+    activity::{self, ActivityEntry, Source},
+    api::ApiClient,
+    cli::utils::resolve_partial_id,
+    state::LocalState,
+    term,
+};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use colored::Colorize;
+
+/// Pins a todo to the top of every list, whatever the sort order
+///
+/// Pinning is local to this machine, like tags and archiving; `patui`
+/// shows the same pins.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The todo ID cannot be resolved
+/// - Network request fails
+/// - Local state cannot be loaded or saved
+pub async fn pin(client: &ApiClient, id: String) -> Result<()> {
+    let full_id = resolve_partial_id(&id, client)
+        .await
+        .context(format!("Failed to resolve ID '{id}'"))?;
+    let todo = client.get_todo(&full_id).await?;
+
+    let mut state = LocalState::load()?;
+    if !state.pin(&full_id, Utc::now().timestamp()) {
+        println!(
+            "{} '{}' is already pinned",
+            term::symbols().info.cyan(),
+            todo.title.bold()
+        );
+        return Ok(());
+    }
+    state.save()?;
+    activity::record(&ActivityEntry::new(Source::Cli, "pin").with_todo(&todo));
+
+    println!(
+        "{} Pinned '{}'",
+        term::symbols().ok.green(),
+        todo.title.bold()
+    );
+
+    Ok(())
+}
+
+/// Lets a pinned todo take its place in the sort order again
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The todo ID cannot be resolved
+/// - Network request fails
+/// - Local state cannot be loaded or saved
+pub async fn unpin(client: &ApiClient, id: String) -> Result<()> {
+    let full_id = resolve_partial_id(&id, client)
+        .await
+        .context(format!("Failed to resolve ID '{id}'"))?;
+    let todo = client.get_todo(&full_id).await?;
+
+    let mut state = LocalState::load()?;
+    if !state.unpin(&full_id) {
+        println!(
+            "{} '{}' is not pinned",
+            term::symbols().info.cyan(),
+            todo.title.bold()
+        );
+        return Ok(());
+    }
+    state.save()?;
+    activity::record(&ActivityEntry::new(Source::Cli, "unpin").with_todo(&todo));
+
+    println!(
+        "{} Unpinned '{}'",
+        term::symbols().ok.green(),
+        todo.title.bold()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use crate::{
+    api::{ApiClient, Todo},
+    cli::{
+        commands::todo::is_overdue,
+        types::{ReportFormat, ReportGroupBy},
+        utils::local_day_start,
+    },
+    config::PriorityLabels,
+    state::LocalState,
+    time_operation, ID_DISPLAY_LENGTH,
+};
+use anyhow::Result;
+use chrono::{Datelike, Days, Local, NaiveDate};
+use colored::Colorize;
+use pali_types::priority;
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+/// Priority groups in the order they are reported
+const PRIORITY_GROUPS: [i32; 3] = [priority::HIGH, priority::MEDIUM, priority::LOW];
+
+/// Unknown priorities are reported as medium, matching `pacli list`
+fn priority_group(p: i32) -> i32 {
+    if p == priority::HIGH || p == priority::LOW {
+        p
+    } else {
+        priority::MEDIUM
+    }
+}
+
+/// Todos that fall into a reporting period
+struct Report<'a> {
+    title: String,
+    created: Vec<&'a Todo>,
+    completed: Vec<&'a Todo>,
+    overdue: Vec<&'a Todo>,
+}
+
+impl<'a> Report<'a> {
+    /// Collects the todos created and completed in `[from, to)` plus those overdue on `today`
+    ///
+    /// The API has no completion timestamp, so a completed todo counts as
+    /// completed in the period when it was last updated within it.
+    fn build(title: String, todos: &'a [Todo], from: i64, to: i64, today: NaiveDate) -> Self {
+        let in_period = |ts: i64| ts >= from && ts < to;
+
+        Self {
+            title,
+            created: todos.iter().filter(|t| in_period(t.created_at)).collect(),
+            completed: todos
+                .iter()
+                .filter(|t| t.completed && in_period(t.updated_at))
+                .collect(),
+            overdue: todos.iter().filter(|t| is_overdue(t, today)).collect(),
+        }
+    }
+
+    fn sections(&self) -> [(&'static str, &[&'a Todo]); 3] {
+        [
+            ("Completed", &self.completed),
+            ("Created", &self.created),
+            ("Overdue", &self.overdue),
+        ]
+    }
+}
+
+/// How the todos of each section are grouped
+enum Grouping<'c> {
+    Priority(&'c PriorityLabels),
+    Tag(&'c LocalState),
+}
+
+impl Grouping<'_> {
+    /// Splits `todos` into headed groups, skipping empty ones
+    ///
+    /// Priorities come highest first. Tags come in alphabetical order, a todo
+    /// with several tags appears in each of their groups and untagged todos
+    /// come last, as in `pacli list --group-by tag`.
+    fn groups<'a>(&self, todos: &[&'a Todo]) -> Vec<(String, Vec<&'a Todo>)> {
+        let collect = |pred: &dyn Fn(&Todo) -> bool| -> Vec<&'a Todo> {
+            todos.iter().copied().filter(|t| pred(t)).collect()
+        };
+
+        let groups: Vec<(String, Vec<&Todo>)> = match self {
+            Self::Priority(labels) => PRIORITY_GROUPS
+                .iter()
+                .map(|&level| {
+                    (
+                        labels.label(level).to_string(),
+                        collect(&|t| priority_group(t.priority) == level),
+                    )
+                })
+                .collect(),
+            Self::Tag(state) => {
+                let tags: BTreeSet<&String> =
+                    todos.iter().flat_map(|t| state.tags_of(&t.id)).collect();
+                tags.into_iter()
+                    .map(|tag| {
+                        (
+                            format!("#{tag}"),
+                            collect(&|t| state.tags_of(&t.id).contains(tag)),
+                        )
+                    })
+                    .chain([(
+                        "Untagged".to_string(),
+                        collect(&|t| state.tags_of(&t.id).is_empty()),
+                    )])
+                    .collect()
+            }
+        };
+
+        groups
+            .into_iter()
+            .filter(|(_, group)| !group.is_empty())
+            .collect()
+    }
+}
+
+fn render_markdown(report: &Report, grouping: &Grouping) -> String {
+    let mut out = format!("## {}\n", report.title);
+
+    for (name, todos) in report.sections() {
+        let _ = write!(out, "\n### {name} ({})\n", todos.len());
+        if todos.is_empty() {
+            out.push_str("\n_None_\n");
+            continue;
+        }
+        for (label, group) in grouping.groups(todos) {
+            let _ = write!(out, "\n**{label}**\n\n");
+            for todo in group {
+                let _ = writeln!(out, "- {}", todo.title);
+            }
+        }
+    }
+
+    out
+}
+
+fn print_text(report: &Report, grouping: &Grouping) {
+    println!("{}", report.title.bold());
+
+    for (name, todos) in report.sections() {
+        println!();
+        println!("{} {}", format!("{name}:").cyan().bold(), todos.len());
+        for (label, group) in grouping.groups(todos) {
+            println!("  {}", label.dimmed());
+            for todo in group {
+                println!(
+                    "    {} {}",
+                    format!("[{}]", &todo.id[..ID_DISPLAY_LENGTH.min(todo.id.len())]).cyan(),
+                    todo.title
+                );
+            }
+        }
+    }
+}
+
+/// Prints a summary of created, completed and overdue todos for today or the current week
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Network request fails
+/// - Server returns an error response
+/// - API key is missing or invalid
+/// - Local state cannot be loaded when grouping by tag
+pub async fn report(
+    client: &ApiClient,
+    week: bool,
+    format: ReportFormat,
+    group_by: ReportGroupBy,
+) -> Result<()> {
+    let markdown = format == ReportFormat::Md;
+
+    let today = Local::now().date_naive();
+    let (first_day, title) = if week {
+        let monday = today - Days::new(u64::from(today.weekday().num_days_from_monday()));
+        (
+            monday,
+            format!("Report for week of {}", monday.format("%Y-%m-%d")),
+        )
+    } else {
+        (today, format!("Report for {}", today.format("%Y-%m-%d")))
+    };
+    let last_day = if week {
+        first_day + Days::new(7)
+    } else {
+        first_day + Days::new(1)
+    };
+    let (Some(from), Some(to)) = (local_day_start(first_day), local_day_start(last_day)) else {
+        anyhow::bail!("Could not determine the reporting period in the local time zone");
+    };
+
+    let todos = time_operation!(
+        client.list_todos(None, None).await?,
+        "Fetch todos from server"
+    );
+
+    let labels = client.config().priority_labels.clone();
+    let state;
+    let grouping = match group_by {
+        ReportGroupBy::Priority => Grouping::Priority(&labels),
+        ReportGroupBy::Tag => {
+            state = LocalState::load()?;
+            Grouping::Tag(&state)
+        }
+    };
+    let report = Report::build(title, &todos, from, to, today);
+    if markdown {
+        print!("{}", render_markdown(&report, &grouping));
+    } else {
+        print_text(&report, &grouping);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn todo(id: &str, title: &str, priority: i32, completed: bool, created_at: i64) -> Todo {
+        testing::todo(id)
+            .title(title)
+            .priority(priority)
+            .completed(completed)
+            .created(created_at)
+            .build()
+    }
+
+    #[test]
+    fn test_report_markdown() {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        let todos = vec![
+            todo("a", "Ship release", priority::HIGH, true, 150),
+            todo("b", "Write docs", priority::LOW, false, 120),
+            todo("c", "Old task", priority::MEDIUM, false, 10),
+        ];
+
+        let report = Report::build("Weekly".to_string(), &todos, 100, 200, today);
+        assert_eq!(report.created.len(), 2);
+        assert_eq!(report.completed.len(), 1);
+        assert!(report.overdue.is_empty());
+
+        let labels = PriorityLabels {
+            high: "P1".to_string(),
+            ..PriorityLabels::default()
+        };
+        let md = render_markdown(&report, &Grouping::Priority(&labels));
+        assert!(md.starts_with("## Weekly\n"));
+        assert!(md.contains("### Completed (1)\n\n**P1**\n\n- Ship release\n"));
+        assert!(md.contains("**Low**\n\n- Write docs\n"));
+        assert!(md.contains("### Overdue (0)\n\n_None_\n"));
+        assert!(!md.contains("Old task"));
+    }
+
+    #[test]
+    fn test_report_by_tag() {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        let todos = vec![
+            todo("a", "Ship release", priority::HIGH, false, 150),
+            todo("b", "Write docs", priority::LOW, false, 120),
+            todo("c", "Water plants", priority::MEDIUM, false, 110),
+        ];
+        let mut state = LocalState::default();
+        state.set_tags("a", vec!["work".to_string(), "release".to_string()]);
+        state.set_tags("b", vec!["work".to_string()]);
+
+        let report = Report::build("Daily".to_string(), &todos, 100, 200, today);
+        let md = render_markdown(&report, &Grouping::Tag(&state));
+        assert!(md.contains(
+            "### Created (3)\n\n**#release**\n\n- Ship release\n\n\
+             **#work**\n\n- Ship release\n- Write docs\n\n\
+             **Untagged**\n\n- Water plants\n"
+        ));
+        assert!(md.contains("### Completed (0)\n\n_None_\n"));
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{
+    activity::{self, ActivityEntry, Source},
+    api::{ApiClient, Todo, UpdateTodoRequest},
+    checklist,
+    cli::{types::SubtaskAction, utils::resolve_partial_id},
+    term,
+};
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+/// Handles checklist actions (add, complete, reopen, list)
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Network request fails
+/// - Todo with the given ID is not found
+/// - Checklist item cannot be found or is ambiguous
+/// - Server returns an error response
+/// - API key is missing or invalid
+pub async fn handle(client: &ApiClient, action: SubtaskAction) -> Result<()> {
+    match action {
+        SubtaskAction::Add { id, text } => add(client, id, text).await,
+        SubtaskAction::Complete { id, item } => set_done(client, id, item, true).await,
+        SubtaskAction::Reopen { id, item } => set_done(client, id, item, false).await,
+        SubtaskAction::List { id } => list(client, id).await,
+    }
+}
+
+async fn fetch_todo(client: &ApiClient, id: &str) -> Result<Todo> {
+    let full_id = resolve_partial_id(id, client)
+        .await
+        .context(format!("Failed to resolve ID '{id}'"))?;
+
+    client.get_todo(&full_id).await
+}
+
+async fn save_description(
+    client: &ApiClient,
+    todo: &Todo,
+    description: String,
+    operation: &str,
+) -> Result<Todo> {
+    let request = UpdateTodoRequest {
+        title: None,
+        description: Some(description),
+        completed: None,
+        priority: None,
+        due_date: None,
+    };
+
+    activity::track_todo(
+        ActivityEntry::new(Source::Cli, operation).with_todo(todo),
+        client.update_todo(&todo.id, request).await,
+    )
+}
+
+async fn add(client: &ApiClient, id: String, text: String) -> Result<()> {
+    if text.trim().is_empty() {
+        anyhow::bail!("Checklist item text cannot be empty");
+    }
+
+    let todo = fetch_todo(client, &id).await?;
+
+    let description = checklist::add_item(todo.description.as_deref(), &text);
+    let todo = save_description(client, &todo, description, "subtask add").await?;
+
+    println!(
+        "{} Added checklist item to '{}'{}",
+        term::symbols().ok.green(),
+        todo.title.bold(),
+        progress_suffix(&todo)
+    );
+
+    Ok(())
+}
+
+async fn set_done(client: &ApiClient, id: String, item: String, done: bool) -> Result<()> {
+    let todo = fetch_todo(client, &id).await?;
+
+    let description = todo.description.clone().unwrap_or_default();
+    let index = resolve_item(&description, &item)?;
+    let Some(updated) = checklist::set_item_done(&description, index, done) else {
+        anyhow::bail!("Checklist item '{item}' not found");
+    };
+
+    let operation = if done {
+        "subtask complete"
+    } else {
+        "subtask reopen"
+    };
+    let todo = save_description(client, &todo, updated, operation).await?;
+    let text = todo
+        .description
+        .as_deref()
+        .and_then(|d| checklist::items(d).into_iter().nth(index))
+        .map(|i| i.text)
+        .unwrap_or(item);
+
+    println!(
+        "{} Marked '{}' as {}{}",
+        term::symbols().ok.green(),
+        text.bold(),
+        if done { "done" } else { "not done" },
+        progress_suffix(&todo)
+    );
+
+    Ok(())
+}
+
+async fn list(client: &ApiClient, id: String) -> Result<()> {
+    let todo = fetch_todo(client, &id).await?;
+
+    let items = checklist::items(todo.description.as_deref().unwrap_or_default());
+    if items.is_empty() {
+        println!(
+            "{}",
+            format!("'{}' has no checklist items", todo.title).yellow()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{}{}",
+        format!("Checklist for '{}'", todo.title).bold(),
+        progress_suffix(&todo)
+    );
+    for (i, item) in items.iter().enumerate() {
+        let mark = if item.done {
+            term::symbols().done.green().to_string()
+        } else {
+            term::symbols().pending.normal().to_string()
+        };
+        println!("  {} {mark} {}", format!("{}.", i + 1).cyan(), item.text);
+    }
+
+    Ok(())
+}
+
+fn progress_suffix(todo: &Todo) -> String {
+    todo.description
+        .as_deref()
+        .and_then(checklist::progress)
+        .map(|(done, total)| format!(" ({done}/{total})"))
+        .unwrap_or_default()
+}
+
+/// Resolves an item reference (1-based number or unique text fragment) to an index
+fn resolve_item(description: &str, item: &str) -> Result<usize> {
+    let items = checklist::items(description);
+    if items.is_empty() {
+        anyhow::bail!("This todo has no checklist items");
+    }
+
+    if let Ok(number) = item.parse::<usize>() {
+        if (1..=items.len()).contains(&number) {
+            return Ok(number - 1);
+        }
+        anyhow::bail!(
+            "Checklist item {number} does not exist (this todo has {} item(s))",
+            items.len()
+        );
+    }
+
+    let needle = item.to_lowercase();
+    let matches: Vec<_> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, i)| i.text.to_lowercase().contains(&needle))
+        .map(|(index, _)| index)
+        .collect();
+
+    match matches.as_slice() {
+        [] => anyhow::bail!("No checklist item matches '{item}'"),
+        [index] => Ok(*index),
+        _ => anyhow::bail!(
+            "'{item}' matches {} checklist items. Use the item number instead.",
+            matches.len()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_item() {
+        let description = "- [ ] buy milk\n- [ ] buy bread\n- [x] call mom";
+
+        assert_eq!(resolve_item(description, "1").unwrap(), 0);
+        assert_eq!(resolve_item(description, "3").unwrap(), 2);
+        assert!(resolve_item(description, "4").is_err());
+        assert_eq!(resolve_item(description, "MOM").unwrap(), 2);
+        assert!(resolve_item(description, "buy").is_err());
+        assert!(resolve_item(description, "walk dog").is_err());
+        assert!(resolve_item("no items", "1").is_err());
+    }
+}
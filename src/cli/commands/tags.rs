@@ -0,0 +1,95 @@
+use crate::{
+    activity::{self, ActivityEntry, Source},
+    api::ApiClient,
+    cli::types::TagsAction,
+    state::LocalState,
+    term, time_operation,
+};
+use anyhow::Result;
+use colored::Colorize;
+
+/// Handles tag actions, listing all tags when no action is given
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Network request fails
+/// - Local state cannot be loaded or saved
+/// - The new tag name is empty
+pub async fn handle(client: &ApiClient, action: Option<TagsAction>) -> Result<()> {
+    match action {
+        None | Some(TagsAction::List) => list(client).await,
+        Some(TagsAction::Rename { old, new }) => rename(&old, &new),
+    }
+}
+
+/// Prints bare tag names from local state, one per line, for shell completion
+///
+/// Works offline, so completion never waits for the server.
+///
+/// # Errors
+///
+/// Returns an error if local state cannot be loaded
+pub fn print_names() -> Result<()> {
+    let state = LocalState::load()?;
+    for tag in state.all_tags() {
+        println!("{tag}");
+    }
+    Ok(())
+}
+
+async fn list(client: &ApiClient) -> Result<()> {
+    let todos = time_operation!(
+        client.list_todos(None, None).await?,
+        "Fetch todos from server"
+    );
+
+    // Only count tags on todos that still exist on the server
+    let state = LocalState::load()?;
+    let counts = state.tag_counts(todos.iter().map(|t| t.id.as_str()));
+
+    if counts.is_empty() {
+        println!("{}", "No tags found".yellow());
+        return Ok(());
+    }
+
+    println!("{}", format!("Found {} tag(s):", counts.len()).bold());
+    println!();
+    for (tag, count) in counts {
+        println!(
+            "  {} {}",
+            format!("#{tag}").magenta(),
+            format!("({count})").dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+fn rename(old: &str, new: &str) -> Result<()> {
+    let new = new.trim().trim_start_matches('#');
+    if new.is_empty() {
+        anyhow::bail!("New tag name cannot be empty");
+    }
+
+    let mut state = LocalState::load()?;
+    let changed = state.rename_tag(old, new);
+    if changed == 0 {
+        anyhow::bail!("No todos are tagged '{old}'");
+    }
+    state.save()?;
+    activity::record(&ActivityEntry::new(
+        Source::Cli,
+        format!("rename tag {old} -> {new}"),
+    ));
+
+    println!(
+        "{} Renamed '{}' to '{}' on {} todo(s)",
+        term::symbols().ok.green(),
+        old.magenta(),
+        new.magenta(),
+        changed
+    );
+
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use crate::{
+    activity::{self, ActivityEntry, Source},
+    api::{ApiClient, Todo},
+    cli::utils::{local_day_start, resolve_partial_id},
+    state::{format_duration, LocalState, TimeEntry},
+    term, ID_DISPLAY_LENGTH,
+};
+use anyhow::{Context, Result};
+use chrono::{Datelike, Days, Local, Utc};
+use colored::Colorize;
+
+fn short_id(id: &str) -> &str {
+    &id[..ID_DISPLAY_LENGTH.min(id.len())]
+}
+
+/// Returns the title of `id`, falling back to its short ID if it can't be fetched
+async fn title_of(client: &ApiClient, id: &str) -> String {
+    client
+        .get_todo(id)
+        .await
+        .map_or_else(|_| format!("[{}]", short_id(id)), |todo| todo.title)
+}
+
+/// Starts tracking time on a todo, stopping any timer that is already running
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The todo ID cannot be resolved
+/// - Network request fails
+/// - Local state cannot be loaded or saved
+pub async fn start(client: &ApiClient, id: String) -> Result<()> {
+    let full_id = resolve_partial_id(&id, client)
+        .await
+        .context(format!("Failed to resolve ID '{id}'"))?;
+    let todo = client.get_todo(&full_id).await?;
+
+    let mut state = LocalState::load()?;
+    let now = Utc::now().timestamp();
+
+    if state.is_timer_running(&full_id) {
+        println!(
+            "{} Timer already running on '{}' ({})",
+            term::symbols().info.cyan(),
+            todo.title.bold(),
+            format_duration(state.tracked_seconds(&full_id, now))
+        );
+        return Ok(());
+    }
+
+    let stopped = state.start_timer(&full_id, now);
+    state.save()?;
+    activity::record(&ActivityEntry::new(Source::Cli, "start timer").with_todo(&todo));
+
+    if let Some((stopped_id, entry)) = stopped {
+        println!(
+            "{} Stopped timer on '{}' after {}",
+            term::symbols().ok.green(),
+            title_of(client, &stopped_id).await.bold(),
+            format_duration(entry.seconds())
+        );
+    }
+
+    println!(
+        "{} Started timer on '{}'",
+        term::symbols().ok.green(),
+        todo.title.bold()
+    );
+
+    Ok(())
+}
+
+/// Stops the running timer and records the work interval
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - No timer is running
+/// - Local state cannot be loaded or saved
+pub async fn stop(client: &ApiClient) -> Result<()> {
+    let mut state = LocalState::load()?;
+    let now = Utc::now().timestamp();
+
+    let Some((todo_id, entry)) = state.stop_timer(now) else {
+        anyhow::bail!("No timer is running. Start one with 'pacli start <id>'.");
+    };
+    state.save()?;
+
+    let title = title_of(client, &todo_id).await;
+    activity::record(
+        &ActivityEntry::new(Source::Cli, "stop timer")
+            .with_id(&todo_id)
+            .with_title(&title),
+    );
+
+    println!(
+        "{} Stopped timer on '{}' after {} (total: {})",
+        term::symbols().ok.green(),
+        title.bold(),
+        format_duration(entry.seconds()),
+        format_duration(state.tracked_seconds(&todo_id, now)).cyan()
+    );
+
+    Ok(())
+}
+
+/// Prints tracked time per day and todo for today or the current week
+///
+/// # Errors
+///
+/// Returns an error if local state cannot be loaded
+pub async fn timesheet(client: &ApiClient, week: bool) -> Result<()> {
+    let today = Local::now().date_naive();
+    let (first_day, days) = if week {
+        let monday = today - Days::new(u64::from(today.weekday().num_days_from_monday()));
+        (monday, 7)
+    } else {
+        (today, 1)
+    };
+
+    let state = LocalState::load()?;
+    let now = Utc::now().timestamp();
+
+    // Titles are nice to have - the timesheet itself is purely local
+    let todos: Vec<Todo> = client.list_todos(None, None).await.unwrap_or_else(|err| {
+        log::warn!("Could not fetch todo titles: {err}");
+        Vec::new()
+    });
+    let title_of = |id: &str| {
+        todos
+            .iter()
+            .find(|todo| todo.id == id)
+            .map_or_else(|| "(unknown todo)".to_string(), |todo| todo.title.clone())
+    };
+
+    if week {
+        println!(
+            "{}",
+            format!("Timesheet for week of {}", first_day.format("%Y-%m-%d")).bold()
+        );
+    } else {
+        println!(
+            "{}",
+            format!("Timesheet for {}", first_day.format("%Y-%m-%d")).bold()
+        );
+    }
+    println!();
+
+    let mut total = 0;
+    for offset in 0..days {
+        let day = first_day + Days::new(offset);
+        let (Some(from), Some(to)) = (local_day_start(day), local_day_start(day + Days::new(1)))
+        else {
+            continue;
+        };
+
+        let mut entries = state.entries_between(from, to);
+        if let Some(timer) = &state.active_timer {
+            if timer.started_at >= from && timer.started_at < to {
+                entries.push((
+                    timer.todo_id.as_str(),
+                    TimeEntry {
+                        start: timer.started_at,
+                        end: now,
+                    },
+                ));
+            }
+        }
+
+        if entries.is_empty() {
+            continue;
+        }
+
+        // Sum per todo, keeping the order in which work started
+        let mut per_todo: Vec<(&str, i64)> = Vec::new();
+        for (id, entry) in entries {
+            match per_todo.iter_mut().find(|(todo_id, _)| *todo_id == id) {
+                Some((_, seconds)) => *seconds += entry.seconds(),
+                None => per_todo.push((id, entry.seconds())),
+            }
+        }
+
+        let day_total: i64 = per_todo.iter().map(|(_, seconds)| seconds).sum();
+        total += day_total;
+
+        println!(
+            "{}  {}",
+            day.format("%a %Y-%m-%d").to_string().bold(),
+            format_duration(day_total).cyan()
+        );
+        for (id, seconds) in per_todo {
+            println!(
+                "  {} {} {}",
+                format!("[{}]", short_id(id)).cyan(),
+                title_of(id),
+                format_duration(seconds).dimmed()
+            );
+        }
+        println!();
+    }
+
+    if total == 0 {
+        println!("{}", "No time tracked in this period".yellow());
+    } else {
+        println!(
+            "{} {}",
+            "Total:".bold(),
+            format_duration(total).green().bold()
+        );
+    }
+
+    Ok(())
+}
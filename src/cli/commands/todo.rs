@@ -1,12 +1,27 @@
 use crate::{
-    api::{ApiClient, CreateTodoRequest, Todo, UpdateTodoRequest},
-    cli::utils::resolve_partial_id,
-    time_operation, ID_DISPLAY_LENGTH,
+    activity::{self, ActivityEntry, Source},
+    api::{ApiClient, Capabilities, CreateTodoRequest, Todo, UpdateTodoRequest},
+    cache, checklist,
+    cli::{
+        commands::{attachment::print_attachments, comment::print_comments, escalate},
+        types::{DateFilters, GroupBy},
+        utils::{confirm, local_day_start, parse_duration_secs, resolve_partial_id},
+    },
+    config::{Config, PriorityLabels},
+    due::DueBucket,
+    highlight::{self, ColorRule},
+    markdown,
+    search::Query,
+    sort::SortSpec,
+    state::{format_duration, parse_tags, LocalState},
+    term, time_operation, ID_DISPLAY_LENGTH,
 };
 use anyhow::{Context, Result};
-use chrono::{Local, NaiveDateTime, TimeZone, Utc};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use colored::{ColoredString, Colorize};
+use futures_util::future::try_join_all;
 use pali_types::priority;
+use std::collections::BTreeSet;
 
 fn format_due_date(due_ts: i64) -> Option<ColoredString> {
     let due_dt = Utc.timestamp_opt(due_ts, 0).latest()?;
@@ -27,6 +42,36 @@ fn format_due_date(due_ts: i64) -> Option<ColoredString> {
     }
 }
 
+/// Returns the local calendar day a todo is due on, if it has a due date
+pub(crate) fn due_local_date(todo: &Todo) -> Option<NaiveDate> {
+    let due_dt = Utc.timestamp_opt(todo.due_date?, 0).latest()?;
+    Some(due_dt.with_timezone(&Local).date_naive())
+}
+
+/// A todo is overdue when it is pending and its due day is before `today`
+pub(crate) fn is_overdue(todo: &Todo, today: NaiveDate) -> bool {
+    !todo.completed && due_local_date(todo).is_some_and(|due| due < today)
+}
+
+/// Parses a day specifier into a local date
+///
+/// Supports `today`, `tomorrow`, `yesterday` (case insensitive) and `YYYY-MM-DD`.
+///
+/// # Errors
+/// Returns an error if the string is neither a keyword nor a valid date
+pub fn parse_day(day_str: &str) -> Result<NaiveDate> {
+    let today = Local::now().date_naive();
+
+    match day_str.to_lowercase().as_str() {
+        "today" => Ok(today),
+        "tomorrow" => Ok(today + chrono::Days::new(1)),
+        "yesterday" => Ok(today - chrono::Days::new(1)),
+        other => NaiveDate::parse_from_str(other, "%Y-%m-%d").map_err(|_| {
+            anyhow::anyhow!("Invalid day '{day_str}'. Use today, tomorrow, yesterday or YYYY-MM-DD")
+        }),
+    }
+}
+
 /// Adds a new todo item with the specified details
 ///
 /// # Errors
@@ -37,18 +82,19 @@ fn format_due_date(due_ts: i64) -> Option<ColoredString> {
 /// - Server returns an error response
 /// - API key is missing or invalid
 pub async fn add(
+    client: &ApiClient,
     title: String,
     description: Option<String>,
     due: Option<String>,
     priority: Option<String>,
-    _tags: Option<String>,
+    tags: Option<String>,
 ) -> Result<()> {
-    let client = ApiClient::new()?;
-
     let due_timestamp = due.map(|d| parse_date(&d)).transpose()?;
 
-    let priority_int = priority.map(|p| parse_priority(&p));
+    let labels = client.config().priority_labels.clone();
+    let priority_int = priority.map(|p| parse_priority(&labels, &p));
 
+    let entry = ActivityEntry::new(Source::Cli, "add").with_title(&title);
     let request = CreateTodoRequest {
         title,
         description,
@@ -56,11 +102,15 @@ pub async fn add(
         due_date: due_timestamp,
     };
 
-    let todo = client.create_todo(request).await?;
+    let todo = activity::track_todo(entry, client.create_todo(request).await)?;
+
+    if let Some(tags) = tags {
+        save_tags(&todo.id, &tags)?;
+    }
 
     println!(
         "{} Created todo: {} (ID: {})",
-        "✓".green(),
+        term::symbols().ok.green(),
         todo.title.bold(),
         todo.id.cyan()
     );
@@ -68,6 +118,13 @@ pub async fn add(
     Ok(())
 }
 
+/// Replaces the local tags of `id` with the comma-separated `tags`
+fn save_tags(id: &str, tags: &str) -> Result<()> {
+    let mut state = LocalState::load()?;
+    state.set_tags(id, parse_tags(tags));
+    state.save()
+}
+
 /// Parses a date string into a Unix timestamp
 ///
 /// Supports two formats:
@@ -98,40 +155,126 @@ pub fn parse_date(date_str: &str) -> Result<i64> {
 /// - "low" → 1
 /// - "medium" → 2  
 /// - "high" → 3
+/// - configured labels and aliases (see [`PriorityLabels`])
 ///
 /// Any other value defaults to medium priority (2)
 #[must_use]
-pub fn parse_priority(priority_str: &str) -> i32 {
-    match priority_str.to_lowercase().as_str() {
-        "low" => priority::LOW,
-        "high" => priority::HIGH,
-        _ => priority::MEDIUM,
+pub fn parse_priority(labels: &PriorityLabels, priority_str: &str) -> i32 {
+    labels.parse(priority_str).unwrap_or(priority::MEDIUM)
+}
+
+/// Translates a `--priority` filter to a priority level
+///
+/// Filters are applied locally (to the possibly cached list), so unknown
+/// values are an error rather than being passed on to the server.
+fn priority_filter(labels: &PriorityLabels, priority_str: &str) -> Result<i32> {
+    labels
+        .parse(priority_str)
+        .ok_or_else(|| anyhow::anyhow!("Unknown priority '{priority_str}'"))
+}
+
+/// Which todos `pacli list` shows
+#[derive(Debug, Default)]
+pub struct ListFilters {
+    /// Include completed todos
+    pub all: bool,
+    /// Show only archived todos
+    pub archived: bool,
+    pub tag: Option<String>,
+    /// Priority name or level, parsed with the configured labels
+    pub priority: Option<String>,
+    pub dates: DateFilters,
+}
+
+/// Local data used to decorate todos when printing them
+struct PrintContext {
+    state: LocalState,
+    rules: Vec<ColorRule>,
+    labels: PriorityLabels,
+}
+
+impl PrintContext {
+    /// Loads local state (falling back to empty state) and takes display settings from `config`
+    fn load(config: &Config) -> Self {
+        Self {
+            state: LocalState::load().unwrap_or_default(),
+            rules: highlight::parse_rules(&config.color_rules),
+            labels: config.priority_labels.clone(),
+        }
+    }
+}
+
+fn colored_priority(labels: &PriorityLabels, level: i32) -> ColoredString {
+    let label = labels.label(level);
+    match level {
+        p if p == priority::HIGH => label.red(),
+        p if p == priority::MEDIUM => label.yellow(),
+        p if p == priority::LOW => label.blue(),
+        _ => label.normal(),
     }
 }
 
 /// Lists todos with optional filtering by completion status, tag, and priority
 ///
+/// Archived todos are hidden unless `filters.archived` is set, in which case only
+/// archived todos (completed or not) are shown. `sort` is a spec such as
+/// `priority,due` (see [`SortSpec`]); an empty one keeps the server's order.
+/// `group_by` prints the todos in headed sections. `dates` narrows the list
+/// by due date or completion; `--completed-after` implies completed todos.
+/// Servers reporting [`Capabilities::DUE_FILTER`] are asked for due windows
+/// directly instead of the whole (cached) list.
+/// The configured escalation rules are applied first, with a report of
+/// what they changed.
+///
 /// # Errors
 ///
 /// Returns an error if:
 /// - Network request fails
 /// - Server returns an error response
 /// - API key is missing or invalid
-pub async fn list(all: bool, tag: Option<String>, priority: Option<String>) -> Result<()> {
+pub async fn list(
+    client: &ApiClient,
+    filters: ListFilters,
+    sort: &SortSpec,
+    group_by: Option<GroupBy>,
+) -> Result<()> {
+    let ListFilters {
+        all,
+        archived,
+        tag,
+        priority,
+        dates,
+    } = filters;
     log::info!("Loading configuration and connecting to server");
-    let client = ApiClient::new()?;
+    escalate::escalate_quietly(client).await;
+
+    // Loaded after escalating, which may have added tags
+    let ctx = PrintContext::load(client.config());
+    let priority = priority
+        .map(|p| priority_filter(&ctx.labels, &p))
+        .transpose()?;
 
-    log::info!("Fetching todos from server (all={all}, tag={tag:?}, priority={priority:?})");
+    log::info!("Fetching todos (all={all}, tag={tag:?}, priority={priority:?}, {dates:?})");
     let todos = time_operation!(
-        client.list_todos(tag, priority).await?,
-        "Fetch todos from server"
+        fetch_for_list(client, priority, &dates).await?,
+        "Fetch todos"
     );
 
-    let filtered_todos: Vec<_> = if all {
-        todos
-    } else {
-        todos.into_iter().filter(|t| !t.completed).collect()
-    };
+    // Tags and archiving are client-side metadata, so those filters are applied locally
+    let today = Local::now().date_naive();
+    let all = all || dates.completed_after.is_some();
+    let mut filtered_todos: Vec<_> = todos
+        .iter()
+        .filter(|t| ctx.state.is_archived(&t.id) == archived)
+        .filter(|t| all || archived || !t.completed)
+        .filter(|t| {
+            tag.as_deref()
+                .is_none_or(|tag| ctx.state.has_tag(&t.id, tag))
+        })
+        .filter(|t| dates.matches(t, today))
+        .collect();
+    sort.sort(&mut filtered_todos);
+    ctx.state.pinned_first(&mut filtered_todos);
 
     if filtered_todos.is_empty() {
         println!("{}", "No todos found".yellow());
@@ -144,37 +287,285 @@ pub async fn list(all: bool, tag: Option<String>, priority: Option<String>) -> R
     );
     println!();
 
-    for todo in filtered_todos {
-        print_todo(&todo);
+    let Some(group_by) = group_by else {
+        for todo in filtered_todos {
+            print_todo(todo, &ctx, &todos);
+            println!();
+        }
+        return Ok(());
+    };
+
+    for (heading, group) in group_todos(&filtered_todos, group_by, &ctx) {
+        println!(
+            "{} {}",
+            heading.bold().underline(),
+            format!("({})", group.len()).dimmed()
+        );
         println!();
+        for todo in group {
+            print_todo(todo, &ctx, &todos);
+            println!();
+        }
     }
 
     Ok(())
 }
 
-/// Retrieves and displays a specific todo by ID
+/// Fetches the todos `pacli list` filters, asking the server for the due window when it can
+///
+/// The server's answer is filtered again locally, so it only needs to be a superset.
+async fn fetch_for_list(
+    client: &ApiClient,
+    priority: Option<i32>,
+    dates: &DateFilters,
+) -> Result<Vec<Todo>> {
+    let window = dates.due_before.is_some() || dates.due_after.is_some();
+    if !window
+        || !client
+            .capabilities()
+            .await
+            .supports(Capabilities::DUE_FILTER)
+    {
+        return cache::list_todos(client, priority).await;
+    }
+
+    // Due after a day means due from the start of the next one
+    let after = dates
+        .due_after
+        .and_then(|day| local_day_start(day + chrono::Days::new(1)));
+    let before = dates.due_before.and_then(local_day_start);
+    let todos = client.list_todos_due(after, before).await?;
+    Ok(match priority {
+        Some(priority) => todos
+            .into_iter()
+            .filter(|t| t.priority == priority)
+            .collect(),
+        None => todos,
+    })
+}
+
+/// Splits `todos` into headed groups, keeping their order within each group
+///
+/// Empty groups are left out. With tags, a todo with several tags appears in
+/// each of their groups, and untagged todos come last.
+fn group_todos<'a>(
+    todos: &[&'a Todo],
+    group_by: GroupBy,
+    ctx: &PrintContext,
+) -> Vec<(String, Vec<&'a Todo>)> {
+    let collect = |pred: &dyn Fn(&Todo) -> bool| -> Vec<&'a Todo> {
+        todos.iter().copied().filter(|todo| pred(todo)).collect()
+    };
+
+    let groups: Vec<(String, Vec<&Todo>)> = match group_by {
+        GroupBy::Priority => {
+            let mut levels: Vec<i32> = todos.iter().map(|todo| todo.priority).collect();
+            levels.sort_unstable_by(|a, b| b.cmp(a));
+            levels.dedup();
+            levels
+                .into_iter()
+                .map(|level| {
+                    (
+                        ctx.labels.label(level).to_string(),
+                        collect(&|todo| todo.priority == level),
+                    )
+                })
+                .collect()
+        }
+        GroupBy::Due => {
+            let today = Local::now().date_naive();
+            DueBucket::ALL
+                .into_iter()
+                .map(|bucket| {
+                    (
+                        bucket.label().to_string(),
+                        collect(&|todo| DueBucket::of(todo, today) == bucket),
+                    )
+                })
+                .collect()
+        }
+        GroupBy::Tag => {
+            let tags: BTreeSet<&String> = todos
+                .iter()
+                .flat_map(|todo| ctx.state.tags_of(&todo.id))
+                .collect();
+            tags.into_iter()
+                .map(|tag| {
+                    (
+                        format!("#{tag}"),
+                        collect(&|todo| ctx.state.tags_of(&todo.id).contains(tag)),
+                    )
+                })
+                .chain([(
+                    "Untagged".to_string(),
+                    collect(&|todo| ctx.state.tags_of(&todo.id).is_empty()),
+                )])
+                .collect()
+        }
+    };
+
+    groups
+        .into_iter()
+        .filter(|(_, group)| !group.is_empty())
+        .collect()
+}
+
+/// Counts todos matching the given filters
+///
+/// Prints a bare integer so the output can be embedded in shell prompts and
+/// scripts. With `json` set, prints an object with a breakdown by status and
+/// priority instead.
 ///
 /// # Errors
 ///
 /// Returns an error if:
+/// - Invalid day format provided
 /// - Network request fails
-/// - Todo with the given ID is not found
 /// - Server returns an error response
 /// - API key is missing or invalid
-pub async fn get(id: String) -> Result<()> {
-    let client = ApiClient::new()?;
+pub async fn count(
+    client: &ApiClient,
+    all: bool,
+    overdue: bool,
+    due: Option<String>,
+    priority: Option<String>,
+    tag: Option<String>,
+    json: bool,
+) -> Result<()> {
+    let due_day = due.map(|d| parse_day(&d)).transpose()?;
+
+    let labels = client.config().priority_labels.clone();
+    let priority = priority.map(|p| priority_filter(&labels, &p)).transpose()?;
+    let todos = time_operation!(cache::list_todos(client, priority).await?, "Fetch todos");
+
+    let state = LocalState::load().unwrap_or_default();
+    let today = Local::now().date_naive();
+    let matching: Vec<_> = todos
+        .into_iter()
+        .filter(|t| !state.is_archived(&t.id))
+        .filter(|t| all || !t.completed)
+        .filter(|t| tag.as_deref().is_none_or(|tag| state.has_tag(&t.id, tag)))
+        .filter(|t| !overdue || is_overdue(t, today))
+        .filter(|t| due_day.is_none_or(|day| due_local_date(t) == Some(day)))
+        .collect();
+
+    if json {
+        let with_priority = |p: i32| matching.iter().filter(|t| t.priority == p).count();
+        let output = serde_json::json!({
+            "count": matching.len(),
+            "pending": matching.iter().filter(|t| !t.completed).count(),
+            "completed": matching.iter().filter(|t| t.completed).count(),
+            "overdue": matching.iter().filter(|t| is_overdue(t, today)).count(),
+            "due_today": matching
+                .iter()
+                .filter(|t| due_local_date(t) == Some(today))
+                .count(),
+            "priority": {
+                "high": with_priority(priority::HIGH),
+                "medium": with_priority(priority::MEDIUM),
+                "low": with_priority(priority::LOW),
+            },
+        });
+        println!("{output}");
+    } else {
+        println!("{}", matching.len());
+    }
 
-    // Resolve partial ID to full ID
-    let full_id = resolve_partial_id(&id, &client)
-        .await
-        .context(format!("Failed to resolve ID '{id}'"))?;
+    Ok(())
+}
 
-    let todo = client.get_todo(&full_id).await?;
+/// Retrieves and displays todos by ID, resolving prefixes and fetching in parallel
+///
+/// A todo named more than once (e.g. by two prefixes) is shown once. With
+/// `render_markdown`, descriptions are shown with their markdown styled.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Network request fails
+/// - A todo with one of the given IDs is not found
+/// - Server returns an error response
+/// - API key is missing or invalid
+pub async fn get(
+    client: &ApiClient,
+    ids: &[String],
+    json: bool,
+    render_markdown: bool,
+) -> Result<()> {
+    let mut full_ids = try_join_all(ids.iter().map(|id| async move {
+        resolve_partial_id(id, client)
+            .await
+            .with_context(|| format!("Failed to resolve ID '{id}'"))
+    }))
+    .await?;
+    let mut seen = BTreeSet::new();
+    full_ids.retain(|id| seen.insert(id.clone()));
+
+    let todos = try_join_all(full_ids.iter().map(|id| client.get_todo(id))).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&todos)?);
+        return Ok(());
+    }
 
+    let labels = client.config().priority_labels.clone();
+    let state = LocalState::load().unwrap_or_default();
+    for (i, todo) in todos.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        print_details(client, todo, &state, &labels, render_markdown).await;
+    }
+    Ok(())
+}
+
+/// Prints a todo with its tags, tracked time, attachments and comments
+async fn print_details(
+    client: &ApiClient,
+    todo: &Todo,
+    state: &LocalState,
+    labels: &PriorityLabels,
+    render_markdown: bool,
+) {
     println!("{}", "Todo Details:".bold());
-    print_todo_detailed(&todo);
+    print_todo_detailed(todo, labels, render_markdown);
 
-    Ok(())
+    if let Some(alias) = state.alias_of(&todo.id) {
+        println!("  {} {alias}", "Alias:".cyan());
+    }
+
+    let tags = state.tags_of(&todo.id);
+    if !tags.is_empty() {
+        println!("  {} {}", "Tags:".cyan(), tags.join(", "));
+    }
+
+    let tracked = state.tracked_seconds(&todo.id, Utc::now().timestamp());
+    if tracked > 0 {
+        print!("  {} {}", "Time tracked:".cyan(), format_duration(tracked));
+        if state.is_timer_running(&todo.id) {
+            print!(" {}", "(timer running)".green());
+        }
+        println!();
+    }
+
+    // Attachments are optional server functionality - don't fail `get` over them
+    match client.list_attachments(&todo.id).await {
+        Ok(attachments) if !attachments.is_empty() => {
+            println!("  {}", "Attachments:".cyan());
+            print_attachments(&attachments);
+        }
+        Ok(_) => {}
+        Err(err) => log::debug!("Could not fetch attachments: {err}"),
+    }
+
+    match client.list_comments(&todo.id).await {
+        Ok(comments) if !comments.is_empty() => {
+            println!("  {}", format!("Comments ({}):", comments.len()).cyan());
+            print_comments(&comments);
+        }
+        Ok(_) => {}
+        Err(err) => log::debug!("Could not fetch comments: {err}"),
+    }
 }
 
 /// Updates an existing todo item with new values
@@ -188,23 +579,23 @@ pub async fn get(id: String) -> Result<()> {
 /// - Server returns an error response
 /// - API key is missing or invalid
 pub async fn update(
+    client: &ApiClient,
     id: String,
     title: Option<String>,
     description: Option<String>,
     due: Option<String>,
     priority: Option<String>,
-    _tags: Option<String>,
+    tags: Option<String>,
 ) -> Result<()> {
-    let client = ApiClient::new()?;
-
     // Resolve partial ID to full ID
-    let full_id = resolve_partial_id(&id, &client)
+    let full_id = resolve_partial_id(&id, client)
         .await
         .context(format!("Failed to resolve ID '{id}'"))?;
 
     let due_timestamp = due.map(|d| parse_date(&d)).transpose()?;
 
-    let priority_int = priority.map(|p| parse_priority(&p));
+    let labels = client.config().priority_labels.clone();
+    let priority_int = priority.map(|p| parse_priority(&labels, &p));
 
     let request = UpdateTodoRequest {
         title,
@@ -214,13 +605,263 @@ pub async fn update(
         due_date: due_timestamp,
     };
 
-    let todo = client.update_todo(&full_id, request).await?;
+    let todo = activity::track_todo(
+        ActivityEntry::new(Source::Cli, "update").with_id(&full_id),
+        client.update_todo(&full_id, request).await,
+    )?;
 
-    println!("{} Updated todo: {}", "✓".green(), todo.title.bold());
+    if let Some(tags) = tags {
+        save_tags(&todo.id, &tags)?;
+    }
+
+    println!(
+        "{} Updated todo: {}",
+        term::symbols().ok.green(),
+        todo.title.bold()
+    );
 
     Ok(())
 }
 
+/// One condition of a `--where` expression; all conditions must match
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    Tag(String),
+    Priority(i32),
+    Due(NaiveDate),
+    TitleContains(String),
+    Overdue,
+    Open,
+    Completed,
+}
+
+impl Condition {
+    fn parse(term: &str, labels: &PriorityLabels) -> Result<Self> {
+        let (key, value) = term.split_once(':').unwrap_or((term, ""));
+        match (key.to_lowercase().as_str(), value) {
+            ("tag", tag) if !tag.is_empty() => {
+                Ok(Self::Tag(tag.trim_start_matches('#').to_string()))
+            }
+            ("priority", level) => priority_filter(labels, level).map(Self::Priority),
+            ("due", day) => parse_day(day).map(Self::Due),
+            ("title", text) if !text.is_empty() => Ok(Self::TitleContains(text.to_lowercase())),
+            ("overdue", "") => Ok(Self::Overdue),
+            ("open" | "pending", "") => Ok(Self::Open),
+            ("completed" | "done", "") => Ok(Self::Completed),
+            _ => anyhow::bail!(
+                "Unknown condition '{term}'. Use tag:<name>, priority:<level>, due:<day>, title:<text>, overdue, open or completed"
+            ),
+        }
+    }
+
+    fn matches(&self, todo: &Todo, state: &LocalState, today: NaiveDate) -> bool {
+        match self {
+            Self::Tag(tag) => state.has_tag(&todo.id, tag),
+            Self::Priority(level) => todo.priority == *level,
+            Self::Due(day) => due_local_date(todo) == Some(*day),
+            Self::TitleContains(text) => todo.title.to_lowercase().contains(text),
+            Self::Overdue => is_overdue(todo, today),
+            Self::Open => !todo.completed,
+            Self::Completed => todo.completed,
+        }
+    }
+}
+
+/// Parses a `--where` expression such as `tag:home priority:low`
+fn parse_conditions(filter: &str, labels: &PriorityLabels) -> Result<Vec<Condition>> {
+    let conditions = filter
+        .split_whitespace()
+        .map(|term| Condition::parse(term, labels))
+        .collect::<Result<Vec<_>>>()?;
+    if conditions.is_empty() {
+        anyhow::bail!("The --where expression is empty");
+    }
+    Ok(conditions)
+}
+
+/// A new due date from `--set due=...`: absolute, or relative to the current one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DueChange {
+    At(i64),
+    /// Seconds to move the due date by; todos without one count from now
+    Shift(i64),
+}
+
+/// The changes from the `--set field=value` arguments of a bulk update
+#[derive(Debug, Default, PartialEq, Eq)]
+struct BulkChanges {
+    title: Option<String>,
+    description: Option<String>,
+    priority: Option<i32>,
+    due: Option<DueChange>,
+    completed: Option<bool>,
+    tags: Option<String>,
+}
+
+impl BulkChanges {
+    fn parse(assignments: &[String], labels: &PriorityLabels) -> Result<Self> {
+        let mut changes = Self::default();
+        for assignment in assignments {
+            let Some((field, value)) = assignment.split_once('=') else {
+                anyhow::bail!("Invalid --set '{assignment}'. Expected <field>=<value>");
+            };
+            let value = value.trim();
+            match field.trim().to_lowercase().as_str() {
+                "title" if !value.is_empty() => changes.title = Some(value.to_string()),
+                "description" => changes.description = Some(value.to_string()),
+                "priority" => changes.priority = Some(priority_filter(labels, value)?),
+                "due" => {
+                    changes.due = Some(if let Some(shift) = value.strip_prefix('+') {
+                        DueChange::Shift(parse_duration_secs(shift)?)
+                    } else if let Some(shift) = value.strip_prefix('-') {
+                        DueChange::Shift(-parse_duration_secs(shift)?)
+                    } else {
+                        DueChange::At(parse_date(value)?)
+                    });
+                }
+                "completed" => {
+                    changes.completed = Some(match value.to_lowercase().as_str() {
+                        "true" | "yes" => true,
+                        "false" | "no" => false,
+                        _ => anyhow::bail!(
+                            "Invalid value '{value}' for completed. Use true or false"
+                        ),
+                    });
+                }
+                "tags" => changes.tags = Some(value.to_string()),
+                other => anyhow::bail!(
+                    "Cannot set '{other}'. Use title, description, priority, due, completed or tags"
+                ),
+            }
+        }
+        Ok(changes)
+    }
+
+    /// The server-side part of the changes for `todo`, or `None` if only tags change
+    fn request_for(&self, todo: &Todo, now: i64) -> Option<UpdateTodoRequest> {
+        let due_date = self.due.map(|due| match due {
+            DueChange::At(at) => at,
+            DueChange::Shift(secs) => todo.due_date.unwrap_or(now) + secs,
+        });
+        let request = UpdateTodoRequest {
+            title: self.title.clone(),
+            description: self.description.clone(),
+            completed: self.completed,
+            priority: self.priority,
+            due_date,
+        };
+        let changes_server_fields = request.title.is_some()
+            || request.description.is_some()
+            || request.completed.is_some()
+            || request.priority.is_some()
+            || request.due_date.is_some();
+        changes_server_fields.then_some(request)
+    }
+}
+
+/// Updates every todo matching a `--where` expression with the `--set` changes
+///
+/// Archived todos are never matched, and completed ones only with the
+/// `completed` condition. The matching todos are listed and, unless `yes`
+/// is set, the user is asked to confirm. Todos are updated one at a time;
+/// failures are reported and the rest still get updated.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The expression or a `--set` argument is invalid
+/// - Confirmation is needed but input is disabled
+/// - Network request fails
+/// - Local state cannot be loaded or saved
+/// - Any of the updates failed
+pub async fn update_where(
+    client: &ApiClient,
+    filter: String,
+    set: Vec<String>,
+    yes: bool,
+) -> Result<()> {
+    let labels = client.config().priority_labels.clone();
+    let conditions = parse_conditions(&filter, &labels)?;
+    let changes = BulkChanges::parse(&set, &labels)?;
+
+    let todos = time_operation!(cache::list_todos(client, None).await?, "Fetch todos");
+    let mut state = LocalState::load()?;
+    let today = Local::now().date_naive();
+    let include_completed = conditions.contains(&Condition::Completed);
+    let matching: Vec<&Todo> = todos
+        .iter()
+        .filter(|t| !state.is_archived(&t.id))
+        .filter(|t| include_completed || !t.completed)
+        .filter(|t| conditions.iter().all(|c| c.matches(t, &state, today)))
+        .collect();
+
+    if matching.is_empty() {
+        println!("{}", "No todos match".yellow());
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!(
+            "{} todo(s) will be updated ({}):",
+            matching.len(),
+            set.join(", ")
+        )
+        .bold()
+    );
+    for todo in &matching {
+        println!(
+            "  {} {}",
+            format!("[{}]", &todo.id[..ID_DISPLAY_LENGTH.min(todo.id.len())]).cyan(),
+            todo.title
+        );
+    }
+    println!();
+    if !yes {
+        crate::prompt::require("confirm updating several todos (pass --yes to skip it)")?;
+        if !confirm("Update these todos?")? {
+            println!("{} Nothing updated", term::symbols().info.cyan());
+            return Ok(());
+        }
+    }
+
+    let now = Utc::now().timestamp();
+    let mut failed = 0;
+    for todo in matching {
+        if let Some(request) = changes.request_for(todo, now) {
+            let result = activity::track_todo(
+                ActivityEntry::new(Source::Cli, "update").with_id(&todo.id),
+                client.update_todo(&todo.id, request).await,
+            );
+            if let Err(err) = result {
+                failed += 1;
+                println!(
+                    "{} Failed to update '{}': {err}",
+                    term::symbols().warning.yellow(),
+                    todo.title
+                );
+                continue;
+            }
+        }
+        if let Some(tags) = &changes.tags {
+            state.set_tags(&todo.id, parse_tags(tags));
+        }
+        println!(
+            "{} Updated todo: {}",
+            term::symbols().ok.green(),
+            todo.title.bold()
+        );
+    }
+    if changes.tags.is_some() {
+        state.save()?;
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{failed} todo(s) could not be updated");
+    }
+    Ok(())
+}
+
 /// Deletes a todo item by ID
 ///
 /// # Errors
@@ -230,17 +871,22 @@ pub async fn update(
 /// - Todo with the given ID is not found
 /// - Server returns an error response
 /// - API key is missing or invalid
-pub async fn delete(id: String) -> Result<()> {
-    let client = ApiClient::new()?;
-
+pub async fn delete(client: &ApiClient, id: String) -> Result<()> {
     // Resolve partial ID to full ID
-    let full_id = resolve_partial_id(&id, &client)
+    let full_id = resolve_partial_id(&id, client)
         .await
         .context(format!("Failed to resolve ID '{id}'"))?;
 
-    client.delete_todo(&full_id).await?;
+    activity::track(
+        ActivityEntry::new(Source::Cli, "delete").with_id(&full_id),
+        client.delete_todo(&full_id).await,
+    )?;
 
-    println!("{} Deleted todo with ID: {}", "✓".green(), id.cyan());
+    println!(
+        "{} Deleted todo with ID: {}",
+        term::symbols().ok.green(),
+        id.cyan()
+    );
 
     Ok(())
 }
@@ -254,15 +900,16 @@ pub async fn delete(id: String) -> Result<()> {
 /// - Todo with the given ID is not found
 /// - Server returns an error response
 /// - API key is missing or invalid
-pub async fn toggle(id: String) -> Result<()> {
-    let client = ApiClient::new()?;
-
+pub async fn toggle(client: &ApiClient, id: String) -> Result<()> {
     // Resolve partial ID to full ID
-    let full_id = resolve_partial_id(&id, &client)
+    let full_id = resolve_partial_id(&id, client)
         .await
         .context(format!("Failed to resolve ID '{id}'"))?;
 
-    let todo = client.toggle_todo(&full_id).await?;
+    let todo = activity::track_todo(
+        ActivityEntry::new(Source::Cli, "toggle").with_id(&full_id),
+        client.toggle_todo(&full_id).await,
+    )?;
 
     let status = if todo.completed {
         "completed"
@@ -271,11 +918,15 @@ pub async fn toggle(id: String) -> Result<()> {
     };
     println!(
         "{} Toggled todo '{}' to {}",
-        "✓".green(),
+        term::symbols().ok.green(),
         todo.title.bold(),
         status.cyan()
     );
 
+    if todo.completed {
+        warn_if_blocked(client, &todo).await;
+    }
+
     Ok(())
 }
 
@@ -288,11 +939,9 @@ pub async fn toggle(id: String) -> Result<()> {
 /// - Todo with the given ID is not found
 /// - Server returns an error response
 /// - API key is missing or invalid
-pub async fn complete(id: String) -> Result<()> {
-    let client = ApiClient::new()?;
-
+pub async fn complete(client: &ApiClient, id: String) -> Result<()> {
     // Resolve partial ID to full ID
-    let full_id = resolve_partial_id(&id, &client)
+    let full_id = resolve_partial_id(&id, client)
         .await
         .context(format!("Failed to resolve ID '{id}'"))?;
 
@@ -304,9 +953,17 @@ pub async fn complete(id: String) -> Result<()> {
         priority: None,
     };
 
-    let todo = client.update_todo(&full_id, request).await?;
+    let todo = activity::track_todo(
+        ActivityEntry::new(Source::Cli, "complete").with_id(&full_id),
+        client.update_todo(&full_id, request).await,
+    )?;
 
-    println!("{} Marked '{}' as complete", "✓".green(), todo.title.bold());
+    println!(
+        "{} Marked '{}' as complete",
+        term::symbols().ok.green(),
+        todo.title.bold()
+    );
+    warn_if_blocked(client, &todo).await;
 
     Ok(())
 }
@@ -316,12 +973,23 @@ pub async fn complete(id: String) -> Result<()> {
 /// # Errors
 ///
 /// Returns an error if:
+/// - `regex` is set and the query is not a valid regular expression
 /// - Network request fails
 /// - Server returns an error response
 /// - API key is missing or invalid
-pub async fn search(query: String) -> Result<()> {
-    let client = ApiClient::new()?;
-    let todos = client.search_todos(&query).await?;
+pub async fn search(client: &ApiClient, query: String, regex: bool) -> Result<()> {
+    let mut todos = if regex {
+        // The server only does text search, so regexes are evaluated here
+        let matcher = Query::new(&query, true)?;
+        let mut todos = cache::list_todos(client, None).await?;
+        todos.retain(|todo| matcher.matches(todo));
+        todos
+    } else {
+        client.search_todos(&query).await?
+    };
+    let ctx = PrintContext::load(client.config());
+    todos.retain(|t| !ctx.state.is_archived(&t.id));
+    ctx.state.pinned_first(&mut todos);
 
     if todos.is_empty() {
         println!("{}", format!("No todos found matching '{query}'").yellow());
@@ -334,35 +1002,81 @@ pub async fn search(query: String) -> Result<()> {
     );
     println!();
 
-    for todo in todos {
-        print_todo(&todo);
+    for todo in &todos {
+        print_todo(todo, &ctx, &todos);
         println!();
     }
 
     Ok(())
 }
 
-fn print_todo(todo: &Todo) {
+/// Warns if `todo` still has prerequisites (see `pacli link`) that are incomplete
+async fn warn_if_blocked(client: &ApiClient, todo: &Todo) {
+    let Ok(state) = LocalState::load() else {
+        return;
+    };
+
+    let mut pending = Vec::new();
+    for blocker_id in state.blockers_of(&todo.id) {
+        if let Ok(blocker) = client.get_todo(blocker_id).await {
+            if !blocker.completed {
+                pending.push(blocker.title);
+            }
+        }
+    }
+
+    if !pending.is_empty() {
+        println!(
+            "{} '{}' is still blocked by: {}",
+            term::symbols().warning.yellow(),
+            todo.title,
+            pending.join(", ")
+        );
+    }
+}
+
+fn print_todo(todo: &Todo, ctx: &PrintContext, todos: &[Todo]) {
     let status = if todo.completed {
-        "✓".green().to_string()
+        term::symbols().done.green().to_string()
     } else {
-        "○".normal().to_string()
+        term::symbols().pending.normal().to_string()
     };
 
+    let title = highlight::style_for(&ctx.rules, todo, ctx.state.tags_of(&todo.id))
+        .map_or_else(|| todo.title.bold(), |style| style.paint(&todo.title));
+
     print!(
-        "{} {} {}",
+        "{} {} ",
         status,
-        format!("[{}]", &todo.id[..ID_DISPLAY_LENGTH]).cyan(),
-        todo.title.bold()
+        format!("[{}]", &todo.id[..ID_DISPLAY_LENGTH]).cyan()
     );
+    if ctx.state.is_pinned(&todo.id) {
+        print!("{} ", term::symbols().pinned.yellow());
+    }
+    print!("{title}");
 
-    let priority_str = match todo.priority {
-        p if p == priority::HIGH => "high".red(),
-        p if p == priority::MEDIUM => "medium".yellow(),
-        p if p == priority::LOW => "low".blue(),
-        _ => "medium".normal(),
-    };
-    print!(" ({priority_str})");
+    if let Some(alias) = ctx.state.alias_of(&todo.id) {
+        print!(" {}", format!("@{alias}").blue());
+    }
+
+    print!(" ({})", colored_priority(&ctx.labels, todo.priority));
+
+    if !todo.completed && ctx.state.is_blocked(&todo.id, todos) {
+        print!(" {}", format!("{} blocked", term::symbols().blocked).red());
+    }
+
+    for tag in ctx.state.tags_of(&todo.id) {
+        print!(" {}", format!("#{tag}").magenta());
+    }
+
+    if let Some((done, total)) = todo.description.as_deref().and_then(checklist::progress) {
+        let progress = format!("{done}/{total}");
+        if done == total {
+            print!(" {}", progress.green());
+        } else {
+            print!(" {}", progress.cyan());
+        }
+    }
 
     if let Some(due_ts) = todo.due_date {
         if let Some(due_str) = format_due_date(due_ts) {
@@ -377,12 +1091,37 @@ fn print_todo(todo: &Todo) {
     }
 }
 
-fn print_todo_detailed(todo: &Todo) {
+fn print_todo_detailed(todo: &Todo, labels: &PriorityLabels, render_markdown: bool) {
     println!("  {} {}", "ID:".cyan(), todo.id);
     println!("  {} {}", "Title:".cyan(), todo.title.bold());
 
     if let Some(desc) = &todo.description {
-        println!("  {} {}", "Description:".cyan(), desc);
+        let text = checklist::strip_items(desc);
+        if render_markdown && !text.is_empty() {
+            println!("  {}", "Description:".cyan());
+            for line in markdown::paint(&text) {
+                println!("    {line}");
+            }
+        } else if !text.is_empty() {
+            println!("  {} {}", "Description:".cyan(), text);
+        }
+
+        let items = checklist::items(desc);
+        if !items.is_empty() {
+            let done = items.iter().filter(|item| item.done).count();
+            println!("  {} {done}/{}", "Checklist:".cyan(), items.len());
+            for item in &items {
+                if item.done {
+                    println!(
+                        "    {} {}",
+                        term::symbols().done.green(),
+                        item.text.dimmed()
+                    );
+                } else {
+                    println!("    {} {}", term::symbols().pending, item.text);
+                }
+            }
+        }
     }
 
     println!(
@@ -395,13 +1134,11 @@ fn print_todo_detailed(todo: &Todo) {
         }
     );
 
-    let priority_str = match todo.priority {
-        p if p == priority::HIGH => "high".red(),
-        p if p == priority::MEDIUM => "medium".yellow(),
-        p if p == priority::LOW => "low".blue(),
-        _ => "medium".normal(),
-    };
-    println!("  {} {}", "Priority:".cyan(), priority_str);
+    println!(
+        "  {} {}",
+        "Priority:".cyan(),
+        colored_priority(labels, todo.priority)
+    );
 
     if let Some(due_ts) = todo.due_date {
         if let Some(due) = Utc.timestamp_opt(due_ts, 0).latest() {
@@ -437,6 +1174,7 @@ fn print_todo_detailed(todo: &Todo) {
 mod tests {
     use super::*;
     use chrono::{NaiveDate, Utc};
+    use clap::ValueEnum;
 
     #[test]
     fn test_parse_date_datetime_format() {
@@ -472,21 +1210,197 @@ mod tests {
             .contains("Invalid date format"));
     }
 
+    #[test]
+    fn test_group_todos_by_tag() {
+        let todo = |id: &str| Todo {
+            id: id.to_string(),
+            title: id.to_string(),
+            description: None,
+            completed: false,
+            priority: priority::MEDIUM,
+            due_date: None,
+            created_at: 0,
+            updated_at: 0,
+        };
+        let todos = [todo("a"), todo("b"), todo("c")];
+        let refs: Vec<&Todo> = todos.iter().collect();
+        let mut ctx = PrintContext {
+            state: LocalState::default(),
+            rules: Vec::new(),
+            labels: PriorityLabels::default(),
+        };
+        ctx.state
+            .set_tags("a", vec!["work".to_string(), "home".to_string()]);
+        ctx.state.set_tags("b", vec!["work".to_string()]);
+
+        let groups: Vec<(String, Vec<&str>)> = group_todos(&refs, GroupBy::Tag, &ctx)
+            .into_iter()
+            .map(|(heading, group)| (heading, group.iter().map(|t| t.id.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                ("#home".to_string(), vec!["a"]),
+                ("#work".to_string(), vec!["a", "b"]),
+                ("Untagged".to_string(), vec!["c"]),
+            ]
+        );
+        assert_eq!(GroupBy::from_str("Due", true), Ok(GroupBy::Due));
+        assert!(GroupBy::from_str("size", true).is_err());
+    }
+
     #[test]
     fn test_parse_priority_valid_values() {
-        assert_eq!(parse_priority("low"), priority::LOW);
-        assert_eq!(parse_priority("LOW"), priority::LOW);
-        assert_eq!(parse_priority("medium"), priority::MEDIUM);
-        assert_eq!(parse_priority("MEDIUM"), priority::MEDIUM);
-        assert_eq!(parse_priority("high"), priority::HIGH);
-        assert_eq!(parse_priority("HIGH"), priority::HIGH);
+        let labels = PriorityLabels::default();
+        assert_eq!(parse_priority(&labels, "low"), priority::LOW);
+        assert_eq!(parse_priority(&labels, "LOW"), priority::LOW);
+        assert_eq!(parse_priority(&labels, "medium"), priority::MEDIUM);
+        assert_eq!(parse_priority(&labels, "MEDIUM"), priority::MEDIUM);
+        assert_eq!(parse_priority(&labels, "high"), priority::HIGH);
+        assert_eq!(parse_priority(&labels, "HIGH"), priority::HIGH);
     }
 
     #[test]
     fn test_parse_priority_invalid_defaults_to_medium() {
-        assert_eq!(parse_priority("invalid"), priority::MEDIUM);
-        assert_eq!(parse_priority(""), priority::MEDIUM);
-        assert_eq!(parse_priority("123"), priority::MEDIUM);
+        let labels = PriorityLabels::default();
+        assert_eq!(parse_priority(&labels, "invalid"), priority::MEDIUM);
+        assert_eq!(parse_priority(&labels, ""), priority::MEDIUM);
+        assert_eq!(parse_priority(&labels, "123"), priority::MEDIUM);
+    }
+
+    #[test]
+    fn test_bulk_update_parsing() {
+        let labels = PriorityLabels::default();
+        let conditions = parse_conditions("tag:#home priority:low overdue", &labels).unwrap();
+        assert_eq!(
+            conditions,
+            [
+                Condition::Tag("home".to_string()),
+                Condition::Priority(priority::LOW),
+                Condition::Overdue,
+            ]
+        );
+        assert!(parse_conditions("size:big", &labels).is_err());
+        assert!(parse_conditions("  ", &labels).is_err());
+
+        let set = ["priority=medium".to_string(), "due=+7d".to_string()];
+        let changes = BulkChanges::parse(&set, &labels).unwrap();
+        assert_eq!(changes.priority, Some(priority::MEDIUM));
+        assert_eq!(changes.due, Some(DueChange::Shift(7 * 24 * 60 * 60)));
+        assert!(BulkChanges::parse(&["size=big".to_string()], &labels).is_err());
+        assert!(BulkChanges::parse(&["priority".to_string()], &labels).is_err());
+
+        let todo = Todo {
+            id: "a".to_string(),
+            title: "a".to_string(),
+            description: None,
+            completed: false,
+            priority: priority::LOW,
+            due_date: Some(1_000),
+            created_at: 0,
+            updated_at: 0,
+        };
+        let request = changes.request_for(&todo, 0).unwrap();
+        assert_eq!(request.due_date, Some(1_000 + 7 * 24 * 60 * 60));
+        let tags_only = BulkChanges::parse(&["tags=home".to_string()], &labels).unwrap();
+        assert!(tags_only.request_for(&todo, 0).is_none());
+    }
+
+    #[test]
+    fn test_parse_day_keywords() {
+        let today = Local::now().date_naive();
+        assert_eq!(parse_day("today").unwrap(), today);
+        assert_eq!(parse_day("TODAY").unwrap(), today);
+        assert_eq!(parse_day("tomorrow").unwrap(), today + chrono::Days::new(1));
+        assert_eq!(
+            parse_day("2024-01-15").unwrap(),
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+        );
+        assert!(parse_day("someday").is_err());
+    }
+
+    #[test]
+    fn test_is_overdue() {
+        let today = Local::now().date_naive();
+        let yesterday = today - chrono::Days::new(1);
+        let due_ts = Local
+            .from_local_datetime(&yesterday.and_hms_opt(12, 0, 0).unwrap())
+            .unwrap()
+            .timestamp();
+
+        let mut todo = Todo {
+            id: "test1".to_string(),
+            title: "Test".to_string(),
+            description: None,
+            completed: false,
+            priority: priority::MEDIUM,
+            due_date: Some(due_ts),
+            created_at: 1640995200,
+            updated_at: 1640995200,
+        };
+        assert!(is_overdue(&todo, today));
+        assert!(!is_overdue(&todo, yesterday));
+
+        todo.completed = true;
+        assert!(!is_overdue(&todo, today));
+
+        todo.completed = false;
+        todo.due_date = None;
+        assert!(!is_overdue(&todo, today));
+    }
+
+    #[test]
+    fn test_date_filters() {
+        let day = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let at_noon = |date: NaiveDate| {
+            Local
+                .from_local_datetime(&date.and_hms_opt(12, 0, 0).unwrap())
+                .unwrap()
+                .timestamp()
+        };
+        let mut todo = Todo {
+            id: "test1".to_string(),
+            title: "Test".to_string(),
+            description: None,
+            completed: false,
+            priority: priority::MEDIUM,
+            due_date: Some(at_noon(day)),
+            created_at: 0,
+            updated_at: at_noon(day),
+        };
+        let next_day = day + chrono::Days::new(1);
+
+        assert!(DateFilters::default().matches(&todo, day));
+        let before = |due_before| DateFilters {
+            due_before: Some(due_before),
+            ..DateFilters::default()
+        };
+        assert!(before(next_day).matches(&todo, day));
+        assert!(!before(day).matches(&todo, day));
+        let after = DateFilters {
+            due_after: Some(day),
+            ..DateFilters::default()
+        };
+        assert!(!after.matches(&todo, day));
+
+        let overdue = DateFilters {
+            overdue: true,
+            ..DateFilters::default()
+        };
+        assert!(overdue.matches(&todo, next_day));
+        assert!(!overdue.matches(&todo, day));
+
+        let completed_after = DateFilters {
+            completed_after: Some(day - chrono::Days::new(1)),
+            ..DateFilters::default()
+        };
+        assert!(!completed_after.matches(&todo, day));
+        todo.completed = true;
+        assert!(completed_after.matches(&todo, day));
+
+        // Todos without a due date never fall in a due window
+        todo.due_date = None;
+        assert!(!before(next_day).matches(&todo, day));
     }
 
     #[test]
@@ -1,10 +1,189 @@
 //! CLI-specific types and command definitions
 
-use clap::{Parser, Subcommand};
+use crate::cli::commands::todo::parse_day;
+use crate::config::{Config, PriorityLabels};
+use crate::due::{is_overdue, local_date};
+use crate::sort::SortSpec;
+use chrono::NaiveDate;
+use clap::builder::{PossibleValue, TypedValueParser};
+use clap::error::{ContextKind, ContextValue, ErrorKind};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use pali_types::{priority, Todo};
+use std::ffi::OsStr;
+use std::path::PathBuf;
+
+/// Built-in priority names, listed in `--help` and completions
+const PRIORITY_NAMES: [&str; 3] = ["low", "medium", "high"];
+
+/// Parses `--priority`: the built-in names and 1-3, or a label or alias from the config
+///
+/// Completions and `--help` only list the built-in names.
+#[derive(Clone)]
+pub struct PriorityParser;
+
+impl TypedValueParser for PriorityParser {
+    type Value = String;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<String, clap::Error> {
+        let value = value.to_string_lossy();
+        let known = PriorityLabels::default().parse(&value).is_some()
+            || Config::priority_labels_setting().parse(&value).is_some();
+        if known {
+            return Ok(value.into_owned());
+        }
+
+        let mut err = clap::Error::new(ErrorKind::InvalidValue).with_cmd(cmd);
+        let arg = arg.map_or_else(|| "...".to_string(), ToString::to_string);
+        err.insert(ContextKind::InvalidArg, ContextValue::String(arg));
+        err.insert(
+            ContextKind::InvalidValue,
+            ContextValue::String(value.into_owned()),
+        );
+        err.insert(
+            ContextKind::ValidValue,
+            ContextValue::Strings(PRIORITY_NAMES.map(str::to_string).to_vec()),
+        );
+        Err(err)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(PRIORITY_NAMES.into_iter().map(PossibleValue::new)))
+    }
+}
+
+/// Checks a `--sort` list when parsing, keeping it as written
+fn sort_spec(value: &str) -> Result<String, String> {
+    SortSpec::parse(value)
+        .map(|_| value.to_string())
+        .map_err(|err| err.to_string())
+}
+
+/// Due and completion date filters of `pacli list`
+#[derive(Args, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateFilters {
+    #[arg(
+        long,
+        value_parser = parse_day,
+        help = "Only todos due before a day (today, tomorrow, or YYYY-MM-DD)"
+    )]
+    pub due_before: Option<NaiveDate>,
+    #[arg(long, value_parser = parse_day, help = "Only todos due after a day")]
+    pub due_after: Option<NaiveDate>,
+    #[arg(long, help = "Only overdue todos")]
+    pub overdue: bool,
+    #[arg(
+        long,
+        value_parser = parse_day,
+        help = "Only todos completed after a day (judged by their last update)"
+    )]
+    pub completed_after: Option<NaiveDate>,
+}
+
+impl DateFilters {
+    /// Returns `true` if `todo` passes every filter that is set
+    ///
+    /// The API has no completion time, so `completed_after` compares the
+    /// last update of completed todos.
+    #[must_use]
+    pub fn matches(&self, todo: &Todo, today: NaiveDate) -> bool {
+        let due = todo.due_date.and_then(local_date);
+        self.due_before
+            .is_none_or(|day| due.is_some_and(|due| due < day))
+            && self
+                .due_after
+                .is_none_or(|day| due.is_some_and(|due| due > day))
+            && (!self.overdue || is_overdue(todo, today))
+            && self.completed_after.is_none_or(|day| {
+                todo.completed && local_date(todo.updated_at).is_some_and(|updated| updated > day)
+            })
+    }
+}
+
+/// A built-in priority level, for settings that must not depend on labels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PriorityLevel {
+    #[value(alias = "1")]
+    Low,
+    #[value(alias = "2")]
+    Medium,
+    #[value(alias = "3")]
+    High,
+}
+
+impl PriorityLevel {
+    #[must_use]
+    pub fn level(self) -> i32 {
+        match self {
+            Self::Low => priority::LOW,
+            Self::Medium => priority::MEDIUM,
+            Self::High => priority::HIGH,
+        }
+    }
+}
+
+/// Sections of `pacli list --group-by`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GroupBy {
+    /// High, Medium, Low
+    Priority,
+    /// Overdue, Today, Tomorrow, This week, Later, No due date
+    Due,
+    /// One section per tag
+    Tag,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// One todo per line
+    #[value(alias = "jsonl")]
+    Ndjson,
+    /// A single array
+    Json,
+    /// A markdown task list
+    #[value(alias = "markdown")]
+    Md,
+    /// Spreadsheet rows
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Text,
+    /// Markdown
+    #[value(alias = "markdown")]
+    Md,
+}
+
+/// Groups within the sections of `pacli report`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportGroupBy {
+    /// High, Medium, Low
+    Priority,
+    /// One group per local tag
+    Tag,
+}
+
+/// Settings `pacli config unset` can remove
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum UnsetSetting {
+    /// Delete the API key
+    #[value(aliases = ["api-key", "api_key"])]
+    Key,
+    /// Restore the default endpoint
+    Endpoint,
+    /// Show the help again when no command is given
+    DefaultCommand,
+}
 
 #[derive(Parser)]
 #[command(name = "pacli")]
 #[command(about = "A CLI for managing todos with Pali server", long_about = None)]
+#[command(disable_help_subcommand = true)]
 pub struct Cli {
     /// Show version information
     #[arg(short = 'V', long)]
@@ -14,6 +193,42 @@ pub struct Cli {
     #[arg(short = 'v', long = "verbose", global = true, action = clap::ArgAction::Count)]
     pub verbose: u8,
 
+    /// Print request/response headers and a timing breakdown for every API call
+    #[arg(long, global = true)]
+    pub trace: bool,
+
+    /// Dump response bodies that cannot be decoded (credentials masked)
+    #[arg(long, global = true)]
+    pub debug_body: bool,
+
+    /// Save every request and response (credentials masked) to this file, e.g. for a bug report
+    #[arg(long, global = true, value_name = "PATH")]
+    pub record: Option<PathBuf>,
+
+    /// Answer requests from a file written by --record instead of the server
+    #[arg(long, global = true, value_name = "PATH", conflicts_with = "record")]
+    pub replay: Option<PathBuf>,
+
+    /// Never prompt; fail with exit code 3 instead (implied when stdin is not a terminal)
+    #[arg(long, global = true)]
+    pub no_input: bool,
+
+    /// After the command, print how long config loading, each HTTP call and rendering took
+    #[arg(long, global = true)]
+    pub timings: bool,
+
+    /// Read and write this config file (JSON, or TOML by extension) instead of the default one
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Try pacli against a built-in server with sample todos; nothing is saved
+    #[arg(long, global = true, conflicts_with = "config")]
+    pub demo: bool,
+
+    /// Write logs to this file (rotated by size) instead of stderr; relative paths are in the data directory
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<std::path::PathBuf>,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -27,45 +242,131 @@ pub enum Commands {
     },
     #[command(about = "Create a new todo")]
     Add {
-        #[arg(help = "Todo title")]
-        title: String,
-        #[arg(short = 'D', long, help = "Todo description")]
+        #[arg(help = "Todo title", required_unless_present_any = ["from_clipboard", "from_email"])]
+        title: Option<String>,
+        #[arg(short = 'D', long, help = "Todo description ('-' reads it from stdin)")]
         description: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "description",
+            help = "Read the description from a file"
+        )]
+        description_file: Option<PathBuf>,
         #[arg(short, long, help = "Due date (YYYY-MM-DD or YYYY-MM-DD HH:MM:SS)")]
         due: Option<String>,
-        #[arg(short, long, help = "Priority (low, medium, high)")]
+        #[arg(short, long, value_parser = PriorityParser, ignore_case = true, help = "Priority (low, medium, high)")]
         priority: Option<String>,
         #[arg(short, long, help = "Tags (comma-separated)")]
         tags: Option<String>,
+        #[arg(
+            long,
+            conflicts_with_all = ["title", "description", "description_file"],
+            help = "Use the clipboard: its first line as the title, the rest as the description"
+        )]
+        from_clipboard: bool,
+        #[arg(
+            long,
+            conflicts_with_all = ["title", "description", "description_file", "from_clipboard"],
+            help = "Read an email (RFC 822) from stdin: the subject becomes the title, the body and a Message-ID link the description"
+        )]
+        from_email: bool,
     },
     #[command(about = "List all todos")]
     List {
         #[arg(short, long, help = "Show completed todos")]
         all: bool,
+        #[arg(long, help = "Show only archived todos")]
+        archived: bool,
         #[arg(short, long, help = "Filter by tag")]
         tag: Option<String>,
-        #[arg(short, long, help = "Filter by priority")]
+        #[arg(short, long, value_parser = PriorityParser, ignore_case = true, help = "Filter by priority")]
         priority: Option<String>,
+        #[arg(
+            long,
+            value_parser = sort_spec,
+            help = "Sort by comma-separated keys: priority, due, created, updated, title (prefix - to reverse)"
+        )]
+        sort: Option<String>,
+        #[arg(long, help = "List completed todos after the open ones")]
+        completed_last: bool,
+        #[arg(long, ignore_case = true, help = "Print sections with counts")]
+        group_by: Option<GroupBy>,
+        #[command(flatten)]
+        dates: DateFilters,
     },
-    #[command(about = "Get a specific todo")]
+    #[command(about = "Count todos (prints a bare number for prompts and scripts)")]
+    Count {
+        #[arg(short, long, help = "Include completed todos")]
+        all: bool,
+        #[arg(long, help = "Only count overdue todos")]
+        overdue: bool,
+        #[arg(
+            short,
+            long,
+            help = "Only count todos due on a day (today, tomorrow, or YYYY-MM-DD)"
+        )]
+        due: Option<String>,
+        #[arg(short, long, value_parser = PriorityParser, ignore_case = true, help = "Filter by priority")]
+        priority: Option<String>,
+        #[arg(short, long, help = "Filter by tag")]
+        tag: Option<String>,
+        #[arg(long, help = "Print JSON with a breakdown by status and priority")]
+        json: bool,
+    },
+    #[command(about = "Show one or more todos")]
     Get {
-        #[arg(help = "Todo ID")]
-        id: String,
+        #[arg(
+            required = true,
+            value_delimiter = ',',
+            help = "Todo IDs or prefixes (space- or comma-separated)"
+        )]
+        ids: Vec<String>,
+        #[arg(long, help = "Print the todos as a JSON array")]
+        json: bool,
+        #[arg(
+            long,
+            conflicts_with = "json",
+            help = "Render markdown (bold, lists, code, links) in descriptions"
+        )]
+        markdown: bool,
     },
     #[command(about = "Update a todo")]
     Update {
-        #[arg(help = "Todo ID")]
-        id: String,
+        #[arg(help = "Todo ID", required_unless_present = "filter")]
+        id: Option<String>,
         #[arg(short, long, help = "New title")]
         title: Option<String>,
-        #[arg(short = 'D', long, help = "New description")]
+        #[arg(short = 'D', long, help = "New description ('-' reads it from stdin)")]
         description: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "description",
+            help = "Read the new description from a file"
+        )]
+        description_file: Option<PathBuf>,
         #[arg(short, long, help = "New due date")]
         due: Option<String>,
-        #[arg(short, long, help = "New priority")]
+        #[arg(short, long, value_parser = PriorityParser, ignore_case = true, help = "New priority")]
         priority: Option<String>,
         #[arg(short, long, help = "New tags (comma-separated)")]
         tags: Option<String>,
+        #[arg(
+            long = "where",
+            value_name = "FILTER",
+            conflicts_with_all = ["id", "title", "description", "description_file", "due", "priority", "tags"],
+            requires = "set",
+            help = "Update every todo matching all conditions: tag:<name>, priority:<level>, due:<day>, title:<text>, overdue, open, completed"
+        )]
+        filter: Option<String>,
+        #[arg(
+            long,
+            value_name = "FIELD=VALUE",
+            requires = "filter",
+            help = "Change to make with --where: title, description, priority, due (a date or +7d/-2d), completed or tags"
+        )]
+        set: Vec<String>,
+        #[arg(short, long, requires = "filter", help = "Don't ask for confirmation")]
+        yes: bool,
     },
     #[command(about = "Delete a todo")]
     Delete {
@@ -82,10 +383,219 @@ pub enum Commands {
         #[arg(help = "Todo ID")]
         id: String,
     },
+    #[command(about = "Open a URL from a todo's title or description in the browser")]
+    Open {
+        #[arg(help = "Todo ID")]
+        id: String,
+        #[arg(help = "Which URL to open when there are several (1 = first)")]
+        number: Option<usize>,
+    },
+    #[command(about = "Hide a todo from default listings without deleting it")]
+    Archive {
+        #[arg(help = "Todo ID")]
+        id: String,
+    },
+    #[command(about = "Restore an archived todo")]
+    Unarchive {
+        #[arg(help = "Todo ID")]
+        id: String,
+    },
+    #[command(about = "Keep a todo at the top of every list, whatever the sort order")]
+    Pin {
+        #[arg(help = "Todo ID")]
+        id: String,
+    },
+    #[command(about = "Let a pinned todo take its place in the sort order again")]
+    Unpin {
+        #[arg(help = "Todo ID")]
+        id: String,
+    },
+    #[command(about = "Give todos short names usable instead of their IDs")]
+    Alias {
+        #[command(subcommand)]
+        action: Option<AliasAction>,
+    },
+    #[command(about = "Mark a todo as a prerequisite of another todo")]
+    Link {
+        #[arg(help = "Todo ID of the prerequisite")]
+        id: String,
+        #[arg(long, help = "Todo ID that cannot start until this one is complete")]
+        blocks: String,
+    },
+    #[command(about = "Remove a dependency created with 'link'")]
+    Unlink {
+        #[arg(help = "Todo ID of the prerequisite")]
+        id: String,
+        #[arg(long, help = "Todo ID it currently blocks")]
+        blocks: String,
+    },
+    #[command(about = "Start tracking time on a todo")]
+    Start {
+        #[arg(help = "Todo ID")]
+        id: String,
+    },
+    #[command(about = "Stop the running timer")]
+    Stop,
+    #[command(about = "Show tracked time per day")]
+    Timesheet {
+        #[arg(short, long, help = "Show the current week instead of today")]
+        week: bool,
+    },
+    #[command(about = "Print a kanban-style board of todos")]
+    Board {
+        #[arg(
+            long,
+            help = "Board width in characters (defaults to the terminal width or 100)"
+        )]
+        width: Option<usize>,
+    },
+    #[command(about = "Show how many todos are due on each of the coming days")]
+    Forecast {
+        #[arg(
+            default_value_t = 14,
+            value_parser = clap::value_parser!(u32).range(1..=366),
+            help = "Number of days to show"
+        )]
+        days: u32,
+    },
+    #[command(about = "Summarize created, completed and overdue todos")]
+    Report {
+        #[arg(short, long, help = "Report on the current week instead of today")]
+        week: bool,
+        #[arg(
+            short,
+            long,
+            default_value = "text",
+            ignore_case = true,
+            help = "Output format"
+        )]
+        format: ReportFormat,
+        #[arg(
+            long,
+            default_value = "priority",
+            ignore_case = true,
+            help = "Group the todos in each section by priority or tag"
+        )]
+        group_by: ReportGroupBy,
+    },
+    #[command(
+        about = "Show recent operations made from this machine, or the changes made to one todo"
+    )]
+    History {
+        #[arg(
+            help = "Todo ID (or unique prefix) whose revisions to show, if the server keeps them"
+        )]
+        id: Option<String>,
+        #[arg(
+            long,
+            help = "How far back to look: a duration like 2h, 7d, 1w or a date (YYYY-MM-DD)"
+        )]
+        since: Option<String>,
+    },
+    #[command(about = "List and rename tags")]
+    Tags {
+        #[command(subcommand)]
+        action: Option<TagsAction>,
+        #[arg(
+            long,
+            help = "Print bare tag names from local state (for shell completion)"
+        )]
+        names: bool,
+    },
+    #[command(about = "Add a comment (progress note) to a todo")]
+    Comment {
+        #[arg(help = "Todo ID")]
+        id: String,
+        #[arg(help = "Comment text")]
+        text: String,
+    },
+    #[command(about = "Attach a file to a todo")]
+    Attach {
+        #[arg(help = "Todo ID")]
+        id: String,
+        #[arg(help = "File to upload")]
+        file: PathBuf,
+    },
+    #[command(about = "List the attachments of a todo")]
+    Attachments {
+        #[arg(help = "Todo ID")]
+        id: String,
+    },
+    #[command(about = "Download an attachment of a todo")]
+    Download {
+        #[arg(help = "Todo ID")]
+        id: String,
+        #[arg(help = "Attachment number (as shown by 'attachments')")]
+        number: usize,
+        #[arg(
+            short,
+            long,
+            help = "Where to save the file (defaults to its name in the current directory)"
+        )]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Manage checklist items (subtasks) of a todo")]
+    Subtask {
+        #[command(subcommand)]
+        action: SubtaskAction,
+    },
+    #[command(about = "Export all todos as JSON, NDJSON, markdown or CSV")]
+    Export {
+        #[arg(
+            long,
+            default_value = "ndjson",
+            ignore_case = true,
+            help = "Output format"
+        )]
+        format: ExportFormat,
+        #[arg(short, long, help = "File to write (defaults to stdout)")]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Measure API latency and check connection reuse")]
+    Bench {
+        #[arg(long, default_value_t = 20, help = "Requests per endpoint")]
+        requests: usize,
+    },
+    #[command(about = "Inspect the client's own behavior")]
+    Debug {
+        #[command(subcommand)]
+        action: DebugAction,
+    },
+    #[command(about = "Apply the escalation rules to overdue todos")]
+    Escalate {
+        #[arg(long, help = "Only show what would change")]
+        dry_run: bool,
+    },
+    #[cfg(feature = "caldav")]
+    #[command(about = "Sync todos with a CalDAV task list (Nextcloud Tasks, etc.)")]
+    Caldav {
+        #[command(subcommand)]
+        action: CaldavAction,
+    },
     #[command(about = "Search todos")]
     Search {
         #[arg(help = "Search query")]
         query: String,
+        #[arg(
+            long,
+            help = "Treat the query as a regular expression, matched locally against titles and descriptions"
+        )]
+        regex: bool,
+    },
+    #[command(about = "Log in interactively (the API key is read without echo)")]
+    Login {
+        #[arg(long, help = "Server URL (prompted for if omitted)")]
+        endpoint: Option<String>,
+        #[arg(long, help = "Sign in through SSO with the OAuth device-code flow")]
+        oauth: bool,
+        #[arg(long, requires = "oauth", help = "OAuth client ID")]
+        client_id: Option<String>,
+        #[arg(long, requires = "oauth", help = "Device authorization endpoint URL")]
+        device_url: Option<String>,
+        #[arg(long, requires = "oauth", help = "Token endpoint URL")]
+        token_url: Option<String>,
+        #[arg(long, requires = "oauth", help = "Scopes to request (space-separated)")]
+        scope: Option<String>,
     },
     #[command(about = "Initialize server and configure CLI")]
     Init {
@@ -97,6 +607,37 @@ pub enum Commands {
         #[command(subcommand)]
         action: AdminAction,
     },
+    #[command(about = "Show help for a command or a topic: dates, query, where, rules, projects")]
+    Help {
+        #[arg(help = "Topic or command name")]
+        topic: Option<String>,
+    },
+    #[command(hide = true)]
+    Internal {
+        #[command(subcommand)]
+        action: InternalAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DebugAction {
+    #[command(about = "Show API call counts and latencies per endpoint (needs metrics = true)")]
+    Metrics {
+        #[arg(long, help = "Output as JSON")]
+        json: bool,
+        #[arg(long, help = "Clear the recorded metrics")]
+        reset: bool,
+    },
+}
+
+/// Commands used when building and packaging releases
+#[derive(Subcommand)]
+pub enum InternalAction {
+    #[command(about = "Write man pages for pacli, its commands and the help topics")]
+    GenMan {
+        #[arg(long, default_value = "man", help = "Directory to write the pages to")]
+        out_dir: PathBuf,
+    },
 }
 
 #[derive(Subcommand)]
@@ -112,25 +653,232 @@ pub enum ConfigAction {
         key: String,
     },
     #[command(about = "Show current configuration")]
-    Show,
+    Show {
+        #[arg(long, help = "Print the full API key instead of a masked fingerprint")]
+        reveal: bool,
+    },
+    #[command(about = "Convert config.json into a commented config.toml")]
+    Migrate,
+    #[command(about = "Encrypt the stored API key with a passphrase")]
+    Encrypt {
+        #[arg(
+            long,
+            help = "Derive the key from this machine and user instead of a passphrase"
+        )]
+        machine: bool,
+    },
+    #[command(about = "Store the API key in plain text again")]
+    Decrypt,
+    #[command(about = "Remove a setting (key, endpoint, default-command)")]
+    Unset {
+        #[arg(ignore_case = true, help = "Setting to remove")]
+        setting: UnsetSetting,
+    },
+    #[command(
+        about = "Add a color rule, e.g. 'tag:urgent -> red bold' or 'title~\"waiting\" -> dim'"
+    )]
+    AddColorRule {
+        #[arg(help = "Rule in the form '<matcher> -> <style>'")]
+        rule: String,
+    },
+    #[command(about = "Remove all color rules")]
+    ClearColorRules,
+    #[command(
+        about = "Add an escalation rule, e.g. 'overdue > 3d -> priority:high' or 'overdue > 7d -> tag:stale'"
+    )]
+    AddEscalationRule {
+        #[arg(help = "Rule in the form '<condition> -> <action>'")]
+        rule: String,
+    },
+    #[command(about = "Remove all escalation rules")]
+    ClearEscalationRules,
+    #[command(about = "Change how a priority level is displayed, e.g. 'high P1'")]
+    PriorityLabel {
+        #[arg(ignore_case = true, help = "Priority level (low, medium, high or 1-3)")]
+        level: PriorityLevel,
+        #[arg(help = "Label to display")]
+        label: String,
+    },
+    #[command(about = "Accept an extra name for a priority level, e.g. 'p0 high'")]
+    PriorityAlias {
+        #[arg(help = "Alias to accept in --priority")]
+        alias: String,
+        #[arg(ignore_case = true, help = "Priority level (low, medium, high or 1-3)")]
+        level: PriorityLevel,
+    },
+    #[command(about = "Set what a bare 'pacli' runs, e.g. 'list' or 'list --sort due'")]
+    DefaultCommand {
+        #[arg(help = "Command to run, quoted as one argument")]
+        command: String,
+    },
+    #[command(about = "Define a command of your own, e.g. 'ls' for 'list --sort due'")]
+    CommandAlias {
+        #[arg(help = "New command name")]
+        name: String,
+        #[arg(help = "What it runs, quoted as one argument (e.g. \"list --sort due\")")]
+        command: String,
+    },
+    #[command(about = "Remove a command alias")]
+    RemoveCommandAlias {
+        #[arg(help = "Command alias name")]
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SubtaskAction {
+    #[command(about = "Add a checklist item to a todo")]
+    Add {
+        #[arg(help = "Todo ID")]
+        id: String,
+        #[arg(help = "Checklist item text")]
+        text: String,
+    },
+    #[command(about = "Mark a checklist item as done")]
+    Complete {
+        #[arg(help = "Todo ID")]
+        id: String,
+        #[arg(help = "Item number (as shown by 'subtask list') or text")]
+        item: String,
+    },
+    #[command(about = "Mark a checklist item as not done")]
+    Reopen {
+        #[arg(help = "Todo ID")]
+        id: String,
+        #[arg(help = "Item number (as shown by 'subtask list') or text")]
+        item: String,
+    },
+    #[command(about = "List checklist items of a todo")]
+    List {
+        #[arg(help = "Todo ID")]
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AliasAction {
+    #[command(about = "List all aliases with the todos they name")]
+    List,
+    #[command(about = "Name a todo, e.g. 'pacli alias set grocery d2fa'")]
+    Set {
+        #[arg(help = "Alias (letters, digits, '-' and '_')")]
+        alias: String,
+        #[arg(help = "Todo ID")]
+        id: String,
+    },
+    #[command(about = "Remove an alias (the todo is kept)")]
+    Remove {
+        #[arg(help = "Alias")]
+        alias: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TagsAction {
+    #[command(about = "List all tags with the number of todos using them")]
+    List,
+    #[command(about = "Rename a tag on every todo")]
+    Rename {
+        #[arg(help = "Current tag name")]
+        old: String,
+        #[arg(help = "New tag name")]
+        new: String,
+    },
+}
+
+#[cfg(feature = "caldav")]
+#[derive(Subcommand)]
+pub enum CaldavAction {
+    #[command(about = "Two-way sync with the task list configured under [caldav]")]
+    Sync {
+        #[arg(long, help = "Only show what would change")]
+        dry_run: bool,
+        #[arg(
+            long,
+            ignore_case = true,
+            help = "Which side wins when a todo changed on both (default: newer)"
+        )]
+        prefer: Option<crate::caldav::Prefer>,
+    },
 }
 
 #[derive(Subcommand)]
 pub enum AdminAction {
+    #[command(about = "Show server version, uptime, storage and rate limits")]
+    Status {
+        #[arg(long, help = "Print JSON instead of text")]
+        json: bool,
+    },
+    #[command(about = "Show the server's audit log (key used, action, IP)")]
+    Audit {
+        #[arg(
+            long,
+            default_value = "24h",
+            help = "How far back to look: a duration like 2h, 7d or a date (YYYY-MM-DD)"
+        )]
+        since: String,
+        #[arg(long, help = "Only show events of this key ID (or unique prefix)")]
+        key: Option<String>,
+        #[arg(long, help = "Only show actions containing this text, e.g. 'revoke'")]
+        action: Option<String>,
+        #[arg(short, long, help = "Keep running and print new events as they happen")]
+        follow: bool,
+    },
     #[command(about = "Rotate admin API key")]
     RotateKey,
     #[command(about = "Generate a new API key")]
     GenerateKey {
         #[arg(short, long, help = "Key name")]
         name: Option<String>,
+        #[arg(long, conflicts_with = "scope", help = "Only allow reading todos")]
+        read_only: bool,
+        #[arg(
+            long,
+            help = "Comma-separated scopes, e.g. todos:read,todos:write (default: full access)"
+        )]
+        scope: Option<String>,
+        #[arg(long, help = "Expire the key after a duration such as 12h, 30d or 4w")]
+        expires: Option<String>,
+        #[arg(
+            long,
+            help = "Also show the endpoint and key as a QR code for pairing a device"
+        )]
+        qr: bool,
     },
     #[command(about = "List all API keys")]
     ListKeys,
-    #[command(about = "Revoke an API key")]
-    RevokeKey {
-        #[arg(help = "Key ID")]
+    #[command(about = "Show request counts per API key")]
+    KeyStats {
+        #[arg(help = "Key ID (or a unique prefix) to show per-route counts for")]
+        id: Option<String>,
+        #[arg(long, help = "Print JSON instead of a table")]
+        json: bool,
+    },
+    #[command(about = "Rename an API key without regenerating it")]
+    RenameKey {
+        #[arg(help = "Key ID (or a unique prefix)")]
         id: String,
+        #[arg(help = "New key name")]
+        name: String,
+    },
+    #[command(about = "Revoke API keys by ID, name pattern or inactivity")]
+    RevokeKey {
+        #[arg(
+            help = "Key IDs (or unique prefixes)",
+            required_unless_present_any = ["name", "inactive"],
+            conflicts_with_all = ["name", "inactive"]
+        )]
+        ids: Vec<String>,
+        #[arg(long, help = "Revoke keys whose name matches a pattern such as 'ci-*'")]
+        name: Option<String>,
+        #[arg(long, help = "Revoke keys that are already inactive")]
+        inactive: bool,
+        #[arg(short, long, help = "Don't ask for confirmation")]
+        yes: bool,
     },
     #[command(about = "Emergency server reset (deactivates ALL admin keys)")]
-    Reinitialize,
+    Reinitialize {
+        #[arg(long, help = "Skip typing the server hostname to confirm")]
+        force: bool,
+    },
 }
@@ -1,16 +1,32 @@
 //! CLI utility functions for improved user experience
 
-use crate::api::ApiClient;
-use anyhow::Result;
+use crate::api::{ApiClient, Capabilities, Todo};
+use crate::cache;
+use crate::cli::types::Cli;
+use crate::id_index::IdIndex;
+use crate::prompt;
+use crate::state::LocalState;
+use crate::term;
+use crate::ID_DISPLAY_LENGTH;
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate, TimeZone, Utc};
+use clap::CommandFactory;
+use colored::Colorize;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::io::{BufRead, Read, Write};
+use std::path::{Path, PathBuf};
 
 /// Resolves a partial ID to a full ID by matching against todos
 ///
 /// This allows users to type just the prefix they see in the list output
-/// instead of needing the full UUID.
+/// instead of needing the full UUID, or an alias set with `pacli alias set`.
 ///
 /// **Implementation Strategy:**
-/// 1. First tries server-side resolution (when `/todos/resolve/{prefix}` is available)
-/// 2. Falls back to client-side resolution (current implementation)
+/// 1. Looks the ID up as an alias in the local state
+/// 2. Resolves locally from the ID index (see [`crate::id_index`]) when it is fresh and unambiguous
+/// 3. Tries server-side resolution (when the server reports [`Capabilities::RESOLVE`])
+/// 4. Falls back to client-side resolution over the full list (which also refreshes the index)
 ///
 /// # Arguments
 ///
@@ -20,7 +36,8 @@ use anyhow::Result;
 /// # Returns
 ///
 /// * `Ok(String)` - The full UUID if exactly one match is found
-/// * `Err` - If no matches found or multiple ambiguous matches
+/// * `Err` - If no matches found, or multiple matches and the user can't be
+///   asked to pick one (see [`prompt::can_prompt`])
 ///
 /// # Examples
 ///
@@ -36,14 +53,34 @@ pub async fn resolve_partial_id(partial_id: &str, client: &ApiClient) -> Result<
         return Ok(partial_id.to_string());
     }
 
-    // Try server-side resolution first (much faster!)
-    if let Ok(full_id) = client.resolve_id_prefix(partial_id).await {
+    // Aliases win over prefixes (they can't be mistaken for one)
+    match LocalState::load() {
+        Ok(state) => {
+            if let Some(full_id) = state.resolve_alias(partial_id) {
+                log::debug!("Resolved alias '{partial_id}'");
+                return Ok(full_id.to_string());
+            }
+        }
+        Err(err) => log::warn!("Could not load aliases: {err:#}"),
+    }
+
+    // A fresh local index resolves unique prefixes without any request
+    if let Some(full_id) = IdIndex::load().resolve(partial_id) {
+        log::debug!("Resolved '{partial_id}' from the local ID index");
         return Ok(full_id);
     }
 
-    // Fallback: Client-side resolution (if server doesn't support it)
-    // Fetch all todos to find matches
-    let todos = client.list_todos(None, None).await?;
+    // Server-side resolution avoids fetching every todo
+    if client.capabilities().await.supports(Capabilities::RESOLVE) {
+        match client.resolve_id_prefix(partial_id).await {
+            Ok(full_id) => return Ok(full_id),
+            // Unknown and ambiguous prefixes are explained (or picked from) below
+            Err(err) => log::debug!("Server could not resolve '{partial_id}': {err:#}"),
+        }
+    }
+
+    // Client-side resolution: fetch all todos (or reuse the cached list) to find matches
+    let todos = cache::list_todos(client, None).await?;
 
     // Find all todos whose ID starts with the partial
     let matches: Vec<_> = todos
@@ -56,6 +93,7 @@ pub async fn resolve_partial_id(partial_id: &str, client: &ApiClient) -> Result<
             "No todo found with ID starting with '{partial_id}'. Please check the ID and try again."
         ),
         1 => Ok(matches[0].id.clone()),
+        _ if prompt::can_prompt() => pick_todo(partial_id, &matches),
         n => {
             // Multiple matches - show them to help the user
             let mut error_msg = format!(
@@ -72,7 +110,46 @@ pub async fn resolve_partial_id(partial_id: &str, client: &ApiClient) -> Result<
                 }
             }
 
-            anyhow::bail!(error_msg)
+            Err(anyhow::Error::new(prompt::InputRequired {
+                what: "pick one of the matching todos".to_string(),
+            })
+            .context(error_msg.trim_end().to_string()))
+        }
+    }
+}
+
+/// Asks the user to choose one of several todos matching an ambiguous prefix
+fn pick_todo(partial_id: &str, matches: &[&Todo]) -> Result<String> {
+    eprintln!(
+        "{} '{partial_id}' matches {} todos:",
+        "?".yellow(),
+        matches.len()
+    );
+    for (i, todo) in matches.iter().enumerate() {
+        eprintln!(
+            "  {:>2}) {} {}",
+            i + 1,
+            todo.id[..ID_DISPLAY_LENGTH.min(todo.id.len())].dimmed(),
+            todo.title
+        );
+    }
+
+    loop {
+        eprint!("Select 1-{} (Enter to cancel): ", matches.len());
+        std::io::stderr().flush()?;
+
+        let mut answer = String::new();
+        std::io::stdin().lock().read_line(&mut answer)?;
+        let answer = answer.trim();
+        if answer.is_empty() || answer.eq_ignore_ascii_case("q") {
+            anyhow::bail!("Cancelled: '{partial_id}' is ambiguous");
+        }
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=matches.len()).contains(&n) => return Ok(matches[n - 1].id.clone()),
+            _ => eprintln!(
+                "{} Enter a number from the list",
+                term::symbols().warning.yellow()
+            ),
         }
     }
 }
@@ -93,8 +170,549 @@ pub async fn resolve_partial_ids(
     Ok(resolved)
 }
 
+/// Returns the Unix timestamp of local midnight at the start of `day`
+///
+/// Returns `None` if midnight doesn't exist in the local timezone (DST gaps
+/// are resolved to the earliest valid instant when possible).
+#[must_use]
+pub fn local_day_start(day: NaiveDate) -> Option<i64> {
+    let midnight = day.and_hms_opt(0, 0, 0)?;
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|dt| dt.timestamp())
+}
+
+/// Parses a relative duration such as `90s`, `30m`, `24h`, `7d` or `2w` into seconds
+///
+/// # Errors
+///
+/// Returns an error if the number or unit is invalid
+pub fn parse_duration_secs(spec: &str) -> Result<i64> {
+    let spec = spec.trim();
+    let split = spec
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(spec.len());
+    let (number, unit) = spec.split_at(split);
+
+    let invalid = || anyhow::anyhow!("Invalid duration '{spec}'. Use e.g. 30m, 24h, 7d or 2w");
+    let value: i64 = number.parse().map_err(|_| invalid())?;
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+
+    value.checked_mul(multiplier).ok_or_else(invalid)
+}
+
+/// Parses a `--since` value into a Unix timestamp
+///
+/// Accepts either a relative duration (`24h`, `7d`, see [`parse_duration_secs`])
+/// or a local date (`YYYY-MM-DD`, meaning midnight at the start of that day).
+///
+/// # Errors
+///
+/// Returns an error if the value is neither a valid duration nor a date
+pub fn parse_since(spec: &str) -> Result<i64> {
+    if let Ok(day) = NaiveDate::parse_from_str(spec.trim(), "%Y-%m-%d") {
+        return local_day_start(day)
+            .ok_or_else(|| anyhow::anyhow!("Invalid date '{spec}' in the local timezone"));
+    }
+
+    Ok(Utc::now().timestamp() - parse_duration_secs(spec)?)
+}
+
+/// Asks a yes/no question on the terminal, defaulting to "no"
+///
+/// Returns `false` when standard input is closed, so scripts never confirm by accident.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Prompting is disabled ([`prompt::InputRequired`])
+/// - The prompt cannot be written or the answer cannot be read
+pub fn confirm(prompt: &str) -> Result<bool> {
+    prompt::require(&format!("confirm '{prompt}'"))?;
+    print!("{prompt} [y/N] ");
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Returns the value of `--config` in raw arguments, which is needed before they are parsed
+#[must_use]
+pub fn config_arg(args: &[OsString]) -> Option<PathBuf> {
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        if arg == "--config" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.to_str().and_then(|arg| arg.strip_prefix("--config=")) {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+/// Returns `true` if raw arguments hold `--demo`, which is needed before they are parsed
+#[must_use]
+pub fn demo_arg(args: &[OsString]) -> bool {
+    args.iter()
+        .skip(1)
+        .take_while(|arg| *arg != "--")
+        .any(|arg| arg == "--demo")
+}
+
+/// Expands a command alias from the config in `pacli`'s arguments
+///
+/// The first argument that is neither an option nor an option's value is
+/// the command. If it isn't built in but names an alias, it is replaced by
+/// the alias's words, as git does; the arguments after it are kept, so
+/// `pacli ls --all` with `ls = "list --sort due"` runs
+/// `pacli list --sort due --all`. Aliases may expand to other aliases.
+///
+/// # Errors
+///
+/// Returns an error if an expansion is empty or has an unclosed quote, or
+/// if aliases expand to each other in a loop
+pub fn expand_command_alias(
+    mut args: Vec<OsString>,
+    aliases: &BTreeMap<String, String>,
+) -> Result<Vec<OsString>> {
+    if aliases.is_empty() {
+        return Ok(args);
+    }
+    let cli = Cli::command();
+    let Some(position) = command_position(&cli, &args) else {
+        return Ok(args);
+    };
+
+    let mut expanded: Vec<String> = Vec::new();
+    loop {
+        let Some(name) = args[position].to_str().map(str::to_string) else {
+            return Ok(args);
+        };
+        if cli.find_subcommand(&name).is_some() {
+            return Ok(args);
+        }
+        let Some(expansion) = aliases.get(&name) else {
+            return Ok(args);
+        };
+        if expanded.contains(&name) {
+            anyhow::bail!("Alias loop: {} -> {name}", expanded.join(" -> "));
+        }
+
+        let words = split_words(expansion).with_context(|| format!("Invalid alias '{name}'"))?;
+        if words.is_empty() {
+            anyhow::bail!("Alias '{name}' expands to nothing");
+        }
+        args.splice(position..=position, words.into_iter().map(OsString::from));
+        expanded.push(name);
+    }
+}
+
+/// Appends the configured default command when `pacli` is run without one
+///
+/// Options are kept, so `pacli -v` runs the default command verbosely;
+/// `--help` and `--version` never run it. The default command may be an
+/// alias.
+///
+/// # Errors
+///
+/// Returns an error if the default command is empty or has an unclosed quote
+pub fn apply_default_command(
+    mut args: Vec<OsString>,
+    default: Option<&str>,
+) -> Result<Vec<OsString>> {
+    let Some(default) = default else {
+        return Ok(args);
+    };
+    let informational = args.iter().skip(1).any(|arg| {
+        ["-h", "--help", "-V", "--version", "--"]
+            .iter()
+            .any(|flag| arg == flag)
+    });
+    if informational || command_position(&Cli::command(), &args).is_some() {
+        return Ok(args);
+    }
+
+    let words = split_words(default).context("Invalid default_command in the config")?;
+    if words.is_empty() {
+        anyhow::bail!("default_command in the config is empty");
+    }
+    args.extend(words.into_iter().map(OsString::from));
+    Ok(args)
+}
+
+/// Returns the index of the command in raw arguments, skipping options and their values
+fn command_position(cli: &clap::Command, args: &[OsString]) -> Option<usize> {
+    let mut position = 1;
+    while let Some(arg) = args.get(position) {
+        let arg = arg.to_str()?;
+        if arg == "--" {
+            return None;
+        }
+        if !arg.starts_with('-') || arg == "-" {
+            return Some(position);
+        }
+        let takes_value = arg.strip_prefix("--").is_some_and(|long| {
+            cli.get_arguments()
+                .any(|option| option.get_long() == Some(long) && option.get_action().takes_values())
+        });
+        position += if takes_value { 2 } else { 1 };
+    }
+    None
+}
+
+/// Splits `text` into words like a shell: whitespace separates words, quotes
+/// group them and `\` escapes the next character (except in single quotes)
+///
+/// # Errors
+///
+/// Returns an error if a quote is not closed
+pub fn split_words(text: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (None | Some('"'), '\\') => {
+                if let Some(escaped) = chars.next() {
+                    word.get_or_insert_with(String::new).push(escaped);
+                }
+            }
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            _ => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if let Some(open) = quote {
+        anyhow::bail!("Unclosed {open} in '{text}'");
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Matches `text` against a shell-style pattern where `*` matches any run of
+/// characters and `?` a single character (case insensitive)
+#[must_use]
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    // Iterative matching with backtracking to the most recent '*'
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Resolves the value of `--description` / `--description-file`
+///
+/// `--description -` reads the description from standard input. Trailing
+/// whitespace (such as the final newline of a file) is removed.
+///
+/// # Errors
+///
+/// Returns an error if the file or standard input cannot be read
+pub fn read_description(
+    description: Option<String>,
+    file: Option<&Path>,
+) -> Result<Option<String>> {
+    if let Some(path) = file {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read description from {}", path.display()))?;
+        return Ok(Some(content.trim_end().to_string()));
+    }
+
+    match description.as_deref() {
+        Some("-") => read_to_string(std::io::stdin().lock()).map(Some),
+        _ => Ok(description),
+    }
+}
+
+/// Reads the clipboard as a todo: the first non-blank line is the title, the rest the description
+///
+/// # Errors
+///
+/// Returns an error if the clipboard cannot be accessed, holds no text or only whitespace
+pub fn read_clipboard() -> Result<(String, Option<String>)> {
+    let text = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .context("Failed to read text from the clipboard")?;
+    split_title(&text).ok_or_else(|| anyhow::anyhow!("The clipboard is empty"))
+}
+
+/// Splits text into its first non-blank line and the trimmed rest, if any
+#[must_use]
+pub fn split_title(text: &str) -> Option<(String, Option<String>)> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let (title, rest) = text.split_once('\n').unwrap_or((text, ""));
+    let rest = rest.trim();
+    Some((
+        title.trim().to_string(),
+        (!rest.is_empty()).then(|| rest.to_string()),
+    ))
+}
+
+/// Longest email body kept in a description, in characters
+const MAX_EMAIL_BODY: usize = 2000;
+
+/// Reads an email from standard input as a todo, see [`todo_from_email`]
+///
+/// # Errors
+///
+/// Returns an error if standard input cannot be read or holds no usable email
+pub fn read_email() -> Result<(String, Option<String>)> {
+    let mut raw = Vec::new();
+    std::io::stdin()
+        .lock()
+        .read_to_end(&mut raw)
+        .context("Failed to read the email from standard input")?;
+    todo_from_email(&raw)
+}
+
+/// Turns an RFC 822 message into a todo title and description
+///
+/// The subject is the title. The description is the plain-text body, cut at
+/// the signature and any quoted reply, followed by the sender and a `mid:`
+/// link (RFC 2392) to the message, which mail clients such as mutt and
+/// notmuch can look up.
+///
+/// # Errors
+///
+/// Returns an error if the message cannot be parsed or has no subject
+pub fn todo_from_email(raw: &[u8]) -> Result<(String, Option<String>)> {
+    use mailparse::MailHeaderMap;
+
+    let mail = mailparse::parse_mail(raw).context("Failed to parse the email")?;
+    let title = mail
+        .headers
+        .get_first_value("Subject")
+        .map(|subject| subject.trim().to_string())
+        .filter(|subject| !subject.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("The email has no subject to use as the title"))?;
+
+    let mut sections = Vec::new();
+    if let Some(body) = plain_text_body(&mail) {
+        let body = trim_email_body(&body);
+        if !body.is_empty() {
+            sections.push(body);
+        }
+    }
+    let mut footer = Vec::new();
+    if let Some(from) = mail.headers.get_first_value("From") {
+        footer.push(format!("From: {}", from.trim()));
+    }
+    if let Some(id) = mail.headers.get_first_value("Message-ID") {
+        let id = id.trim().trim_start_matches('<').trim_end_matches('>');
+        if !id.is_empty() {
+            footer.push(format!("Message: mid:{id}"));
+        }
+    }
+    if !footer.is_empty() {
+        sections.push(footer.join("\n"));
+    }
+
+    let description = (!sections.is_empty()).then(|| sections.join("\n\n"));
+    Ok((title, description))
+}
+
+/// The first `text/plain` part of a message, decoded
+fn plain_text_body(mail: &mailparse::ParsedMail) -> Option<String> {
+    if mail.subparts.is_empty() {
+        let is_text = mail.ctype.mimetype.eq_ignore_ascii_case("text/plain");
+        return is_text.then(|| mail.get_body().ok()).flatten();
+    }
+    mail.subparts.iter().find_map(plain_text_body)
+}
+
+/// Drops the signature and quoted reply of an email body and caps its length
+fn trim_email_body(body: &str) -> String {
+    let mut kept = Vec::new();
+    for line in body.lines() {
+        let line = line.trim_end();
+        if line == "--" || line.starts_with('>') {
+            break;
+        }
+        kept.push(line);
+    }
+    // An "On <date>, <someone> wrote:" line introduces the quote
+    if kept.last().is_some_and(|line| line.ends_with("wrote:")) {
+        kept.pop();
+    }
+
+    let body = kept.join("\n").trim().to_string();
+    match body.char_indices().nth(MAX_EMAIL_BODY) {
+        Some((cut, _)) => format!("{}…", &body[..cut]),
+        None => body,
+    }
+}
+
+fn read_to_string(mut reader: impl Read) -> Result<String> {
+    let mut content = String::new();
+    reader
+        .read_to_string(&mut content)
+        .context("Failed to read description from standard input")?;
+    Ok(content.trim_end().to_string())
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_day_start() {
+        let day = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let start = local_day_start(day).unwrap();
+        let next = local_day_start(day + chrono::Days::new(1)).unwrap();
+        assert!(next > start);
+        assert_eq!(
+            chrono::DateTime::from_timestamp(start, 0)
+                .unwrap()
+                .with_timezone(&Local)
+                .date_naive(),
+            day
+        );
+    }
+
+    #[test]
+    fn test_parse_duration_secs() {
+        assert_eq!(parse_duration_secs("90s").unwrap(), 90);
+        assert_eq!(parse_duration_secs("30m").unwrap(), 30 * 60);
+        assert_eq!(parse_duration_secs("24h").unwrap(), 24 * 60 * 60);
+        assert_eq!(parse_duration_secs("7d").unwrap(), 7 * 24 * 60 * 60);
+        assert_eq!(parse_duration_secs("2w").unwrap(), 14 * 24 * 60 * 60);
+        assert!(parse_duration_secs("7").is_err());
+        assert!(parse_duration_secs("d").is_err());
+        assert!(parse_duration_secs("7y").is_err());
+        assert!(parse_duration_secs("99999999999999999w").is_err());
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = Utc::now().timestamp();
+        let since = parse_since("1h").unwrap();
+        assert!((now - 3600 - since).abs() <= 1);
+
+        let day = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        assert_eq!(
+            parse_since("2024-01-15").unwrap(),
+            local_day_start(day).unwrap()
+        );
+        assert!(parse_since("last tuesday").is_err());
+    }
+
+    #[test]
+    fn test_expand_command_alias() {
+        let args = |line: &str| -> Vec<OsString> { line.split(' ').map(OsString::from).collect() };
+        let aliases: BTreeMap<String, String> = [
+            ("ls", "list --sort due --group-by due"),
+            ("urgent", "ls --priority high"),
+            ("list", "search nothing"),
+            ("a", "b"),
+            ("b", "a"),
+        ]
+        .into_iter()
+        .map(|(name, command)| (name.to_string(), command.to_string()))
+        .collect();
+
+        assert_eq!(
+            expand_command_alias(args("pacli -v --config work.toml urgent --all"), &aliases)
+                .unwrap(),
+            args(
+                "pacli -v --config work.toml list --sort due --group-by due --priority high --all"
+            )
+        );
+        // Built-in commands win
+        assert_eq!(
+            expand_command_alias(args("pacli list"), &aliases).unwrap(),
+            args("pacli list")
+        );
+        assert_eq!(
+            expand_command_alias(args("pacli --version"), &aliases).unwrap(),
+            args("pacli --version")
+        );
+        assert!(expand_command_alias(args("pacli a"), &aliases).is_err());
+
+        assert_eq!(
+            config_arg(&args("pacli ls --config=work.toml")),
+            Some(PathBuf::from("work.toml"))
+        );
+        assert_eq!(config_arg(&args("pacli ls")), None);
+        assert!(demo_arg(&args("pacli ls --demo")));
+        assert!(!demo_arg(&args("pacli add -- --demo")));
+
+        assert_eq!(
+            apply_default_command(args("pacli -v"), Some("ls")).unwrap(),
+            args("pacli -v ls")
+        );
+        assert_eq!(
+            apply_default_command(args("pacli get d2fa"), Some("ls")).unwrap(),
+            args("pacli get d2fa")
+        );
+        assert_eq!(
+            apply_default_command(args("pacli --help"), Some("ls")).unwrap(),
+            args("pacli --help")
+        );
+
+        assert_eq!(
+            split_words(r#"add "buy milk" -d 'it\'s' a\ b"#)
+                .unwrap_err()
+                .to_string(),
+            r#"Unclosed ' in 'add "buy milk" -d 'it\'s' a\ b'"#
+        );
+        assert_eq!(
+            split_words(r#" add "buy \"oat\" milk" -d '' a\ b "#).unwrap(),
+            vec!["add", r#"buy "oat" milk"#, "-d", "", "a b"]
+        );
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("ci-*", "CI-build-42"));
+        assert!(wildcard_match("*-old", "deploy-old"));
+        assert!(wildcard_match("job-?", "job-7"));
+        assert!(wildcard_match("laptop", "Laptop"));
+        assert!(!wildcard_match("ci-*", "local-ci-1"));
+        assert!(!wildcard_match("job-?", "job-12"));
+        assert!(!wildcard_match("laptop", "laptop-2"));
+    }
+
     #[test]
     fn test_full_uuid_detection() {
         let full_uuid = "d2fadfdb-5541-4ace-9443-d01cd917a640";
@@ -103,4 +721,69 @@ mod tests {
         let partial = "d2fadfdb";
         assert!(!(partial.len() >= 36 && partial.contains('-')));
     }
+
+    #[test]
+    fn test_split_title() {
+        assert_eq!(
+            split_title(
+                "\n  thread 'main' panicked at src/lib.rs:12\r\nnote: run with RUST_BACKTRACE=1\n"
+            ),
+            Some((
+                "thread 'main' panicked at src/lib.rs:12".to_string(),
+                Some("note: run with RUST_BACKTRACE=1".to_string())
+            ))
+        );
+        assert_eq!(
+            split_title("https://example.com/issue/7"),
+            Some(("https://example.com/issue/7".to_string(), None))
+        );
+        assert_eq!(split_title(" \n\t"), None);
+    }
+
+    #[test]
+    fn test_todo_from_email() {
+        let raw = concat!(
+            "From: Ada <ada@example.com>\r\n",
+            "Subject: =?UTF-8?Q?Review_the_caf=C3=A9_budget?=\r\n",
+            "Message-ID: <1234@mail.example.com>\r\n",
+            "Content-Type: text/plain; charset=utf-8\r\n",
+            "\r\n",
+            "Could you check the numbers by Friday?\r\n",
+            "\r\n",
+            "On Mon, Bob wrote:\r\n",
+            "> Sending the draft\r\n",
+            "-- \r\n",
+            "Ada\r\n",
+        );
+        let (title, description) = todo_from_email(raw.as_bytes()).unwrap();
+        assert_eq!(title, "Review the café budget");
+        assert_eq!(
+            description.unwrap(),
+            "Could you check the numbers by Friday?\n\nFrom: Ada <ada@example.com>\nMessage: mid:1234@mail.example.com"
+        );
+
+        assert!(todo_from_email(b"From: a@b.c\r\n\r\nNo subject").is_err());
+    }
+
+    #[test]
+    fn test_read_description() {
+        assert_eq!(read_description(None, None).unwrap(), None);
+        assert_eq!(
+            read_description(Some("inline".to_string()), None).unwrap(),
+            Some("inline".to_string())
+        );
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, b"First paragraph.\n\nSecond one.\n").unwrap();
+        assert_eq!(
+            read_description(None, Some(file.path())).unwrap(),
+            Some("First paragraph.\n\nSecond one.".to_string())
+        );
+
+        assert!(read_description(None, Some(Path::new("/nonexistent/notes.md"))).is_err());
+        assert_eq!(
+            read_to_string("from stdin\n".as_bytes()).unwrap(),
+            "from stdin"
+        );
+    }
 }
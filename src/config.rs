@@ -1,20 +1,358 @@
+use crate::redact;
+use crate::secret::{self, DerivedKey, EncryptedSecret, KeySource};
+use crate::timings;
 use anyhow::Result;
 use directories::ProjectDirs;
+use pali_types::priority;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Config file given with `--config`, used instead of the default location
+static CONFIG_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Data directory chosen with [`Config::use_data_dir`], e.g. for `--demo`
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Written at the top of `config.toml`, since TOML values are regenerated on every save
+const TOML_HEADER: &str = r#"# Pali configuration
+#
+# Settings not listed below use these defaults:
+#
+# api_endpoint = "http://localhost:8787"   # also ":8787" or "unix:///path/to/pali.sock"
+# api_key = "..."                    # prefer `pacli login`; `pacli config encrypt` seals it
+#
+# Highlight matching todos, later rules win
+# color_rules = ["tag:urgent -> red bold", 'title~"waiting" -> dim']
+#
+# Applied to overdue todos by `pacli list` and `pacli escalate`
+# escalation_rules = ["overdue > 3d -> priority:high", "overdue > 7d -> tag:stale"]
+#
+# [priority_labels]                  # how priorities are shown and typed
+# low = "low"
+# medium = "medium"
+# high = "high"
+# aliases = { p0 = 3 }
+#
+# [oauth]                            # written by `pacli login --oauth`
+# client_id = "..."
+# device_authorization_url = "https://sso.example.com/device"
+# token_url = "https://sso.example.com/token"
+# scope = "openid offline_access"
+#
+# [hooks]                            # webhooks; the todo is POSTed as JSON
+# on_complete = "https://hooks.slack.com/services/..."
+# on_create = "https://example.com/pali-hook"
+# min_priority = 3                   # only for todos of at least this priority (1-3)
+#
+# [caldav]                           # `pacli caldav sync` (built with the caldav feature)
+# url = "https://cloud.example.com/remote.php/dav/calendars/me/tasks/"
+# username = "me"
+# password = "..."                   # or set PALI_CALDAV_PASSWORD
+#
+# Network
+# timeout = 30                       # seconds per request
+# connect_timeout = 5                # seconds to establish a connection
+# proxy = "http://proxy.corp:3128"
+# ca_cert_path = "/etc/ssl/corp-ca.pem"
+# insecure_skip_verify = false       # never enable outside of debugging
+#
+# log_file = "pali.log"              # relative to the data directory; rotated at 1 MB
+# cache_ttl = 30                     # seconds pacli reuses the cached todo list; 0 disables
+# reminder_window = 15               # minutes before a due date patui shows a reminder
+# reminder_bell = false              # also ring the terminal bell
+# metrics = false                    # count API calls and latencies (`pacli debug metrics`)
+# default_command = "list"           # what a bare `pacli` runs; without it, the help is shown
+#
+# [aliases]                          # pacli commands of your own; built-in commands win
+# ls = "list --sort due --group-by due"
+# urgent = "list --priority high"
+
+"#;
+
+fn is_toml(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "toml")
+}
+
+/// Current config schema version, written to every saved config
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Upgrades a raw config from one version to the next; `MIGRATIONS[n]` turns version `n` into `n + 1`
+type Migration = fn(&mut serde_json::Map<String, Value>);
+
+const MIGRATIONS: [Migration; CONFIG_VERSION as usize] = [migrate_v0_to_v1];
+
+/// Version 0 (unversioned) configs written by early releases used camelCase keys
+fn migrate_v0_to_v1(config: &mut serde_json::Map<String, Value>) {
+    for (old, new) in [("apiEndpoint", "api_endpoint"), ("apiKey", "api_key")] {
+        if let Some(value) = config.remove(old) {
+            config.entry(new).or_insert(value);
+        }
+    }
+}
+
+/// Applies pending migrations to a raw config, returning the version it started at
+///
+/// # Errors
+///
+/// Returns an error if the config is not a JSON object or comes from a newer release
+fn migrate(config: &mut Value) -> Result<u32> {
+    let Some(map) = config.as_object_mut() else {
+        anyhow::bail!("Configuration file must contain a JSON object");
+    };
+
+    let version = map
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(Ok(0), u32::try_from)?;
+    if version > CONFIG_VERSION {
+        anyhow::bail!(
+            "Configuration file is version {version}, but this release only understands up to \
+             version {CONFIG_VERSION}. Please upgrade pacli/patui."
+        );
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(map);
+    }
+    map.insert("version".to_string(), Value::from(CONFIG_VERSION));
+
+    Ok(version)
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
+    /// Schema version, see [`CONFIG_VERSION`]
+    #[serde(default)]
+    pub version: u32,
     pub api_endpoint: String,
     pub api_key: Option<String>,
+    /// The API key sealed by `pacli config encrypt`; `api_key` is then not written to disk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_api_key: Option<EncryptedSecret>,
+    /// Key used to re-encrypt `api_key` on save
+    #[serde(skip)]
+    pub(crate) encryption: Option<DerivedKey>,
+    /// Highlighting rules such as `tag:urgent -> red bold` (see [`crate::highlight`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub color_rules: Vec<String>,
+    /// Rules such as `overdue > 3d -> priority:high` (see [`crate::escalate`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub escalation_rules: Vec<String>,
+    #[serde(default, skip_serializing_if = "PriorityLabels::is_default")]
+    pub priority_labels: PriorityLabels,
+    /// When set, requests use OAuth bearer tokens (see [`crate::auth`]) instead of `api_key`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth: Option<OAuthSettings>,
+    /// Webhooks called when todos change (see [`crate::hooks`])
+    #[serde(default, skip_serializing_if = "HookSettings::is_empty")]
+    pub hooks: HookSettings,
+    /// Task list synced by `pacli caldav sync`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caldav: Option<CalDavSettings>,
+    /// Total request timeout in seconds (default 30)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Connection timeout in seconds (default 5, or 10 without `http-optimized`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<u64>,
+    /// HTTP(S) proxy URL such as `http://proxy.corp:3128`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Extra PEM root certificate(s) to trust, e.g. a corporate CA
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert_path: Option<PathBuf>,
+    /// Disables TLS certificate verification. Dangerous; for debugging only
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub insecure_skip_verify: bool,
+    /// Write logs to this file (relative to the data directory) instead of stderr
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_file: Option<PathBuf>,
+    /// How long `pacli` reuses the cached todo list, in seconds (default 30, 0 disables)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_ttl: Option<u64>,
+    /// `patui` shows a reminder this many minutes before a todo is due (default 15)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reminder_window: Option<u64>,
+    /// Ring the terminal bell when a `patui` reminder appears
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub reminder_bell: bool,
+    /// Count API calls and their latency per endpoint (see [`crate::metrics`])
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub metrics: bool,
+    /// What `pacli` runs when no command is given, e.g. `list --sort due`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_command: Option<String>,
+    /// `pacli` command aliases such as `ls = "list --sort due"`, expanded before parsing
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+}
+
+/// Identity provider used by `pacli login --oauth`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct OAuthSettings {
+    pub client_id: String,
+    pub device_authorization_url: String,
+    pub token_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+/// Webhook URLs, each POSTed the todo as JSON
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct HookSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_create: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_complete: Option<String>,
+    /// Only call hooks for todos with at least this priority
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_priority: Option<i32>,
+}
+
+impl HookSettings {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// CalDAV task list used by `pacli caldav sync`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CalDavSettings {
+    /// URL of the calendar collection holding the tasks
+    pub url: String,
+    pub username: String,
+    /// Prefer the `PALI_CALDAV_PASSWORD` environment variable over storing it here
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+/// Display names and input aliases for the three priority levels
+///
+/// Only affects how priorities are shown and typed; the server always sees
+/// the numeric levels from [`pali_types::priority`].
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct PriorityLabels {
+    pub low: String,
+    pub medium: String,
+    pub high: String,
+    /// Extra input names, e.g. `"p0": 3`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, i32>,
+}
+
+impl Default for PriorityLabels {
+    fn default() -> Self {
+        Self {
+            low: "Low".to_string(),
+            medium: "Medium".to_string(),
+            high: "High".to_string(),
+            aliases: BTreeMap::new(),
+        }
+    }
+}
+
+impl PriorityLabels {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns the display label for a priority level (unknown levels show as medium)
+    #[must_use]
+    pub fn label(&self, level: i32) -> &str {
+        match level {
+            l if l == priority::HIGH => &self.high,
+            l if l == priority::LOW => &self.low,
+            _ => &self.medium,
+        }
+    }
+
+    /// Parses user input into a priority level (case insensitive)
+    ///
+    /// Accepts configured aliases, configured labels, the built-in names
+    /// `low`/`medium`/`high` and the numbers `1`-`3`.
+    #[must_use]
+    pub fn parse(&self, input: &str) -> Option<i32> {
+        let input = input.trim().to_lowercase();
+
+        let alias = self
+            .aliases
+            .iter()
+            .find(|(name, _)| name.to_lowercase() == input)
+            .map(|(_, level)| *level);
+        if alias.is_some() {
+            return alias;
+        }
+
+        [
+            (&self.low, priority::LOW),
+            (&self.medium, priority::MEDIUM),
+            (&self.high, priority::HIGH),
+        ]
+        .into_iter()
+        .find(|(label, _)| label.to_lowercase() == input)
+        .map(|(_, level)| level)
+        .or(match input.as_str() {
+            "low" | "1" => Some(priority::LOW),
+            "medium" | "2" => Some(priority::MEDIUM),
+            "high" | "3" => Some(priority::HIGH),
+            _ => None,
+        })
+    }
+}
+
+/// Returns a masked fingerprint of a secret: its first and last four
+/// characters plus a short hash, e.g. `pk_a…9f3c (3e1b7c90)`
+///
+/// Keys too short to mask safely only show the hash.
+#[must_use]
+pub fn key_fingerprint(key: &str) -> String {
+    // FNV-1a: stable across platforms and Rust versions, unlike `DefaultHasher`
+    let hash = key.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    let hash = format!("{:08x}", hash >> 32);
+
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() < 12 {
+        return format!("({hash})");
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{head}…{tail} ({hash})")
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            version: CONFIG_VERSION,
             api_endpoint: "http://localhost:8787".to_string(),
             api_key: None,
+            encrypted_api_key: None,
+            encryption: None,
+            color_rules: Vec::new(),
+            escalation_rules: Vec::new(),
+            priority_labels: PriorityLabels::default(),
+            oauth: None,
+            hooks: HookSettings::default(),
+            caldav: None,
+            timeout: None,
+            connect_timeout: None,
+            proxy: None,
+            ca_cert_path: None,
+            insecure_skip_verify: false,
+            log_file: None,
+            cache_ttl: None,
+            reminder_window: None,
+            reminder_bell: false,
+            metrics: false,
+            default_command: None,
+            aliases: BTreeMap::new(),
         }
     }
 }
@@ -22,46 +360,186 @@ impl Default for Config {
 impl Config {
     /// Loads configuration from disk, returning default config if file doesn't exist
     ///
+    /// Files written by older releases are migrated to [`CONFIG_VERSION`] and
+    /// saved, keeping the original as `config.json.v<old version>.bak`. An
+    /// encrypted API key is decrypted transparently (see [`crate::secret`]).
+    ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - Configuration file exists but cannot be read
     /// - Configuration file format is invalid JSON
+    /// - Configuration file comes from a newer release
     /// - File permissions prevent access
+    /// - The passphrase for an encrypted API key is wrong or unavailable
     pub fn load() -> Result<Self> {
+        let start = std::time::Instant::now();
+        let config = Self::read();
+        timings::record("config load", start.elapsed());
+        config
+    }
+
+    fn read() -> Result<Self> {
         let config_path = Self::config_path()?;
 
-        if config_path.exists() {
-            let content = fs::read_to_string(config_path)?;
-            Ok(serde_json::from_str(&content)?)
+        if !config_path.exists() {
+            if CONFIG_FILE.get().is_some() {
+                log::warn!(
+                    "Config file {} doesn't exist, using defaults",
+                    config_path.display()
+                );
+            }
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&config_path)?;
+        let mut raw: Value = if is_toml(&config_path) {
+            toml::from_str(&content)?
         } else {
-            Ok(Self::default())
+            serde_json::from_str(&content)?
+        };
+        let old_version = migrate(&mut raw)?;
+        let mut config: Self = serde_json::from_value(raw)?;
+        if let Some(sealed) = &config.encrypted_api_key {
+            let (key, derived) = secret::decrypt(sealed)?;
+            config.api_key = Some(key);
+            config.encryption = Some(derived);
+        }
+        if let Some(key) = &config.api_key {
+            redact::register(key);
         }
+
+        if old_version < CONFIG_VERSION {
+            let extension = if is_toml(&config_path) {
+                "toml"
+            } else {
+                "json"
+            };
+            let backup = config_path.with_extension(format!("{extension}.v{old_version}.bak"));
+            fs::copy(&config_path, &backup)?;
+            config.save()?;
+            log::info!(
+                "Migrated config from version {old_version} to {CONFIG_VERSION} (backup: {})",
+                backup.display()
+            );
+        }
+
+        Ok(config)
     }
 
-    /// Saves the current configuration to disk
+    /// Saves the current configuration to disk, in TOML if `config.toml` is in use
     ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - Cannot create config directory
     /// - Cannot write to config file
-    /// - JSON serialization fails
+    /// - JSON/TOML serialization fails
     /// - File permissions prevent writing
     pub fn save(&self) -> Result<()> {
-        let config_path = Self::config_path()?;
+        self.save_to(&Self::config_path()?)
+    }
 
+    fn save_to(&self, config_path: &Path) -> Result<()> {
         if let Some(parent) = config_path.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        let content = serde_json::to_string_pretty(self)?;
+        let mut on_disk = self.clone();
+        if let Some(derived) = &self.encryption {
+            on_disk.encrypted_api_key = on_disk
+                .api_key
+                .take()
+                .map(|key| derived.encrypt(&key))
+                .transpose()?;
+        }
+
+        let content = if is_toml(config_path) {
+            format!("{TOML_HEADER}{}", toml::to_string_pretty(&on_disk)?)
+        } else {
+            serde_json::to_string_pretty(&on_disk)?
+        };
         fs::write(config_path, content)?;
         Ok(())
     }
 
+    /// Converts `config.json` into a commented `config.toml`, keeping the JSON file as `config.json.bak`
+    ///
+    /// Returns the path of the new file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The configuration is already in TOML
+    /// - There is no configuration to convert
+    /// - The files cannot be read, written or renamed
+    pub fn migrate_to_toml() -> Result<PathBuf> {
+        let current = Self::config_path()?;
+        if is_toml(&current) {
+            anyhow::bail!("Configuration is already stored in {}", current.display());
+        }
+        if !current.exists() {
+            anyhow::bail!("No configuration file found at {}", current.display());
+        }
+
+        let config = Self::load()?;
+        let toml_path = current.with_extension("toml");
+        config.save_to(&toml_path)?;
+        fs::rename(&current, current.with_extension("json.bak"))?;
+
+        Ok(toml_path)
+    }
+
+    /// Returns `true` if the API key is stored encrypted
+    #[must_use]
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption.is_some()
+    }
+
+    /// Encrypts the API key on the next save, prompting for a passphrase if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no API key is set or the key material cannot be obtained
+    pub fn encrypt_api_key(&mut self, source: KeySource) -> Result<()> {
+        if self.api_key.is_none() {
+            anyhow::bail!("No API key is set, nothing to encrypt");
+        }
+        self.encryption = Some(DerivedKey::create(source)?);
+        Ok(())
+    }
+
+    /// Stores the API key in plain text again on the next save
+    pub fn decrypt_api_key(&mut self) {
+        self.encryption = None;
+        self.encrypted_api_key = None;
+    }
+
+    /// Reads and writes the configuration at `path` for the rest of the process
+    ///
+    /// Called once at startup for `--config`; the format follows the extension
+    /// (`.toml` or JSON). Later calls are ignored.
+    pub fn use_file(path: PathBuf) {
+        if CONFIG_FILE.set(path).is_err() {
+            log::warn!("Config file already chosen, ignoring another --config");
+        }
+    }
+
+    /// Keeps local client data (state, logs, caches) in `path` for the rest of the process
+    ///
+    /// Used by demo mode so it never touches the real cache and state. Later
+    /// calls are ignored.
+    pub fn use_data_dir(path: PathBuf) {
+        if DATA_DIR.set(path).is_err() {
+            log::warn!("Data directory already chosen, ignoring another one");
+        }
+    }
+
     /// Returns the path to the configuration file
     ///
+    /// A file given with [`Config::use_file`] wins; otherwise `config.toml`
+    /// is preferred when present, then `config.json`.
+    ///
     /// # Errors
     ///
     /// Returns an error if:
@@ -69,12 +547,124 @@ impl Config {
     /// - HOME environment variable is not set
     /// - Cannot determine user's config directory
     pub fn config_path() -> Result<PathBuf> {
+        if let Some(path) = CONFIG_FILE.get() {
+            return Ok(path.clone());
+        }
+        let dir = Self::config_dir()?;
+        let toml_path = dir.join("config.toml");
+        if toml_path.exists() {
+            Ok(toml_path)
+        } else {
+            Ok(dir.join("config.json"))
+        }
+    }
+
+    fn config_dir() -> Result<PathBuf> {
         let proj_dirs = ProjectDirs::from("com", "pali", "pali")
             .ok_or_else(|| anyhow::anyhow!(
                 "Could not determine config directory. This usually means your system doesn't support standard config directories or the HOME environment variable is not set."
             ))?;
 
-        Ok(proj_dirs.config_dir().join("config.json"))
+        Ok(proj_dirs.config_dir().to_path_buf())
+    }
+
+    /// Returns the directory used for local client data (state, logs, caches)
+    ///
+    /// A directory given with [`Config::use_data_dir`] wins.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - System doesn't support standard data directories
+    /// - HOME environment variable is not set
+    pub fn data_dir() -> Result<PathBuf> {
+        if let Some(path) = DATA_DIR.get() {
+            return Ok(path.clone());
+        }
+        let proj_dirs = ProjectDirs::from("com", "pali", "pali")
+            .ok_or_else(|| anyhow::anyhow!(
+                "Could not determine data directory. This usually means your system doesn't support standard data directories or the HOME environment variable is not set."
+            ))?;
+
+        Ok(proj_dirs.data_dir().to_path_buf())
+    }
+
+    /// Returns the `log_file` setting without fully loading the config
+    ///
+    /// Logging starts before anything else, so this skips migration and
+    /// decryption (which could prompt for a passphrase). Relative paths are
+    /// resolved against the data directory.
+    #[must_use]
+    pub fn log_file_setting() -> Option<PathBuf> {
+        let log_file = Self::raw_setting("log_file")?;
+        Self::resolve_log_path(Path::new(log_file.as_str()?)).ok()
+    }
+
+    /// Reads the command aliases without loading the whole configuration
+    ///
+    /// `pacli` expands aliases before parsing its arguments, so like
+    /// [`Config::log_file_setting`] this skips migration and decryption.
+    /// Aliases whose expansion isn't a string are ignored.
+    #[must_use]
+    pub fn aliases_setting() -> BTreeMap<String, String> {
+        Self::raw_setting("aliases")
+            .and_then(|aliases| match aliases {
+                Value::Object(aliases) => Some(
+                    aliases
+                        .into_iter()
+                        .filter_map(|(name, command)| Some((name, command.as_str()?.to_string())))
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Reads the priority labels without loading the whole configuration
+    ///
+    /// `--priority` is checked while the arguments are parsed, before a
+    /// command could ask for a passphrase, so like [`Config::aliases_setting`]
+    /// this skips migration and decryption. Falls back to the built-in labels.
+    #[must_use]
+    pub fn priority_labels_setting() -> PriorityLabels {
+        Self::raw_setting("priority_labels")
+            .and_then(|labels| serde_json::from_value(labels).ok())
+            .unwrap_or_default()
+    }
+
+    /// Reads the default command without loading the whole configuration
+    ///
+    /// See [`Config::aliases_setting`] for why.
+    #[must_use]
+    pub fn default_command_setting() -> Option<String> {
+        Self::raw_setting("default_command")?
+            .as_str()
+            .map(str::to_string)
+    }
+
+    /// Returns one top-level setting from the raw config file
+    fn raw_setting(key: &str) -> Option<Value> {
+        let path = Self::config_path().ok()?;
+        let content = fs::read_to_string(&path).ok()?;
+        let mut raw: Value = if is_toml(&path) {
+            toml::from_str(&content).ok()?
+        } else {
+            serde_json::from_str(&content).ok()?
+        };
+        raw.get_mut(key).map(Value::take)
+    }
+
+    /// Resolves a log file path given on the command line or in the config
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is relative and the data directory cannot be determined
+    pub fn resolve_log_path(path: &Path) -> Result<PathBuf> {
+        if path.is_absolute() {
+            Ok(path.to_path_buf())
+        } else {
+            Ok(Self::data_dir()?.join(path))
+        }
     }
 
     pub fn set_endpoint(&mut self, endpoint: impl Into<String>) {
@@ -82,7 +672,9 @@ impl Config {
     }
 
     pub fn set_api_key(&mut self, key: impl Into<String>) {
-        self.api_key = Some(key.into());
+        let key = key.into();
+        redact::register(&key);
+        self.api_key = Some(key);
     }
 }
 
@@ -102,6 +694,7 @@ mod tests {
         let config = Config {
             api_endpoint: "https://api.example.com".to_string(),
             api_key: Some("test-key".to_string()),
+            ..Config::default()
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -111,6 +704,79 @@ mod tests {
         assert_eq!(config.api_key, deserialized.api_key);
     }
 
+    #[test]
+    fn test_migrate_legacy_config() {
+        let mut raw = serde_json::json!({
+            "apiEndpoint": "https://api.example.com",
+            "apiKey": "legacy-key"
+        });
+        assert_eq!(migrate(&mut raw).unwrap(), 0);
+
+        let config: Config = serde_json::from_value(raw.clone()).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.api_endpoint, "https://api.example.com");
+        assert_eq!(config.api_key.as_deref(), Some("legacy-key"));
+
+        // Already current: nothing to do
+        assert_eq!(migrate(&mut raw).unwrap(), CONFIG_VERSION);
+
+        let mut future = serde_json::json!({ "version": CONFIG_VERSION + 1 });
+        assert!(migrate(&mut future).is_err());
+    }
+
+    #[test]
+    fn test_toml_roundtrip() {
+        let mut config = Config {
+            api_endpoint: "https://api.example.com".to_string(),
+            api_key: Some("test-key".to_string()),
+            color_rules: vec!["tag:urgent -> red bold".to_string()],
+            ..Config::default()
+        };
+        config.priority_labels.high = "P1".to_string();
+
+        let text = format!("{TOML_HEADER}{}", toml::to_string_pretty(&config).unwrap());
+        let mut raw: Value = toml::from_str(&text).unwrap();
+        assert_eq!(migrate(&mut raw).unwrap(), CONFIG_VERSION);
+        let parsed: Config = serde_json::from_value(raw).unwrap();
+
+        assert_eq!(parsed.api_endpoint, config.api_endpoint);
+        assert_eq!(parsed.api_key, config.api_key);
+        assert_eq!(parsed.color_rules, config.color_rules);
+        assert_eq!(parsed.priority_labels, config.priority_labels);
+    }
+
+    #[test]
+    fn test_priority_labels() {
+        let labels: PriorityLabels = serde_json::from_str(
+            r#"{"low": "P3", "medium": "P2", "high": "P1", "aliases": {"p0": 3}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(labels.label(priority::HIGH), "P1");
+        assert_eq!(labels.label(42), "P2");
+        assert_eq!(labels.parse("P0"), Some(priority::HIGH));
+        assert_eq!(labels.parse("p3"), Some(priority::LOW));
+        assert_eq!(labels.parse("high"), Some(priority::HIGH));
+        assert_eq!(labels.parse("2"), Some(priority::MEDIUM));
+        assert_eq!(labels.parse("urgent"), None);
+
+        // Default labels are not written to the config file
+        let json = serde_json::to_string(&Config::default()).unwrap();
+        assert!(!json.contains("priority_labels"));
+    }
+
+    #[test]
+    fn test_key_fingerprint() {
+        let fingerprint = key_fingerprint("pk_live_0123456789abcdef");
+        assert!(fingerprint.starts_with("pk_l…cdef ("));
+        assert!(!fingerprint.contains("0123456789"));
+        assert_eq!(fingerprint, key_fingerprint("pk_live_0123456789abcdef"));
+        assert_ne!(fingerprint, key_fingerprint("pk_live_0123456789abcdeg"));
+
+        let short = key_fingerprint("secret");
+        assert!(!short.contains("secr"));
+    }
+
     #[test]
     fn test_set_endpoint() {
         let mut config = Config::default();
@@ -133,4 +799,10 @@ mod tests {
         assert!(path.to_string_lossy().contains("pali"));
         assert!(path.to_string_lossy().ends_with("config.json"));
     }
+
+    #[test]
+    fn test_data_dir_generation() {
+        let dir = Config::data_dir().unwrap();
+        assert!(dir.to_string_lossy().contains("pali"));
+    }
 }
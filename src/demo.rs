@@ -0,0 +1,616 @@
+//! Demo mode: `pacli --demo` and `patui --demo` without a Pali server
+//!
+//! [`start`] serves a small in-memory version of the todo API on a local
+//! port, seeded with sample todos, and points the config and the data
+//! directory at a throwaway directory, so the real config, cache and state
+//! are never touched. Changes last until the process exits.
+
+use crate::api::{Comment, Todo};
+use crate::config::{Config, PriorityLabels};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Requests with a larger body are refused
+const MAX_BODY: usize = 1024 * 1024;
+
+/// Keeps demo mode running; dropping it removes the throwaway directory
+///
+/// The server itself stops with the Tokio runtime.
+#[derive(Debug)]
+pub struct Demo {
+    dir: PathBuf,
+}
+
+impl Demo {
+    /// The throwaway directory holding the demo config and data
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl Drop for Demo {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Starts the fake server and switches the config and data directory to demo ones
+///
+/// Call it before anything loads the config; it replaces `--config`.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - No local port can be opened for the server
+/// - The throwaway directory or its config cannot be written
+pub async fn start() -> Result<Demo> {
+    let listener = TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .context("Failed to start the demo server")?;
+    let address = listener.local_addr()?;
+
+    let dir = std::env::temp_dir().join(format!("pali-demo-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let demo = Demo { dir };
+
+    Config::use_file(demo.dir.join("config.json"));
+    Config::use_data_dir(demo.dir.join("data"));
+    let config = Config {
+        api_endpoint: format!("http://{address}"),
+        api_key: Some("demo".to_string()),
+        ..Config::default()
+    };
+    config.save()?;
+
+    let store = Arc::new(Mutex::new(Store::sample(Utc::now().timestamp())));
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(serve(stream, Arc::clone(&store)));
+                }
+                Err(err) => log::debug!("Demo server failed to accept a connection: {err}"),
+            }
+        }
+    });
+    log::info!("Demo server listening on {address}");
+    Ok(demo)
+}
+
+/// Answers a single request; every response closes the connection
+async fn serve(stream: TcpStream, store: Arc<Mutex<Store>>) {
+    let mut stream = BufReader::new(stream);
+    let (method, reply) = match read_request(&mut stream).await {
+        Ok((method, target, body)) => {
+            let now = Utc::now().timestamp();
+            let mut store = store
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            let reply = store.handle(&method, &target, &body, now);
+            log::debug!("Demo server: {method} {target} -> {}", reply.status);
+            (method, reply)
+        }
+        Err(err) => {
+            log::debug!("Demo server got a malformed request: {err:#}");
+            (String::new(), Reply::error(400, "Malformed request"))
+        }
+    };
+    let _ = write_reply(stream.get_mut(), &method, &reply).await;
+}
+
+/// Reads the request line, the headers and the body of an HTTP/1.1 request
+async fn read_request(stream: &mut BufReader<TcpStream>) -> Result<(String, String, Vec<u8>)> {
+    let mut line = String::new();
+    stream.read_line(&mut line).await?;
+    let mut words = line.split_whitespace();
+    let (Some(method), Some(target)) = (words.next(), words.next()) else {
+        anyhow::bail!("Bad request line {line:?}");
+    };
+    let (method, target) = (method.to_string(), target.to_string());
+
+    let mut length = 0;
+    loop {
+        line.clear();
+        if stream.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().context("Bad Content-Length")?;
+            }
+        }
+    }
+    if length > MAX_BODY {
+        anyhow::bail!("Body of {length} bytes is too large");
+    }
+    let mut body = vec![0; length];
+    stream.read_exact(&mut body).await?;
+    Ok((method, target, body))
+}
+
+async fn write_reply(stream: &mut TcpStream, method: &str, reply: &Reply) -> Result<()> {
+    let reason = match reply.status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        404 => "Not Found",
+        409 => "Conflict",
+        _ => "Error",
+    };
+    let body = if method == "HEAD" { "" } else { &reply.body };
+    let head = format!(
+        "HTTP/1.1 {} {reason}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        reply.status,
+        reply.content_type,
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+#[derive(Debug)]
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Reply {
+    /// Wraps `data` in the API's `{ success, data }` envelope
+    fn ok(status: u16, data: impl serde::Serialize) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: json!({ "success": true, "data": data, "error": null }).to_string(),
+        }
+    }
+
+    /// An error status with a plain message, as the client shows the body of failed requests
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: message.to_string(),
+        }
+    }
+}
+
+/// Body of `POST /todos`
+#[derive(Deserialize)]
+struct NewTodo {
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    priority: Option<i32>,
+    #[serde(default)]
+    due_date: Option<i64>,
+}
+
+/// Body of `PUT /todos/{id}`; absent fields are left alone
+#[derive(Deserialize)]
+struct TodoChanges {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    completed: Option<bool>,
+    #[serde(default)]
+    priority: Option<i32>,
+    #[serde(default)]
+    due_date: Option<i64>,
+}
+
+/// Body of `POST /todos/{id}/comments`
+#[derive(Deserialize)]
+struct NewComment {
+    text: String,
+}
+
+/// The demo server's todos and comments
+#[derive(Debug, Default)]
+struct Store {
+    todos: Vec<Todo>,
+    comments: HashMap<String, Vec<Comment>>,
+    next_id: u64,
+}
+
+/// A sample todo, due `due_in` hours from now
+struct Sample {
+    title: &'static str,
+    description: Option<&'static str>,
+    priority: i32,
+    due_in: Option<i64>,
+    completed: bool,
+}
+
+const SAMPLES: [Sample; 10] = [
+    Sample {
+        title: "Prepare the quarterly review",
+        description: Some("- collect numbers from finance\n- draft the slides\n- book the room"),
+        priority: 3,
+        due_in: Some(26),
+        completed: false,
+    },
+    Sample {
+        title: "Renew passport",
+        description: None,
+        priority: 3,
+        due_in: Some(-30),
+        completed: false,
+    },
+    Sample {
+        title: "Reply to Alice about the offsite",
+        description: Some("She asked whether Thursday works for everyone."),
+        priority: 2,
+        due_in: Some(4),
+        completed: false,
+    },
+    Sample {
+        title: "Water the plants",
+        description: None,
+        priority: 1,
+        due_in: Some(8),
+        completed: false,
+    },
+    Sample {
+        title: "Read \"Designing Data-Intensive Applications\"",
+        description: Some("Chapters 5 to 7 before the book club."),
+        priority: 1,
+        due_in: Some(24 * 9),
+        completed: false,
+    },
+    Sample {
+        title: "Book dentist appointment",
+        description: None,
+        priority: 2,
+        due_in: None,
+        completed: false,
+    },
+    Sample {
+        title: "Fix the flaky login test",
+        description: Some("Fails about once in twenty runs on CI, see https://example.com/ci/1234"),
+        priority: 3,
+        due_in: Some(50),
+        completed: false,
+    },
+    Sample {
+        title: "Plan weekend hike",
+        description: Some("Check the weather first."),
+        priority: 1,
+        due_in: Some(24 * 4),
+        completed: false,
+    },
+    Sample {
+        title: "Send invoice #42",
+        description: None,
+        priority: 2,
+        due_in: Some(-72),
+        completed: true,
+    },
+    Sample {
+        title: "Buy groceries",
+        description: Some("Milk, eggs, bread, coffee"),
+        priority: 1,
+        due_in: None,
+        completed: true,
+    },
+];
+
+impl Store {
+    /// The sample todos, with due dates relative to `now`
+    fn sample(now: i64) -> Self {
+        let mut store = Self::default();
+        for (i, sample) in SAMPLES.into_iter().enumerate() {
+            let created = now - 3600 * 24 * (10 - i64::try_from(i).unwrap_or_default());
+            let mut todo = store.insert(
+                NewTodo {
+                    title: sample.title.to_string(),
+                    description: sample.description.map(str::to_string),
+                    priority: Some(sample.priority),
+                    due_date: sample.due_in.map(|hours| now + hours * 3600),
+                },
+                created,
+            );
+            if sample.completed {
+                todo.completed = true;
+                todo.updated_at = now - 3600;
+                store.replace(todo);
+            }
+        }
+
+        let review = store.todos[0].id.clone();
+        let id = store.new_id();
+        store.comments.insert(
+            review,
+            vec![Comment {
+                id,
+                text: "Finance will send the numbers on Monday".to_string(),
+                created_at: now - 3600 * 20,
+            }],
+        );
+        store
+    }
+
+    /// A fresh ID; scrambled so that short prefixes are distinct, as with real IDs
+    fn new_id(&mut self) -> String {
+        self.next_id += 1;
+        let scrambled = self.next_id.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        format!("{scrambled:016x}")
+    }
+
+    fn insert(&mut self, new: NewTodo, now: i64) -> Todo {
+        let todo = Todo {
+            id: self.new_id(),
+            title: new.title,
+            description: new.description.filter(|desc| !desc.is_empty()),
+            completed: false,
+            priority: new.priority.unwrap_or(2),
+            due_date: new.due_date,
+            created_at: now,
+            updated_at: now,
+        };
+        self.todos.push(todo.clone());
+        todo
+    }
+
+    fn replace(&mut self, todo: Todo) {
+        if let Some(slot) = self.todos.iter_mut().find(|t| t.id == todo.id) {
+            *slot = todo;
+        }
+    }
+
+    fn find(&self, id: &str) -> Option<&Todo> {
+        self.todos.iter().find(|todo| todo.id == id)
+    }
+
+    /// Routes a request to the matching endpoint
+    fn handle(&mut self, method: &str, target: &str, body: &[u8], now: i64) -> Reply {
+        let Ok(url) = reqwest::Url::parse(&format!("http://demo{target}")) else {
+            return Reply::error(400, "Malformed URL");
+        };
+        let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        let segments: Vec<String> = url
+            .path_segments()
+            .into_iter()
+            .flatten()
+            .filter(|segment| !segment.is_empty())
+            .map(percent_decode)
+            .collect();
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+        match (method, segments.as_slice()) {
+            ("HEAD" | "GET", ["health"]) => Reply::ok(200, "ok"),
+            ("GET", ["capabilities"]) => Reply::ok(
+                200,
+                json!({ "version": "demo", "features": ["resolve", "due_filter"] }),
+            ),
+            ("GET", ["todos"]) => Reply::ok(200, self.list(&query)),
+            ("POST", ["todos"]) => match serde_json::from_slice::<NewTodo>(body) {
+                Ok(new) if !new.title.trim().is_empty() => Reply::ok(201, self.insert(new, now)),
+                Ok(_) => Reply::error(400, "Title cannot be empty"),
+                Err(err) => Reply::error(400, &format!("Invalid todo: {err}")),
+            },
+            ("GET", ["todos", "search"]) => {
+                let q = query.get("q").map(|q| q.to_lowercase()).unwrap_or_default();
+                let found: Vec<&Todo> = self
+                    .todos
+                    .iter()
+                    .filter(|todo| {
+                        todo.title.to_lowercase().contains(&q)
+                            || todo
+                                .description
+                                .as_ref()
+                                .is_some_and(|desc| desc.to_lowercase().contains(&q))
+                    })
+                    .collect();
+                Reply::ok(200, found)
+            }
+            ("GET", ["todos", "resolve", prefix]) => {
+                let matches: Vec<&Todo> = self
+                    .todos
+                    .iter()
+                    .filter(|todo| todo.id.starts_with(prefix))
+                    .collect();
+                match matches.as_slice() {
+                    [todo] => Reply::ok(200, json!({ "full_id": todo.id })),
+                    [] => Reply::error(404, &format!("No todo found with ID prefix '{prefix}'")),
+                    _ => Reply::error(409, &format!("Multiple todos match ID prefix '{prefix}'")),
+                }
+            }
+            ("GET", ["todos", id]) => self.with_todo(id, |todo| Reply::ok(200, todo)),
+            ("PUT", ["todos", id]) => match serde_json::from_slice::<TodoChanges>(body) {
+                Ok(changes) => self.update(id, now, |todo| {
+                    if let Some(title) = changes.title {
+                        todo.title = title;
+                    }
+                    if let Some(description) = changes.description {
+                        todo.description = Some(description).filter(|desc| !desc.is_empty());
+                    }
+                    if let Some(completed) = changes.completed {
+                        todo.completed = completed;
+                    }
+                    if let Some(priority) = changes.priority {
+                        todo.priority = priority;
+                    }
+                    if let Some(due_date) = changes.due_date {
+                        todo.due_date = Some(due_date);
+                    }
+                }),
+                Err(err) => Reply::error(400, &format!("Invalid changes: {err}")),
+            },
+            ("PATCH", ["todos", id, "toggle"]) => {
+                self.update(id, now, |todo| todo.completed = !todo.completed)
+            }
+            ("DELETE", ["todos", id]) => {
+                let before = self.todos.len();
+                self.todos.retain(|todo| todo.id != *id);
+                if self.todos.len() == before {
+                    Reply::error(404, "Todo not found")
+                } else {
+                    self.comments.remove(*id);
+                    Reply::ok(200, Value::Null)
+                }
+            }
+            ("GET", ["todos", id, "comments"]) => {
+                let comments = self.comments.get(*id).cloned().unwrap_or_default();
+                self.with_todo(id, |_| Reply::ok(200, comments))
+            }
+            ("POST", ["todos", id, "comments"]) => {
+                if self.find(id).is_none() {
+                    return Reply::error(404, "Todo not found");
+                }
+                match serde_json::from_slice::<NewComment>(body) {
+                    Ok(new) => {
+                        let comment = Comment {
+                            id: self.new_id(),
+                            text: new.text,
+                            created_at: now,
+                        };
+                        self.comments
+                            .entry((*id).to_string())
+                            .or_default()
+                            .push(comment.clone());
+                        Reply::ok(201, comment)
+                    }
+                    Err(err) => Reply::error(400, &format!("Invalid comment: {err}")),
+                }
+            }
+            ("GET", ["todos", id, "attachments"]) => {
+                self.with_todo(id, |_| Reply::ok(200, Vec::<Value>::new()))
+            }
+            _ => Reply::error(
+                404,
+                &format!("{method} {} is not available in demo mode", url.path()),
+            ),
+        }
+    }
+
+    /// `GET /todos` with the filters the client sends
+    fn list(&self, query: &HashMap<String, String>) -> Vec<&Todo> {
+        let number = |name: &str| query.get(name).and_then(|value| value.parse::<i64>().ok());
+        // The client sends priority names such as `high`, as well as numbers
+        let labels = PriorityLabels::default();
+        let priority = query.get("priority").and_then(|value| labels.parse(value));
+        let (updated_after, due_after, due_before) = (
+            number("updated_after"),
+            number("due_after"),
+            number("due_before"),
+        );
+        self.todos
+            .iter()
+            .filter(|todo| priority.is_none_or(|priority| todo.priority == priority))
+            .filter(|todo| updated_after.is_none_or(|after| todo.updated_at > after))
+            .filter(|todo| {
+                due_after.is_none_or(|after| todo.due_date.is_some_and(|due| due >= after))
+            })
+            .filter(|todo| {
+                due_before.is_none_or(|before| todo.due_date.is_some_and(|due| due < before))
+            })
+            .collect()
+    }
+
+    fn with_todo(&self, id: &str, reply: impl FnOnce(&Todo) -> Reply) -> Reply {
+        self.find(id)
+            .map_or_else(|| Reply::error(404, "Todo not found"), reply)
+    }
+
+    fn update(&mut self, id: &str, now: i64, change: impl FnOnce(&mut Todo)) -> Reply {
+        match self.todos.iter_mut().find(|todo| todo.id == id) {
+            Some(todo) => {
+                change(todo);
+                todo.updated_at = now.max(todo.updated_at + 1);
+                Reply::ok(200, &*todo)
+            }
+            None => Reply::error(404, "Todo not found"),
+        }
+    }
+}
+
+/// Decodes `%XX` escapes in a path segment
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| segment.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        if let Some(byte) = escaped {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(reply: &Reply) -> Value {
+        assert_eq!(reply.status / 100, 2, "{}", reply.body);
+        serde_json::from_str::<Value>(&reply.body).unwrap()["data"].clone()
+    }
+
+    #[test]
+    fn test_store() {
+        let now = 1_700_000_000;
+        let mut store = Store::sample(now);
+        let all = data(&store.handle("GET", "/todos", b"", now));
+        assert_eq!(all.as_array().unwrap().len(), SAMPLES.len());
+        let high = data(&store.handle("GET", "/todos?priority=high", b"", now));
+        assert_eq!(high.as_array().unwrap().len(), 3);
+        assert_eq!(
+            data(&store.handle("GET", "/todos?priority=3", b"", now)),
+            high
+        );
+        let due = format!("/todos?due_after={now}&due_before={}", now + 3600 * 24);
+        assert_eq!(
+            data(&store.handle("GET", &due, b"", now))
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let body =
+            br#"{"title":"Try the demo","description":null,"priority":null,"due_date":null}"#;
+        let created: Todo =
+            serde_json::from_value(data(&store.handle("POST", "/todos", body, now))).unwrap();
+        assert_eq!(created.priority, 2);
+
+        let toggled = store.handle("PATCH", &format!("/todos/{}/toggle", created.id), b"", now);
+        assert_eq!(data(&toggled)["completed"], true);
+        let prefix = &created.id[..6];
+        let resolved = store.handle("GET", &format!("/todos/resolve/{prefix}"), b"", now);
+        assert_eq!(data(&resolved)["full_id"], created.id.as_str());
+
+        let found = data(&store.handle("GET", "/todos/search?q=try%20the", b"", now));
+        assert_eq!(found.as_array().unwrap().len(), 1);
+
+        let path = format!("/todos/{}", created.id);
+        assert_eq!(store.handle("DELETE", &path, b"", now).status, 200);
+        assert_eq!(store.handle("GET", &path, b"", now).status, 404);
+        assert_eq!(
+            store.handle("POST", "/admin/keys/rotate", b"", now).status,
+            404
+        );
+        assert_eq!(percent_decode("a%20b%zz"), "a b%zz");
+    }
+}
@@ -0,0 +1,163 @@
+//! Due date buckets ("Overdue", "Today", "Later", ...) for grouped and agenda views
+//!
+//! Buckets follow local calendar days, like the due dates shown by `pacli`
+//! and `patui`.
+
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone, Utc};
+use pali_types::Todo;
+use serde::{Deserialize, Serialize};
+
+/// Days after today (inclusive) that count as "This week"
+const WEEK_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DueBucket {
+    /// Open and due before today
+    Overdue,
+    /// Completed, with a due date before today
+    Earlier,
+    Today,
+    Tomorrow,
+    /// Due within the next week, after tomorrow
+    ThisWeek,
+    Later,
+    NoDueDate,
+}
+
+impl DueBucket {
+    /// All buckets, in display order
+    pub const ALL: [Self; 7] = [
+        Self::Overdue,
+        Self::Earlier,
+        Self::Today,
+        Self::Tomorrow,
+        Self::ThisWeek,
+        Self::Later,
+        Self::NoDueDate,
+    ];
+
+    /// Returns the bucket of `todo` as seen on `today`
+    #[must_use]
+    pub fn of(todo: &Todo, today: NaiveDate) -> Self {
+        let Some(date) = todo.due_date.and_then(local_date) else {
+            return Self::NoDueDate;
+        };
+        let days = (date - today).num_days();
+        match days {
+            ..=-1 if todo.completed => Self::Earlier,
+            ..=-1 => Self::Overdue,
+            0 => Self::Today,
+            1 => Self::Tomorrow,
+            2..=WEEK_DAYS => Self::ThisWeek,
+            _ => Self::Later,
+        }
+    }
+
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Overdue => "Overdue",
+            Self::Earlier => "Earlier",
+            Self::Today => "Today",
+            Self::Tomorrow => "Tomorrow",
+            Self::ThisWeek => "This week",
+            Self::Later => "Later",
+            Self::NoDueDate => "No due date",
+        }
+    }
+}
+
+/// Local calendar date of a due timestamp
+#[must_use]
+pub fn local_date(due_ts: i64) -> Option<NaiveDate> {
+    Utc.timestamp_opt(due_ts, 0)
+        .latest()
+        .map(|due| due.with_timezone(&Local).date_naive())
+}
+
+/// Moves a due timestamp to another local day, keeping its time of day
+///
+/// Returns `None` if that time doesn't exist on `date` (a DST gap).
+#[must_use]
+pub fn move_to_date(due_ts: i64, date: NaiveDate) -> Option<i64> {
+    let due = Utc.timestamp_opt(due_ts, 0).latest()?.with_timezone(&Local);
+    Local
+        .from_local_datetime(&date.and_time(due.time()))
+        .earliest()
+        .map(|moved| moved.timestamp())
+}
+
+/// The Monday starting the week that contains `date`
+#[must_use]
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+}
+
+/// Returns `true` if `todo` is open and was due before `today`
+#[must_use]
+pub fn is_overdue(todo: &Todo, today: NaiveDate) -> bool {
+    DueBucket::of(todo, today) == DueBucket::Overdue
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn due_in(days: i64, completed: bool) -> Todo {
+        let noon = Local::now()
+            .date_naive()
+            .and_hms_opt(12, 0, 0)
+            .and_then(|noon| Local.from_local_datetime(&noon).earliest())
+            .unwrap();
+        Todo {
+            id: "a".to_string(),
+            title: "a".to_string(),
+            description: None,
+            completed,
+            priority: 2,
+            due_date: Some((noon + chrono::Duration::days(days)).timestamp()),
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_buckets() {
+        let today = Local::now().date_naive();
+        let bucket = |days, completed| DueBucket::of(&due_in(days, completed), today);
+
+        assert_eq!(bucket(-3, false), DueBucket::Overdue);
+        assert_eq!(bucket(-3, true), DueBucket::Earlier);
+        assert_eq!(bucket(0, false), DueBucket::Today);
+        assert_eq!(bucket(1, false), DueBucket::Tomorrow);
+        assert_eq!(bucket(5, false), DueBucket::ThisWeek);
+        assert_eq!(bucket(30, false), DueBucket::Later);
+
+        let mut undated = due_in(0, false);
+        undated.due_date = None;
+        assert_eq!(DueBucket::of(&undated, today), DueBucket::NoDueDate);
+    }
+
+    #[test]
+    fn test_move_to_date() {
+        let due = due_in(0, false).due_date.unwrap();
+        let today = Local::now().date_naive();
+        let moved = move_to_date(due, today + Duration::days(3)).unwrap();
+        assert_eq!(local_date(moved), Some(today + Duration::days(3)));
+        assert_eq!(
+            Utc.timestamp_opt(moved, 0)
+                .unwrap()
+                .with_timezone(&Local)
+                .time(),
+            Utc.timestamp_opt(due, 0)
+                .unwrap()
+                .with_timezone(&Local)
+                .time()
+        );
+
+        let monday = week_start(today);
+        assert_eq!(monday.weekday(), chrono::Weekday::Mon);
+        assert!((today - monday).num_days() < 7);
+    }
+}
@@ -0,0 +1,104 @@
+//! Editing a todo's title and description in the user's `$EDITOR`
+//!
+//! The todo is written to a temporary file as its title, a blank line and
+//! its description, so long descriptions can be edited with a real editor.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Formats a title and description as the text of the file to edit
+#[must_use]
+pub fn to_text(title: &str, description: Option<&str>) -> String {
+    match description.filter(|desc| !desc.is_empty()) {
+        Some(desc) => format!("{title}\n\n{desc}\n"),
+        None => format!("{title}\n"),
+    }
+}
+
+/// Splits edited text back into the title (its first line) and description
+///
+/// Blank lines between the title and the description are dropped; an empty
+/// description is returned as an empty string.
+#[must_use]
+pub fn parse(text: &str) -> (String, String) {
+    let mut lines = text.lines();
+    let title = lines.next().unwrap_or_default().trim().to_string();
+    let rest: Vec<&str> = lines.skip_while(|line| line.trim().is_empty()).collect();
+    (title, rest.join("\n").trim_end().to_string())
+}
+
+/// The editor to run: `$VISUAL`, then `$EDITOR`, then the platform's default
+fn command() -> String {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string())
+}
+
+/// Opens `text` in the editor and waits for it to exit
+///
+/// Returns the saved text, or `None` if it was left unchanged. `name`
+/// becomes part of the temporary file's name, which ends in `.md` so
+/// editors highlight descriptions as markdown.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The temporary file cannot be written or read back
+/// - The editor cannot be started or exits unsuccessfully
+pub fn edit(text: &str, name: &str) -> Result<Option<String>> {
+    let path = std::env::temp_dir().join(format!("pali-{}-{name}.md", std::process::id()));
+    std::fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let edited = run(&path).and_then(|()| {
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))
+    });
+    let _ = std::fs::remove_file(&path);
+
+    let edited = edited?;
+    Ok((edited != text).then_some(edited))
+}
+
+/// Runs the editor on `path`; the command may carry arguments, e.g. `code --wait`
+fn run(path: &Path) -> Result<()> {
+    let editor = command();
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to start the editor '{editor}'"))?;
+    if !status.success() {
+        anyhow::bail!("The editor '{editor}' exited with {status}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = to_text("Write report", Some("- intro\n\n- results"));
+        assert_eq!(text, "Write report\n\n- intro\n\n- results\n");
+        assert_eq!(
+            parse(&text),
+            (
+                "Write report".to_string(),
+                "- intro\n\n- results".to_string()
+            )
+        );
+
+        assert_eq!(to_text("Call Bob", None), "Call Bob\n");
+        assert_eq!(to_text("Call Bob", Some("")), "Call Bob\n");
+        assert_eq!(
+            parse("  Call Bob  \n\n\n"),
+            ("Call Bob".to_string(), String::new())
+        );
+        assert_eq!(parse(""), (String::new(), String::new()));
+    }
+}
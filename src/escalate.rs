@@ -0,0 +1,244 @@
+//! Escalation rules for overdue todos
+//!
+//! Rules are configured as strings such as `overdue > 3d -> priority:high`
+//! or `overdue > 7d -> tag:stale`. `pacli list` applies them before listing
+//! and `pacli escalate` applies (or, with `--dry-run`, previews) them on
+//! demand. Rules only ever raise a priority or add a tag, so applying them
+//! twice changes nothing.
+
+use crate::config::PriorityLabels;
+use crate::due::local_date;
+use crate::state::LocalState;
+use anyhow::Result;
+use chrono::NaiveDate;
+use pali_types::Todo;
+use std::str::FromStr;
+
+/// What a rule does to a matching todo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// `priority:<level>` - raise the priority to the level (never lowers it)
+    Priority(i32),
+    /// `tag:<name>` - add the tag
+    Tag(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EscalationRule {
+    /// The rule applies to open todos overdue by more than this many days
+    pub overdue_days: i64,
+    pub action: Action,
+}
+
+/// The changes the rules make to one todo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Escalation {
+    pub todo: Todo,
+    /// Days since the due date
+    pub overdue_days: i64,
+    /// New priority, if raised
+    pub priority: Option<i32>,
+    /// Tags to add
+    pub tags: Vec<String>,
+}
+
+/// Parses the condition: `overdue` or `overdue > <n>[d|days]`
+fn parse_condition(s: &str) -> Result<i64> {
+    let s = s.trim();
+    let Some(rest) = s.strip_prefix("overdue") else {
+        anyhow::bail!("Unknown condition '{s}'. Use overdue or overdue > <days>d");
+    };
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return Ok(0);
+    }
+
+    let Some(days) = rest.strip_prefix('>') else {
+        anyhow::bail!("Expected '>' after 'overdue' in '{s}'");
+    };
+    let days = days.trim();
+    let days = days
+        .strip_suffix("days")
+        .or_else(|| days.strip_suffix("day"))
+        .or_else(|| days.strip_suffix('d'))
+        .unwrap_or(days)
+        .trim();
+    days.parse::<u32>()
+        .map(i64::from)
+        .map_err(|_| anyhow::anyhow!("Invalid number of days '{days}' in '{s}'"))
+}
+
+impl FromStr for Action {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Some(level) = s.strip_prefix("priority:") {
+            // Built-in names only, so rules keep working when labels are renamed
+            let level = PriorityLabels::default().parse(level).ok_or_else(|| {
+                anyhow::anyhow!("Invalid priority '{level}'. Use low, medium, high or 1-3")
+            })?;
+            return Ok(Self::Priority(level));
+        }
+
+        if let Some(tag) = s.strip_prefix("tag:") {
+            let tag = tag.trim().trim_start_matches('#');
+            if tag.is_empty() {
+                anyhow::bail!("Tag name missing in '{s}'");
+            }
+            return Ok(Self::Tag(tag.to_string()));
+        }
+
+        anyhow::bail!("Unknown action '{s}'. Use priority:<level> or tag:<name>")
+    }
+}
+
+impl FromStr for EscalationRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((condition, action)) = s.split_once("->") else {
+            anyhow::bail!("Invalid escalation rule '{s}'. Expected '<condition> -> <action>'");
+        };
+
+        Ok(Self {
+            overdue_days: parse_condition(condition)?,
+            action: action.parse()?,
+        })
+    }
+}
+
+/// Parses configured rules, skipping (and logging) invalid ones
+#[must_use]
+pub fn parse_rules(rules: &[String]) -> Vec<EscalationRule> {
+    rules
+        .iter()
+        .filter_map(|rule| match rule.parse() {
+            Ok(parsed) => Some(parsed),
+            Err(err) => {
+                log::warn!("Ignoring escalation rule '{rule}': {err}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Works out what `rules` would change, as seen on `today`
+///
+/// Only open, unarchived todos are considered, and only changes that do
+/// something are returned: a priority already at or above the target and a
+/// tag already present are left alone.
+#[must_use]
+pub fn plan(
+    rules: &[EscalationRule],
+    todos: &[Todo],
+    state: &LocalState,
+    today: NaiveDate,
+) -> Vec<Escalation> {
+    todos
+        .iter()
+        .filter(|todo| !todo.completed && !state.is_archived(&todo.id))
+        .filter_map(|todo| {
+            let overdue_days = (today - todo.due_date.and_then(local_date)?).num_days();
+            let mut escalation = Escalation {
+                todo: todo.clone(),
+                overdue_days,
+                priority: None,
+                tags: Vec::new(),
+            };
+            for rule in rules.iter().filter(|rule| overdue_days > rule.overdue_days) {
+                match &rule.action {
+                    Action::Priority(level) => {
+                        let current = escalation.priority.unwrap_or(todo.priority);
+                        if *level > current {
+                            escalation.priority = Some(*level);
+                        }
+                    }
+                    Action::Tag(tag) => {
+                        let queued = escalation.tags.iter().any(|t| t.eq_ignore_ascii_case(tag));
+                        if !queued && !state.has_tag(&todo.id, tag) {
+                            escalation.tags.push(tag.clone());
+                        }
+                    }
+                }
+            }
+            (escalation.priority.is_some() || !escalation.tags.is_empty()).then_some(escalation)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use chrono::{Local, TimeZone};
+
+    fn todo(id: &str, priority: i32, overdue_days: i64) -> Todo {
+        let noon = Local::now()
+            .date_naive()
+            .and_hms_opt(12, 0, 0)
+            .and_then(|noon| Local.from_local_datetime(&noon).earliest())
+            .unwrap();
+        testing::todo(id)
+            .priority(priority)
+            .due(Some(
+                (noon - chrono::Duration::days(overdue_days)).timestamp(),
+            ))
+            .build()
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let rule: EscalationRule = "overdue > 3 days -> priority:high".parse().unwrap();
+        assert_eq!(rule.overdue_days, 3);
+        assert_eq!(rule.action, Action::Priority(3));
+
+        let rule: EscalationRule = "overdue -> tag:#stale".parse().unwrap();
+        assert_eq!(rule.overdue_days, 0);
+        assert_eq!(rule.action, Action::Tag("stale".to_string()));
+
+        assert!("overdue>3d -> priority:urgent"
+            .parse::<EscalationRule>()
+            .is_err());
+        assert!("due < 3d -> tag:soon".parse::<EscalationRule>().is_err());
+        assert!("overdue > 3d".parse::<EscalationRule>().is_err());
+    }
+
+    #[test]
+    fn test_plan() {
+        let rules = parse_rules(&[
+            "overdue > 3d -> priority:medium".to_string(),
+            "overdue > 7d -> priority:high".to_string(),
+            "overdue > 7d -> tag:stale".to_string(),
+        ]);
+        let todos = [
+            todo("a", 1, 5),
+            todo("b", 1, 10),
+            todo("c", 3, 5),
+            todo("d", 1, 2),
+        ];
+        let mut state = LocalState::default();
+        let today = Local::now().date_naive();
+
+        let planned = plan(&rules, &todos, &state, today);
+        let summary: Vec<_> = planned
+            .iter()
+            .map(|e| (e.todo.id.as_str(), e.priority, e.tags.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("a", Some(2), vec![]),
+                ("b", Some(3), vec!["stale".to_string()]),
+            ]
+        );
+
+        // Already applied changes aren't planned again
+        state.set_tags("b", vec!["stale".to_string()]);
+        let mut todos = todos;
+        todos[1].priority = 3;
+        let planned = plan(&rules, &todos, &state, today);
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].todo.id, "a");
+    }
+}
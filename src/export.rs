@@ -0,0 +1,208 @@
+//! Writing todos to files, shared by `pacli export` and the `patui` selection export
+//!
+//! An [`Exporter`] writes todos one at a time, so a streamed export never
+//! holds every todo in memory.
+
+use crate::api::Todo;
+use crate::config::PriorityLabels;
+use anyhow::Result;
+use chrono::{Local, TimeZone};
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// One JSON todo per line
+    Ndjson,
+    /// A single JSON array
+    Json,
+    /// A task list, e.g. to paste into meeting notes
+    Markdown,
+    /// A header row, then one row per todo
+    Csv,
+}
+
+impl Format {
+    /// Picks the format matching the extension of `path`
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(Self::Json),
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            "md" | "markdown" => Some(Self::Markdown),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+const CSV_HEADER: &str = "id,title,description,completed,priority,due,created,updated";
+
+/// Writes todos in a [`Format`]; call [`Exporter::finish`] to complete the file
+pub struct Exporter<W: Write> {
+    writer: W,
+    format: Format,
+    labels: PriorityLabels,
+    count: usize,
+}
+
+impl<W: Write> Exporter<W> {
+    /// Starts an export, writing what comes before the first todo
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails
+    pub fn new(mut writer: W, format: Format, labels: PriorityLabels) -> Result<Self> {
+        match format {
+            Format::Json => write!(writer, "[")?,
+            Format::Csv => writeln!(writer, "{CSV_HEADER}")?,
+            Format::Ndjson | Format::Markdown => {}
+        }
+        Ok(Self {
+            writer,
+            format,
+            labels,
+            count: 0,
+        })
+    }
+
+    /// Writes one todo
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails
+    pub fn write(&mut self, todo: &Todo) -> Result<()> {
+        match self.format {
+            Format::Ndjson => {
+                serde_json::to_writer(&mut self.writer, todo)?;
+                writeln!(self.writer)?;
+            }
+            Format::Json => {
+                write!(
+                    self.writer,
+                    "{}\n  ",
+                    if self.count == 0 { "" } else { "," }
+                )?;
+                serde_json::to_writer(&mut self.writer, todo)?;
+            }
+            Format::Markdown => {
+                let mut details = vec![self.labels.label(todo.priority).to_string()];
+                if let Some(due) = todo.due_date.and_then(|due| format_time(due, "%Y-%m-%d")) {
+                    details.push(format!("due {due}"));
+                }
+                writeln!(
+                    self.writer,
+                    "- [{}] **{}** ({})",
+                    if todo.completed { 'x' } else { ' ' },
+                    todo.title,
+                    details.join(", ")
+                )?;
+                for line in todo.description.iter().flat_map(|desc| desc.lines()) {
+                    writeln!(self.writer, "  {line}")?;
+                }
+            }
+            Format::Csv => {
+                let time = |ts: i64| format_time(ts, "%Y-%m-%dT%H:%M:%S%:z").unwrap_or_default();
+                let fields = [
+                    todo.id.clone(),
+                    todo.title.clone(),
+                    todo.description.clone().unwrap_or_default(),
+                    todo.completed.to_string(),
+                    self.labels.label(todo.priority).to_string(),
+                    todo.due_date.map(time).unwrap_or_default(),
+                    time(todo.created_at),
+                    time(todo.updated_at),
+                ];
+                let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+                writeln!(self.writer, "{}", row.join(","))?;
+            }
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Completes the file and returns how many todos were written
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing or flushing fails
+    pub fn finish(mut self) -> Result<usize> {
+        if self.format == Format::Json {
+            writeln!(self.writer, "{}]", if self.count == 0 { "" } else { "\n" })?;
+        }
+        self.writer.flush()?;
+        Ok(self.count)
+    }
+}
+
+/// Formats a Unix timestamp in local time
+fn format_time(ts: i64, format: &str) -> Option<String> {
+    Local
+        .timestamp_opt(ts, 0)
+        .single()
+        .map(|time| time.format(format).to_string())
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(format: Format, todos: &[Todo]) -> String {
+        let mut out = Vec::new();
+        let mut exporter = Exporter::new(&mut out, format, PriorityLabels::default()).unwrap();
+        for todo in todos {
+            exporter.write(todo).unwrap();
+        }
+        assert_eq!(exporter.finish().unwrap(), todos.len());
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_formats() {
+        let todo = Todo {
+            id: "abc".to_string(),
+            title: "Call \"Bob\", then Alice".to_string(),
+            description: Some("agenda\n- budget".to_string()),
+            completed: true,
+            priority: 3,
+            due_date: None,
+            created_at: 0,
+            updated_at: 0,
+        };
+        let todos = [todo.clone(), todo];
+
+        let markdown = export(Format::Markdown, &todos[..1]);
+        assert_eq!(
+            markdown,
+            "- [x] **Call \"Bob\", then Alice** (High)\n  agenda\n  - budget\n"
+        );
+
+        let csv = export(Format::Csv, &todos[..1]);
+        let mut rows = csv.lines();
+        assert_eq!(rows.next(), Some(CSV_HEADER));
+        assert!(rows
+            .next()
+            .unwrap()
+            .starts_with("abc,\"Call \"\"Bob\"\", then Alice\",\"agenda"));
+
+        let json: Vec<Todo> = serde_json::from_str(&export(Format::Json, &todos)).unwrap();
+        assert_eq!(json.len(), 2);
+        assert_eq!(export(Format::Ndjson, &todos).lines().count(), 2);
+
+        assert_eq!(
+            Format::from_path(Path::new("notes/today.MD")),
+            Some(Format::Markdown)
+        );
+        assert_eq!(Format::from_path(Path::new("todos")), None);
+    }
+}
@@ -0,0 +1,279 @@
+//! User-defined color rules shared by the CLI and TUI
+//!
+//! Rules are configured as strings such as `tag:urgent -> red bold` or
+//! `title~"waiting" -> dim`. Every rule whose matcher applies to a todo
+//! contributes to its style; later rules override the color of earlier ones
+//! while modifiers accumulate.
+
+use anyhow::Result;
+use colored::{ColoredString, Colorize};
+use pali_types::Todo;
+use std::str::FromStr;
+
+/// What a rule matches on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Matcher {
+    /// `tag:<name>` - todo carries the tag (case insensitive)
+    Tag(String),
+    /// `title~"<text>"` - title contains the text (case insensitive)
+    TitleContains(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleColor {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Gray,
+}
+
+/// Style produced by one or more rules
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RuleStyle {
+    pub color: Option<RuleColor>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorRule {
+    pub matcher: Matcher,
+    pub style: RuleStyle,
+}
+
+impl FromStr for Matcher {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Some(tag) = s.strip_prefix("tag:") {
+            let tag = tag.trim().trim_start_matches('#');
+            if tag.is_empty() {
+                anyhow::bail!("Tag name missing in '{s}'");
+            }
+            return Ok(Self::Tag(tag.to_lowercase()));
+        }
+
+        if let Some(text) = s.strip_prefix("title~") {
+            let text = text.trim();
+            let text = text
+                .strip_prefix('"')
+                .and_then(|t| t.strip_suffix('"'))
+                .unwrap_or(text);
+            if text.is_empty() {
+                anyhow::bail!("Search text missing in '{s}'");
+            }
+            return Ok(Self::TitleContains(text.to_lowercase()));
+        }
+
+        anyhow::bail!("Unknown matcher '{s}'. Use tag:<name> or title~\"<text>\"")
+    }
+}
+
+impl FromStr for RuleStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut style = Self::default();
+        for word in s.split_whitespace() {
+            match word.to_lowercase().as_str() {
+                "bold" => style.bold = true,
+                "dim" => style.dim = true,
+                "italic" => style.italic = true,
+                "underline" => style.underline = true,
+                "red" => style.color = Some(RuleColor::Red),
+                "green" => style.color = Some(RuleColor::Green),
+                "yellow" => style.color = Some(RuleColor::Yellow),
+                "blue" => style.color = Some(RuleColor::Blue),
+                "magenta" => style.color = Some(RuleColor::Magenta),
+                "cyan" => style.color = Some(RuleColor::Cyan),
+                "white" => style.color = Some(RuleColor::White),
+                "gray" | "grey" => style.color = Some(RuleColor::Gray),
+                other => anyhow::bail!("Unknown style '{other}'"),
+            }
+        }
+
+        if style == Self::default() {
+            anyhow::bail!("No style given");
+        }
+        Ok(style)
+    }
+}
+
+impl FromStr for ColorRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((matcher, style)) = s.split_once("->") else {
+            anyhow::bail!("Invalid color rule '{s}'. Expected '<matcher> -> <style>'");
+        };
+
+        Ok(Self {
+            matcher: matcher.parse()?,
+            style: style.parse()?,
+        })
+    }
+}
+
+impl ColorRule {
+    #[must_use]
+    pub fn matches(&self, todo: &Todo, tags: &[String]) -> bool {
+        match &self.matcher {
+            Matcher::Tag(tag) => tags.iter().any(|t| t.eq_ignore_ascii_case(tag)),
+            Matcher::TitleContains(text) => todo.title.to_lowercase().contains(text),
+        }
+    }
+}
+
+impl RuleStyle {
+    fn merge(&mut self, other: Self) {
+        if other.color.is_some() {
+            self.color = other.color;
+        }
+        self.bold |= other.bold;
+        self.dim |= other.dim;
+        self.italic |= other.italic;
+        self.underline |= other.underline;
+    }
+
+    /// Applies the style to text for terminal output
+    #[must_use]
+    pub fn paint(&self, text: &str) -> ColoredString {
+        let mut out = match self.color {
+            Some(RuleColor::Red) => text.red(),
+            Some(RuleColor::Green) => text.green(),
+            Some(RuleColor::Yellow) => text.yellow(),
+            Some(RuleColor::Blue) => text.blue(),
+            Some(RuleColor::Magenta) => text.magenta(),
+            Some(RuleColor::Cyan) => text.cyan(),
+            Some(RuleColor::White) => text.white(),
+            Some(RuleColor::Gray) => text.bright_black(),
+            None => text.normal(),
+        };
+        if self.bold {
+            out = out.bold();
+        }
+        if self.dim {
+            out = out.dimmed();
+        }
+        if self.italic {
+            out = out.italic();
+        }
+        if self.underline {
+            out = out.underline();
+        }
+        out
+    }
+
+    /// Layers the style on top of a ratatui style
+    #[cfg(feature = "tui")]
+    #[must_use]
+    pub fn patch(&self, base: ratatui::style::Style) -> ratatui::style::Style {
+        use ratatui::style::{Color, Modifier};
+
+        let mut style = base;
+        if let Some(color) = self.color {
+            style = style.fg(match color {
+                RuleColor::Red => Color::Red,
+                RuleColor::Green => Color::Green,
+                RuleColor::Yellow => Color::Yellow,
+                RuleColor::Blue => Color::Blue,
+                RuleColor::Magenta => Color::Magenta,
+                RuleColor::Cyan => Color::Cyan,
+                RuleColor::White => Color::White,
+                RuleColor::Gray => Color::Gray,
+            });
+        }
+        if self.bold {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if self.dim {
+            style = style.add_modifier(Modifier::DIM);
+        }
+        if self.italic {
+            style = style.add_modifier(Modifier::ITALIC);
+        }
+        if self.underline {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        style
+    }
+}
+
+/// Parses configured rules, skipping (and logging) invalid ones
+#[must_use]
+pub fn parse_rules(rules: &[String]) -> Vec<ColorRule> {
+    rules
+        .iter()
+        .filter_map(|rule| match rule.parse() {
+            Ok(parsed) => Some(parsed),
+            Err(err) => {
+                log::warn!("Ignoring color rule '{rule}': {err}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Returns the combined style of all rules matching `todo`, if any
+#[must_use]
+pub fn style_for(rules: &[ColorRule], todo: &Todo, tags: &[String]) -> Option<RuleStyle> {
+    let mut matching = rules.iter().filter(|rule| rule.matches(todo, tags));
+    let mut style = matching.next()?.style;
+    for rule in matching {
+        style.merge(rule.style);
+    }
+    Some(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn todo(title: &str) -> Todo {
+        testing::todo("a").title(title).build()
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let rule: ColorRule = "tag:urgent -> red bold".parse().unwrap();
+        assert_eq!(rule.matcher, Matcher::Tag("urgent".to_string()));
+        assert_eq!(rule.style.color, Some(RuleColor::Red));
+        assert!(rule.style.bold);
+
+        let rule: ColorRule = "title~\"Waiting on\" -> dim".parse().unwrap();
+        assert_eq!(
+            rule.matcher,
+            Matcher::TitleContains("waiting on".to_string())
+        );
+        assert!(rule.style.dim);
+
+        assert!("tag:urgent".parse::<ColorRule>().is_err());
+        assert!("tag:urgent -> sparkly".parse::<ColorRule>().is_err());
+        assert!("due:today -> red".parse::<ColorRule>().is_err());
+    }
+
+    #[test]
+    fn test_style_for_merges_matching_rules() {
+        let rules = parse_rules(&[
+            "tag:urgent -> red bold".to_string(),
+            "title~waiting -> dim".to_string(),
+            "not a rule".to_string(),
+        ]);
+        assert_eq!(rules.len(), 2);
+
+        let tags = vec!["Urgent".to_string()];
+        let style = style_for(&rules, &todo("Waiting for review"), &tags).unwrap();
+        assert_eq!(style.color, Some(RuleColor::Red));
+        assert!(style.bold && style.dim);
+
+        assert!(style_for(&rules, &todo("Ship it"), &[]).is_none());
+    }
+}
@@ -0,0 +1,167 @@
+//! Webhooks called when todos are created or completed
+//!
+//! URLs are configured under `[hooks]` (see [`HookSettings`]). The todo is
+//! POSTed as JSON together with the event name and a one-line `text`
+//! summary, which is what Slack and most chat webhooks display. Failed
+//! deliveries are retried a few times and then logged; they never fail
+//! the change that triggered them.
+
+use crate::config::{Config, HookSettings};
+use anyhow::{Context, Result};
+use pali_types::Todo;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Attempts per delivery, including the first one
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry; doubled for each further one
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Timeout of each attempt
+const HOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    Create,
+    Complete,
+}
+
+impl HookEvent {
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Complete => "complete",
+        }
+    }
+
+    /// The URL configured for the event, if any
+    fn url(self, settings: &HookSettings) -> Option<&str> {
+        match self {
+            Self::Create => settings.on_create.as_deref(),
+            Self::Complete => settings.on_complete.as_deref(),
+        }
+    }
+}
+
+/// Returns `true` if `todo` is important enough to trigger hooks
+#[must_use]
+pub fn should_fire(settings: &HookSettings, todo: &Todo) -> bool {
+    settings.min_priority.is_none_or(|min| todo.priority >= min)
+}
+
+/// The JSON body sent for an event
+#[must_use]
+pub fn payload(event: HookEvent, todo: &Todo, config: &Config) -> Value {
+    let verb = match event {
+        HookEvent::Create => "New todo",
+        HookEvent::Complete => "Completed",
+    };
+    json!({
+        "event": event.name(),
+        "text": format!(
+            "{verb}: {} ({} priority)",
+            todo.title,
+            config.priority_labels.label(todo.priority)
+        ),
+        "todo": todo,
+    })
+}
+
+/// Calls the hook configured for `event`, logging failures
+pub async fn fire(config: &Config, event: HookEvent, todo: &Todo) {
+    let Some(url) = event.url(&config.hooks) else {
+        return;
+    };
+    if !should_fire(&config.hooks, todo) {
+        return;
+    }
+
+    let target = host_of(url);
+    match post(config, url, &payload(event, todo, config)).await {
+        Ok(()) => log::debug!("Sent {} hook to {target}", event.name()),
+        Err(err) => log::warn!("Could not send {} hook to {target}: {err:#}", event.name()),
+    }
+}
+
+/// The host of a hook URL; the full URL often contains a secret token
+fn host_of(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "an invalid URL".to_string())
+}
+
+/// POSTs `body`, retrying on network errors, rate limits and server errors
+async fn post(config: &Config, url: &str, body: &Value) -> Result<()> {
+    let mut builder = reqwest::Client::builder()
+        .timeout(HOOK_TIMEOUT)
+        .user_agent(concat!("pali-terminal/", env!("CARGO_PKG_VERSION")));
+    if let Some(proxy) = &config.proxy {
+        builder = builder.proxy(
+            reqwest::Proxy::all(proxy).with_context(|| format!("Invalid proxy URL '{proxy}'"))?,
+        );
+    }
+    let client = builder
+        .build()
+        .context("Unable to initialize network client")?;
+
+    let mut delay = RETRY_DELAY;
+    for attempt in 1..=MAX_ATTEMPTS {
+        let last = attempt == MAX_ATTEMPTS;
+        match client.post(url).json(body).send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => {
+                let status = response.status();
+                let retry =
+                    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+                if last || !retry {
+                    anyhow::bail!("server answered {status}");
+                }
+                log::debug!("Hook attempt {attempt} got {status}, retrying");
+            }
+            Err(err) if last => return Err(err).context("request failed"),
+            Err(err) => log::debug!("Hook attempt {attempt} failed: {err}, retrying"),
+        }
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+    }
+    unreachable!("the last attempt returns")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn todo(priority: i32) -> Todo {
+        testing::todo("a")
+            .title("Ship release")
+            .completed(true)
+            .priority(priority)
+            .build()
+    }
+
+    #[test]
+    fn test_payload_and_filter() {
+        let config = Config::default();
+        let body = payload(HookEvent::Complete, &todo(3), &config);
+        assert_eq!(body["event"], "complete");
+        assert_eq!(body["text"], "Completed: Ship release (High priority)");
+        assert_eq!(body["todo"]["id"], "a");
+
+        let settings = HookSettings {
+            min_priority: Some(3),
+            ..HookSettings::default()
+        };
+        assert!(should_fire(&settings, &todo(3)));
+        assert!(!should_fire(&settings, &todo(2)));
+        assert!(should_fire(&HookSettings::default(), &todo(1)));
+
+        assert_eq!(
+            host_of("https://hooks.slack.com/services/T0/B0/secret"),
+            "hooks.slack.com"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! On-disk index of todo IDs for resolving ID prefixes without the network
+//!
+//! Kept in `id_index.json` in the data directory and refreshed whenever the
+//! API client sees todos (list, get, create). Prefixes are resolved from the
+//! index only while it is fresh and the match is unique; everything else
+//! falls back to the server.
+
+use crate::config::Config;
+use anyhow::Result;
+use chrono::Utc;
+use pali_types::Todo;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// The index is trusted for this long after the last full refresh
+pub const MAX_AGE_SECS: i64 = 60 * 60;
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct IdIndex {
+    /// When the index last saw the complete todo list (Unix timestamp)
+    #[serde(default)]
+    pub refreshed_at: i64,
+    /// Todo ID → title
+    #[serde(default)]
+    pub entries: BTreeMap<String, String>,
+}
+
+impl IdIndex {
+    /// Returns the path to the index file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data directory cannot be determined
+    pub fn path() -> Result<PathBuf> {
+        Ok(Config::data_dir()?.join("id_index.json"))
+    }
+
+    /// Loads the index; a missing or corrupt file gives an empty (stale) index
+    #[must_use]
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| Ok(fs::read_to_string(path)?))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Saves the index
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index file cannot be written
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Returns `true` if the index saw the full todo list recently
+    #[must_use]
+    pub fn is_fresh(&self, now: i64) -> bool {
+        now - self.refreshed_at < MAX_AGE_SECS
+    }
+
+    /// Replaces the index with a complete todo list
+    pub fn replace_all(&mut self, todos: &[Todo], now: i64) {
+        self.entries = todos
+            .iter()
+            .map(|todo| (todo.id.clone(), todo.title.clone()))
+            .collect();
+        self.refreshed_at = now;
+    }
+
+    /// Adds or updates todos without touching the others
+    pub fn merge(&mut self, todos: &[Todo]) {
+        for todo in todos {
+            self.entries.insert(todo.id.clone(), todo.title.clone());
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.entries.remove(id);
+    }
+
+    /// Returns the IDs and titles starting with `prefix`
+    #[must_use]
+    pub fn matches(&self, prefix: &str) -> Vec<(&str, &str)> {
+        self.entries
+            .range(prefix.to_string()..)
+            .take_while(|(id, _)| id.starts_with(prefix))
+            .map(|(id, title)| (id.as_str(), title.as_str()))
+            .collect()
+    }
+
+    /// Resolves a prefix locally if the index is fresh and exactly one todo matches
+    #[must_use]
+    pub fn resolve(&self, prefix: &str) -> Option<String> {
+        if !self.is_fresh(Utc::now().timestamp()) {
+            return None;
+        }
+        match self.matches(prefix).as_slice() {
+            [(id, _)] => Some((*id).to_string()),
+            _ => None,
+        }
+    }
+
+    /// Loads the index, applies `update` and saves it, logging instead of failing
+    ///
+    /// The index is only an optimization, so problems with it never
+    /// interrupt a command.
+    pub fn update(update: impl FnOnce(&mut Self)) {
+        let mut index = Self::load();
+        update(&mut index);
+        if let Err(err) = index.save() {
+            log::debug!("Could not save the ID index: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn todo(id: &str, title: &str) -> Todo {
+        testing::todo(id).title(title).build()
+    }
+
+    #[test]
+    fn test_resolve_prefix() {
+        let mut index = IdIndex::default();
+        let now = Utc::now().timestamp();
+        index.replace_all(
+            &[
+                todo("abc123", "first"),
+                todo("abd456", "second"),
+                todo("ffe789", "third"),
+            ],
+            now,
+        );
+
+        assert_eq!(index.resolve("abc").as_deref(), Some("abc123"));
+        assert_eq!(index.resolve("ab"), None); // ambiguous
+        assert_eq!(index.resolve("zz"), None);
+        assert_eq!(index.matches("ab").len(), 2);
+
+        index.refreshed_at = now - MAX_AGE_SECS;
+        assert_eq!(index.resolve("abc"), None); // stale
+    }
+}
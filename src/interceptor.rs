@@ -0,0 +1,63 @@
+//! Hooks around every request an [`ApiClient`](crate::api::ApiClient) sends
+//!
+//! Embedders register interceptors with
+//! [`ApiClient::with_interceptor`](crate::api::ApiClient::with_interceptor)
+//! to add headers (say, a tenant ID), collect metrics or keep their own
+//! cache of responses, without forking the client. Interceptors run in the
+//! order they were added, after the client's own headers are set.
+
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::{Method, Request, Response, Url};
+use std::time::Duration;
+
+/// Called for each request before it is sent and for each response
+///
+/// Both methods do nothing by default, so implementations only override
+/// what they need.
+pub trait Interceptor: Send + Sync {
+    /// Inspects or changes a request just before it is sent
+    fn on_request(&self, _request: &mut Request) {}
+
+    /// Observes a response as soon as its headers have arrived
+    ///
+    /// `elapsed` is the time from sending the request to the response headers.
+    fn on_response(&self, _method: &Method, _url: &Url, _response: &Response, _elapsed: Duration) {}
+}
+
+/// Adds a fixed header to every request
+#[derive(Debug, Clone)]
+pub struct HeaderInterceptor {
+    name: HeaderName,
+    value: HeaderValue,
+}
+
+impl HeaderInterceptor {
+    #[must_use]
+    pub fn new(name: HeaderName, value: HeaderValue) -> Self {
+        Self { name, value }
+    }
+}
+
+impl Interceptor for HeaderInterceptor {
+    fn on_request(&self, request: &mut Request) {
+        request
+            .headers_mut()
+            .insert(self.name.clone(), self.value.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_interceptor() {
+        let interceptor = HeaderInterceptor::new(
+            HeaderName::from_static("x-tenant"),
+            HeaderValue::from_static("acme"),
+        );
+        let mut request = Request::new(Method::GET, "http://localhost/todos".parse().unwrap());
+        interceptor.on_request(&mut request);
+        assert_eq!(request.headers()["x-tenant"], "acme");
+    }
+}
@@ -10,18 +10,52 @@
 //!
 //! - `cli` - Enables command-line interface functionality
 //! - `tui` - Enables terminal user interface functionality
+//! - `caldav` - Enables `pacli caldav sync` (not enabled by default)
+//! - `ssh` - Enables `patui --serve-ssh` (not enabled by default)
 //!
-//! Both features are enabled by default.
+//! Both `cli` and `tui` are enabled by default.
 
 // Core modules - always available
+pub mod activity;
 pub mod api;
+pub mod auth;
+pub mod cache;
+#[cfg(feature = "caldav")]
+pub mod caldav;
+pub mod checklist;
 pub mod config;
+pub mod demo;
+pub mod due;
+pub mod editor;
+pub mod escalate;
+pub mod export;
+pub mod highlight;
+pub mod hooks;
+pub mod id_index;
+pub mod interceptor;
+pub mod links;
+pub mod markdown;
+pub mod metrics;
+pub mod project;
+pub mod prompt;
+pub mod redact;
+pub mod revisions;
+pub mod search;
+pub mod secret;
+pub mod sort;
+pub mod state;
+pub mod term;
+pub mod timings;
+pub mod trace;
+pub mod transport;
+
+#[cfg(test)]
+mod testing;
 
 // Shared constants
 pub const ID_DISPLAY_LENGTH: usize = 8;
 
-// Logging utilities (CLI only for now)
-#[cfg(feature = "cli")]
+// Logging utilities
 pub mod logging;
 
 // CLI-specific modules
@@ -29,7 +63,30 @@ pub mod logging;
 pub mod cli {
     pub mod commands {
         pub mod admin;
+        pub mod alias;
+        pub mod archive;
+        pub mod attachment;
+        pub mod audit;
+        pub mod bench;
+        pub mod board;
+        #[cfg(feature = "caldav")]
+        pub mod caldav;
+        pub mod comment;
         pub mod config;
+        pub mod debug;
+        pub mod escalate;
+        pub mod export;
+        pub mod forecast;
+        pub mod help;
+        pub mod history;
+        pub mod link;
+        pub mod login;
+        pub mod open;
+        pub mod pin;
+        pub mod report;
+        pub mod subtask;
+        pub mod tags;
+        pub mod timer;
         pub mod todo;
     }
     pub mod types;
@@ -41,14 +98,23 @@ pub mod cli {
 pub mod tui {
     pub mod app;
     pub mod components;
+    pub mod conflict;
+    pub mod filter;
+    pub mod input;
+    pub mod notifications;
+    pub mod presence;
+    pub mod session;
+    #[cfg(feature = "ssh")]
+    pub mod ssh;
+    pub mod tour;
     pub mod ui;
+
+    pub use ui::render_to_buffer;
 }
 
 // Re-exports for convenience
 pub use api::ApiClient;
 pub use config::Config;
-
-#[cfg(feature = "cli")]
 pub use logging::init_logging;
 
 #[cfg(test)]
@@ -0,0 +1,103 @@
+//! URLs mentioned in todos, for opening them from `pacli open` and `patui`
+
+use pali_types::Todo;
+use regex::Regex;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// Characters that end a sentence rather than a URL, e.g. in "see https://x.org."
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', '\'', '"'];
+
+fn url_regex() -> &'static Regex {
+    static URL: OnceLock<Regex> = OnceLock::new();
+    URL.get_or_init(|| Regex::new(r#"https?://[^\s<>"'`]+"#).expect("valid URL regex"))
+}
+
+/// Finds `http(s)` URLs in `text`, in order of appearance
+#[must_use]
+pub fn find_urls(text: &str) -> Vec<String> {
+    url_ranges(text)
+        .into_iter()
+        .map(|range| text[range].to_string())
+        .collect()
+}
+
+/// Byte ranges of the URLs [`find_urls`] finds in `text`
+#[must_use]
+pub fn url_ranges(text: &str) -> Vec<Range<usize>> {
+    url_regex()
+        .find_iter(text)
+        .map(|found| found.start()..found.start() + trim_url(found.as_str()).len())
+        .filter(|range| {
+            text[range.clone()]
+                .split_once("://")
+                .is_some_and(|(_, rest)| !rest.is_empty())
+        })
+        .collect()
+}
+
+/// Drops trailing punctuation and unbalanced closing brackets, as in "(see https://x.org/a)"
+fn trim_url(mut url: &str) -> &str {
+    loop {
+        let trimmed = url.trim_end_matches(TRAILING_PUNCTUATION);
+        let trimmed = match trimmed.chars().last() {
+            Some(close @ (')' | ']' | '}')) => {
+                let open = match close {
+                    ')' => '(',
+                    ']' => '[',
+                    _ => '{',
+                };
+                let opened = trimmed.matches(open).count();
+                let closed = trimmed.matches(close).count();
+                if closed > opened {
+                    &trimmed[..trimmed.len() - 1]
+                } else {
+                    trimmed
+                }
+            }
+            _ => trimmed,
+        };
+        if trimmed == url {
+            return url;
+        }
+        url = trimmed;
+    }
+}
+
+/// URLs in the title and description of `todo`, without duplicates
+#[must_use]
+pub fn todo_urls(todo: &Todo) -> Vec<String> {
+    let mut urls = find_urls(&todo.title);
+    if let Some(description) = &todo.description {
+        urls.extend(find_urls(description));
+    }
+    let mut seen = std::collections::HashSet::new();
+    urls.retain(|url| seen.insert(url.clone()));
+    urls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_urls() {
+        assert_eq!(
+            find_urls("Review https://github.com/pali-org/terminal/pull/12, then http://x.org."),
+            [
+                "https://github.com/pali-org/terminal/pull/12",
+                "http://x.org"
+            ]
+        );
+        assert_eq!(
+            find_urls("(see https://en.wikipedia.org/wiki/Rust_(programming_language))"),
+            ["https://en.wikipedia.org/wiki/Rust_(programming_language)"]
+        );
+        assert_eq!(
+            find_urls("<https://a.io/x?y=1&z=2>"),
+            ["https://a.io/x?y=1&z=2"]
+        );
+        assert!(find_urls("no links, just https:// and ftp://x").is_empty());
+        assert_eq!(url_ranges("see <https://a.io>."), vec![5..17]);
+    }
+}
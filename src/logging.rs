@@ -1,8 +1,156 @@
-//! Logging utilities for CLI verbose output
+//! Logging utilities: colored output on stderr, a rotating log file, or an
+//! in-memory buffer for the TUI's debug pane
+//!
+//! Every record passes through [`crate::redact`], so credentials never reach
+//! any of them.
 
-use anyhow::Result;
+use crate::redact::redact;
+use anyhow::{Context, Result};
 use log::LevelFilter;
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
 use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// A log file is rotated once it grows past this size
+pub const LOG_FILE_MAX_BYTES: u64 = 1024 * 1024;
+
+/// Number of rotated files kept next to the log file (`pali.log.1` ... `pali.log.3`)
+pub const LOG_FILE_KEEP: usize = 3;
+
+/// Number of log lines kept in memory for [`recent_logs`]
+pub const RECENT_LOG_LINES: usize = 500;
+
+static RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Returns up to `count` of the most recent log lines captured by
+/// [`init_tui_logging`], oldest first
+#[must_use]
+pub fn recent_logs(count: usize) -> Vec<String> {
+    RECENT.lock().map_or_else(
+        |_| Vec::new(),
+        |recent| {
+            recent
+                .iter()
+                .skip(recent.len().saturating_sub(count))
+                .cloned()
+                .collect()
+        },
+    )
+}
+
+/// Keeps formatted log lines in memory, also writing them to a log file if given
+struct CaptureSink {
+    file: Option<RotatingFile>,
+    partial: Vec<u8>,
+}
+
+impl Write for CaptureSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Some(file) = &mut self.file {
+            file.write_all(buf)?;
+        }
+
+        self.partial.extend_from_slice(buf);
+        while let Some(end) = self.partial.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.partial.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line).trim_end().to_string();
+            if let Ok(mut recent) = RECENT.lock() {
+                if recent.len() == RECENT_LOG_LINES {
+                    recent.pop_front();
+                }
+                recent.push_back(line);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.file {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+fn plain_format(buf: &mut env_logger::fmt::Formatter, record: &log::Record) -> std::io::Result<()> {
+    writeln!(
+        buf,
+        "{} [{:<5}] {}: {}",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+        record.level(),
+        record.target(),
+        redact(&record.args().to_string())
+    )
+}
+
+/// Appends to a log file, rotating it by size
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open log file {}", path.display()))?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            size,
+        })
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{n}"));
+        PathBuf::from(name)
+    }
+
+    /// Shifts `log.1` to `log.2` and so on, dropping the oldest, and starts a new file
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        for n in (1..LOG_FILE_KEEP).rev() {
+            let from = self.rotated_path(n);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(n + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.rotated_path(1))?;
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > LOG_FILE_MAX_BYTES {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
 
 /// Initialize logging based on verbosity count
 ///
@@ -10,33 +158,56 @@ use std::io::Write;
 /// - 1: INFO level (-v)
 /// - 2: DEBUG level (-vv)  
 /// - 3+: TRACE level (-vvv)
-pub fn init_logging(verbose_count: u8) -> Result<()> {
-    let log_level = match verbose_count {
+///
+/// With a `log_file`, output goes to that file instead of stderr, at DEBUG
+/// level or higher, with timestamps and without colors.
+///
+/// # Errors
+///
+/// Returns an error if the log file cannot be opened
+pub fn init_logging(verbose_count: u8, log_file: Option<&Path>) -> Result<()> {
+    let mut log_level = match verbose_count {
         0 => LevelFilter::Warn,
         1 => LevelFilter::Info,
         2 => LevelFilter::Debug,
         _ => LevelFilter::Trace,
     };
 
-    let result = env_logger::Builder::from_default_env()
-        .filter_level(log_level)
+    let mut builder = env_logger::Builder::from_default_env();
+    builder
         .filter_module("reqwest", LevelFilter::Info) // Keep reqwest quiet unless trace
-        .filter_module("hyper", LevelFilter::Info) // Keep hyper quiet unless trace
-        .format(|buf, record| {
-            use log::Level;
-
-            let level_style = match record.level() {
-                Level::Error => "\x1b[31m[ERROR]\x1b[0m", // Red
-                Level::Warn => "\x1b[33m[WARN ]\x1b[0m",  // Yellow
-                Level::Info => "\x1b[32m[INFO ]\x1b[0m",  // Green
-                Level::Debug => "\x1b[36m[DEBUG]\x1b[0m", // Cyan
-                Level::Trace => "\x1b[37m[TRACE]\x1b[0m", // White
-            };
-
-            writeln!(buf, "{} {}", level_style, record.args())
-        })
-        .target(env_logger::Target::Stderr)
-        .try_init();
+        .filter_module("hyper", LevelFilter::Info); // Keep hyper quiet unless trace
+
+    if let Some(path) = log_file {
+        log_level = log_level.max(LevelFilter::Debug);
+        let file = RotatingFile::open(path)?;
+        builder
+            .format(plain_format)
+            .target(env_logger::Target::Pipe(Box::new(file)));
+    } else {
+        builder
+            .format(|buf, record| {
+                use log::Level;
+
+                let level_style = match record.level() {
+                    Level::Error => "\x1b[31m[ERROR]\x1b[0m", // Red
+                    Level::Warn => "\x1b[33m[WARN ]\x1b[0m",  // Yellow
+                    Level::Info => "\x1b[32m[INFO ]\x1b[0m",  // Green
+                    Level::Debug => "\x1b[36m[DEBUG]\x1b[0m", // Cyan
+                    Level::Trace => "\x1b[37m[TRACE]\x1b[0m", // White
+                };
+
+                writeln!(
+                    buf,
+                    "{} {}",
+                    level_style,
+                    redact(&record.args().to_string())
+                )
+            })
+            .target(env_logger::Target::Stderr);
+    }
+
+    let result = builder.filter_level(log_level).try_init();
 
     // If logger is already initialized, that's fine - just continue
     match result {
@@ -47,13 +218,39 @@ pub fn init_logging(verbose_count: u8) -> Result<()> {
         }
     }
 
-    if verbose_count > 0 {
+    if verbose_count > 0 || log_file.is_some() {
         log::info!("Verbose logging enabled (level: {log_level})");
     }
 
     Ok(())
 }
 
+/// Initialize logging for the TUI, where stderr is hidden behind the alternate screen
+///
+/// Records at DEBUG level and above are kept in memory for the debug pane
+/// (see [`recent_logs`]) and also written to `log_file` if given.
+///
+/// # Errors
+///
+/// Returns an error if the log file cannot be opened
+pub fn init_tui_logging(log_file: Option<&Path>) -> Result<()> {
+    let file = log_file.map(RotatingFile::open).transpose()?;
+
+    // Already initialized is fine, as in `init_logging`
+    let _ = env_logger::Builder::from_default_env()
+        .filter_level(LevelFilter::Debug)
+        .filter_module("reqwest", LevelFilter::Info)
+        .filter_module("hyper", LevelFilter::Info)
+        .format(plain_format)
+        .target(env_logger::Target::Pipe(Box::new(CaptureSink {
+            file,
+            partial: Vec::new(),
+        })))
+        .try_init();
+
+    Ok(())
+}
+
 /// Macro for timing operations and logging results
 #[macro_export]
 macro_rules! time_operation {
@@ -68,12 +265,13 @@ macro_rules! time_operation {
 }
 
 /// Log HTTP request details
-pub fn log_http_request(method: &str, url: &str, has_body: bool) {
+pub fn log_http_request(method: &str, url: &str, has_body: bool, request_id: &str) {
     log::debug!(
-        "HTTP {} {}{}",
+        "HTTP {} {}{} [{}]",
         method,
         url,
-        if has_body { " (with body)" } else { "" }
+        if has_body { " (with body)" } else { "" },
+        request_id
     );
 }
 
@@ -126,11 +324,42 @@ mod tests {
         // but should not panic
 
         // First call should succeed or fail gracefully
-        let result1 = init_logging(1);
+        let result1 = init_logging(1, None);
         assert!(result1.is_ok() || result1.is_err()); // Either is acceptable
 
         // Second call should handle the "already initialized" case gracefully
-        let result2 = init_logging(2);
+        let result2 = init_logging(2, None);
         assert!(result2.is_ok() || result2.is_err()); // Either is acceptable, shouldn't panic
     }
+
+    #[test]
+    fn test_capture_sink_keeps_recent_lines() {
+        let mut sink = CaptureSink {
+            file: None,
+            partial: Vec::new(),
+        };
+        write!(sink, "first line\nsecond ").unwrap();
+        writeln!(sink, "line").unwrap();
+
+        let recent = recent_logs(2);
+        assert_eq!(recent, ["first line", "second line"]);
+    }
+
+    #[test]
+    fn test_log_file_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pali.log");
+        let mut file = RotatingFile::open(&path).unwrap();
+        let line = vec![b'x'; 1024];
+
+        for _ in 0..(LOG_FILE_KEEP + 2) * 1024 {
+            file.write_all(&line).unwrap();
+        }
+
+        assert!(fs::metadata(&path).unwrap().len() <= LOG_FILE_MAX_BYTES);
+        for n in 1..=LOG_FILE_KEEP {
+            assert!(file.rotated_path(n).exists());
+        }
+        assert!(!file.rotated_path(LOG_FILE_KEEP + 1).exists());
+    }
 }
@@ -0,0 +1,462 @@
+//! Basic markdown rendering for todo descriptions
+//!
+//! Descriptions are often pasted markdown. This understands the common
+//! subset - headings, bullet and numbered lists, quotes, fenced code blocks
+//! and inline `**bold**`, `*italics*`, `` `code` `` and `[links](url)` - well
+//! enough to show it styled in the `patui` detail view and in
+//! `pacli get --markdown`. Anything else is shown as written.
+
+use crate::term;
+use colored::Colorize;
+
+/// How a piece of inline text is emphasized; flags combine when nested
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Emphasis {
+    pub bold: bool,
+    pub italic: bool,
+    pub code: bool,
+    /// The text of a link
+    pub link: bool,
+    /// Secondary text, such as the URL after a link
+    pub dim: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub emphasis: Emphasis,
+}
+
+/// What a line is, deciding its prefix and style
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    Paragraph,
+    Blank,
+    /// `#` to `######`
+    Heading(usize),
+    /// `-`, `*` or `+` items, `depth` levels deep
+    Bullet {
+        depth: usize,
+    },
+    /// `1.` or `1)` items
+    Numbered {
+        depth: usize,
+        number: String,
+    },
+    Quote,
+    /// A line inside a fenced code block, shown verbatim
+    Code,
+    /// `---`
+    Rule,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownLine {
+    pub block: Block,
+    pub spans: Vec<Span>,
+}
+
+/// Parses `text` into styled lines
+#[must_use]
+pub fn parse(text: &str) -> Vec<MarkdownLine> {
+    let mut lines = Vec::new();
+    let mut in_fence = false;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            let code = Emphasis {
+                code: true,
+                ..Emphasis::default()
+            };
+            lines.push(MarkdownLine {
+                block: Block::Code,
+                spans: vec![Span {
+                    text: line.to_string(),
+                    emphasis: code,
+                }],
+            });
+            continue;
+        }
+        let (block, content) = parse_block(line);
+        let mut spans = Vec::new();
+        parse_inline(content, Emphasis::default(), &mut spans);
+        lines.push(MarkdownLine { block, spans });
+    }
+    lines
+}
+
+/// Splits a line into its block kind and the inline content after the marker
+fn parse_block(line: &str) -> (Block, &str) {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() {
+        return (Block::Blank, "");
+    }
+    let indent: usize = line[..line.len() - trimmed.len()]
+        .chars()
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum();
+    let depth = indent / 2;
+
+    let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+    if (1..=6).contains(&hashes) {
+        if let Some(title) = trimmed[hashes..].strip_prefix(' ') {
+            return (Block::Heading(hashes), title.trim());
+        }
+    }
+    let rule_chars: Vec<char> = trimmed.chars().filter(|c| !c.is_whitespace()).collect();
+    if rule_chars.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|marker| rule_chars.iter().all(|c| c == marker))
+    {
+        return (Block::Rule, "");
+    }
+    if let Some(quote) = trimmed.strip_prefix('>') {
+        return (Block::Quote, quote.strip_prefix(' ').unwrap_or(quote));
+    }
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(item) = trimmed.strip_prefix(marker) {
+            return (Block::Bullet { depth }, item);
+        }
+    }
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        let rest = &trimmed[digits..];
+        if let Some(item) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            let number = trimmed[..digits].to_string();
+            return (Block::Numbered { depth, number }, item);
+        }
+    }
+    (Block::Paragraph, line)
+}
+
+/// Appends the spans of inline markdown in `text`, nested in `emphasis`
+fn parse_inline(text: &str, emphasis: Emphasis, out: &mut Vec<Span>) {
+    let mut plain = String::new();
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+
+        if let Some(escaped) = rest
+            .strip_prefix('\\')
+            .and_then(|after| after.chars().next())
+            .filter(char::is_ascii_punctuation)
+        {
+            plain.push(escaped);
+            i += 1 + escaped.len_utf8();
+            continue;
+        }
+
+        if let Some(code) = rest
+            .strip_prefix('`')
+            .and_then(|after| after.find('`').map(|end| &after[..end]))
+        {
+            push(out, &mut plain, emphasis);
+            let code_emphasis = Emphasis {
+                code: true,
+                ..emphasis
+            };
+            push(out, &mut code.to_string(), code_emphasis);
+            i += code.len() + 2;
+            continue;
+        }
+
+        let delimiter = ["**", "__", "*", "_"]
+            .into_iter()
+            .find(|delimiter| rest.starts_with(delimiter));
+        if let Some(delimiter) = delimiter {
+            if let Some(len) = emphasized_len(text, i, delimiter) {
+                push(out, &mut plain, emphasis);
+                let inner = &rest[delimiter.len()..delimiter.len() + len];
+                let nested = if delimiter.len() == 2 {
+                    Emphasis {
+                        bold: true,
+                        ..emphasis
+                    }
+                } else {
+                    Emphasis {
+                        italic: true,
+                        ..emphasis
+                    }
+                };
+                parse_inline(inner, nested, out);
+                i += len + 2 * delimiter.len();
+                continue;
+            }
+        }
+
+        if let Some((label, url, consumed)) = link(rest) {
+            push(out, &mut plain, emphasis);
+            let link_emphasis = Emphasis {
+                link: true,
+                ..emphasis
+            };
+            parse_inline(label, link_emphasis, out);
+            if url != label {
+                let dim = Emphasis {
+                    dim: true,
+                    ..Emphasis::default()
+                };
+                push(out, &mut format!(" <{url}>"), dim);
+            }
+            i += consumed;
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap_or_default();
+        plain.push(c);
+        i += c.len_utf8();
+    }
+    push(out, &mut plain, emphasis);
+}
+
+/// Moves `text` into a span, merging it with the previous span if styled the same
+fn push(out: &mut Vec<Span>, text: &mut String, emphasis: Emphasis) {
+    if text.is_empty() {
+        return;
+    }
+    match out.last_mut() {
+        Some(last) if last.emphasis == emphasis => last.text.push_str(text),
+        _ => out.push(Span {
+            text: text.clone(),
+            emphasis,
+        }),
+    }
+    text.clear();
+}
+
+/// Returns the length of the text emphasized by the `delimiter` at `start`, if it is closed
+///
+/// Like markdown, the delimiters must hug the text (`* a *` is not
+/// emphasis), and underscores inside words (`snake_case`) are left alone.
+fn emphasized_len(text: &str, start: usize, delimiter: &str) -> Option<usize> {
+    let open_end = start + delimiter.len();
+    if text[open_end..]
+        .chars()
+        .next()
+        .is_none_or(char::is_whitespace)
+    {
+        return None;
+    }
+    let underscore = delimiter.starts_with('_');
+    if underscore
+        && text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric)
+    {
+        return None;
+    }
+    let marker = delimiter.chars().next();
+    let mut from = open_end;
+    while let Some(found) = text[from..].find(delimiter) {
+        let close = from + found;
+        let before = text[..close].chars().next_back();
+        let after = text[close + delimiter.len()..].chars().next();
+        // A single `*` right before another is part of a `**`
+        let doubled = delimiter.len() == 1 && after == marker;
+        let intraword = underscore && after.is_some_and(char::is_alphanumeric);
+        if close > open_end && !before.is_some_and(char::is_whitespace) && !doubled && !intraword {
+            return Some(close - open_end);
+        }
+        from = close + delimiter.len() + usize::from(doubled);
+    }
+    None
+}
+
+/// Parses `[label](url)` at the start of `text`, returning the label, URL and length
+fn link(text: &str) -> Option<(&str, &str, usize)> {
+    let after = text.strip_prefix('[')?;
+    let label_end = after.find("](")?;
+    let label = &after[..label_end];
+    let target = &after[label_end + 2..];
+    let url_end = target.find(')')?;
+    let url = target[..url_end].trim();
+    if label.is_empty() || url.is_empty() || url.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((label, url, 1 + label_end + 2 + url_end + 1))
+}
+
+/// The text put in front of a line of `block`
+fn prefix(block: &Block) -> String {
+    match block {
+        Block::Bullet { depth } => format!("{}{} ", "  ".repeat(*depth), term::glyph("•", "-")),
+        Block::Numbered { depth, number } => format!("{}{number}. ", "  ".repeat(*depth)),
+        Block::Quote => format!("{} ", term::glyph("│", "|")),
+        Block::Code => "  ".to_string(),
+        Block::Rule => term::glyph("────────────────", "----------------").to_string(),
+        Block::Paragraph | Block::Blank | Block::Heading(_) => String::new(),
+    }
+}
+
+/// Renders `text` with terminal colors, one string per line
+#[must_use]
+pub fn paint(text: &str) -> Vec<String> {
+    parse(text)
+        .into_iter()
+        .map(|line| {
+            let mut out = prefix(&line.block).dimmed().to_string();
+            for span in &line.spans {
+                let mut emphasis = span.emphasis;
+                if matches!(line.block, Block::Heading(_)) {
+                    emphasis.bold = true;
+                }
+                let mut painted = span.text.normal();
+                if emphasis.code {
+                    painted = painted.yellow();
+                }
+                if emphasis.link {
+                    painted = painted.blue().underline();
+                }
+                if emphasis.bold {
+                    painted = painted.bold();
+                }
+                if emphasis.italic {
+                    painted = painted.italic();
+                }
+                if emphasis.dim || line.block == Block::Quote {
+                    painted = painted.dimmed();
+                }
+                out.push_str(&painted.to_string());
+            }
+            out
+        })
+        .collect()
+}
+
+/// Renders `text` as `patui` lines
+#[cfg(feature = "tui")]
+#[must_use]
+pub fn to_lines(text: &str) -> Vec<ratatui::text::Line<'static>> {
+    use ratatui::style::{Color, Modifier, Style};
+    use ratatui::text::{Line, Span as TuiSpan};
+
+    parse(text)
+        .into_iter()
+        .map(|line| {
+            let mut spans = vec![TuiSpan::styled(
+                prefix(&line.block),
+                Style::default().fg(Color::DarkGray),
+            )];
+            for span in line.spans {
+                let mut style = Style::default();
+                match line.block {
+                    Block::Heading(1) => {
+                        style = style
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                    }
+                    Block::Heading(_) => style = style.fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                    Block::Quote => style = style.fg(Color::Gray).add_modifier(Modifier::ITALIC),
+                    _ => {}
+                }
+                let emphasis = span.emphasis;
+                if emphasis.code {
+                    style = style.fg(Color::Yellow);
+                }
+                if emphasis.link {
+                    style = style.fg(Color::Blue).add_modifier(Modifier::UNDERLINED);
+                }
+                if emphasis.bold {
+                    style = style.add_modifier(Modifier::BOLD);
+                }
+                if emphasis.italic {
+                    style = style.add_modifier(Modifier::ITALIC);
+                }
+                if emphasis.dim {
+                    style = style.fg(Color::DarkGray);
+                }
+                spans.push(TuiSpan::styled(span.text, style));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(spans: &[Span]) -> Vec<(&str, Emphasis)> {
+        spans
+            .iter()
+            .map(|span| (span.text.as_str(), span.emphasis))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let bold = Emphasis {
+            bold: true,
+            ..Emphasis::default()
+        };
+        let italic = Emphasis {
+            italic: true,
+            ..Emphasis::default()
+        };
+        let code = Emphasis {
+            code: true,
+            ..Emphasis::default()
+        };
+        let link = Emphasis {
+            link: true,
+            ..Emphasis::default()
+        };
+        let dim = Emphasis {
+            dim: true,
+            ..Emphasis::default()
+        };
+        let plain = Emphasis::default();
+
+        let lines = parse(
+            "# Plan\n\nRun **`cargo test`** then *ship*\n  - see [docs](https://x.org)\n\
+             1. keep snake_case and 2 * 3 * 4\n> quoted\n```\nlet **x** = 1;\n```\n---",
+        );
+        let blocks: Vec<&Block> = lines.iter().map(|line| &line.block).collect();
+        assert_eq!(
+            blocks,
+            [
+                &Block::Heading(1),
+                &Block::Blank,
+                &Block::Paragraph,
+                &Block::Bullet { depth: 1 },
+                &Block::Numbered {
+                    depth: 0,
+                    number: "1".to_string()
+                },
+                &Block::Quote,
+                &Block::Code,
+                &Block::Rule,
+            ]
+        );
+        assert_eq!(texts(&lines[0].spans), [("Plan", plain)]);
+        assert_eq!(
+            texts(&lines[2].spans),
+            [
+                ("Run ", plain),
+                ("cargo test", Emphasis { code: true, ..bold }),
+                (" then ", plain),
+                ("ship", italic),
+            ]
+        );
+        assert_eq!(
+            texts(&lines[3].spans),
+            [("see ", plain), ("docs", link), (" <https://x.org>", dim)]
+        );
+        assert_eq!(
+            texts(&lines[4].spans),
+            [("keep snake_case and 2 * 3 * 4", plain)]
+        );
+        assert_eq!(texts(&lines[5].spans), [("quoted", plain)]);
+        assert_eq!(texts(&lines[6].spans), [("let **x** = 1;", code)]);
+
+        let mut spans = Vec::new();
+        parse_inline(r"\*not italic\* and [x](y z)", plain, &mut spans);
+        assert_eq!(texts(&spans), [("*not italic* and [x](y z)", plain)]);
+    }
+}
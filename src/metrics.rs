@@ -0,0 +1,249 @@
+//! Opt-in performance counters (`metrics = true` in the config)
+//!
+//! While enabled, every API call is counted per endpoint together with a
+//! latency histogram. The counters live in memory and are added to
+//! `metrics.json` in the data directory when `pacli` or `patui` exits, so
+//! `pacli debug metrics` can summarize many short-lived runs. IDs in paths
+//! are replaced with `{id}` to group calls by endpoint.
+
+use crate::config::Config;
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds of the latency buckets in milliseconds; slower calls go in a last, open bucket
+pub const BUCKETS_MS: [u64; 9] = [10, 25, 50, 100, 250, 500, 1000, 2500, 5000];
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static CURRENT: Mutex<Option<Metrics>> = Mutex::new(None);
+
+/// Starts counting calls for the rest of the process
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+#[must_use]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Counts and latencies of one endpoint
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EndpointStats {
+    pub calls: u64,
+    /// Calls that failed or got an error status
+    pub errors: u64,
+    pub total_ms: u64,
+    pub max_ms: u64,
+    /// Calls per bucket of [`BUCKETS_MS`], plus one for slower calls
+    pub buckets: Vec<u64>,
+}
+
+impl EndpointStats {
+    fn record(&mut self, elapsed_ms: u64, failed: bool) {
+        self.buckets.resize(BUCKETS_MS.len() + 1, 0);
+        let bucket = BUCKETS_MS
+            .iter()
+            .position(|&bound| elapsed_ms <= bound)
+            .unwrap_or(BUCKETS_MS.len());
+        self.buckets[bucket] += 1;
+        self.calls += 1;
+        self.errors += u64::from(failed);
+        self.total_ms += elapsed_ms;
+        self.max_ms = self.max_ms.max(elapsed_ms);
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.buckets.resize(BUCKETS_MS.len() + 1, 0);
+        for (bucket, count) in self.buckets.iter_mut().zip(&other.buckets) {
+            *bucket += count;
+        }
+        self.calls += other.calls;
+        self.errors += other.errors;
+        self.total_ms += other.total_ms;
+        self.max_ms = self.max_ms.max(other.max_ms);
+    }
+
+    #[must_use]
+    pub fn average_ms(&self) -> u64 {
+        self.total_ms.checked_div(self.calls).unwrap_or(0)
+    }
+
+    /// Estimates a percentile (0-100) as the upper bound of the bucket it falls in
+    ///
+    /// Returns [`EndpointStats::max_ms`] for the open last bucket and `None`
+    /// without calls.
+    #[must_use]
+    pub fn percentile_ms(&self, percentile: u64) -> Option<u64> {
+        let rank = (percentile * self.calls).div_ceil(100).max(1);
+        let mut seen = 0;
+        for (i, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some(
+                    BUCKETS_MS
+                        .get(i)
+                        .map_or(self.max_ms, |&bound| bound.min(self.max_ms)),
+                );
+            }
+        }
+        None
+    }
+}
+
+/// Counters of all endpoints since `since`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Metrics {
+    /// When counting started (Unix timestamp)
+    pub since: i64,
+    /// By method and path, e.g. `GET /todos/{id}`
+    pub endpoints: BTreeMap<String, EndpointStats>,
+}
+
+impl Metrics {
+    /// Returns the path to the metrics file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data directory cannot be determined
+    pub fn path() -> Result<PathBuf> {
+        Ok(Config::data_dir()?.join("metrics.json"))
+    }
+
+    /// Loads the saved counters; a missing or corrupt file gives empty ones
+    #[must_use]
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| Ok(fs::read_to_string(path)?))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Deletes the saved counters
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be removed
+    pub fn reset() -> Result<()> {
+        match fs::remove_file(Self::path()?) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        if self.endpoints.is_empty() || other.since < self.since {
+            self.since = other.since;
+        }
+        for (endpoint, stats) in &other.endpoints {
+            self.endpoints
+                .entry(endpoint.clone())
+                .or_default()
+                .merge(stats);
+        }
+    }
+}
+
+/// Counts a call if metrics are enabled
+pub fn record(method: &str, path: &str, elapsed: Duration, failed: bool) {
+    if !is_enabled() {
+        return;
+    }
+    let endpoint = format!("{method} {}", endpoint_path(path));
+    let elapsed_ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
+    if let Ok(mut current) = CURRENT.lock() {
+        let metrics = current.get_or_insert_with(|| Metrics {
+            since: Utc::now().timestamp(),
+            endpoints: BTreeMap::new(),
+        });
+        metrics
+            .endpoints
+            .entry(endpoint)
+            .or_default()
+            .record(elapsed_ms, failed);
+    }
+}
+
+/// Returns the counters of this process
+#[must_use]
+pub fn current() -> Metrics {
+    CURRENT
+        .lock()
+        .ok()
+        .and_then(|current| current.clone())
+        .unwrap_or_default()
+}
+
+/// Adds this process's counters to the metrics file, logging failures
+pub fn flush() {
+    let Some(current) = CURRENT.lock().ok().and_then(|mut current| current.take()) else {
+        return;
+    };
+    let mut saved = Metrics::load();
+    saved.merge(&current);
+    let result = Metrics::path().and_then(|path| {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(&saved)?)?;
+        Ok(())
+    });
+    if let Err(err) = result {
+        log::warn!("Could not save metrics: {err:#}");
+    }
+}
+
+/// Replaces path segments that look like IDs with `{id}`
+fn endpoint_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            let is_id = segment.len() >= 8
+                && segment.chars().any(|c| c.is_ascii_digit())
+                && segment.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+            if is_id {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_and_paths() {
+        let mut stats = EndpointStats::default();
+        for ms in [5, 8, 40, 90, 3000] {
+            stats.record(ms, false);
+        }
+        stats.record(7000, true);
+        assert_eq!(stats.calls, 6);
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.percentile_ms(50), Some(50));
+        assert_eq!(stats.percentile_ms(95), Some(7000));
+        assert_eq!(stats.average_ms(), 1690);
+
+        let mut total = stats.clone();
+        total.merge(&stats);
+        assert_eq!(total.calls, 12);
+        assert_eq!(total.buckets[0], 4);
+
+        assert_eq!(
+            endpoint_path("/todos/d2fadfdb-5541-4ace-9443-d01cd917a640/comments"),
+            "/todos/{id}/comments"
+        );
+        assert_eq!(endpoint_path("/todos/resolve/d2fa"), "/todos/resolve/d2fa");
+        assert_eq!(endpoint_path("/admin/status"), "/admin/status");
+    }
+}
@@ -0,0 +1,134 @@
+//! Per-project settings from a `.pali.toml` in the current directory or a parent
+//!
+//! ```toml
+//! tag = "website"          # added to todos created inside the project
+//!
+//! [filters]                # defaults for `pacli list` / `pacli count`
+//! tag = "website"
+//! priority = "high"
+//! sort = "priority,due"
+//! all = false
+//! ```
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// File name searched for, like `.git`
+pub const PROJECT_FILE: &str = ".pali.toml";
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Tag added to todos created inside the project
+    pub tag: Option<String>,
+    pub filters: ProjectFilters,
+    /// Where the settings were loaded from
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+/// Defaults for list filters that aren't given on the command line
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectFilters {
+    pub tag: Option<String>,
+    pub priority: Option<String>,
+    pub sort: Option<String>,
+    pub all: bool,
+}
+
+impl ProjectConfig {
+    /// Finds the nearest `.pali.toml`, starting at the current directory
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a project file is found but cannot be read or parsed
+    pub fn discover() -> Result<Option<Self>> {
+        let Ok(cwd) = std::env::current_dir() else {
+            return Ok(None);
+        };
+        Self::discover_from(&cwd)
+    }
+
+    fn discover_from(start: &Path) -> Result<Option<Self>> {
+        let Some(path) = start
+            .ancestors()
+            .map(|dir| dir.join(PROJECT_FILE))
+            .find(|path| path.is_file())
+        else {
+            return Ok(None);
+        };
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut config: Self =
+            toml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))?;
+        log::info!("Using project settings from {}", path.display());
+        config.path = path;
+        Ok(Some(config))
+    }
+
+    /// Adds the project tag to a comma-separated tag list (if not already present)
+    #[must_use]
+    pub fn with_project_tag(&self, tags: Option<String>) -> Option<String> {
+        let Some(project_tag) = &self.tag else {
+            return tags;
+        };
+
+        match tags {
+            Some(tags)
+                if tags
+                    .split(',')
+                    .any(|t| t.trim().eq_ignore_ascii_case(project_tag)) =>
+            {
+                Some(tags)
+            }
+            Some(tags) if !tags.trim().is_empty() => Some(format!("{tags},{project_tag}")),
+            _ => Some(project_tag.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_walks_up() {
+        let root = tempfile::tempdir().unwrap();
+        let nested = root.path().join("src/deep");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(
+            root.path().join(PROJECT_FILE),
+            "tag = \"website\"\n[filters]\npriority = \"high\"\n",
+        )
+        .unwrap();
+
+        let config = ProjectConfig::discover_from(&nested).unwrap().unwrap();
+        assert_eq!(config.tag.as_deref(), Some("website"));
+        assert_eq!(config.filters.priority.as_deref(), Some("high"));
+        assert_eq!(config.path, root.path().join(PROJECT_FILE));
+    }
+
+    #[test]
+    fn test_with_project_tag() {
+        let config = ProjectConfig {
+            tag: Some("website".to_string()),
+            ..ProjectConfig::default()
+        };
+        assert_eq!(config.with_project_tag(None).as_deref(), Some("website"));
+        assert_eq!(
+            config
+                .with_project_tag(Some("urgent".to_string()))
+                .as_deref(),
+            Some("urgent,website")
+        );
+        assert_eq!(
+            config
+                .with_project_tag(Some("Website,urgent".to_string()))
+                .as_deref(),
+            Some("Website,urgent")
+        );
+    }
+}
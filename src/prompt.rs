@@ -0,0 +1,95 @@
+//! Whether the user may be asked questions
+//!
+//! Prompts (confirmations, the login wizard, picking between ambiguous IDs,
+//! passphrases) only appear when standard input is a terminal and
+//! `--no-input` isn't given. Otherwise they fail with [`InputRequired`],
+//! which `pacli` turns into exit code [`EXIT_CODE`], so scripts can tell
+//! "needed an answer" apart from other failures and never hang.
+
+use anyhow::Result;
+use std::fmt;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exit code of `pacli` when a command needed an answer it couldn't ask for
+pub const EXIT_CODE: u8 = 3;
+
+static NO_INPUT: AtomicBool = AtomicBool::new(false);
+
+/// Disables prompts for the rest of the process (`--no-input`)
+pub fn set_no_input(no_input: bool) {
+    NO_INPUT.store(no_input, Ordering::Relaxed);
+}
+
+/// Returns `true` if the user can be asked questions: `--no-input` isn't set
+/// and standard input is a terminal
+#[must_use]
+pub fn can_prompt() -> bool {
+    !NO_INPUT.load(Ordering::Relaxed) && std::io::stdin().is_terminal()
+}
+
+/// A prompt was needed but prompting is disabled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputRequired {
+    /// What the user would have been asked to do, e.g. "enter the API key"
+    pub what: String,
+}
+
+impl fmt::Display for InputRequired {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Cannot ask to {}: prompting is disabled (--no-input, or stdin is not a terminal)",
+            self.what
+        )
+    }
+}
+
+impl std::error::Error for InputRequired {}
+
+/// Fails with [`InputRequired`] unless the user can be asked to do `what`
+///
+/// # Errors
+///
+/// Returns [`InputRequired`] if [`can_prompt`] is `false`
+pub fn require(what: &str) -> Result<()> {
+    require_if(can_prompt(), what)
+}
+
+/// [`require`] with the answer of [`can_prompt`] given, so tests don't touch `--no-input`
+fn require_if(can_prompt: bool, what: &str) -> Result<()> {
+    if can_prompt {
+        Ok(())
+    } else {
+        Err(InputRequired {
+            what: what.to_string(),
+        }
+        .into())
+    }
+}
+
+/// Returns `true` if `err` (or an error it wraps) is [`InputRequired`]
+#[must_use]
+pub fn is_input_required(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<InputRequired>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_input_required_in_chain() {
+        let err = require_if(false, "confirm")
+            .context("Ambiguous ID")
+            .unwrap_err();
+        assert!(is_input_required(&err));
+        assert!(err
+            .root_cause()
+            .to_string()
+            .starts_with("Cannot ask to confirm:"));
+        assert!(!is_input_required(&anyhow::anyhow!("Not found")));
+        assert!(require_if(true, "confirm").is_ok());
+    }
+}
@@ -0,0 +1,160 @@
+//! Masks credentials before text reaches logs or `--trace` output
+//!
+//! Two mechanisms work together: values following well-known names
+//! (`X-API-Key: ...`, `"access_token": "..."`) are masked wherever they
+//! appear, and secrets the client knows about (the configured key, keys
+//! generated or rotated in this process, OAuth tokens) are registered with
+//! [`register`] and masked verbatim.
+
+use std::borrow::Cow;
+use std::sync::Mutex;
+
+/// Replacement for masked values
+pub const REDACTED: &str = "[redacted]";
+
+/// Names whose values are secrets, matched case-insensitively
+const SECRET_NAMES: [&str; 9] = [
+    "x-api-key",
+    "authorization",
+    "cookie",
+    "api_key",
+    "access_token",
+    "refresh_token",
+    "new_key",
+    "\"key\"",
+    "passphrase",
+];
+
+/// Secrets shorter than this are not registered, to avoid masking common words
+const MIN_SECRET_LEN: usize = 8;
+
+static SECRETS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Masks every future occurrence of `secret` in logs and traces
+pub fn register(secret: &str) {
+    let secret = secret.trim();
+    if secret.len() < MIN_SECRET_LEN {
+        return;
+    }
+    if let Ok(mut secrets) = SECRETS.lock() {
+        if !secrets.iter().any(|known| known == secret) {
+            secrets.push(secret.to_string());
+        }
+    }
+}
+
+/// Returns `true` if `name` (a header or field name) holds a secret
+#[must_use]
+pub fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_NAMES
+        .iter()
+        .any(|secret| secret.trim_matches('"') == name)
+}
+
+/// Returns `text` with registered secrets and values of secret names masked
+#[must_use]
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let mut result = Cow::Borrowed(text);
+
+    if let Ok(secrets) = SECRETS.lock() {
+        for secret in secrets.iter() {
+            if result.contains(secret.as_str()) {
+                result = Cow::Owned(result.replace(secret.as_str(), REDACTED));
+            }
+        }
+    }
+
+    let lower = result.to_ascii_lowercase();
+    if !SECRET_NAMES.iter().any(|name| lower.contains(name)) {
+        return result;
+    }
+    Cow::Owned(mask_named_values(&result))
+}
+
+/// Masks the value after each secret name, e.g. `X-API-Key: abc` or `"api_key":"abc"`
+fn mask_named_values(text: &str) -> String {
+    let lower = text.to_ascii_lowercase();
+    let mut out = String::with_capacity(text.len());
+    let mut pos = 0;
+
+    while pos < text.len() {
+        let next = SECRET_NAMES
+            .iter()
+            .filter_map(|name| lower[pos..].find(name).map(|i| (pos + i, name.len())))
+            .min();
+        let Some((start, len)) = next else {
+            break;
+        };
+
+        let mut cursor = start + len;
+        // Closing quote of a JSON field name, then a `:` or `=` separator
+        cursor += skip(&text[cursor..], |c| c == '"' || c == ' ');
+        if !text[cursor..].starts_with([':', '=']) {
+            out.push_str(&text[pos..cursor]);
+            pos = cursor;
+            continue;
+        }
+        cursor += 1;
+        cursor += skip(&text[cursor..], |c| c == '"' || c == ' ');
+        for scheme in ["Bearer ", "Basic "] {
+            if text[cursor..].starts_with(scheme) {
+                cursor += scheme.len();
+            }
+        }
+
+        let value_len = text[cursor..]
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | ',' | '}' | '&' | ';'))
+            .unwrap_or(text.len() - cursor);
+        out.push_str(&text[pos..cursor]);
+        if value_len > 0 {
+            out.push_str(REDACTED);
+        }
+        pos = cursor + value_len;
+    }
+
+    out.push_str(&text[pos..]);
+    out
+}
+
+/// Returns the byte length of the prefix made of characters matching `pred`
+fn skip(text: &str, pred: impl Fn(char) -> bool) -> usize {
+    text.find(|c: char| !pred(c)).unwrap_or(text.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacts_named_values() {
+        assert_eq!(
+            redact("X-API-Key: pk_live_abc123 sent"),
+            "X-API-Key: [redacted] sent"
+        );
+        assert_eq!(
+            redact("authorization: Bearer eyJhbGci.x.y"),
+            "authorization: Bearer [redacted]"
+        );
+        assert_eq!(
+            redact(r#"{"key":"pk_new_999","id":"abc"}"#),
+            r#"{"key":"[redacted]","id":"abc"}"#
+        );
+        assert_eq!(redact(r#"{"key_id": "abc"}"#), r#"{"key_id": "abc"}"#);
+        assert_eq!(
+            redact("Authorization failed for this request"),
+            "Authorization failed for this request"
+        );
+        assert_eq!(redact("nothing to see"), "nothing to see");
+    }
+
+    #[test]
+    fn test_redacts_registered_secrets() {
+        register("sk_registered_secret_42");
+        register("short");
+        assert_eq!(
+            redact("using sk_registered_secret_42 and short"),
+            "using [redacted] and short"
+        );
+    }
+}
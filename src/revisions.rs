@@ -0,0 +1,159 @@
+//! Change history of a todo, on servers that keep one
+//!
+//! Servers with [`Capabilities::REVISIONS`](crate::api::Capabilities::REVISIONS)
+//! list the states a todo went through, oldest first. What a revision
+//! changed is found by comparing it with the one before, so `pacli history
+//! <id>` and the history popup of `patui` (`H`) show the same diffs.
+
+use crate::api::Revision;
+use chrono::{Local, TimeZone};
+
+/// One field a revision changed
+///
+/// Single-line fields have one removed and one added value; descriptions
+/// keep only the lines that differ.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub field: &'static str,
+    pub removed: Vec<String>,
+    pub added: Vec<String>,
+}
+
+impl Change {
+    fn value(field: &'static str, before: String, after: String) -> Option<Self> {
+        (before != after).then(|| Self {
+            field,
+            removed: vec![before],
+            added: vec![after],
+        })
+    }
+}
+
+/// The fields `after` changed compared to `before`
+#[must_use]
+pub fn changes(before: &Revision, after: &Revision) -> Vec<Change> {
+    let status = |revision: &Revision| {
+        if revision.completed {
+            "completed"
+        } else {
+            "pending"
+        }
+        .to_string()
+    };
+    let mut changes: Vec<Change> = [
+        Change::value("title", before.title.clone(), after.title.clone()),
+        Change::value("status", status(before), status(after)),
+        Change::value(
+            "priority",
+            before.priority.to_string(),
+            after.priority.to_string(),
+        ),
+        Change::value(
+            "due",
+            format_due(before.due_date),
+            format_due(after.due_date),
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let old = before.description.as_deref().unwrap_or_default();
+    let new = after.description.as_deref().unwrap_or_default();
+    if old != new {
+        let (removed, added) = diff_lines(old, new);
+        changes.push(Change {
+            field: "description",
+            removed,
+            added,
+        });
+    }
+    changes
+}
+
+/// When and by whom a revision was made, e.g. `2025-03-01 14:02 by ci-bot`
+#[must_use]
+pub fn describe(revision: &Revision) -> String {
+    let when = Local
+        .timestamp_opt(revision.changed_at, 0)
+        .single()
+        .map_or_else(
+            || "Invalid date".to_string(),
+            |dt| dt.format("%Y-%m-%d %H:%M").to_string(),
+        );
+    match &revision.changed_by {
+        Some(who) => format!("{when} by {who}"),
+        None => when,
+    }
+}
+
+fn format_due(due_date: Option<i64>) -> String {
+    due_date
+        .and_then(|ts| Local.timestamp_opt(ts, 0).single())
+        .map_or_else(
+            || "none".to_string(),
+            |dt| dt.format("%Y-%m-%d %H:%M").to_string(),
+        )
+}
+
+/// The lines between the common start and end of `old` and `new`
+fn diff_lines(old: &str, new: &str) -> (Vec<String>, Vec<String>) {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let lines = |lines: &[&str]| {
+        lines[prefix..lines.len() - suffix]
+            .iter()
+            .map(|line| (*line).to_string())
+            .collect()
+    };
+    (lines(&old), lines(&new))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn revision(title: &str, description: &str, priority: i32) -> Revision {
+        Revision {
+            changed_at: 1_700_000_000,
+            changed_by: None,
+            title: title.to_string(),
+            description: Some(description.to_string()),
+            priority,
+            due_date: None,
+            completed: false,
+        }
+    }
+
+    #[test]
+    fn test_changes() {
+        let before = revision("Renew passport", "Photos\nForm\nFee", 2);
+        assert!(changes(&before, &before).is_empty());
+
+        let after = Revision {
+            completed: true,
+            ..revision("Renew the passport", "Photos\nForm B\nSignature\nFee", 3)
+        };
+        let changes = changes(&before, &after);
+        let fields: Vec<&str> = changes.iter().map(|change| change.field).collect();
+        assert_eq!(fields, ["title", "status", "priority", "description"]);
+        assert_eq!(changes[0].removed, ["Renew passport"]);
+        assert_eq!(changes[0].added, ["Renew the passport"]);
+        assert_eq!(changes[1].added, ["completed"]);
+        assert_eq!(changes[3].removed, ["Form"]);
+        assert_eq!(changes[3].added, ["Form B", "Signature"]);
+
+        let by_bot = Revision {
+            changed_by: Some("ci-bot".to_string()),
+            ..before
+        };
+        assert!(describe(&by_bot).ends_with(" by ci-bot"));
+    }
+}
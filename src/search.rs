@@ -0,0 +1,174 @@
+//! Client-side text matching shared by `pacli` and `patui`
+//!
+//! Finds where a query occurs in a todo's title or description, so results
+//! can show why they matched. Besides plain text, queries can be regular
+//! expressions, for matches the server's text search can't express.
+
+use anyhow::{Context, Result};
+use pali_types::Todo;
+use regex::Regex;
+use std::ops::Range;
+
+/// A search query, matched against todo titles and descriptions
+#[derive(Debug, Clone)]
+pub enum Query {
+    /// Case-insensitive substring
+    Text(String),
+    /// Regular expression, case-sensitive unless it starts with `(?i)`
+    Regex(Regex),
+}
+
+impl Query {
+    /// Creates a plain text query, or a regex query if `regex` is set
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `regex` is set and `query` is not a valid regular expression
+    pub fn new(query: &str, regex: bool) -> Result<Self> {
+        if regex {
+            let regex = Regex::new(query)
+                .with_context(|| format!("Invalid regular expression '{query}'"))?;
+            Ok(Self::Regex(regex))
+        } else {
+            Ok(Self::Text(query.to_string()))
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Text(text) => text.is_empty(),
+            Self::Regex(regex) => regex.as_str().is_empty(),
+        }
+    }
+
+    /// Byte ranges of the non-empty matches in `text`
+    #[must_use]
+    pub fn find_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        match self {
+            Self::Text(query) => match_ranges(text, query),
+            Self::Regex(regex) => regex
+                .find_iter(text)
+                .filter(|found| !found.is_empty())
+                .map(|found| found.range())
+                .collect(),
+        }
+    }
+
+    /// Returns `true` if the title or description matches
+    #[must_use]
+    pub fn matches(&self, todo: &Todo) -> bool {
+        let is_match = |text: &str| match self {
+            Self::Text(query) => !match_ranges(text, query).is_empty(),
+            Self::Regex(regex) => regex.is_match(text),
+        };
+        is_match(&todo.title) || todo.description.as_deref().is_some_and(is_match)
+    }
+}
+
+/// Byte ranges of the case-insensitive, non-overlapping occurrences of `query` in `text`
+#[must_use]
+pub fn match_ranges(text: &str, query: &str) -> Vec<Range<usize>> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ranges = Vec::new();
+    let mut search_from = 0;
+    for (start, _) in text.char_indices() {
+        if start < search_from {
+            continue;
+        }
+        // Compare lowercased characters so byte offsets stay those of `text`
+        let mut lowered = text[start..].chars().flat_map(char::to_lowercase);
+        if query.iter().all(|c| lowered.next() == Some(*c)) {
+            let len = matched_len(&text[start..], query.len());
+            ranges.push(start..start + len);
+            search_from = start + len;
+        }
+    }
+    ranges
+}
+
+/// Byte length of the shortest prefix of `text` whose lowercase form has `lowered_chars` characters
+fn matched_len(text: &str, lowered_chars: usize) -> usize {
+    let mut count = 0;
+    for (index, c) in text.char_indices() {
+        if count >= lowered_chars {
+            return index;
+        }
+        count += c.to_lowercase().count();
+    }
+    text.len()
+}
+
+/// A window of about `width` characters of `text` around the first range, with ranges rebased
+///
+/// Used to show the matching part of a long description. Ellipses mark
+/// omitted text.
+#[must_use]
+pub fn snippet(text: &str, ranges: &[Range<usize>], width: usize) -> (String, Vec<Range<usize>>) {
+    let Some(first) = ranges.first() else {
+        return (String::new(), Vec::new());
+    };
+
+    let before = width / 3;
+    let start = text[..first.start]
+        .char_indices()
+        .rev()
+        .nth(before.saturating_sub(1))
+        .map_or(0, |(index, _)| index);
+    let end = text[start..]
+        .char_indices()
+        .nth(width)
+        .map_or(text.len(), |(index, _)| start + index)
+        .max(first.end);
+
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < text.len() { "…" } else { "" };
+    let offset = prefix.len();
+    let ranges = ranges
+        .iter()
+        .filter(|range| range.start >= start && range.end <= end)
+        .map(|range| range.start - start + offset..range.end - start + offset)
+        .collect();
+    let snippet = format!("{prefix}{}{suffix}", &text[start..end]).replace('\n', " ");
+    (snippet, ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_ranges() {
+        assert_eq!(match_ranges("Buy milk, MILK!", "milk"), vec![4..8, 10..14]);
+        assert_eq!(match_ranges("aaaa", "aa"), vec![0..2, 2..4]);
+        assert_eq!(match_ranges("Grüße", "GRÜ"), vec![0..4]);
+        assert!(match_ranges("anything", "").is_empty());
+        assert!(match_ranges("short", "longer query").is_empty());
+    }
+
+    #[test]
+    fn test_regex_query() {
+        let query = Query::new(r"\bv\d+\.\d+", true).unwrap();
+        assert_eq!(
+            query.find_ranges("release v1.2 and v10.0"),
+            vec![8..12, 17..22]
+        );
+        assert!(query.find_ranges("nov1.2").is_empty());
+        assert!(Query::new("(unclosed", true).is_err());
+        assert!(Query::new("(unclosed", false).is_ok());
+    }
+
+    #[test]
+    fn test_snippet() {
+        let text = "a long description that mentions the dentist somewhere in the middle";
+        let ranges = match_ranges(text, "dentist");
+        let (snippet, rebased) = snippet(text, &ranges, 20);
+
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert_eq!(&snippet[rebased[0].clone()], "dentist");
+    }
+}
@@ -0,0 +1,227 @@
+//! Encryption at rest for the stored API key (`pacli config encrypt`)
+//!
+//! The key is sealed with ChaCha20-Poly1305 using a key derived with Argon2
+//! from either a passphrase or a machine-specific secret. The passphrase is
+//! taken from `PALI_PASSPHRASE` or prompted for once per process.
+
+use anyhow::{Context, Result};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Nonce,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+/// Environment variable checked before prompting for the passphrase
+pub const PASSPHRASE_ENV: &str = "PALI_PASSPHRASE";
+
+const SALT_LEN: usize = 16;
+
+/// Where the encryption key comes from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeySource {
+    /// A passphrase typed by the user (or `PALI_PASSPHRASE`)
+    Passphrase,
+    /// The machine ID and user name; keeps the key out of backups and
+    /// copies of the config, but not away from other users of the same account
+    Machine,
+}
+
+/// An encrypted secret as stored in the config file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EncryptedSecret {
+    pub source: KeySource,
+    /// Base64 encoded
+    pub salt: String,
+    /// Base64 encoded
+    pub nonce: String,
+    /// Base64 encoded
+    pub ciphertext: String,
+}
+
+/// Derived key kept in memory so the passphrase is asked for (and Argon2 run) only once
+#[derive(Clone)]
+pub struct DerivedKey {
+    source: KeySource,
+    salt: Vec<u8>,
+    key: [u8; 32],
+}
+
+impl std::fmt::Debug for DerivedKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DerivedKey")
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+static KEY_CACHE: Mutex<Option<DerivedKey>> = Mutex::new(None);
+
+impl DerivedKey {
+    fn derive(source: KeySource, salt: Vec<u8>, material: &[u8]) -> Result<Self> {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(material, &salt, &mut key)
+            .map_err(|err| anyhow::anyhow!("Key derivation failed: {err}"))?;
+        Ok(Self { source, salt, key })
+    }
+
+    /// Creates a key with a fresh salt for `pacli config encrypt`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key material cannot be obtained
+    pub fn create(source: KeySource) -> Result<Self> {
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let material = key_material(source, true)?;
+        let key = Self::derive(source, salt, material.as_bytes())?;
+        remember(&key);
+        Ok(key)
+    }
+
+    /// Returns the key for an existing secret, reusing the cached one when possible
+    fn for_secret(secret: &EncryptedSecret) -> Result<Self> {
+        let salt = STANDARD
+            .decode(&secret.salt)
+            .context("Encrypted API key is corrupt")?;
+
+        if let Ok(cache) = KEY_CACHE.lock() {
+            if let Some(key) = cache.as_ref() {
+                if key.source == secret.source && key.salt == salt {
+                    return Ok(key.clone());
+                }
+            }
+        }
+
+        let material = key_material(secret.source, false)?;
+        Self::derive(secret.source, salt, material.as_bytes())
+    }
+
+    /// Encrypts `plaintext` with a fresh nonce
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if encryption fails
+    pub fn encrypt(&self, plaintext: &str) -> Result<EncryptedSecret> {
+        let cipher = ChaCha20Poly1305::new(&self.key.into());
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| anyhow::anyhow!("Failed to encrypt the API key"))?;
+
+        Ok(EncryptedSecret {
+            source: self.source,
+            salt: STANDARD.encode(&self.salt),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        })
+    }
+
+    fn decrypt(&self, secret: &EncryptedSecret) -> Result<String> {
+        let nonce = STANDARD
+            .decode(&secret.nonce)
+            .context("Encrypted API key is corrupt")?;
+        let ciphertext = STANDARD
+            .decode(&secret.ciphertext)
+            .context("Encrypted API key is corrupt")?;
+        if nonce.len() != 12 {
+            anyhow::bail!("Encrypted API key is corrupt");
+        }
+
+        let cipher = ChaCha20Poly1305::new(&self.key.into());
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| match self.source {
+                KeySource::Passphrase => anyhow::anyhow!(
+                    "Wrong passphrase for the encrypted API key (set {PASSPHRASE_ENV} or try again)"
+                ),
+                KeySource::Machine => anyhow::anyhow!(
+                    "The API key was encrypted on another machine or user account. \
+                     Set it again with 'pacli config key' or 'pacli login'."
+                ),
+            })?;
+
+        String::from_utf8(plaintext).context("Encrypted API key is corrupt")
+    }
+}
+
+fn remember(key: &DerivedKey) {
+    if let Ok(mut cache) = KEY_CACHE.lock() {
+        *cache = Some(key.clone());
+    }
+}
+
+/// Decrypts a stored secret, asking for the passphrase if needed
+///
+/// Returns the key used so the caller can re-encrypt on save.
+///
+/// # Errors
+///
+/// Returns an error if the passphrase is wrong, cannot be obtained, or the
+/// secret is corrupt
+pub fn decrypt(secret: &EncryptedSecret) -> Result<(String, DerivedKey)> {
+    let key = DerivedKey::for_secret(secret)?;
+    let plaintext = key.decrypt(secret)?;
+    remember(&key);
+    Ok((plaintext, key))
+}
+
+/// Returns the passphrase or machine secret the key is derived from
+fn key_material(source: KeySource, confirm: bool) -> Result<String> {
+    match source {
+        KeySource::Passphrase => {
+            if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+                return Ok(passphrase);
+            }
+            crate::prompt::require("enter the config passphrase").with_context(|| {
+                format!(
+                    "The API key is encrypted. Set {PASSPHRASE_ENV} to decrypt it non-interactively."
+                )
+            })?;
+
+            let passphrase = rpassword::prompt_password("Config passphrase: ")?;
+            if passphrase.is_empty() {
+                anyhow::bail!("Passphrase cannot be empty");
+            }
+            if confirm && rpassword::prompt_password("Repeat passphrase: ")? != passphrase {
+                anyhow::bail!("Passphrases do not match");
+            }
+            Ok(passphrase)
+        }
+        KeySource::Machine => {
+            let machine_id = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+                .iter()
+                .find_map(|path| std::fs::read_to_string(path).ok())
+                .or_else(|| std::env::var("COMPUTERNAME").ok())
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Could not determine a machine ID. Use a passphrase instead.")
+                })?;
+            let user = std::env::var("USER")
+                .or_else(|_| std::env::var("USERNAME"))
+                .unwrap_or_default();
+            Ok(format!("{}:{user}", machine_id.trim()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let key = DerivedKey::derive(KeySource::Passphrase, vec![7; SALT_LEN], b"hunter2").unwrap();
+        let secret = key.encrypt("pk_live_123").unwrap();
+        assert!(!secret.ciphertext.contains("pk_live"));
+        assert_eq!(key.decrypt(&secret).unwrap(), "pk_live_123");
+
+        let wrong =
+            DerivedKey::derive(KeySource::Passphrase, vec![7; SALT_LEN], b"hunter3").unwrap();
+        let err = wrong.decrypt(&secret).unwrap_err();
+        assert!(err.to_string().contains("Wrong passphrase"));
+    }
+}
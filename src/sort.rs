@@ -0,0 +1,217 @@
+//! Compound todo ordering shared by `pacli list --sort` and the `patui` sort cycle
+//!
+//! A sort spec is a comma-separated list of keys such as `priority,due`;
+//! later keys break ties of earlier ones and todos equal on every key keep
+//! their original order. Each key has a natural direction (most urgent
+//! first), which a `-` prefix reverses.
+
+use anyhow::{bail, Result};
+use pali_types::Todo;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// High priority first
+    Priority,
+    /// Earliest due date first, todos without one last
+    Due,
+    /// Newest first
+    Created,
+    /// Most recently updated first
+    Updated,
+    /// Alphabetical, case-insensitive
+    Title,
+}
+
+impl SortKey {
+    const ALL: [Self; 5] = [
+        Self::Priority,
+        Self::Due,
+        Self::Created,
+        Self::Updated,
+        Self::Title,
+    ];
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Priority => "priority",
+            Self::Due => "due",
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Title => "title",
+        }
+    }
+
+    fn compare(self, a: &Todo, b: &Todo) -> Ordering {
+        match self {
+            Self::Priority => b.priority.cmp(&a.priority),
+            Self::Due => match (a.due_date, b.due_date) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                // Todos without a due date go last
+                (a, b) => a.is_none().cmp(&b.is_none()),
+            },
+            Self::Created => b.created_at.cmp(&a.created_at),
+            Self::Updated => b.updated_at.cmp(&a.updated_at),
+            Self::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+        }
+    }
+}
+
+/// An ordering of todos, built from a spec like `priority,-created`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SortSpec {
+    /// Keys in order of precedence, with `true` for reversed ones
+    keys: Vec<(SortKey, bool)>,
+    /// Put completed todos after all open ones, whatever the keys say
+    completed_last: bool,
+}
+
+impl SortSpec {
+    /// Parses a comma-separated list of keys
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a key is unknown or repeated
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut keys: Vec<(SortKey, bool)> = Vec::new();
+        for part in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            let (name, reversed) = match part.strip_prefix('-') {
+                Some(name) => (name, true),
+                None => (part, false),
+            };
+            let Some(key) = SortKey::ALL
+                .into_iter()
+                .find(|key| key.name().eq_ignore_ascii_case(name))
+            else {
+                let names: Vec<&str> = SortKey::ALL.iter().map(|key| key.name()).collect();
+                bail!(
+                    "Unknown sort key '{name}'. Use a comma-separated list of: {}",
+                    names.join(", ")
+                );
+            };
+            if keys.iter().any(|(existing, _)| *existing == key) {
+                bail!("Sort key '{name}' is given twice");
+            }
+            keys.push((key, reversed));
+        }
+        Ok(Self {
+            keys,
+            completed_last: false,
+        })
+    }
+
+    /// Puts completed todos after the open ones
+    #[must_use]
+    pub fn completed_last(mut self, completed_last: bool) -> Self {
+        self.completed_last = completed_last;
+        self
+    }
+
+    /// Returns `true` if sorting would keep the original order
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty() && !self.completed_last
+    }
+
+    /// Compares two todos by every key in turn
+    #[must_use]
+    pub fn compare(&self, a: &Todo, b: &Todo) -> Ordering {
+        let completed = if self.completed_last {
+            a.completed.cmp(&b.completed)
+        } else {
+            Ordering::Equal
+        };
+        self.keys
+            .iter()
+            .fold(completed, |ordering, (key, reversed)| {
+                ordering.then_with(|| {
+                    let ordering = key.compare(a, b);
+                    if *reversed {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                })
+            })
+    }
+
+    /// Sorts `todos` in place; the sort is stable
+    pub fn sort<T: Borrow<Todo>>(&self, todos: &mut [T]) {
+        if !self.is_empty() {
+            todos.sort_by(|a, b| self.compare(a.borrow(), b.borrow()));
+        }
+    }
+}
+
+/// Formats the keys back into a spec, e.g. `priority,-created`
+impl fmt::Display for SortSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (key, reversed)) in self.keys.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            if *reversed {
+                f.write_str("-")?;
+            }
+            f.write_str(key.name())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn todo(id: &str, priority: i32, due_date: Option<i64>, completed: bool) -> Todo {
+        testing::todo(id)
+            .priority(priority)
+            .due(due_date)
+            .completed(completed)
+            .build()
+    }
+
+    fn ids(todos: &[Todo]) -> Vec<&str> {
+        todos.iter().map(|todo| todo.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_compound_sort_is_stable() {
+        let mut todos = vec![
+            todo("a", 2, None, false),
+            todo("b", 3, Some(200), false),
+            todo("c", 2, Some(100), false),
+            todo("d", 3, Some(100), true),
+            todo("e", 2, None, false),
+        ];
+
+        SortSpec::parse("priority,due").unwrap().sort(&mut todos);
+        assert_eq!(ids(&todos), ["d", "b", "c", "a", "e"]);
+
+        SortSpec::parse("priority,due")
+            .unwrap()
+            .completed_last(true)
+            .sort(&mut todos);
+        assert_eq!(ids(&todos), ["b", "c", "a", "e", "d"]);
+
+        SortSpec::parse("-priority").unwrap().sort(&mut todos);
+        assert_eq!(ids(&todos), ["c", "a", "e", "b", "d"]);
+    }
+
+    #[test]
+    fn test_parse_spec() {
+        let spec = SortSpec::parse(" Priority, -due ").unwrap();
+        assert_eq!(spec.to_string(), "priority,-due");
+        assert!(SortSpec::parse("").unwrap().is_empty());
+        assert!(SortSpec::parse("priority,size").is_err());
+        assert!(SortSpec::parse("due,-due").is_err());
+    }
+}
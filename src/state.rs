@@ -0,0 +1,786 @@
+//! Local client-side state
+//!
+//! Stores per-todo metadata that the server has no fields for (such as
+//! tags, aliases, dependencies between todos and tracked time) in
+//! `state.json` inside the data directory.
+
+use crate::config::Config;
+use anyhow::Result;
+use pali_types::Todo;
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct LocalState {
+    #[serde(default)]
+    pub todos: BTreeMap<String, TodoMeta>,
+    /// Timer currently running, if any (only one todo is tracked at a time)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_timer: Option<ActiveTimer>,
+}
+
+/// Client-side metadata attached to a single todo
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct TodoMeta {
+    /// Tags, kept sorted and without duplicates
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// IDs of todos that cannot be started until this one is complete
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<String>,
+    /// Completed work intervals
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub time_entries: Vec<TimeEntry>,
+    /// Completion timestamps of pomodoros worked on this todo
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pomodoros: Vec<i64>,
+    /// When the todo was archived; archived todos are hidden from default lists
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<i64>,
+    /// Due date whose `patui` reminder was dismissed; a new due date reminds again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reminder_dismissed: Option<i64>,
+    /// Name accepted in place of the ID, e.g. `grocery`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// When the todo was pinned; pinned todos come first in every list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_at: Option<i64>,
+}
+
+impl TodoMeta {
+    fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+            && self.blocks.is_empty()
+            && self.time_entries.is_empty()
+            && self.pomodoros.is_empty()
+            && self.archived_at.is_none()
+            && self.reminder_dismissed.is_none()
+            && self.alias.is_none()
+            && self.pinned_at.is_none()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ActiveTimer {
+    pub todo_id: String,
+    pub started_at: i64,
+}
+
+/// A recorded work interval (Unix timestamps)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct TimeEntry {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl TimeEntry {
+    #[must_use]
+    pub fn seconds(&self) -> i64 {
+        (self.end - self.start).max(0)
+    }
+}
+
+/// Parses a comma-separated tag list, trimming whitespace and dropping empty entries
+#[must_use]
+pub fn parse_tags(tags: &str) -> Vec<String> {
+    let mut parsed: Vec<String> = tags
+        .split(',')
+        .map(|tag| tag.trim().trim_start_matches('#').to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    parsed.sort();
+    parsed.dedup();
+    parsed
+}
+
+/// Checks that `alias` can name a todo, returning it trimmed
+///
+/// Aliases are letters, digits, `-` and `_`, and need at least one
+/// character that isn't a hex digit so they never shadow an ID prefix.
+///
+/// # Errors
+///
+/// Returns an error if the alias is empty, contains other characters or
+/// could be an ID prefix
+pub fn validate_alias(alias: &str) -> Result<String> {
+    let alias = alias.trim();
+    if alias.is_empty() {
+        anyhow::bail!("Alias cannot be empty");
+    }
+    if !alias
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!("Alias '{alias}' may only contain letters, digits, '-' and '_'");
+    }
+    if alias.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+        anyhow::bail!(
+            "Alias '{alias}' could be mistaken for an ID prefix; use at least one letter from g to z"
+        );
+    }
+    Ok(alias.to_string())
+}
+
+/// Formats a number of seconds as a compact duration (e.g. `1h 05m`, `12m`, `40s`)
+#[must_use]
+pub fn format_duration(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else if minutes > 0 {
+        format!("{minutes}m")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+impl LocalState {
+    /// Loads local state from disk, returning empty state if the file doesn't exist
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - State file exists but cannot be read
+    /// - State file format is invalid JSON
+    pub fn load() -> Result<Self> {
+        let state_path = Self::state_path()?;
+
+        if state_path.exists() {
+            let content = fs::read_to_string(state_path)?;
+            Ok(serde_json::from_str(&content)?)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Saves local state to disk
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Cannot create data directory
+    /// - Cannot write to state file
+    /// - JSON serialization fails
+    pub fn save(&self) -> Result<()> {
+        let state_path = Self::state_path()?;
+
+        if let Some(parent) = state_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(state_path, content)?;
+        Ok(())
+    }
+
+    /// Returns the path to the local state file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data directory cannot be determined
+    pub fn state_path() -> Result<PathBuf> {
+        Ok(Config::data_dir()?.join("state.json"))
+    }
+
+    /// Returns the tags of `id`
+    #[must_use]
+    pub fn tags_of(&self, id: &str) -> &[String] {
+        self.todos.get(id).map_or(&[], |meta| meta.tags.as_slice())
+    }
+
+    /// Returns `true` if `id` has `tag` (case insensitive)
+    #[must_use]
+    pub fn has_tag(&self, id: &str, tag: &str) -> bool {
+        self.tags_of(id).iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Replaces the tags of `id`
+    pub fn set_tags(&mut self, id: &str, tags: Vec<String>) {
+        let meta = self.todos.entry(id.to_string()).or_default();
+        meta.tags = tags;
+        meta.tags.sort();
+        meta.tags.dedup();
+
+        if meta.is_empty() {
+            self.todos.remove(id);
+        }
+    }
+
+    /// Counts how many of the todos with the given IDs carry each tag
+    #[must_use]
+    pub fn tag_counts<'a>(
+        &self,
+        ids: impl IntoIterator<Item = &'a str>,
+    ) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for id in ids {
+            for tag in self.tags_of(id) {
+                *counts.entry(tag.clone()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Returns every tag in use, sorted
+    #[must_use]
+    pub fn all_tags(&self) -> Vec<&str> {
+        let mut tags: Vec<&str> = self
+            .todos
+            .values()
+            .flat_map(|meta| meta.tags.iter().map(String::as_str))
+            .collect();
+        tags.sort_unstable();
+        tags.dedup();
+        tags
+    }
+
+    /// Renames `old` (case insensitive) to `new` on every todo, returning the
+    /// number of todos changed
+    ///
+    /// Todos that already have `new` simply lose `old`.
+    pub fn rename_tag(&mut self, old: &str, new: &str) -> usize {
+        let mut changed = 0;
+        for meta in self.todos.values_mut() {
+            let before = meta.tags.len();
+            meta.tags.retain(|t| !t.eq_ignore_ascii_case(old));
+            if meta.tags.len() < before {
+                meta.tags.push(new.to_string());
+                meta.tags.sort();
+                meta.tags.dedup();
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// Returns the alias of `id`, if it has one
+    #[must_use]
+    pub fn alias_of(&self, id: &str) -> Option<&str> {
+        self.todos.get(id).and_then(|meta| meta.alias.as_deref())
+    }
+
+    /// Returns the ID of the todo named `alias` (case insensitive)
+    #[must_use]
+    pub fn resolve_alias(&self, alias: &str) -> Option<&str> {
+        self.todos
+            .iter()
+            .find(|(_, meta)| {
+                meta.alias
+                    .as_deref()
+                    .is_some_and(|a| a.eq_ignore_ascii_case(alias))
+            })
+            .map(|(id, _)| id.as_str())
+    }
+
+    /// Names `id` `alias`, replacing its previous alias
+    ///
+    /// An alias names a single todo: if another todo had it, it is taken
+    /// away and that todo's ID is returned.
+    pub fn set_alias(&mut self, id: &str, alias: &str) -> Option<String> {
+        let previous = self
+            .resolve_alias(alias)
+            .filter(|other| *other != id)
+            .map(str::to_string);
+        if let Some(other) = &previous {
+            self.clear_alias(other);
+        }
+        self.todos.entry(id.to_string()).or_default().alias = Some(alias.to_string());
+        previous
+    }
+
+    /// Removes the alias `alias`, returning the ID it named
+    pub fn remove_alias(&mut self, alias: &str) -> Option<String> {
+        let id = self.resolve_alias(alias)?.to_string();
+        self.clear_alias(&id);
+        Some(id)
+    }
+
+    fn clear_alias(&mut self, id: &str) {
+        if let Some(meta) = self.todos.get_mut(id) {
+            meta.alias = None;
+            if meta.is_empty() {
+                self.todos.remove(id);
+            }
+        }
+    }
+
+    /// Returns every `(alias, id)` pair, sorted by alias
+    #[must_use]
+    pub fn aliases(&self) -> Vec<(&str, &str)> {
+        let mut aliases: Vec<_> = self
+            .todos
+            .iter()
+            .filter_map(|(id, meta)| Some((meta.alias.as_deref()?, id.as_str())))
+            .collect();
+        aliases.sort_unstable();
+        aliases
+    }
+
+    /// Archives `id`, returning `false` if it was already archived
+    pub fn archive(&mut self, id: &str, now: i64) -> bool {
+        let meta = self.todos.entry(id.to_string()).or_default();
+        if meta.archived_at.is_some() {
+            return false;
+        }
+        meta.archived_at = Some(now);
+        true
+    }
+
+    /// Restores an archived todo, returning `false` if it wasn't archived
+    pub fn unarchive(&mut self, id: &str) -> bool {
+        let Some(meta) = self.todos.get_mut(id) else {
+            return false;
+        };
+
+        let was_archived = meta.archived_at.take().is_some();
+        if meta.is_empty() {
+            self.todos.remove(id);
+        }
+        was_archived
+    }
+
+    #[must_use]
+    pub fn is_archived(&self, id: &str) -> bool {
+        self.todos
+            .get(id)
+            .is_some_and(|meta| meta.archived_at.is_some())
+    }
+
+    /// Pins `id` to the top of lists, returning `false` if it was already pinned
+    pub fn pin(&mut self, id: &str, now: i64) -> bool {
+        let meta = self.todos.entry(id.to_string()).or_default();
+        if meta.pinned_at.is_some() {
+            return false;
+        }
+        meta.pinned_at = Some(now);
+        true
+    }
+
+    /// Unpins `id`, returning `false` if it wasn't pinned
+    pub fn unpin(&mut self, id: &str) -> bool {
+        let Some(meta) = self.todos.get_mut(id) else {
+            return false;
+        };
+
+        let was_pinned = meta.pinned_at.take().is_some();
+        if meta.is_empty() {
+            self.todos.remove(id);
+        }
+        was_pinned
+    }
+
+    #[must_use]
+    pub fn is_pinned(&self, id: &str) -> bool {
+        self.todos
+            .get(id)
+            .is_some_and(|meta| meta.pinned_at.is_some())
+    }
+
+    /// Moves pinned todos to the front, keeping the order within both groups
+    pub fn pinned_first<T: Borrow<Todo>>(&self, todos: &mut [T]) {
+        todos.sort_by_key(|todo| !self.is_pinned(&todo.borrow().id));
+    }
+
+    /// Stops reminding about `id` being due at `due_date`
+    pub fn dismiss_reminder(&mut self, id: &str, due_date: i64) {
+        self.todos
+            .entry(id.to_string())
+            .or_default()
+            .reminder_dismissed = Some(due_date);
+    }
+
+    #[must_use]
+    pub fn is_reminder_dismissed(&self, id: &str, due_date: i64) -> bool {
+        self.todos
+            .get(id)
+            .is_some_and(|meta| meta.reminder_dismissed == Some(due_date))
+    }
+
+    /// Records that `blocker` must be completed before `blocked`
+    ///
+    /// Returns `false` if the link already existed.
+    pub fn add_block(&mut self, blocker: &str, blocked: &str) -> bool {
+        let meta = self.todos.entry(blocker.to_string()).or_default();
+        if meta.blocks.iter().any(|id| id == blocked) {
+            return false;
+        }
+        meta.blocks.push(blocked.to_string());
+        true
+    }
+
+    /// Removes a dependency link, returning `false` if it didn't exist
+    pub fn remove_block(&mut self, blocker: &str, blocked: &str) -> bool {
+        let Some(meta) = self.todos.get_mut(blocker) else {
+            return false;
+        };
+
+        let before = meta.blocks.len();
+        meta.blocks.retain(|id| id != blocked);
+        let removed = meta.blocks.len() != before;
+
+        if meta.is_empty() {
+            self.todos.remove(blocker);
+        }
+        removed
+    }
+
+    /// Returns the IDs of todos that block `id`
+    #[must_use]
+    pub fn blockers_of(&self, id: &str) -> Vec<&str> {
+        self.todos
+            .iter()
+            .filter(|(_, meta)| meta.blocks.iter().any(|blocked| blocked == id))
+            .map(|(blocker, _)| blocker.as_str())
+            .collect()
+    }
+
+    /// Returns `true` if `id` has a prerequisite in `todos` that is not yet complete
+    ///
+    /// Prerequisites that are not present in `todos` (deleted or filtered out)
+    /// are not considered blocking.
+    #[must_use]
+    pub fn is_blocked(&self, id: &str, todos: &[Todo]) -> bool {
+        self.blockers_of(id).into_iter().any(|blocker| {
+            todos
+                .iter()
+                .any(|todo| todo.id == blocker && !todo.completed)
+        })
+    }
+
+    /// Starts tracking time on `todo_id`
+    ///
+    /// A timer already running on another todo is stopped first; its todo ID
+    /// and recorded entry are returned.
+    pub fn start_timer(&mut self, todo_id: &str, now: i64) -> Option<(String, TimeEntry)> {
+        if self.is_timer_running(todo_id) {
+            return None;
+        }
+
+        let stopped = self.stop_timer(now);
+
+        self.active_timer = Some(ActiveTimer {
+            todo_id: todo_id.to_string(),
+            started_at: now,
+        });
+        stopped
+    }
+
+    /// Stops the running timer, recording its interval
+    ///
+    /// Returns the tracked todo ID and the recorded entry, or `None` if no
+    /// timer was running.
+    pub fn stop_timer(&mut self, now: i64) -> Option<(String, TimeEntry)> {
+        let timer = self.active_timer.take()?;
+        let entry = TimeEntry {
+            start: timer.started_at,
+            end: now.max(timer.started_at),
+        };
+
+        self.todos
+            .entry(timer.todo_id.clone())
+            .or_default()
+            .time_entries
+            .push(entry);
+        Some((timer.todo_id, entry))
+    }
+
+    /// Returns the total tracked seconds for `todo_id`, including a running timer
+    #[must_use]
+    pub fn tracked_seconds(&self, todo_id: &str, now: i64) -> i64 {
+        let recorded: i64 = self.todos.get(todo_id).map_or(0, |meta| {
+            meta.time_entries.iter().map(TimeEntry::seconds).sum()
+        });
+
+        let running = self
+            .active_timer
+            .as_ref()
+            .filter(|timer| timer.todo_id == todo_id)
+            .map_or(0, |timer| (now - timer.started_at).max(0));
+
+        recorded + running
+    }
+
+    /// Returns `true` if the running timer belongs to `todo_id`
+    #[must_use]
+    pub fn is_timer_running(&self, todo_id: &str) -> bool {
+        self.active_timer
+            .as_ref()
+            .is_some_and(|timer| timer.todo_id == todo_id)
+    }
+
+    /// Records a completed pomodoro on `todo_id`
+    pub fn record_pomodoro(&mut self, todo_id: &str, completed_at: i64) {
+        self.todos
+            .entry(todo_id.to_string())
+            .or_default()
+            .pomodoros
+            .push(completed_at);
+    }
+
+    /// Returns the number of completed pomodoros on `todo_id`
+    #[must_use]
+    pub fn pomodoro_count(&self, todo_id: &str) -> usize {
+        self.todos
+            .get(todo_id)
+            .map_or(0, |meta| meta.pomodoros.len())
+    }
+
+    /// Returns all recorded entries that started within `[from, to)`, as `(todo_id, entry)`
+    #[must_use]
+    pub fn entries_between(&self, from: i64, to: i64) -> Vec<(&str, TimeEntry)> {
+        let mut entries: Vec<_> = self
+            .todos
+            .iter()
+            .flat_map(|(id, meta)| {
+                meta.time_entries
+                    .iter()
+                    .filter(|entry| entry.start >= from && entry.start < to)
+                    .map(move |entry| (id.as_str(), *entry))
+            })
+            .collect();
+        entries.sort_by_key(|(_, entry)| entry.start);
+        entries
+    }
+
+    /// Returns `true` if linking `blocker -> blocked` would create a dependency cycle
+    #[must_use]
+    pub fn would_create_cycle(&self, blocker: &str, blocked: &str) -> bool {
+        // A cycle exists if `blocker` is already reachable from `blocked`
+        let mut stack = vec![blocked];
+        let mut visited = Vec::new();
+
+        while let Some(current) = stack.pop() {
+            if current == blocker {
+                return true;
+            }
+            if visited.contains(&current) {
+                continue;
+            }
+            visited.push(current);
+
+            if let Some(meta) = self.todos.get(current) {
+                stack.extend(meta.blocks.iter().map(String::as_str));
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn todo(id: &str, completed: bool) -> Todo {
+        testing::todo(id)
+            .title(&format!("Todo {id}"))
+            .completed(completed)
+            .created(1640995200)
+            .build()
+    }
+
+    #[test]
+    fn test_add_and_remove_block() {
+        let mut state = LocalState::default();
+        assert!(state.add_block("a", "b"));
+        assert!(!state.add_block("a", "b"));
+        assert_eq!(state.blockers_of("b"), vec!["a"]);
+
+        assert!(state.remove_block("a", "b"));
+        assert!(!state.remove_block("a", "b"));
+        assert!(state.blockers_of("b").is_empty());
+        assert!(state.todos.is_empty());
+    }
+
+    #[test]
+    fn test_is_blocked() {
+        let mut state = LocalState::default();
+        state.add_block("a", "b");
+
+        assert!(state.is_blocked("b", &[todo("a", false), todo("b", false)]));
+        assert!(!state.is_blocked("b", &[todo("a", true), todo("b", false)]));
+        assert!(!state.is_blocked("b", &[todo("b", false)]));
+        assert!(!state.is_blocked("a", &[todo("a", false), todo("b", false)]));
+    }
+
+    #[test]
+    fn test_would_create_cycle() {
+        let mut state = LocalState::default();
+        state.add_block("a", "b");
+        state.add_block("b", "c");
+
+        assert!(state.would_create_cycle("c", "a"));
+        assert!(state.would_create_cycle("a", "a"));
+        assert!(!state.would_create_cycle("a", "c"));
+        assert!(!state.would_create_cycle("d", "a"));
+    }
+
+    #[test]
+    fn test_timer_start_stop() {
+        let mut state = LocalState::default();
+        assert!(state.stop_timer(100).is_none());
+
+        assert!(state.start_timer("a", 100).is_none());
+        assert!(state.is_timer_running("a"));
+        assert_eq!(state.tracked_seconds("a", 160), 60);
+
+        // Starting another todo stops the running timer
+        let (stopped_id, entry) = state.start_timer("b", 400).unwrap();
+        assert_eq!(stopped_id, "a");
+        assert_eq!(entry.seconds(), 300);
+        assert!(state.is_timer_running("b"));
+
+        let (stopped_id, _) = state.stop_timer(500).unwrap();
+        assert_eq!(stopped_id, "b");
+        assert!(state.active_timer.is_none());
+        assert_eq!(state.tracked_seconds("a", 1000), 300);
+        assert_eq!(state.tracked_seconds("b", 1000), 100);
+
+        let entries = state.entries_between(0, 450);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0, "a");
+        assert_eq!(state.entries_between(450, 1000).len(), 0);
+    }
+
+    #[test]
+    fn test_archive() {
+        let mut state = LocalState::default();
+        assert!(state.archive("a", 100));
+        assert!(!state.archive("a", 200));
+        assert!(state.is_archived("a"));
+        assert_eq!(state.todos["a"].archived_at, Some(100));
+
+        assert!(state.unarchive("a"));
+        assert!(!state.is_archived("a"));
+        assert!(!state.todos.contains_key("a"));
+        assert!(!state.unarchive("a"));
+    }
+
+    #[test]
+    fn test_pin() {
+        let mut state = LocalState::default();
+        assert!(state.pin("b", 100));
+        assert!(!state.pin("b", 200));
+        assert!(state.is_pinned("b"));
+
+        let mut todos = vec![todo("a", false), todo("b", false), todo("c", true)];
+        state.pinned_first(&mut todos);
+        let ids: Vec<&str> = todos.iter().map(|todo| todo.id.as_str()).collect();
+        assert_eq!(ids, ["b", "a", "c"]);
+
+        assert!(state.unpin("b"));
+        assert!(!state.todos.contains_key("b"));
+        assert!(!state.unpin("b"));
+    }
+
+    #[test]
+    fn test_dismiss_reminder() {
+        let mut state = LocalState::default();
+        assert!(!state.is_reminder_dismissed("a", 100));
+
+        state.dismiss_reminder("a", 100);
+        assert!(state.is_reminder_dismissed("a", 100));
+        // Rescheduled
+        assert!(!state.is_reminder_dismissed("a", 200));
+    }
+
+    #[test]
+    fn test_record_pomodoro() {
+        let mut state = LocalState::default();
+        assert_eq!(state.pomodoro_count("a"), 0);
+
+        state.record_pomodoro("a", 100);
+        state.record_pomodoro("a", 2000);
+        assert_eq!(state.pomodoro_count("a"), 2);
+        assert_eq!(state.pomodoro_count("b"), 0);
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(parse_tags("work, #urgent,,work "), vec!["urgent", "work"]);
+        assert!(parse_tags(" , ").is_empty());
+    }
+
+    #[test]
+    fn test_tags() {
+        let mut state = LocalState::default();
+        state.set_tags("a", parse_tags("work,home"));
+        state.set_tags("b", parse_tags("work"));
+
+        assert!(state.has_tag("a", "HOME"));
+        assert!(!state.has_tag("b", "home"));
+        assert_eq!(state.all_tags(), vec!["home", "work"]);
+
+        let counts = state.tag_counts(["a", "b"]);
+        assert_eq!(counts.get("work"), Some(&2));
+        assert_eq!(counts.get("home"), Some(&1));
+
+        assert_eq!(state.rename_tag("work", "home"), 2);
+        assert_eq!(state.tags_of("a"), ["home"]);
+        assert_eq!(state.tags_of("b"), ["home"]);
+
+        // Whatever case the tag was given in
+        state.set_tags("c", vec!["Errands".to_string(), "urgent".to_string()]);
+        assert_eq!(state.rename_tag("errANDS", "shopping"), 1);
+        assert_eq!(state.tags_of("c"), ["shopping", "urgent"]);
+        assert_eq!(state.rename_tag("Shopping", "Shopping"), 1);
+        assert_eq!(state.tags_of("c"), ["Shopping", "urgent"]);
+
+        state.set_tags("b", Vec::new());
+        assert!(!state.todos.contains_key("b"));
+    }
+
+    #[test]
+    fn test_aliases() {
+        assert_eq!(validate_alias(" grocery ").unwrap(), "grocery");
+        assert!(validate_alias("").is_err());
+        assert!(validate_alias("two words").is_err());
+        assert!(validate_alias("cafe").is_err());
+        assert!(validate_alias("d2fa-1234").is_err());
+
+        let mut state = LocalState::default();
+        state.set_tags("a", parse_tags("home"));
+        assert_eq!(state.set_alias("a", "grocery"), None);
+        assert_eq!(state.resolve_alias("Grocery"), Some("a"));
+        assert_eq!(state.alias_of("a"), Some("grocery"));
+
+        // Moving an alias takes it from the todo that had it
+        assert_eq!(state.set_alias("b", "grocery"), Some("a".to_string()));
+        assert_eq!(state.alias_of("a"), None);
+        assert_eq!(state.tags_of("a"), ["home"]);
+        assert_eq!(state.aliases(), vec![("grocery", "b")]);
+
+        assert_eq!(state.remove_alias("grocery"), Some("b".to_string()));
+        assert_eq!(state.remove_alias("grocery"), None);
+        assert!(!state.todos.contains_key("b"));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(40), "40s");
+        assert_eq!(format_duration(12 * 60 + 5), "12m");
+        assert_eq!(format_duration(3600 + 5 * 60), "1h 05m");
+        assert_eq!(format_duration(-5), "0s");
+    }
+
+    #[test]
+    fn test_state_serialization_skips_empty_fields() {
+        let mut state = LocalState::default();
+        state.add_block("a", "b");
+
+        let json = serde_json::to_string(&state).unwrap();
+        let deserialized: LocalState = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.todos, state.todos);
+
+        let empty: LocalState = serde_json::from_str("{}").unwrap();
+        assert!(empty.todos.is_empty());
+    }
+}
@@ -0,0 +1,425 @@
+//! What the terminal can display
+//!
+//! Colors, Unicode glyphs and the width are detected once from the
+//! environment (`NO_COLOR`, `CLICOLOR_FORCE`, `TERM`, `COLORTERM`, the
+//! locale, ...) and whether standard output is a terminal. `pacli` turns
+//! colors off through [`colored`] and `patui` strips or simplifies colors
+//! in each frame, so output degrades by itself on dumb terminals, in CI
+//! logs, when piped and on legacy Windows consoles.
+//!
+//! On Windows, escape codes are only used once the console accepted virtual
+//! terminal processing, and Unicode symbols only in terminals known to show
+//! them (Windows Terminal, VS Code, ConEmu, mintty); the classic console
+//! gets the ASCII [`Symbols`].
+
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+/// How many colors the terminal shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    None,
+    /// The 16 ANSI colors
+    Basic,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorSupport {
+    #[must_use]
+    pub fn is_enabled(self) -> bool {
+        self != Self::None
+    }
+}
+
+/// Detected terminal capabilities, see [`capabilities`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    pub color: ColorSupport,
+    /// Whether symbols such as ✓ and box-drawing lines display correctly
+    pub unicode: bool,
+    /// Columns, if known
+    pub width: Option<usize>,
+}
+
+static DETECTED: OnceLock<Capabilities> = OnceLock::new();
+
+/// Symbols printed in front of messages and todos
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbols {
+    pub ok: &'static str,
+    pub warning: &'static str,
+    pub info: &'static str,
+    pub error: &'static str,
+    /// A completed todo or item
+    pub done: &'static str,
+    /// An open todo or item
+    pub pending: &'static str,
+    pub blocked: &'static str,
+    /// A todo pinned to the top of lists
+    pub pinned: &'static str,
+    pub arrow: &'static str,
+}
+
+impl Symbols {
+    pub const UNICODE: Self = Self {
+        ok: "✓",
+        warning: "⚠",
+        info: "ℹ",
+        error: "✗",
+        done: "✓",
+        pending: "○",
+        blocked: "⛔",
+        pinned: "📌",
+        arrow: "→",
+    };
+
+    pub const ASCII: Self = Self {
+        ok: "v",
+        warning: "!",
+        info: "i",
+        error: "x",
+        done: "x",
+        pending: "o",
+        blocked: "#",
+        pinned: "*",
+        arrow: "->",
+    };
+}
+
+/// What the console on standard output is, besides its environment variables
+#[derive(Debug, Clone, Copy)]
+struct Console {
+    is_terminal: bool,
+    windows: bool,
+    /// Whether escape codes are interpreted; only ever false on Windows
+    escape_codes: bool,
+}
+
+/// Returns the capabilities of the terminal on standard output, detected on first use
+#[must_use]
+pub fn capabilities() -> &'static Capabilities {
+    DETECTED.get_or_init(|| {
+        let is_terminal = std::io::stdout().is_terminal();
+        let console = Console {
+            is_terminal,
+            windows: cfg!(windows),
+            escape_codes: !is_terminal || escape_codes(),
+        };
+        let mut detected = detect(|name| std::env::var(name).ok(), console);
+        if detected.width.is_none() && is_terminal {
+            detected.width = tty_width();
+        }
+        detected
+    })
+}
+
+/// Returns the [`Symbols`] the terminal can show
+#[must_use]
+pub fn symbols() -> &'static Symbols {
+    if capabilities().unicode {
+        &Symbols::UNICODE
+    } else {
+        &Symbols::ASCII
+    }
+}
+
+/// Returns the terminal width, or `default` when it is unknown (e.g. when piped)
+#[must_use]
+pub fn width_or(default: usize) -> usize {
+    capabilities().width.unwrap_or(default)
+}
+
+/// Returns the escape codes that ring the bell and request a desktop
+/// notification showing `message`
+///
+/// Uses the OSC 9 escape sequence, which terminals without notification
+/// support silently ignore. Control characters are left out of `message`, so
+/// a todo title can't end the sequence early and send codes of its own.
+#[must_use]
+pub fn notification(message: &str) -> String {
+    let message: String = message.chars().filter(|c| !c.is_control()).collect();
+    format!("\x07\x1b]9;{message}\x07")
+}
+
+/// Picks the Unicode glyph if the terminal can show it, the ASCII one otherwise
+#[must_use]
+pub fn glyph(unicode: &'static str, ascii: &'static str) -> &'static str {
+    if capabilities().unicode {
+        unicode
+    } else {
+        ascii
+    }
+}
+
+/// Detects capabilities from environment variables (looked up with `var`)
+fn detect(var: impl Fn(&str) -> Option<String>, console: Console) -> Capabilities {
+    let set = |name: &str| var(name).is_some_and(|value| !value.is_empty());
+    let term = var("TERM").unwrap_or_default();
+    let dumb = term == "dumb";
+    // Windows Terminal, VS Code, ConEmu and mintty render Unicode symbols;
+    // the classic console host usually lacks the glyphs in its fonts
+    let legacy_console = console.windows
+        && !set("WT_SESSION")
+        && !set("TERM_PROGRAM")
+        && var("ConEmuANSI").as_deref() != Some("ON")
+        && term.is_empty();
+
+    let color = if set("NO_COLOR") {
+        ColorSupport::None
+    } else if var("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+        ColorSupport::Basic.max(palette(&var))
+    } else if !console.is_terminal || dumb || !console.escape_codes {
+        ColorSupport::None
+    } else if set("CI") || legacy_console {
+        // CI logs and the classic Windows console show basic colors at best
+        ColorSupport::Basic
+    } else {
+        ColorSupport::Basic.max(palette(&var))
+    };
+
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .find_map(|name| var(name).filter(|value| !value.is_empty()));
+    let unicode = !dumb
+        && !legacy_console
+        && locale.is_none_or(|locale| {
+            let locale = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        });
+
+    let width = var("COLUMNS")
+        .and_then(|columns| columns.parse().ok())
+        .filter(|&columns| columns > 0);
+
+    Capabilities {
+        color,
+        unicode,
+        width,
+    }
+}
+
+/// Reads the palette size from `COLORTERM` and `TERM`
+fn palette(var: &impl Fn(&str) -> Option<String>) -> ColorSupport {
+    let colorterm = var("COLORTERM").unwrap_or_default();
+    if colorterm == "truecolor" || colorterm == "24bit" || var("WT_SESSION").is_some() {
+        ColorSupport::TrueColor
+    } else if var("TERM").is_some_and(|term| term.contains("256color")) {
+        ColorSupport::Ansi256
+    } else {
+        ColorSupport::Basic
+    }
+}
+
+/// Enables escape codes in the Windows console, returning whether it accepted them
+#[cfg(all(windows, feature = "tui"))]
+fn escape_codes() -> bool {
+    crossterm::ansi_support::supports_ansi()
+}
+
+#[cfg(all(windows, not(feature = "tui")))]
+fn escape_codes() -> bool {
+    colored::control::set_virtual_terminal(true).is_ok()
+}
+
+#[cfg(not(windows))]
+fn escape_codes() -> bool {
+    true
+}
+
+#[cfg(unix)]
+fn tty_width() -> Option<usize> {
+    // SAFETY: TIOCGWINSZ only writes a `winsize` into the struct we pass
+    let size = unsafe {
+        let mut size: libc::winsize = std::mem::zeroed();
+        (libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) == 0).then_some(size)
+    }?;
+    (size.ws_col > 0).then_some(usize::from(size.ws_col))
+}
+
+#[cfg(all(windows, feature = "tui"))]
+fn tty_width() -> Option<usize> {
+    let (columns, _) = crossterm::terminal::size().ok()?;
+    (columns > 0).then_some(usize::from(columns))
+}
+
+#[cfg(not(any(unix, all(windows, feature = "tui"))))]
+fn tty_width() -> Option<usize> {
+    None
+}
+
+/// Adapts a rendered `patui` frame to the terminal's colors
+///
+/// Without colors, foreground and background colors are dropped and cells
+/// that had a background (such as the selection) are shown reversed. With
+/// only the basic palette, RGB and indexed colors become the nearest ANSI
+/// color.
+#[cfg(feature = "tui")]
+pub fn adapt_buffer(buffer: &mut ratatui::buffer::Buffer) {
+    use ratatui::style::{Color, Modifier};
+
+    let color = capabilities().color;
+    if color >= ColorSupport::Ansi256 {
+        return;
+    }
+    for cell in &mut buffer.content {
+        if color.is_enabled() {
+            cell.fg = basic_color(cell.fg);
+            cell.bg = basic_color(cell.bg);
+        } else {
+            if cell.bg != Color::Reset {
+                cell.modifier.insert(Modifier::REVERSED);
+            }
+            cell.fg = Color::Reset;
+            cell.bg = Color::Reset;
+        }
+    }
+}
+
+/// Maps an RGB or indexed color to the nearest of the 16 ANSI colors
+#[cfg(feature = "tui")]
+fn basic_color(color: ratatui::style::Color) -> ratatui::style::Color {
+    use ratatui::style::Color;
+
+    const ANSI: [(Color, (u8, u8, u8)); 16] = [
+        (Color::Black, (0, 0, 0)),
+        (Color::Red, (205, 0, 0)),
+        (Color::Green, (0, 205, 0)),
+        (Color::Yellow, (205, 205, 0)),
+        (Color::Blue, (0, 0, 238)),
+        (Color::Magenta, (205, 0, 205)),
+        (Color::Cyan, (0, 205, 205)),
+        (Color::Gray, (229, 229, 229)),
+        (Color::DarkGray, (127, 127, 127)),
+        (Color::LightRed, (255, 0, 0)),
+        (Color::LightGreen, (0, 255, 0)),
+        (Color::LightYellow, (255, 255, 0)),
+        (Color::LightBlue, (92, 92, 255)),
+        (Color::LightMagenta, (255, 0, 255)),
+        (Color::LightCyan, (0, 255, 255)),
+        (Color::White, (255, 255, 255)),
+    ];
+
+    let (r, g, b) = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(index) if index < 16 => return ANSI[usize::from(index)].0,
+        Color::Indexed(index) if index >= 232 => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+        Color::Indexed(index) => {
+            // 6x6x6 color cube
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+            (level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        other => return other,
+    };
+    let distance = |(cr, cg, cb): (u8, u8, u8)| {
+        [(r, cr), (g, cg), (b, cb)]
+            .into_iter()
+            .map(|(a, b)| u32::from(a.abs_diff(b)).pow(2))
+            .sum::<u32>()
+    };
+    ANSI.into_iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map_or(color, |(ansi, _)| ansi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect_on(vars: &[(&str, &str)], console: Console) -> Capabilities {
+        detect(
+            |name| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| (*value).to_string())
+            },
+            console,
+        )
+    }
+
+    fn detect_with(vars: &[(&str, &str)], is_terminal: bool) -> Capabilities {
+        let console = Console {
+            is_terminal,
+            windows: false,
+            escape_codes: true,
+        };
+        detect_on(vars, console)
+    }
+
+    #[test]
+    fn test_detect() {
+        let modern = [
+            ("TERM", "xterm-256color"),
+            ("COLORTERM", "truecolor"),
+            ("LANG", "en_US.UTF-8"),
+            ("COLUMNS", "120"),
+        ];
+        let caps = detect_with(&modern, true);
+        assert_eq!(caps.color, ColorSupport::TrueColor);
+        assert!(caps.unicode);
+        assert_eq!(caps.width, Some(120));
+
+        // Piped, unless forced
+        assert_eq!(detect_with(&modern, false).color, ColorSupport::None);
+        let forced = [("TERM", "xterm-256color"), ("CLICOLOR_FORCE", "1")];
+        assert_eq!(detect_with(&forced, false).color, ColorSupport::Ansi256);
+
+        let no_color = [("TERM", "xterm-256color"), ("NO_COLOR", "1")];
+        assert_eq!(detect_with(&no_color, true).color, ColorSupport::None);
+
+        let dumb = detect_with(&[("TERM", "dumb")], true);
+        assert_eq!(dumb.color, ColorSupport::None);
+        assert!(!dumb.unicode);
+
+        let ci = [("TERM", "xterm-256color"), ("CI", "true"), ("LANG", "C")];
+        let caps = detect_with(&ci, true);
+        assert_eq!(caps.color, ColorSupport::Basic);
+        assert!(!caps.unicode);
+        assert_eq!(caps.width, None);
+
+        let classic = Console {
+            is_terminal: true,
+            windows: true,
+            escape_codes: true,
+        };
+        let caps = detect_on(&[], classic);
+        assert_eq!(caps.color, ColorSupport::Basic);
+        assert!(!caps.unicode);
+        let caps = detect_on(&[("WT_SESSION", "1")], classic);
+        assert_eq!(caps.color, ColorSupport::TrueColor);
+        assert!(caps.unicode);
+        let no_escapes = Console {
+            escape_codes: false,
+            ..classic
+        };
+        assert_eq!(detect_on(&[], no_escapes).color, ColorSupport::None);
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn test_basic_color() {
+        use ratatui::style::Color;
+
+        assert_eq!(basic_color(Color::Rgb(250, 10, 10)), Color::LightRed);
+        assert_eq!(basic_color(Color::Rgb(20, 20, 20)), Color::Black);
+        assert_eq!(basic_color(Color::Indexed(9)), Color::LightRed);
+        assert_eq!(basic_color(Color::Indexed(46)), Color::LightGreen);
+        assert_eq!(basic_color(Color::Yellow), Color::Yellow);
+    }
+
+    #[test]
+    fn test_notification_strips_control_characters() {
+        assert_eq!(
+            notification("Pomodoro complete: Write"),
+            "\x07\x1b]9;Pomodoro complete: Write\x07"
+        );
+        assert_eq!(
+            notification("Pomodoro complete: \x1b]52;c;ZXZpbA==\x07Write\n"),
+            "\x07\x1b]9;Pomodoro complete: ]52;c;ZXZpbA==Write\x07"
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! Fixtures shared by the unit tests
+
+use pali_types::Todo;
+
+/// Starts a todo with the given ID: pending, medium priority, titled after
+/// the ID, without description or due date, and with all times at 0
+pub fn todo(id: &str) -> TodoBuilder {
+    TodoBuilder(Todo {
+        id: id.to_string(),
+        title: id.to_string(),
+        description: None,
+        completed: false,
+        priority: 2,
+        due_date: None,
+        created_at: 0,
+        updated_at: 0,
+    })
+}
+
+/// A test todo being built; see [`todo`]
+pub struct TodoBuilder(Todo);
+
+impl TodoBuilder {
+    pub fn title(mut self, title: &str) -> Self {
+        self.0.title = title.to_string();
+        self
+    }
+
+    pub fn description(mut self, description: Option<&str>) -> Self {
+        self.0.description = description.map(str::to_string);
+        self
+    }
+
+    pub fn completed(mut self, completed: bool) -> Self {
+        self.0.completed = completed;
+        self
+    }
+
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.0.priority = priority;
+        self
+    }
+
+    pub fn due(mut self, due_date: Option<i64>) -> Self {
+        self.0.due_date = due_date;
+        self
+    }
+
+    /// Sets when the todo was created, and last updated then too
+    pub fn created(mut self, created_at: i64) -> Self {
+        self.0.created_at = created_at;
+        self.0.updated_at = created_at;
+        self
+    }
+
+    pub fn updated(mut self, updated_at: i64) -> Self {
+        self.0.updated_at = updated_at;
+        self
+    }
+
+    pub fn build(self) -> Todo {
+        self.0
+    }
+}
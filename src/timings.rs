@@ -0,0 +1,75 @@
+//! Timing summary for `pacli --timings`
+//!
+//! Config loads and HTTP calls record how long they took; [`Report`] prints
+//! them when the command finishes, attributing the remaining time to
+//! rendering and local work.
+
+use colored::Colorize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ENTRIES: Mutex<Vec<(String, Duration)>> = Mutex::new(Vec::new());
+
+/// Records a measurement if `--timings` is on
+pub fn record(label: impl Into<String>, elapsed: Duration) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Ok(mut entries) = ENTRIES.lock() {
+        entries.push((label.into(), elapsed));
+    }
+}
+
+/// Prints the collected timings when dropped, so they also appear when the command fails
+pub struct Report {
+    start: Instant,
+}
+
+impl Report {
+    /// Starts collecting timings for the rest of the process
+    #[must_use]
+    pub fn start() -> Self {
+        ENABLED.store(true, Ordering::Relaxed);
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+fn format_duration(elapsed: Duration) -> String {
+    format!("{:.1}ms", elapsed.as_secs_f64() * 1000.0)
+}
+
+impl Drop for Report {
+    fn drop(&mut self) {
+        let total = self.start.elapsed();
+        let entries = ENTRIES
+            .lock()
+            .map(|entries| entries.clone())
+            .unwrap_or_default();
+        let measured: Duration = entries.iter().map(|(_, elapsed)| *elapsed).sum();
+        let width = entries
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("rendering and other".len());
+
+        eprintln!();
+        eprintln!("{}", "Timings:".bold());
+        for (label, elapsed) in &entries {
+            eprintln!("  {label:<width$}  {:>10}", format_duration(*elapsed));
+        }
+        eprintln!(
+            "  {:<width$}  {:>10}",
+            "rendering and other",
+            format_duration(total.saturating_sub(measured))
+        );
+        eprintln!(
+            "  {}",
+            format!("{:<width$}  {:>10}", "total", format_duration(total)).bold()
+        );
+    }
+}
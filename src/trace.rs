@@ -0,0 +1,171 @@
+//! Request tracing for `pacli --trace`
+//!
+//! Every request carries an `X-Request-Id` so it can be matched with server
+//! logs. With tracing enabled, request and response headers are printed to
+//! stderr together with a timing breakdown, with credentials masked by
+//! [`crate::redact`].
+//!
+//! `--debug-body` separately dumps response bodies that could not be
+//! decoded, which is usually a sign of client and server version skew.
+
+use crate::redact;
+use crate::term;
+use colored::Colorize;
+use reqwest::{header::HeaderMap, Request, Response};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Header used to correlate a request with server logs
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static DUMP_BODIES: AtomicBool = AtomicBool::new(false);
+static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// Turns on `--trace` output for the rest of the process
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+#[must_use]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Turns on `--debug-body` for the rest of the process
+pub fn enable_body_dumps() {
+    DUMP_BODIES.store(true, Ordering::Relaxed);
+}
+
+#[must_use]
+pub fn dumps_bodies() -> bool {
+    DUMP_BODIES.load(Ordering::Relaxed)
+}
+
+/// Prints a response body that could not be decoded, with credentials masked
+pub fn print_body(url: &str, body: &str) {
+    // Pretty-printed so the failing field is easy to find
+    let body = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        .unwrap_or_else(|| body.to_string());
+    eprintln!(
+        "{} {} {}",
+        "<".dimmed(),
+        "Undecodable response from".bold(),
+        redact::redact(url)
+    );
+    for line in redact::redact(&body).lines() {
+        eprintln!("{} {line}", "<".dimmed());
+    }
+}
+
+/// Returns a new ID that is unique within the process and very likely across processes
+#[must_use]
+pub fn new_request_id() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!(
+        "{:x}-{:08x}-{count:04x}",
+        now.as_secs(),
+        now.subsec_nanos() ^ std::process::id().rotate_left(16)
+    )
+}
+
+/// Timings measured before a traced request is sent
+///
+/// reqwest doesn't report connection phases, so DNS and TCP connect are
+/// measured with a separate lookup and connection to the same host. The TLS
+/// handshake is part of the time to first byte.
+#[derive(Debug, Default)]
+pub struct Probe {
+    dns: Option<Duration>,
+    connect: Option<Duration>,
+}
+
+impl Probe {
+    /// Resolves and connects to the request's host, skipping what fails
+    pub async fn run(request: &Request) -> Self {
+        let url = request.url();
+        let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+            return Self::default();
+        };
+
+        let start = Instant::now();
+        let Ok(addrs) = tokio::net::lookup_host((host, port)).await else {
+            return Self::default();
+        };
+        let dns = Some(start.elapsed());
+
+        let mut connect = None;
+        if let Some(addr) = addrs.into_iter().next() {
+            let start = Instant::now();
+            let attempt =
+                tokio::time::timeout(Duration::from_secs(5), tokio::net::TcpStream::connect(addr))
+                    .await;
+            if matches!(attempt, Ok(Ok(_))) {
+                connect = Some(start.elapsed());
+            }
+        }
+
+        Self { dns, connect }
+    }
+}
+
+fn print_headers(prefix: &str, headers: &HeaderMap) {
+    for (name, value) in headers {
+        let value = if redact::is_secret_name(name.as_str()) {
+            redact::REDACTED.to_string()
+        } else {
+            redact::redact(&String::from_utf8_lossy(value.as_bytes())).into_owned()
+        };
+        eprintln!("{} {}: {}", prefix.dimmed(), name.as_str().cyan(), value);
+    }
+}
+
+/// Prints the request line and headers
+pub fn print_request(request: &Request, request_id: &str) {
+    eprintln!(
+        "{} {} {} {}",
+        ">".dimmed(),
+        request.method().as_str().bold(),
+        redact::redact(request.url().as_str()),
+        format!("[{request_id}]").dimmed()
+    );
+    print_headers(">", request.headers());
+}
+
+/// Prints the response status, headers and timing breakdown
+pub fn print_response(response: &Response, probe: &Probe, ttfb: Duration) {
+    eprintln!(
+        "{} {} {:?}",
+        "<".dimmed(),
+        response.status().to_string().bold(),
+        response.version()
+    );
+    print_headers("<", response.headers());
+
+    let format = |d: Option<Duration>| d.map_or_else(|| "-".to_string(), |d| format!("{d:.1?}"));
+    eprintln!(
+        "{} dns {}  connect {}  ttfb {} (includes TLS and server time)",
+        term::glyph("⏱", "~").dimmed(),
+        format(probe.dns),
+        format(probe.connect),
+        format(Some(ttfb))
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_ids_are_unique() {
+        let first = new_request_id();
+        let second = new_request_id();
+        assert_ne!(first, second);
+        assert!(first.is_ascii());
+    }
+}
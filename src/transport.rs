@@ -0,0 +1,361 @@
+//! How an [`ApiClient`](crate::api::ApiClient) sends its requests, and
+//! recording and replaying sessions (`--record`, `--replay`)
+//!
+//! By default requests go straight to the server. A [`Transport`] given to
+//! [`ApiClient::with_transport`](crate::api::ApiClient::with_transport)
+//! takes over instead: a [`Recorder`] sends them and keeps each exchange
+//! (with credentials masked by [`crate::redact`]) for a session file that
+//! can be attached to a bug report; a [`Replay`] answers them from such a
+//! file without any server, so the session can be reproduced, also in tests.
+
+use crate::config::Config;
+use crate::redact;
+use anyhow::{Context, Result};
+use futures_util::future::BoxFuture;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, Request, Response, ResponseBuilderExt, Url};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Format version of session files
+pub const SESSION_VERSION: u32 = 1;
+
+/// Sends a request and returns its response in place of the HTTP client
+pub trait Transport: Send + Sync {
+    /// Sends `request`; `client` is the client's configured HTTP client
+    fn execute<'a>(
+        &'a self,
+        client: &'a Client,
+        request: Request,
+    ) -> BoxFuture<'a, Result<Response>>;
+}
+
+/// One request and the response it got
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Exchange {
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<String>,
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    pub body: String,
+}
+
+/// The contents of a session file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    pub version: u32,
+    /// Client version that recorded the session
+    pub client: String,
+    pub exchanges: Vec<Exchange>,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            version: SESSION_VERSION,
+            client: concat!("pali-terminal ", env!("CARGO_PKG_VERSION")).to_string(),
+            exchanges: Vec::new(),
+        }
+    }
+}
+
+impl Session {
+    /// Reads a session file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, isn't a session or was
+    /// written by a newer client
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read session {}", path.display()))?;
+        let session: Self = serde_json::from_str(&content)
+            .with_context(|| format!("{} is not a recorded session", path.display()))?;
+        if session.version > SESSION_VERSION {
+            anyhow::bail!(
+                "{} was recorded by a newer client ({}); upgrade to replay it",
+                path.display(),
+                session.client
+            );
+        }
+        Ok(session)
+    }
+
+    /// Writes the session file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write session {}", path.display()))
+    }
+}
+
+/// Sends requests to the server and keeps every exchange, redacted
+#[derive(Debug, Default)]
+pub struct Recorder {
+    session: Mutex<Session>,
+}
+
+impl Recorder {
+    /// The exchanges recorded so far
+    #[must_use]
+    pub fn session(&self) -> Session {
+        self.session
+            .lock()
+            .map(|session| session.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Transport for Recorder {
+    fn execute<'a>(
+        &'a self,
+        client: &'a Client,
+        request: Request,
+    ) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            let method = request.method().to_string();
+            let url = request.url().clone();
+            let request_body = request
+                .body()
+                .and_then(reqwest::Body::as_bytes)
+                .map(|body| redact::redact(&String::from_utf8_lossy(body)).into_owned());
+
+            let response = client.execute(request).await?;
+            let status = response.status().as_u16();
+            let content_type = response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            // Read in full, so streamed responses are recorded too
+            let body = response
+                .bytes()
+                .await
+                .context("Unable to read server response")?;
+
+            if let Ok(mut session) = self.session.lock() {
+                session.exchanges.push(Exchange {
+                    method,
+                    path: url.path().to_string(),
+                    query: url.query().map(|query| redact::redact(query).into_owned()),
+                    request_body,
+                    status,
+                    content_type: content_type.clone(),
+                    body: redact::redact(&String::from_utf8_lossy(&body)).into_owned(),
+                });
+            }
+            build_response(url, status, content_type.as_deref(), body)
+        })
+    }
+}
+
+/// Answers requests from a recorded [`Session`] instead of a server
+///
+/// A request gets the first exchange with the same method and path that
+/// wasn't replayed yet; the query is ignored since it often holds times.
+/// Once they are used up, the last one is repeated, so refreshes keep
+/// working. Requests the session doesn't know get a 404.
+#[derive(Debug)]
+pub struct Replay {
+    exchanges: Vec<Exchange>,
+    replayed: Mutex<Vec<bool>>,
+}
+
+impl Replay {
+    #[must_use]
+    pub fn new(session: Session) -> Self {
+        let replayed = Mutex::new(vec![false; session.exchanges.len()]);
+        Self {
+            exchanges: session.exchanges,
+            replayed,
+        }
+    }
+
+    /// Picks the exchange answering `method` `path`
+    fn next(&self, method: &str, path: &str) -> Option<&Exchange> {
+        let mut replayed = self
+            .replayed
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let matching: Vec<usize> = (0..self.exchanges.len())
+            .filter(|&i| self.exchanges[i].method == method && self.exchanges[i].path == path)
+            .collect();
+        let index = matching
+            .iter()
+            .copied()
+            .find(|&i| !replayed[i])
+            .or_else(|| matching.last().copied())?;
+        replayed[index] = true;
+        Some(&self.exchanges[index])
+    }
+}
+
+impl Transport for Replay {
+    fn execute<'a>(
+        &'a self,
+        _client: &'a Client,
+        request: Request,
+    ) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            let url = request.url().clone();
+            let method = request.method().as_str();
+            match self.next(method, url.path()) {
+                Some(exchange) => build_response(
+                    url,
+                    exchange.status,
+                    exchange.content_type.as_deref(),
+                    exchange.body.clone(),
+                ),
+                None => {
+                    let message = format!("{method} {} is not in the replayed session", url.path());
+                    build_response(url, 404, Some("text/plain"), message)
+                }
+            }
+        })
+    }
+}
+
+fn build_response(
+    url: Url,
+    status: u16,
+    content_type: Option<&str>,
+    body: impl Into<reqwest::Body>,
+) -> Result<Response> {
+    let mut builder = http::Response::builder().status(status).url(url);
+    if let Some(content_type) = content_type {
+        builder = builder.header(CONTENT_TYPE, content_type);
+    }
+    Ok(Response::from(builder.body(body)?))
+}
+
+/// The session of `--record` or `--replay`, used by every client of the process
+enum Global {
+    Record(Arc<Recorder>, PathBuf),
+    Replay(Arc<Replay>),
+}
+
+static GLOBAL: OnceLock<Global> = OnceLock::new();
+
+/// Records every request of the process, to be written to `path` by [`finish`]
+pub fn record(path: PathBuf) {
+    if GLOBAL.set(Global::Record(Arc::default(), path)).is_err() {
+        log::warn!("A session is already recorded or replayed, ignoring --record");
+    }
+}
+
+/// Answers every request of the process from the session at `path`
+///
+/// The recorded todos are someone else's, so the process also switches to a
+/// throwaway data directory: caching them for the configured endpoint would
+/// show them in later runs, and a fresh real cache could answer in place of
+/// the session.
+///
+/// # Errors
+///
+/// Returns an error if the session cannot be loaded or the throwaway
+/// directory cannot be created
+pub fn replay(path: &Path) -> Result<Replaying> {
+    let replay = Replay::new(Session::load(path)?);
+    if GLOBAL.set(Global::Replay(Arc::new(replay))).is_err() {
+        log::warn!("A session is already recorded or replayed, ignoring --replay");
+    }
+
+    let dir = std::env::temp_dir().join(format!("pali-replay-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    Config::use_data_dir(dir.clone());
+    Ok(Replaying { dir })
+}
+
+/// Keeps a replay apart from the real data; dropping it removes the throwaway directory
+#[derive(Debug)]
+#[must_use]
+pub struct Replaying {
+    dir: PathBuf,
+}
+
+impl Drop for Replaying {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// The transport chosen with [`record`] or [`replay`], if any
+#[must_use]
+pub fn global() -> Option<Arc<dyn Transport>> {
+    match GLOBAL.get()? {
+        Global::Record(recorder, _) => Some(Arc::clone(recorder) as Arc<dyn Transport>),
+        Global::Replay(replay) => Some(Arc::clone(replay) as Arc<dyn Transport>),
+    }
+}
+
+/// Writes the recorded session, if recording; returns where it went
+///
+/// # Errors
+///
+/// Returns an error if the session file cannot be written
+pub fn finish() -> Result<Option<PathBuf>> {
+    match GLOBAL.get() {
+        Some(Global::Record(recorder, path)) => {
+            recorder.session().save(path)?;
+            Ok(Some(path.clone()))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(method: &str, path: &str, body: &str) -> Exchange {
+        Exchange {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: None,
+            request_body: None,
+            status: 200,
+            content_type: Some("application/json".to_string()),
+            body: body.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay() {
+        let session = Session {
+            exchanges: vec![
+                exchange("GET", "/todos/search", r#"{"success":true,"data":[]}"#),
+                exchange(
+                    "GET",
+                    "/todos/search",
+                    r#"{"success":true,"data":[{"id":"a1","title":"Replayed","description":null,"completed":false,"priority":2,"due_date":null,"created_at":0,"updated_at":0}]}"#,
+                ),
+            ],
+            ..Session::default()
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        session.save(&path).unwrap();
+
+        let client = crate::ApiClient::with_config(crate::Config::default())
+            .unwrap()
+            .with_transport(Replay::new(Session::load(&path).unwrap()));
+        assert!(client.search_todos("x").await.unwrap().is_empty());
+        // The second exchange, then the last one again
+        for _ in 0..2 {
+            let todos = client.search_todos("x").await.unwrap();
+            assert_eq!(todos[0].title, "Replayed");
+        }
+        let err = client.get_todo("a1").await.unwrap_err();
+        assert!(err.to_string().contains("not in the replayed session"));
+    }
+}
@@ -1,16 +1,76 @@
 //! TUI application state and logic
 
+use crate::activity::{self, ActivityEntry, Source};
+use crate::cache::{self, TodoCache};
+use crate::due::{self, local_date, DueBucket};
+use crate::export::{Exporter, Format};
+use crate::highlight::{self, ColorRule};
+use crate::search::Query;
+use crate::sort::SortSpec;
+use crate::state::LocalState;
 use crate::tui::components::InputForm;
-use crate::{ApiClient, Config};
-use anyhow::Result;
+use crate::tui::conflict::EditConflict;
+use crate::tui::filter::{Facet, FilterPopup, Filters, Status};
+use crate::tui::input::{Input, Key};
+use crate::tui::notifications::{Kind as NotificationKind, Notifications};
+use crate::tui::presence::{self, Presence};
+use crate::tui::session::Session;
+use crate::tui::tour::Tour;
+use crate::{
+    api::{Attachment, Capabilities, Comment, Revision, TodoModifier},
+    checklist, editor, links, ApiClient, Config, ID_DISPLAY_LENGTH,
+};
+use anyhow::{Context, Result};
+use chrono::{Datelike, Local, NaiveDate};
 use pali_types::Todo;
+use ratatui::layout::{Position, Rect};
 use ratatui::widgets::ListState;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 
 // Constants for better maintainability
 const SPINNER_STATES: usize = 4;
 const MESSAGE_TIMEOUT_TICKS: usize = 20; // 5 seconds at 4 FPS
+pub const POMODORO_DURATION: Duration = Duration::from_secs(25 * 60);
+const CONNECTION_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const CONNECTION_CHECK_TIMEOUT: Duration = Duration::from_secs(5); // Slower counts as offline
+const DEFAULT_REMINDER_WINDOW_MINS: u64 = 15;
+/// Orders cycled through with `S`; the empty spec keeps the server's order
+const SORT_PRESETS: [&str; 5] = ["", "priority,due", "due,priority", "-created", "title"];
 
+/// The list and, if the server says, who last changed each todo
+type Refreshed = (Result<Vec<Todo>>, Option<Vec<TodoModifier>>);
+
+/// Whether the server answered the last connection check, shown in the header
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// No check has finished yet
+    Unknown,
+    /// Round-trip time of the last check
+    Online(Duration),
+    Offline,
+}
+
+/// A running pomodoro work session on a todo
+#[derive(Debug, Clone)]
+pub struct Pomodoro {
+    pub todo_id: String,
+    pub todo_title: String,
+    pub ends_at: Instant,
+}
+
+impl Pomodoro {
+    #[must_use]
+    pub fn remaining(&self) -> Duration {
+        self.ends_at.saturating_duration_since(Instant::now())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AppScreen {
     TodoList,
     AddTodo,
@@ -19,6 +79,8 @@ pub enum AppScreen {
     Settings,
     Search,
     TodoDetail,
+    Week,
+    Notifications,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -31,24 +93,76 @@ pub struct App {
     pub should_quit: bool,
     pub api_client: ApiClient,
     pub config: Config,
+    pub color_rules: Vec<ColorRule>, // Parsed from config.color_rules
+    pub local_state: LocalState,     // Client-side metadata (dependencies, ...)
     pub current_screen: AppScreen,
     pub input_mode: InputMode,
     pub todos: Vec<Todo>,
     pub selected_todo: Option<usize>,
     pub list_state: ListState, // Moved from UI to app state for performance
     pub input_buffer: String,
-    pub input_form: InputForm, // Advanced form for add/edit
+    pub input_form: InputForm,           // Advanced form for add/edit
+    pub edit_base: Option<Todo>, // The todo as it was when editing started, to detect conflicts
+    pub conflict: Option<EditConflict>, // Shown as a merge dialog over the edit form
+    pub url_picker: Option<Vec<String>>, // URLs of the shown todo to choose from (O)
+    pub history: Option<Vec<Revision>>, // Revisions of the shown todo, oldest first (H)
+    pub history_scroll: usize,   // Index of the first revision shown
+    pub multi_select: bool,      // Multi-select mode (V): Space marks todos
+    pub marked: HashSet<String>, // IDs of the todos marked in multi-select mode
+    pub export_prompt: Option<String>, // Path typed for exporting the marked todos (x)
     pub loading: bool,
     pub loading_spinner_state: usize, // For animating spinner
     pub error_message: Option<String>,
     pub success_message: Option<String>,
     pub message_timer: Option<usize>, // Auto-dismiss timer for messages
+    pub notifications: Notifications, // Every toast, sync result and reminder (N)
+    pub notification_scroll: u16,     // Scroll offset of the notification center
+    pub tour: Option<Tour>,           // First-launch tour in progress (replayed from Settings)
+    pub presence: Presence,           // Who changed which todo, from servers that say
+    tour_done: bool,                  // The tour was finished or skipped, so it isn't shown again
     // Search and filtering state
     pub search_query: String,
-    pub show_all_todos: bool,
-    pub filter_priority: Option<i32>,
-    pub filter_tag: Option<String>,
+    pub show_archived: bool, // Archived view: only archived todos are listed
+    pub filters: Filters,    // Status, priority, tag and due facets (F, or f/o/0-3)
+    pub filter_popup: Option<FilterPopup>, // Quick filter popup (F)
+    pub filter_due_day: Option<NaiveDate>, // Day opened from the week view (Esc goes back)
+    pub sort: SortSpec,      // Order of the list, completed todos last (S cycles presets)
+    pub search_history: Vec<String>, // Past queries, most recent first (persisted in the session)
+    search_history_index: Option<usize>, // Entry recalled with ↑/↓ in the search screen
+    pub search_regex: bool,  // Search query is a regular expression (Ctrl+R)
     pub filtered_todos: Vec<Todo>, // Cache filtered results
+    pub week_start: NaiveDate, // Monday of the week shown in the week view
+    pub week_day: usize,     // Selected column of the week view, 0 = Monday
+    pub week_index: usize,   // Selected todo within that day
+    pub moving_todo: Option<String>, // Todo being moved to another day in the week view (m)
+    pub checklist_index: usize, // Highlighted checklist item in the detail view
+    pub detail_attachments: Vec<Attachment>, // Attachments of the todo shown in the detail view
+    pub detail_comments: Vec<Comment>, // Comments of the todo shown in the detail view
+    pub comment_scroll: u16, // Scroll offset of the comments pane
+    pub detail_scroll: u16,  // Scroll offset of the detail view
+    pub detail_max_scroll: u16, // Largest useful detail_scroll, set when rendering
+    pub detail_page: u16,    // Rows visible in the detail view, set when rendering
+    pub pomodoro: Option<Pomodoro>,
+    pub pending_notification: Option<String>, // Bell/desktop notification for the event loop
+    pub pending_clipboard: Option<String>,    // Text the event loop copies through the terminal
+    pending_editor: Option<String>, // Text of the todo the event loop opens in $EDITOR (E)
+    pub remote: bool, // Served over SSH: no exports to files, and links are copied, not opened
+    session_file: Option<PathBuf>, // Where the session is kept, if not in `tui_session.json`
+    editor_screen: AppScreen, // Screen to return to after editing in $EDITOR
+    pub yank_pending: bool, // `y` pressed in the detail view, waiting for a link number
+    pub show_debug_pane: bool, // Recent log lines below the main view (F12)
+    pub connection: ConnectionState,
+    pub overdue_badge_area: Option<Rect>, // Where the header badge was drawn, for mouse clicks
+    pub progress_area: Option<Rect>, // Where the header's progress gauge was drawn, for mouse clicks
+    pub unread_badge_area: Option<Rect>, // Where the header's unread badge was drawn, for mouse clicks
+    pub reminders: Vec<String>,          // IDs of todos due soon, soonest first (banner)
+    reminded: HashSet<String>,           // Todos that already rang the bell this session
+    pending_selection: Option<String>,   // Todo ID from the last session, selected once loaded
+    pending_detail: bool,                // Reopen the detail view once the selection is restored
+    refresh_rx: Option<tokio::sync::oneshot::Receiver<Refreshed>>, // Background list refresh
+    refresh_cancel: CancellationToken,   // Abandons the background refresh when superseded
+    ping_rx: Option<tokio::sync::oneshot::Receiver<Result<Duration>>>, // Background connection check
+    last_connection_check: Option<Instant>,
 }
 
 impl App {
@@ -61,13 +175,59 @@ impl App {
     /// - Configuration file format is invalid
     /// - API client initialization fails
     pub fn new() -> Result<Self> {
-        let config = Config::load()?;
-        let api_client = ApiClient::new()?;
+        Self::load(Config::load()?, None)
+    }
+
+    /// Creates an application for `config` with the saved local state, the
+    /// cached list and the last session
+    ///
+    /// The session is read from and saved to `session_file`, by default
+    /// [`Session::path`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API client cannot be created
+    pub fn load(config: Config, session_file: Option<PathBuf>) -> Result<Self> {
+        let mut app = Self::with_config(config)?;
+        // Local metadata is optional - a corrupt state file shouldn't prevent startup
+        app.local_state = LocalState::load().unwrap_or_default();
+
+        app.restore_session(Session::load(session_file.as_deref()));
+        app.session_file = session_file;
+
+        // Show the cached list right away; `start_background_refresh` replaces it
+        if let Some(cache) = TodoCache::load(&app.config.api_endpoint) {
+            app.todos = cache.todos;
+        }
+
+        // Apply initial filters
+        app.apply_filters();
+        app.apply_pending_selection();
+
+        Ok(app)
+    }
+
+    /// Creates an application for `config` without reading anything saved
+    ///
+    /// No local state, last session or cached list is loaded, so the app
+    /// starts empty on the todo list; set [`App::todos`] and call
+    /// [`App::apply_filters`] to fill it. Meant for rendering with
+    /// [`crate::tui::render_to_buffer`] in tests.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API client cannot be created
+    pub fn with_config(config: Config) -> Result<Self> {
+        let color_rules = highlight::parse_rules(&config.color_rules);
+        let api_client = ApiClient::with_config(config.clone())?;
+        let today = Local::now().date_naive();
 
-        let mut app = Self {
+        Ok(Self {
             should_quit: false,
             api_client,
             config,
+            color_rules,
+            local_state: LocalState::default(),
             current_screen: AppScreen::TodoList,
             input_mode: InputMode::Normal,
             todos: Vec::new(),
@@ -75,26 +235,164 @@ impl App {
             list_state: ListState::default(),
             input_buffer: String::new(),
             input_form: InputForm::new(),
+            edit_base: None,
+            conflict: None,
+            url_picker: None,
+            history: None,
+            history_scroll: 0,
+            multi_select: false,
+            marked: HashSet::new(),
+            export_prompt: None,
             loading: false,
             loading_spinner_state: 0,
             error_message: None,
             success_message: None,
             message_timer: None,
+            notifications: Notifications::default(),
+            presence: Presence::default(),
+            notification_scroll: 0,
+            tour: None,
+            tour_done: false,
             // Initialize search and filtering
             search_query: String::new(),
-            show_all_todos: false,
-            filter_priority: None,
-            filter_tag: None,
+            show_archived: false,
+            filters: Filters::default(),
+            filter_popup: None,
+            filter_due_day: None,
+            sort: SortSpec::default(),
+            search_history: Vec::new(),
+            search_history_index: None,
+            search_regex: false,
             filtered_todos: Vec::new(),
-        };
+            week_start: due::week_start(today),
+            week_day: today.weekday().num_days_from_monday() as usize,
+            week_index: 0,
+            moving_todo: None,
+            checklist_index: 0,
+            detail_attachments: Vec::new(),
+            detail_comments: Vec::new(),
+            comment_scroll: 0,
+            detail_scroll: 0,
+            detail_max_scroll: 0,
+            detail_page: 0,
+            pomodoro: None,
+            pending_notification: None,
+            pending_clipboard: None,
+            pending_editor: None,
+            remote: false,
+            session_file: None,
+            editor_screen: AppScreen::TodoList,
+            yank_pending: false,
+            show_debug_pane: false,
+            connection: ConnectionState::Unknown,
+            overdue_badge_area: None,
+            progress_area: None,
+            unread_badge_area: None,
+            reminders: Vec::new(),
+            reminded: HashSet::new(),
+            pending_selection: None,
+            pending_detail: false,
+            refresh_rx: None,
+            refresh_cancel: CancellationToken::new(),
+            ping_rx: None,
+            last_connection_check: None,
+        })
+    }
 
-        // Apply initial filters
-        app.apply_filters();
+    /// Applies the filters and view of the last session
+    ///
+    /// The selection is applied by [`Self::apply_pending_selection`] once the
+    /// todo is loaded; the detail view by [`Self::resume_detail`].
+    fn restore_session(&mut self, session: Session) {
+        let screen = session.restored_screen();
+        self.show_archived = session.show_archived;
+        self.filters = session.filters();
+        if let Some(sort) = session.sort {
+            self.sort = SortSpec::parse(&sort)
+                .unwrap_or_default()
+                .completed_last(true);
+        }
+        self.search_history = session.search_history;
+        self.search_regex = session.search_regex;
+        self.search_query = session.search_query;
+        self.pending_selection = session.selected_id;
+        match screen {
+            AppScreen::TodoDetail => self.pending_detail = true,
+            screen => self.current_screen = screen,
+        }
+        self.tour_done = session.tour_done;
+        if !self.tour_done {
+            self.start_tour();
+        }
+    }
 
-        Ok(app)
+    /// Returns the state to restore on the next launch
+    #[must_use]
+    pub fn session(&self) -> Session {
+        Session {
+            show_archived: self.show_archived,
+            filters: Some(self.filters.clone()),
+            sort: (!self.sort.is_empty()).then(|| self.sort.to_string()),
+            search_query: self.search_query.clone(),
+            selected_id: self.selected_todo_id().map(str::to_string),
+            screen: Some(self.current_screen),
+            search_history: self.search_history.clone(),
+            search_regex: self.search_regex,
+            tour_done: self.tour_done,
+            // The pre-popup filter fields are only read, never saved
+            ..Session::default()
+        }
+    }
+
+    /// Saves the session for the next launch, logging instead of failing
+    pub fn save_session(&self) {
+        if let Err(err) = self.session().save(self.session_file.as_deref()) {
+            log::warn!("Could not save the TUI session: {err}");
+        }
+    }
+
+    /// Reopens the detail view if the last session ended there
+    ///
+    /// Separate from [`Self::new`] since it fetches attachments and comments;
+    /// does nothing until the selected todo has been loaded. Returns `true`
+    /// if the detail view was opened.
+    pub async fn resume_detail(&mut self) -> bool {
+        if !self.pending_detail || self.pending_selection.is_some() || self.selected_todo.is_none()
+        {
+            return false;
+        }
+        self.pending_detail = false;
+        self.show_todo_detail().await;
+        true
+    }
+
+    fn selected_todo_id(&self) -> Option<&str> {
+        self.selected_todo
+            .and_then(|index| self.filtered_todos.get(index))
+            .map(|todo| todo.id.as_str())
+    }
+
+    /// Selects the todo with `id` if it is in the filtered list
+    fn select_todo_by_id(&mut self, id: &str) -> bool {
+        let Some(index) = self.filtered_todos.iter().position(|todo| todo.id == id) else {
+            return false;
+        };
+        self.selected_todo = Some(index);
+        self.list_state.select(Some(index));
+        true
+    }
+
+    /// Selects the todo remembered from the last session, once it is loaded
+    fn apply_pending_selection(&mut self) {
+        if let Some(id) = self.pending_selection.take() {
+            if !self.select_todo_by_id(&id) {
+                self.pending_selection = Some(id);
+            }
+        }
     }
 
     pub fn quit(&mut self) {
+        self.cancel_background_refresh();
         self.should_quit = true;
     }
 
@@ -103,6 +401,21 @@ impl App {
         self.quit();
     }
 
+    /// Returns `true` while the screen changes without user input
+    ///
+    /// The event loop only ticks (and redraws) at full rate while this holds:
+    /// spinner, message timeout, pomodoro clock, debug pane or a pending
+    /// background request.
+    #[must_use]
+    pub fn is_animating(&self) -> bool {
+        self.loading
+            || self.message_timer.is_some()
+            || self.pomodoro.is_some()
+            || self.show_debug_pane
+            || self.refresh_rx.is_some()
+            || self.ping_rx.is_some()
+    }
+
     pub fn tick_spinner(&mut self) {
         if self.loading {
             self.loading_spinner_state = (self.loading_spinner_state + 1) % SPINNER_STATES;
@@ -120,6 +433,75 @@ impl App {
         }
     }
 
+    /// Completes the running pomodoro once its time is up
+    pub fn tick_pomodoro(&mut self) {
+        let Some(pomodoro) = &self.pomodoro else {
+            return;
+        };
+        if pomodoro.remaining() > Duration::ZERO {
+            return;
+        }
+
+        let Some(pomodoro) = self.pomodoro.take() else {
+            return;
+        };
+        self.local_state
+            .record_pomodoro(&pomodoro.todo_id, chrono::Utc::now().timestamp());
+        if self.local_state.save().is_err() {
+            self.show_error("Pomodoro finished, but it could not be saved".to_string());
+        } else {
+            self.show_success(format!("🍅 Pomodoro complete: {}", pomodoro.todo_title));
+        }
+        self.pending_notification = Some(format!("Pomodoro complete: {}", pomodoro.todo_title));
+    }
+
+    /// Starts a pomodoro on the selected todo, or cancels the running one
+    pub fn toggle_pomodoro(&mut self) {
+        if let Some(pomodoro) = self.pomodoro.take() {
+            self.show_success(format!("Pomodoro cancelled: {}", pomodoro.todo_title));
+            return;
+        }
+
+        let Some(todo) = self
+            .selected_todo
+            .and_then(|index| self.filtered_todos.get(index))
+        else {
+            return;
+        };
+
+        let pomodoro = Pomodoro {
+            todo_id: todo.id.clone(),
+            todo_title: todo.title.clone(),
+            ends_at: Instant::now() + POMODORO_DURATION,
+        };
+        self.show_success(format!(
+            "🍅 Pomodoro started: {} ({} min)",
+            pomodoro.todo_title,
+            POMODORO_DURATION.as_secs() / 60
+        ));
+        self.pomodoro = Some(pomodoro);
+    }
+
+    /// Takes the pending notification the terminal should announce, if any
+    pub fn take_notification(&mut self) -> Option<String> {
+        self.pending_notification.take()
+    }
+
+    /// Takes the text the terminal should copy to the clipboard, if any
+    pub fn take_clipboard(&mut self) -> Option<String> {
+        self.pending_clipboard.take()
+    }
+
+    /// Takes the text the event loop should open in `$EDITOR`, with a name for its file
+    pub fn take_editor(&mut self) -> Option<(String, String)> {
+        let text = self.pending_editor.take()?;
+        let id = self
+            .edit_base
+            .as_ref()
+            .map_or("todo", |todo| todo.id.as_str());
+        Some((text, id[..ID_DISPLAY_LENGTH.min(id.len())].to_string()))
+    }
+
     pub fn clear_messages(&mut self) {
         self.error_message = None;
         self.success_message = None;
@@ -127,105 +509,975 @@ impl App {
     }
 
     pub fn show_error(&mut self, message: String) {
+        self.notifications
+            .push(NotificationKind::Error, message.clone());
         self.error_message = Some(message);
         self.success_message = None;
         self.message_timer = Some(MESSAGE_TIMEOUT_TICKS);
     }
 
     pub fn show_success(&mut self, message: String) {
+        self.notifications
+            .push(NotificationKind::Info, message.clone());
+        self.show_toast(message);
+    }
+
+    /// Shows the result of loading the list, kept as a sync notification
+    fn show_sync(&mut self, message: String) {
+        self.notifications
+            .push(NotificationKind::Sync, message.clone());
+        self.show_toast(message);
+    }
+
+    fn show_toast(&mut self, message: String) {
         self.success_message = Some(message);
         self.error_message = None;
         self.message_timer = Some(MESSAGE_TIMEOUT_TICKS);
     }
 
+    /// Starts the guided tour on the todo list
+    pub fn start_tour(&mut self) {
+        self.tour = Some(Tour::default());
+        self.pending_detail = false;
+        self.current_screen = AppScreen::TodoList;
+    }
+
+    /// Handles the tour: Enter/→ next tip, ← previous, Esc skips
+    ///
+    /// Finishing or skipping both count as done, so the tour isn't shown again.
+    fn handle_tour_key(&mut self, key: Key) {
+        let Some(tour) = self.tour.as_mut() else {
+            return;
+        };
+        let finished = match key {
+            Key::Enter | Key::Right | Key::Char(' ' | 'l') => !tour.advance(),
+            Key::Left | Key::Backspace | Key::Char('h') => {
+                tour.previous();
+                false
+            }
+            Key::Esc | Key::Char('q') => true,
+            _ => false,
+        };
+        if finished {
+            self.tour = None;
+            self.tour_done = true;
+        }
+    }
+
+    /// Opens the notification center, marking everything in it read
+    pub fn open_notifications(&mut self) {
+        self.notifications.mark_read();
+        self.notification_scroll = 0;
+        self.current_screen = AppScreen::Notifications;
+    }
+
     /// Applies current search query and filters to update filtered_todos
     pub fn apply_filters(&mut self) {
+        let today = Local::now().date_naive();
+        let query = self.search_matcher();
         self.filtered_todos = self
             .todos
             .iter()
+            .filter(|todo| self.is_listed(todo, &self.filters, query.as_ref(), today))
+            .cloned()
+            .collect();
+        self.sort.sort(&mut self.filtered_todos);
+        self.local_state.pinned_first(&mut self.filtered_todos);
+
+        // Reset selection when filters change
+        if self.filtered_todos.is_empty() {
+            self.selected_todo = None;
+            self.list_state.select(None);
+        } else {
+            self.selected_todo = Some(0);
+            self.list_state.select(Some(0));
+        }
+    }
+
+    /// Returns `true` if `todo` belongs in the list with `filters` and the search `query`
+    fn is_listed(
+        &self,
+        todo: &Todo,
+        filters: &Filters,
+        query: Option<&Query>,
+        today: NaiveDate,
+    ) -> bool {
+        // Archived todos only appear in the archived view, completed or not
+        if self.local_state.is_archived(&todo.id) != self.show_archived {
+            return false;
+        }
+        if query.is_some_and(|query| !query.matches(todo)) {
+            return false;
+        }
+        if let Some(day) = self.filter_due_day {
+            if todo.due_date.and_then(local_date) != Some(day) {
+                return false;
+            }
+        }
+        // Tags live in local state
+        let tags = self.local_state.tags_of(&todo.id);
+        filters.matches(todo, tags, DueBucket::of(todo, today), !self.show_archived)
+    }
+
+    /// Counts the todos that would be listed with `filters`, to preview a change
+    #[must_use]
+    pub fn count_listed(&self, filters: &Filters) -> usize {
+        let today = Local::now().date_naive();
+        let query = self.search_matcher();
+        self.todos
+            .iter()
+            .filter(|todo| self.is_listed(todo, filters, query.as_ref(), today))
+            .count()
+    }
+
+    /// Opens the filter popup
+    pub fn open_filter_popup(&mut self) {
+        self.filter_popup = Some(FilterPopup::new(&self.local_state.all_tags()));
+    }
+
+    /// Handles the filter popup: ↑/↓ move, Space/Enter check, c resets, Esc closes
+    ///
+    /// Checking an option filters the list behind the popup right away.
+    fn handle_filter_popup_key(&mut self, key: Key) {
+        let Some(popup) = self.filter_popup.as_mut() else {
+            return;
+        };
+        match key {
+            Key::Esc | Key::Char('q' | 'F') => self.filter_popup = None,
+            Key::Up | Key::Char('k') => popup.move_cursor(false),
+            Key::Down | Key::Char('j') => popup.move_cursor(true),
+            Key::Char(' ') | Key::Enter => {
+                if let Some(facet) = popup.selected().cloned() {
+                    self.filters.toggle(&facet);
+                    self.apply_filters();
+                }
+            }
+            Key::Char('c') => {
+                self.filters = Filters::default();
+                self.apply_filters();
+            }
+            _ => {}
+        }
+    }
+
+    /// Starts search mode
+    pub fn start_search(&mut self) {
+        self.current_screen = AppScreen::Search;
+        self.input_mode = InputMode::Editing;
+        self.search_query.clear();
+        self.search_history_index = None;
+        self.clear_messages();
+    }
+
+    /// Replaces the query with an older (`older == true`) or newer past search
+    ///
+    /// Moving past the newest entry clears the query again.
+    pub fn recall_search(&mut self, older: bool) {
+        if self.search_history.is_empty() {
+            return;
+        }
+        let last = self.search_history.len() - 1;
+        self.search_history_index = match (self.search_history_index, older) {
+            (None, true) => Some(0),
+            (None, false) | (Some(0), false) => None,
+            (Some(index), true) => Some((index + 1).min(last)),
+            (Some(index), false) => Some(index - 1),
+        };
+        self.search_query = self
+            .search_history_index
+            .map(|index| self.search_history[index].clone())
+            .unwrap_or_default();
+    }
+
+    /// Runs the most recent search again
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search fails to execute
+    pub async fn rerun_last_search(&mut self) -> Result<()> {
+        let Some(query) = self.search_history.first() else {
+            self.show_error("No previous search to run".to_string());
+            return Ok(());
+        };
+        self.search_query = query.clone();
+        self.execute_search().await
+    }
+
+    /// The active search query, or `None` without one (or with an invalid regex)
+    #[must_use]
+    pub fn search_matcher(&self) -> Option<Query> {
+        if self.search_query.is_empty() {
+            return None;
+        }
+        Query::new(&self.search_query, self.search_regex).ok()
+    }
+
+    /// Switches the search screen between text and regular expression queries
+    pub fn toggle_search_regex(&mut self) {
+        self.search_regex = !self.search_regex;
+    }
+
+    /// Executes search with current query
+    ///
+    /// Text queries use the server's search; regular expressions are matched
+    /// locally against the full todo list.
+    pub async fn execute_search(&mut self) -> Result<()> {
+        if self.search_query.trim().is_empty() {
+            // Empty search - show all todos
+            self.current_screen = AppScreen::TodoList;
+            self.input_mode = InputMode::Normal;
+            self.apply_filters();
+            return Ok(());
+        }
+
+        if let Err(err) = Query::new(&self.search_query, self.search_regex) {
+            self.show_error(format!("{err:#}"));
+            return Ok(());
+        }
+
+        Session::remember_search(&mut self.search_history, &self.search_query);
+        // The results replace the list, which a refresh finishing later would undo
+        self.cancel_background_refresh();
+        self.loading = true;
+        self.clear_messages();
+
+        let result = if self.search_regex {
+            cache::list_todos(&self.api_client, None).await
+        } else {
+            self.api_client.search_todos(&self.search_query).await
+        };
+        match result {
+            Ok(todos) => {
+                self.todos = todos;
+                self.apply_filters();
+                self.current_screen = AppScreen::TodoList;
+                self.input_mode = InputMode::Normal;
+                self.show_success(format!(
+                    "Found {} results for '{}'",
+                    self.filtered_todos.len(),
+                    self.search_query
+                ));
+            }
+            Err(_) => {
+                self.show_error("Search failed. Please try again.".to_string());
+            }
+        }
+
+        self.loading = false;
+        Ok(())
+    }
+
+    /// Toggles between showing all todos and only pending todos
+    pub fn toggle_show_all(&mut self) {
+        self.filters.statuses = if self.filters.shows_completed() {
+            BTreeSet::from([Status::Pending])
+        } else {
+            BTreeSet::from([Status::Pending, Status::Completed])
+        };
+        self.apply_filters();
+        let status = if self.filters.shows_completed() {
+            "all todos"
+        } else {
+            "pending todos"
+        };
+        self.show_success(format!("Now showing {status}"));
+    }
+
+    /// Switches to the next order in [`SORT_PRESETS`]
+    pub fn cycle_sort(&mut self) {
+        let current = self.sort.to_string();
+        let next = SORT_PRESETS
+            .iter()
+            .position(|preset| *preset == current)
+            .map_or(0, |index| (index + 1) % SORT_PRESETS.len());
+        self.sort = if SORT_PRESETS[next].is_empty() {
+            SortSpec::default()
+        } else {
+            SortSpec::parse(SORT_PRESETS[next])
+                .unwrap_or_default()
+                .completed_last(true)
+        };
+        self.apply_filters();
+        if self.sort.is_empty() {
+            self.show_success("Sorted in server order".to_string());
+        } else {
+            self.show_success(format!("Sorted by {}", self.sort));
+        }
+    }
+
+    /// Shows only overdue todos, or all of them again
+    pub fn toggle_overdue_filter(&mut self) {
+        self.filters.toggle(&Facet::Due(DueBucket::Overdue));
+        self.apply_filters();
+        let status = if self.filters.due.contains(&DueBucket::Overdue) {
+            "overdue todos only"
+        } else {
+            "todos regardless of due date"
+        };
+        self.show_success(format!("Now showing {status}"));
+    }
+
+    /// Counts open, unarchived todos that are overdue and due today (local time)
+    #[must_use]
+    pub fn due_counts(&self) -> (usize, usize) {
+        let today = Local::now().date_naive();
+        self.todos
+            .iter()
+            .filter(|todo| !todo.completed && !self.local_state.is_archived(&todo.id))
+            .filter_map(|todo| todo.due_date.and_then(local_date))
+            .fold((0, 0), |(overdue, due_today), date| {
+                (
+                    overdue + usize::from(date < today),
+                    due_today + usize::from(date == today),
+                )
+            })
+    }
+
+    /// Counts today's todos and how many of them are done, as `(done, total)`
+    ///
+    /// Today's todos are the unarchived ones due today, plus those completed
+    /// today (going by their last update) whatever their due date.
+    #[must_use]
+    pub fn today_progress(&self) -> (usize, usize) {
+        let today = Local::now().date_naive();
+        self.todos
+            .iter()
+            .filter(|todo| !self.local_state.is_archived(&todo.id))
             .filter(|todo| {
-                // Apply completion filter
-                if !self.show_all_todos && todo.completed {
-                    return false;
-                }
-
-                // Apply search query filter
-                if !self.search_query.is_empty() {
-                    let query_lower = self.search_query.to_lowercase();
-                    let title_match = todo.title.to_lowercase().contains(&query_lower);
-                    let desc_match = todo
-                        .description
-                        .as_ref()
-                        .map(|d| d.to_lowercase().contains(&query_lower))
-                        .unwrap_or(false);
-
-                    if !title_match && !desc_match {
-                        return false;
+                todo.due_date.and_then(local_date) == Some(today)
+                    || (todo.completed && local_date(todo.updated_at) == Some(today))
+            })
+            .fold((0, 0), |(done, total), todo| {
+                (done + usize::from(todo.completed), total + 1)
+            })
+    }
+
+    /// Shows the list of todos due today, as if opened from this week's view (Esc goes there)
+    pub fn open_today(&mut self) {
+        self.open_week_view();
+        self.open_week_day();
+    }
+
+    /// Handles a left click; the overdue badge in the header jumps to the overdue filter,
+    /// the progress gauge to today's list and the unread badge to the notification center
+    pub fn handle_click(&mut self, column: u16, row: u16) {
+        if self.input_mode != InputMode::Normal {
+            return;
+        }
+        let clicked =
+            |area: Option<Rect>| area.is_some_and(|area| area.contains(Position::new(column, row)));
+        if clicked(self.overdue_badge_area) {
+            self.current_screen = AppScreen::TodoList;
+            self.toggle_overdue_filter();
+        } else if clicked(self.progress_area) {
+            self.open_today();
+        } else if clicked(self.unread_badge_area) {
+            self.open_notifications();
+        }
+    }
+
+    /// Opens the week view on the current week, with today selected
+    pub fn open_week_view(&mut self) {
+        let today = Local::now().date_naive();
+        self.week_start = due::week_start(today);
+        self.week_day = today.weekday().num_days_from_monday() as usize;
+        self.week_index = 0;
+        self.moving_todo = None;
+        self.current_screen = AppScreen::Week;
+    }
+
+    /// Date of the selected column in the week view
+    #[must_use]
+    pub fn selected_week_date(&self) -> NaiveDate {
+        self.week_start + chrono::Duration::days(self.week_day as i64)
+    }
+
+    /// Todos shown on `date` in the week view, earliest first
+    ///
+    /// A todo being moved is shown on the selected day, first, instead of
+    /// on its due date.
+    #[must_use]
+    pub fn week_todos(&self, date: NaiveDate) -> Vec<&Todo> {
+        let moving = self.moving_todo.as_deref();
+        let mut todos: Vec<&Todo> = self
+            .todos
+            .iter()
+            .filter(|todo| Some(todo.id.as_str()) != moving)
+            .filter(|todo| !self.local_state.is_archived(&todo.id))
+            .filter(|todo| self.filters.shows_completed() || !todo.completed)
+            .filter(|todo| todo.due_date.and_then(local_date) == Some(date))
+            .collect();
+        todos.sort_by_key(|todo| todo.due_date);
+        if date == self.selected_week_date() {
+            if let Some(todo) = moving.and_then(|id| self.todos.iter().find(|t| t.id == id)) {
+                todos.insert(0, todo);
+            }
+        }
+        todos
+    }
+
+    /// Shows the previous (negative) or next weeks in the week view
+    pub fn shift_week(&mut self, weeks: i64) {
+        self.week_start += chrono::Duration::weeks(weeks);
+        self.week_index = 0;
+    }
+
+    /// Selects another day in the week view, scrolling past the ends of the week
+    pub fn select_week_day(&mut self, days: i64) {
+        let date = self.selected_week_date() + chrono::Duration::days(days);
+        self.week_start = due::week_start(date);
+        self.week_day = date.weekday().num_days_from_monday() as usize;
+        self.week_index = 0;
+    }
+
+    pub fn next_week_todo(&mut self) {
+        let count = self.week_todos(self.selected_week_date()).len();
+        if self.moving_todo.is_none() && self.week_index + 1 < count {
+            self.week_index += 1;
+        }
+    }
+
+    pub fn previous_week_todo(&mut self) {
+        if self.moving_todo.is_none() {
+            self.week_index = self.week_index.saturating_sub(1);
+        }
+    }
+
+    /// Opens the list of the selected day's todos
+    pub fn open_week_day(&mut self) {
+        self.filter_due_day = Some(self.selected_week_date());
+        self.current_screen = AppScreen::TodoList;
+        self.apply_filters();
+    }
+
+    /// Leaves the day list opened from the week view
+    pub fn close_week_day(&mut self) {
+        self.filter_due_day = None;
+        self.current_screen = AppScreen::Week;
+        self.apply_filters();
+    }
+
+    /// Picks up the selected todo in the week view, or drops the one being moved
+    ///
+    /// # Errors
+    ///
+    /// Currently always returns `Ok`; failures are shown to the user
+    pub async fn toggle_move_week_todo(&mut self) -> Result<()> {
+        if self.moving_todo.is_some() {
+            return self.drop_moving_todo().await;
+        }
+        let date = self.selected_week_date();
+        if let Some(todo) = self.week_todos(date).get(self.week_index) {
+            let (id, title) = (todo.id.clone(), todo.title.clone());
+            self.moving_todo = Some(id);
+            self.week_index = 0;
+            self.show_success(format!(
+                "Moving: {title} (h/l or ←/→ pick a day, m/Enter drop)"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Puts back the todo being moved without changing it
+    pub fn cancel_move(&mut self) {
+        if self.moving_todo.take().is_some() {
+            self.show_success("Move cancelled".to_string());
+        }
+    }
+
+    /// Sets the due date of the todo being moved to the selected day, keeping its time
+    async fn drop_moving_todo(&mut self) -> Result<()> {
+        let Some(todo) = self
+            .moving_todo
+            .take()
+            .and_then(|id| self.todos.iter().find(|todo| todo.id == id).cloned())
+        else {
+            return Ok(());
+        };
+        let date = self.selected_week_date();
+        let Some(due_date) = todo.due_date.and_then(|due| due::move_to_date(due, date)) else {
+            self.show_error(format!("Cannot move the due time to {date}"));
+            return Ok(());
+        };
+        if Some(due_date) == todo.due_date {
+            return Ok(());
+        }
+
+        self.loading = true;
+        let update_request = pali_types::UpdateTodoRequest {
+            title: None,
+            description: None,
+            completed: None,
+            priority: None,
+            due_date: Some(due_date),
+        };
+        let result = self.api_client.update_todo(&todo.id, update_request).await;
+        match activity::track_todo(ActivityEntry::new(Source::Tui, "update"), result) {
+            Ok(updated_todo) => {
+                if let Some(main_index) = self.todos.iter().position(|t| t.id == todo.id) {
+                    self.todos[main_index] = updated_todo.clone();
+                }
+                self.apply_filters();
+                self.week_index = self
+                    .week_todos(date)
+                    .iter()
+                    .position(|t| t.id == todo.id)
+                    .unwrap_or(0);
+                self.show_success(format!(
+                    "Moved to {}: {}",
+                    date.format("%a %b %-d"),
+                    updated_todo.title
+                ));
+            }
+            Err(_) => {
+                self.show_error("Unable to move todo. Please try again.".to_string());
+            }
+        }
+        self.loading = false;
+        Ok(())
+    }
+
+    /// Switches between the regular list and the archived view
+    pub fn toggle_archived_view(&mut self) {
+        self.show_archived = !self.show_archived;
+        self.apply_filters();
+        let status = if self.show_archived {
+            "archived todos"
+        } else {
+            "active todos"
+        };
+        self.show_success(format!("Now showing {status}"));
+    }
+
+    /// Archives the selected todo, or restores it when in the archived view
+    pub fn toggle_archive_selected(&mut self) {
+        let Some(todo) = self
+            .selected_todo
+            .and_then(|index| self.filtered_todos.get(index))
+            .cloned()
+        else {
+            return;
+        };
+
+        let (archived, operation) = if self.local_state.is_archived(&todo.id) {
+            self.local_state.unarchive(&todo.id);
+            (false, "unarchive")
+        } else {
+            self.local_state
+                .archive(&todo.id, chrono::Utc::now().timestamp());
+            (true, "archive")
+        };
+
+        if self.local_state.save().is_err() {
+            self.show_error("Could not save local state".to_string());
+            return;
+        }
+        activity::record(&ActivityEntry::new(Source::Tui, operation).with_todo(&todo));
+
+        self.apply_filters();
+        if archived {
+            self.show_success(format!("Archived: {}", todo.title));
+        } else {
+            self.show_success(format!("Restored: {}", todo.title));
+        }
+    }
+
+    /// Pins the selected todo to the top of the list, or unpins it
+    pub fn toggle_pin_selected(&mut self) {
+        let Some(todo) = self
+            .selected_todo
+            .and_then(|index| self.filtered_todos.get(index))
+            .cloned()
+        else {
+            return;
+        };
+
+        let (pinned, operation) = if self.local_state.is_pinned(&todo.id) {
+            self.local_state.unpin(&todo.id);
+            (false, "unpin")
+        } else {
+            self.local_state
+                .pin(&todo.id, chrono::Utc::now().timestamp());
+            (true, "pin")
+        };
+
+        if self.local_state.save().is_err() {
+            self.show_error("Could not save local state".to_string());
+            return;
+        }
+        activity::record(&ActivityEntry::new(Source::Tui, operation).with_todo(&todo));
+
+        self.apply_filters();
+        // Follow the todo to its new place
+        self.select_todo_by_id(&todo.id);
+        if pinned {
+            self.show_success(format!("Pinned: {}", todo.title));
+        } else {
+            self.show_success(format!("Unpinned: {}", todo.title));
+        }
+    }
+
+    /// Enters or leaves multi-select mode; leaving it clears the marks
+    pub fn toggle_multi_select(&mut self) {
+        self.multi_select = !self.multi_select;
+        self.marked.clear();
+        if self.multi_select {
+            self.show_success("Multi-select: Space marks todos, x exports them".to_string());
+        }
+    }
+
+    /// Marks or unmarks the selected todo in multi-select mode
+    pub fn toggle_mark_selected(&mut self) {
+        let Some(id) = self
+            .selected_todo
+            .and_then(|index| self.filtered_todos.get(index))
+            .map(|todo| todo.id.clone())
+        else {
+            return;
+        };
+        if !self.marked.remove(&id) {
+            self.marked.insert(id);
+        }
+        // Move on to mark runs of todos quickly, without wrapping around
+        if self
+            .selected_todo
+            .is_some_and(|index| index + 1 < self.filtered_todos.len())
+        {
+            self.next_todo();
+        }
+    }
+
+    /// Asks for the file to export the marked todos to
+    pub fn start_export(&mut self) {
+        // The file would be written on the server, as the user serving the TUI
+        if self.remote {
+            self.show_error("Exporting to a file isn't available over SSH".to_string());
+            return;
+        }
+        if self.marked.is_empty() {
+            self.show_error("Mark todos with Space first".to_string());
+            return;
+        }
+        self.export_prompt = Some("todos.md".to_string());
+    }
+
+    /// Handles the export prompt: typing edits the path, Enter exports, Esc cancels
+    fn handle_export_prompt_key(&mut self, key: Key) {
+        let Some(path) = self.export_prompt.as_mut() else {
+            return;
+        };
+        match key {
+            Key::Esc => self.export_prompt = None,
+            Key::Backspace => {
+                path.pop();
+            }
+            Key::Char(c) => path.push(c),
+            Key::Enter => {
+                let path = path.trim().to_string();
+                let Some(format) = Format::from_path(Path::new(&path)) else {
+                    self.show_error("Use a .json, .md or .csv file name".to_string());
+                    return;
+                };
+                self.export_prompt = None;
+                match self.export_marked(Path::new(&path), format) {
+                    Ok(count) => {
+                        self.multi_select = false;
+                        self.marked.clear();
+                        self.show_success(format!("Exported {count} todos to {path}"));
                     }
+                    Err(err) => self.show_error(format!("Export failed: {err:#}")),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Writes the marked todos to `path`, in list order, returning how many were written
+    fn export_marked(&self, path: &Path, format: Format) -> Result<usize> {
+        anyhow::ensure!(!self.remote, "Exporting to a file isn't available over SSH");
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        let labels = self.config.priority_labels.clone();
+        let mut exporter = Exporter::new(BufWriter::new(file), format, labels)?;
+        // Marked todos a filter has hidden since are exported too, after the listed ones
+        let listed: HashSet<&str> = self.filtered_todos.iter().map(|t| t.id.as_str()).collect();
+        let hidden = self
+            .todos
+            .iter()
+            .filter(|todo| !listed.contains(todo.id.as_str()));
+        for todo in self.filtered_todos.iter().chain(hidden) {
+            if self.marked.contains(&todo.id) {
+                exporter.write(todo)?;
+            }
+        }
+        exporter.finish()
+    }
+
+    /// Sets priority filter (None to clear filter)
+    pub fn set_priority_filter(&mut self, priority: Option<i32>) {
+        self.filters.priorities = priority.into_iter().collect();
+        self.apply_filters();
+        let msg = match priority {
+            Some(1) => "Filtering by low priority".to_string(),
+            Some(2) => "Filtering by medium priority".to_string(),
+            Some(3) => "Filtering by high priority".to_string(),
+            None => "Priority filter cleared".to_string(),
+            _ => "Invalid priority".to_string(),
+        };
+        self.show_success(msg);
+    }
+
+    /// Shows detailed view of currently selected todo, fetching its attachments and comments
+    pub async fn show_todo_detail(&mut self) {
+        let Some(todo_id) = self
+            .selected_todo
+            .and_then(|index| self.filtered_todos.get(index))
+            .map(|todo| todo.id.clone())
+        else {
+            return;
+        };
+
+        self.checklist_index = 0;
+        self.comment_scroll = 0;
+        self.detail_scroll = 0;
+        // Attachments and comments are optional server functionality, so a failure just shows none
+        self.detail_attachments = self
+            .api_client
+            .list_attachments(&todo_id)
+            .await
+            .unwrap_or_default();
+        self.detail_comments = self
+            .api_client
+            .list_comments(&todo_id)
+            .await
+            .unwrap_or_default();
+        self.current_screen = AppScreen::TodoDetail;
+    }
+
+    /// Opens the URL in the selected todo, or lists them to pick from if there are several
+    pub fn open_selected_urls(&mut self) {
+        let Some(todo) = self
+            .selected_todo
+            .and_then(|index| self.filtered_todos.get(index))
+        else {
+            return;
+        };
+        let mut urls = links::todo_urls(todo);
+        match urls.len() {
+            0 => self.show_error("This todo contains no URL".to_string()),
+            1 => self.open_url(&urls.remove(0)),
+            _ => self.url_picker = Some(urls),
+        }
+    }
+
+    /// Loads the revisions of the selected todo and shows them over the detail view
+    pub async fn open_history(&mut self) {
+        let Some(id) = self.selected_todo_id().map(str::to_string) else {
+            return;
+        };
+        if !self
+            .api_client
+            .capabilities()
+            .await
+            .supports(Capabilities::REVISIONS)
+        {
+            self.show_error("The server doesn't keep the history of todos".to_string());
+            return;
+        }
+        match self.api_client.list_revisions(&id).await {
+            Ok(revisions) if revisions.is_empty() => {
+                self.show_error("No revisions recorded for this todo".to_string());
+            }
+            Ok(revisions) => {
+                // Start at the latest changes
+                self.history_scroll = revisions.len().saturating_sub(3);
+                self.history = Some(revisions);
+            }
+            Err(_) => self.show_error("Unable to load the history of this todo".to_string()),
+        }
+    }
+
+    /// Handles the history popup: arrows move by revision, Esc closes it
+    fn handle_history_key(&mut self, key: Key) {
+        let count = self.history.as_ref().map_or(0, Vec::len);
+        match key {
+            Key::Esc | Key::Char('q' | 'H') => self.history = None,
+            Key::Up | Key::Char('k') => self.history_scroll = self.history_scroll.saturating_sub(1),
+            Key::Down | Key::Char('j') => {
+                self.history_scroll = (self.history_scroll + 1).min(count.saturating_sub(1));
+            }
+            Key::Home | Key::Char('g') => self.history_scroll = 0,
+            Key::End | Key::Char('G') => self.history_scroll = count.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    /// Handles the URL picker: a number opens that URL, Esc closes it
+    fn handle_url_picker_key(&mut self, key: Key) {
+        match key {
+            Key::Esc | Key::Char('q') => self.url_picker = None,
+            Key::Char(c) => {
+                let url = c
+                    .to_digit(10)
+                    .and_then(|n| n.checked_sub(1))
+                    .and_then(|i| self.url_picker.as_ref()?.get(i as usize).cloned());
+                if let Some(url) = url {
+                    self.url_picker = None;
+                    self.open_url(&url);
                 }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the URL of the selected todo hinted with `number` (`[1]` to `[9]`) in the detail view
+    fn detail_url(&self, number: char) -> Option<String> {
+        let index = number.to_digit(10)?.checked_sub(1)?;
+        let todo = self
+            .selected_todo
+            .and_then(|index| self.filtered_todos.get(index))?;
+        links::todo_urls(todo).into_iter().nth(index as usize)
+    }
+
+    /// Opens the link hinted with `number` in the detail view
+    fn open_detail_link(&mut self, number: char) {
+        match self.detail_url(number) {
+            Some(url) => self.open_url(&url),
+            None => self.show_error(format!("No link [{number}] in this todo")),
+        }
+    }
+
+    /// Copies the link hinted with `number` in the detail view (`y` then the number)
+    fn copy_detail_link(&mut self, number: char) {
+        match self.detail_url(number) {
+            Some(url) => {
+                self.show_success(format!("Copied {url}"));
+                self.pending_clipboard = Some(url);
+            }
+            None => self.show_error(format!("No link [{number}] in this todo")),
+        }
+    }
+
+    fn open_url(&mut self, url: &str) {
+        // A browser would open on the server, so the link goes to the client's clipboard
+        if self.remote {
+            self.show_success(format!("Copied {url} (links can't be opened over SSH)"));
+            self.pending_clipboard = Some(url.to_string());
+            return;
+        }
+        match open::that_detached(url) {
+            Ok(()) => self.show_success(format!("Opened {url}")),
+            Err(err) => self.show_error(format!("Could not open {url}: {err}")),
+        }
+    }
+
+    /// Scrolls the comments pane of the detail view by `lines` (negative scrolls up)
+    pub fn scroll_comments(&mut self, lines: i32) {
+        let max = u16::try_from(self.comment_line_count()).unwrap_or(u16::MAX);
+        self.comment_scroll = if lines < 0 {
+            self.comment_scroll
+                .saturating_sub(u16::try_from(lines.unsigned_abs()).unwrap_or(u16::MAX))
+        } else {
+            self.comment_scroll
+                .saturating_add(u16::try_from(lines).unwrap_or(u16::MAX))
+                .min(max.saturating_sub(1))
+        };
+    }
 
-                // Apply priority filter
-                if let Some(priority) = self.filter_priority {
-                    if todo.priority != priority {
-                        return false;
-                    }
-                }
+    /// Number of text lines the comments pane renders (header plus text per comment)
+    /// Scrolls the detail view by `lines`, staying within its content
+    pub fn scroll_detail(&mut self, lines: i32) {
+        let by = u16::try_from(lines.unsigned_abs()).unwrap_or(u16::MAX);
+        self.detail_scroll = if lines < 0 {
+            self.detail_scroll.saturating_sub(by)
+        } else {
+            self.detail_scroll
+                .saturating_add(by)
+                .min(self.detail_max_scroll)
+        };
+    }
 
-                // Apply tag filter (placeholder - tags not fully implemented yet)
-                // if let Some(tag) = &self.filter_tag {
-                //     // TODO: Implement tag filtering when tags are added
-                // }
+    /// Scrolls the detail view by a page (less one line, to keep some context)
+    pub fn page_detail(&mut self, down: bool) {
+        let page = i32::from(self.detail_page.saturating_sub(1).max(1));
+        self.scroll_detail(if down { page } else { -page });
+    }
 
-                true
-            })
-            .cloned()
-            .collect();
+    pub fn comment_line_count(&self) -> usize {
+        self.detail_comments
+            .iter()
+            .map(|comment| 1 + comment.text.lines().count().max(1))
+            .sum()
+    }
 
-        // Reset selection when filters change
-        if self.filtered_todos.is_empty() {
-            self.selected_todo = None;
-            self.list_state.select(None);
-        } else {
-            self.selected_todo = Some(0);
-            self.list_state.select(Some(0));
+    /// Returns the number of checklist items of the currently selected todo
+    fn selected_checklist_len(&self) -> usize {
+        self.selected_todo
+            .and_then(|index| self.filtered_todos.get(index))
+            .and_then(|todo| todo.description.as_deref())
+            .map_or(0, |desc| checklist::items(desc).len())
+    }
+
+    pub fn next_checklist_item(&mut self) {
+        let len = self.selected_checklist_len();
+        if len > 0 {
+            self.checklist_index = (self.checklist_index + 1) % len;
         }
     }
 
-    /// Starts search mode
-    pub fn start_search(&mut self) {
-        self.current_screen = AppScreen::Search;
-        self.input_mode = InputMode::Editing;
-        self.search_query.clear();
-        self.clear_messages();
+    pub fn previous_checklist_item(&mut self) {
+        let len = self.selected_checklist_len();
+        if len > 0 {
+            self.checklist_index = (self.checklist_index + len - 1) % len;
+        }
     }
 
-    /// Executes search with current query
-    pub async fn execute_search(&mut self) -> Result<()> {
-        if self.search_query.trim().is_empty() {
-            // Empty search - show all todos
-            self.current_screen = AppScreen::TodoList;
-            self.input_mode = InputMode::Normal;
-            self.apply_filters();
+    /// Toggles the highlighted checklist item of the todo shown in the detail view
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Network request fails (displays error message to user)
+    /// - API returns an error response (displays error message to user)
+    ///
+    /// Note: Errors are shown to the user via UI messages and don't propagate
+    pub async fn toggle_checklist_item(&mut self) -> Result<()> {
+        let Some(index) = self.selected_todo else {
             return Ok(());
-        }
+        };
+        let Some(todo) = self.filtered_todos.get(index) else {
+            return Ok(());
+        };
+        let Some(description) = todo
+            .description
+            .as_deref()
+            .and_then(|desc| checklist::toggle_item(desc, self.checklist_index))
+        else {
+            return Ok(());
+        };
 
+        let todo_id = todo.id.clone();
         self.loading = true;
         self.clear_messages();
 
-        match self.api_client.search_todos(&self.search_query).await {
-            Ok(todos) => {
-                self.todos = todos;
-                self.apply_filters();
-                self.current_screen = AppScreen::TodoList;
-                self.input_mode = InputMode::Normal;
-                self.show_success(format!(
-                    "Found {} results for '{}'",
-                    self.filtered_todos.len(),
-                    self.search_query
-                ));
+        let update_request = pali_types::UpdateTodoRequest {
+            title: None,
+            description: Some(description),
+            completed: None,
+            priority: None,
+            due_date: None,
+        };
+
+        let result = self.api_client.update_todo(&todo_id, update_request).await;
+        match activity::track_todo(ActivityEntry::new(Source::Tui, "checklist"), result) {
+            Ok(updated_todo) => {
+                if let Some(main_index) = self.todos.iter().position(|t| t.id == todo_id) {
+                    self.todos[main_index] = updated_todo.clone();
+                }
+                self.filtered_todos[index] = updated_todo;
             }
             Err(_) => {
-                self.show_error("Search failed. Please try again.".to_string());
+                self.show_error("Unable to update checklist. Please try again.".to_string());
             }
         }
 
@@ -233,39 +1485,6 @@ impl App {
         Ok(())
     }
 
-    /// Toggles between showing all todos and only pending todos
-    pub fn toggle_show_all(&mut self) {
-        self.show_all_todos = !self.show_all_todos;
-        self.apply_filters();
-        let status = if self.show_all_todos {
-            "all todos"
-        } else {
-            "pending todos"
-        };
-        self.show_success(format!("Now showing {status}"));
-    }
-
-    /// Sets priority filter (None to clear filter)
-    pub fn set_priority_filter(&mut self, priority: Option<i32>) {
-        self.filter_priority = priority;
-        self.apply_filters();
-        let msg = match priority {
-            Some(1) => "Filtering by low priority".to_string(),
-            Some(2) => "Filtering by medium priority".to_string(),
-            Some(3) => "Filtering by high priority".to_string(),
-            None => "Priority filter cleared".to_string(),
-            _ => "Invalid priority".to_string(),
-        };
-        self.show_success(msg);
-    }
-
-    /// Shows detailed view of currently selected todo
-    pub fn show_todo_detail(&mut self) {
-        if self.selected_todo.is_some() {
-            self.current_screen = AppScreen::TodoDetail;
-        }
-    }
-
     pub fn next_todo(&mut self) {
         if !self.filtered_todos.is_empty() {
             let i = match self.selected_todo {
@@ -311,14 +1530,230 @@ impl App {
     ///
     /// Note: Errors are shown to the user via UI messages and don't propagate
     pub async fn load_todos(&mut self) -> Result<()> {
+        self.cancel_background_refresh();
         self.loading = true;
         self.clear_messages();
 
-        match self.api_client.list_todos(None, None).await {
+        // Pick up local metadata changed by pacli while the TUI was open
+        self.local_state = LocalState::load().unwrap_or_default();
+
+        let result = cache::sync(&self.api_client).await;
+        let modifiers = presence::fetch(&self.api_client).await;
+        self.apply_loaded_todos((result, modifiers));
+
+        self.loading = false;
+        Ok(())
+    }
+
+    /// Starts fetching the todo list in the background
+    ///
+    /// The current (e.g. cached) list stays on screen meanwhile; the loading
+    /// overlay is only shown when there is nothing to show yet.
+    pub fn start_background_refresh(&mut self) {
+        if self.refresh_rx.is_some() {
+            return;
+        }
+        self.refresh_cancel = CancellationToken::new();
+        let client = self
+            .api_client
+            .with_cancellation(self.refresh_cancel.clone());
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let result = cache::sync(&client).await;
+            let _ = tx.send((result, presence::fetch(&client).await));
+        });
+        self.refresh_rx = Some(rx);
+        self.loading = self.todos.is_empty();
+    }
+
+    /// Stops a running background refresh and drops its result
+    ///
+    /// Called before anything that replaces the list, so an older response
+    /// arriving later can't overwrite newer state.
+    pub fn cancel_background_refresh(&mut self) {
+        if self.refresh_rx.take().is_some() {
+            self.refresh_cancel.cancel();
+            self.loading = false;
+        }
+    }
+
+    /// Applies the result of a background refresh once it has arrived
+    ///
+    /// Returns `true` if the refresh finished, i.e. the screen needs a redraw.
+    pub fn poll_background_refresh(&mut self) -> bool {
+        let Some(rx) = &mut self.refresh_rx else {
+            return false;
+        };
+        match rx.try_recv() {
+            Ok(refreshed) => {
+                self.refresh_rx = None;
+                self.local_state = LocalState::load().unwrap_or_default();
+                self.apply_loaded_todos(refreshed);
+                self.loading = false;
+                true
+            }
+            Err(tokio::sync::oneshot::error::TryRecvError::Empty) => false,
+            Err(tokio::sync::oneshot::error::TryRecvError::Closed) => {
+                self.refresh_rx = None;
+                self.loading = false;
+                true
+            }
+        }
+    }
+
+    /// Checks the connection in the background when the last check is old enough
+    ///
+    /// The first call (right after startup) also warms up the connection, so
+    /// the first real request doesn't pay for DNS, connecting and the TLS
+    /// handshake. Call it from the event loop; it also applies finished checks
+    /// and returns `true` if the connection state changed.
+    pub fn check_connection(&mut self) -> bool {
+        let mut changed = false;
+        if let Some(rx) = &mut self.ping_rx {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.ping_rx = None;
+                    let connection = match result {
+                        Ok(latency) => ConnectionState::Online(latency),
+                        Err(err) => {
+                            log::debug!("Connection check failed: {err}");
+                            ConnectionState::Offline
+                        }
+                    };
+                    changed = connection != self.connection;
+                    match (self.connection, connection) {
+                        (ConnectionState::Online(_), ConnectionState::Offline) => {
+                            self.notifications
+                                .push(NotificationKind::Sync, "Server unreachable".to_string());
+                        }
+                        (ConnectionState::Offline, ConnectionState::Online(_)) => {
+                            self.notifications
+                                .push(NotificationKind::Sync, "Server reachable again".to_string());
+                        }
+                        _ => {}
+                    }
+                    self.connection = connection;
+                }
+                Err(tokio::sync::oneshot::error::TryRecvError::Empty) => return false,
+                Err(tokio::sync::oneshot::error::TryRecvError::Closed) => self.ping_rx = None,
+            }
+        }
+
+        if self
+            .last_connection_check
+            .is_some_and(|last| last.elapsed() < CONNECTION_CHECK_INTERVAL)
+        {
+            return changed;
+        }
+        self.last_connection_check = Some(Instant::now());
+        let client = self.api_client.with_timeout(CONNECTION_CHECK_TIMEOUT);
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let _ = tx.send(client.ping().await);
+        });
+        self.ping_rx = Some(rx);
+        changed
+    }
+
+    /// Updates the due-soon reminders, returning `true` if the banner changed
+    ///
+    /// A todo is shown from `reminder_window` minutes before its due date
+    /// until as long after it, unless it is completed, archived or its
+    /// reminder was dismissed. Each todo rings the bell (with `reminder_bell`)
+    /// once per session.
+    pub fn check_reminders(&mut self, now: i64) -> bool {
+        let window = self
+            .config
+            .reminder_window
+            .unwrap_or(DEFAULT_REMINDER_WINDOW_MINS)
+            .saturating_mul(60);
+        let window = i64::try_from(window).unwrap_or(i64::MAX);
+
+        let mut due: Vec<(&str, &str, i64)> = self
+            .todos
+            .iter()
+            .filter(|todo| !todo.completed && !self.local_state.is_archived(&todo.id))
+            .filter_map(|todo| {
+                let due_date = todo.due_date?;
+                let in_window = due_date.saturating_sub(now) <= window
+                    && now.saturating_sub(due_date) <= window;
+                (in_window && !self.local_state.is_reminder_dismissed(&todo.id, due_date))
+                    .then_some((todo.id.as_str(), todo.title.as_str(), due_date))
+            })
+            .collect();
+        due.sort_by_key(|(_, _, due_date)| *due_date);
+
+        let mut notification = None;
+        for (id, title, _) in &due {
+            if self.reminded.insert((*id).to_string()) {
+                self.notifications
+                    .push(NotificationKind::Reminder, format!("Due soon: {title}"));
+                if self.config.reminder_bell {
+                    notification = Some(format!("Due soon: {title}"));
+                }
+            }
+        }
+        let reminders: Vec<String> = due.iter().map(|(id, _, _)| (*id).to_string()).collect();
+
+        if notification.is_some() {
+            self.pending_notification = notification;
+        }
+        let changed = reminders != self.reminders;
+        self.reminders = reminders;
+        changed
+    }
+
+    /// Dismisses the reminder shown in the banner (the soonest one)
+    pub fn dismiss_reminder(&mut self) {
+        let Some(todo) = self
+            .reminders
+            .first()
+            .and_then(|id| self.todos.iter().find(|todo| &todo.id == id))
+        else {
+            return;
+        };
+        let (id, title) = (todo.id.clone(), todo.title.clone());
+        let Some(due_date) = todo.due_date else {
+            return;
+        };
+
+        self.local_state.dismiss_reminder(&id, due_date);
+        if self.local_state.save().is_err() {
+            self.show_error("Failed to save the dismissed reminder".to_string());
+            return;
+        }
+        self.reminders.remove(0);
+        self.show_success(format!("Reminder dismissed: {title}"));
+    }
+
+    /// Returns `true` while a background refresh is running
+    #[must_use]
+    pub fn is_refreshing(&self) -> bool {
+        self.refresh_rx.is_some()
+    }
+
+    fn apply_loaded_todos(&mut self, (result, modifiers): Refreshed) {
+        if let Some(modifiers) = modifiers {
+            self.presence.set_modifiers(modifiers);
+        }
+        match result {
             Ok(todos) => {
+                // Keep the selection on the same todo rather than the same row
+                let selected_id = self.selected_todo_id().map(str::to_string);
+                let now = chrono::Utc::now().timestamp();
+                self.presence.note_changes(&self.todos, &todos, now);
                 self.todos = todos;
                 self.apply_filters(); // Apply current filters
-                                      // Safe bounds checking without unwrap and sync list_state
+                if self.pending_selection.is_some() {
+                    self.apply_pending_selection();
+                    if self.pending_selection.take().is_some() {
+                        // Not in the current list, so gone or filtered out
+                        self.pending_detail = false;
+                    }
+                } else if let Some(id) = selected_id {
+                    self.select_todo_by_id(&id);
+                }
+                // Safe bounds checking without unwrap and sync list_state
                 if let Some(selected_index) = self.selected_todo {
                     if selected_index >= self.filtered_todos.len() {
                         let new_selection = if self.filtered_todos.is_empty() {
@@ -334,11 +1769,15 @@ impl App {
                     self.selected_todo = Some(0);
                     self.list_state.select(Some(0));
                 }
-                self.show_success(format!(
+                let mut message = format!(
                     "Loaded {} todo(s), showing {}",
                     self.todos.len(),
                     self.filtered_todos.len()
-                ));
+                );
+                if let Some(latest) = self.presence.latest(now) {
+                    message.push_str(&format!(" · {latest}"));
+                }
+                self.show_sync(message);
             }
             Err(_) => {
                 self.show_error(
@@ -346,9 +1785,6 @@ impl App {
                 );
             }
         }
-
-        self.loading = false;
-        Ok(())
     }
 
     /// Toggles the completion status of the currently selected todo
@@ -368,15 +1804,25 @@ impl App {
                 self.loading = true;
                 self.clear_messages();
 
-                match self.api_client.toggle_todo(&todo_id).await {
+                let result = self.api_client.toggle_todo(&todo_id).await;
+                match activity::track_todo(ActivityEntry::new(Source::Tui, "toggle"), result) {
                     Ok(updated_todo) => {
                         // Update in main todos list
                         if let Some(main_index) = self.todos.iter().position(|t| t.id == todo_id) {
                             self.todos[main_index] = updated_todo.clone();
                         }
+                        let completed = updated_todo.completed;
                         // Update in filtered list
                         self.filtered_todos[index] = updated_todo;
-                        self.show_success("Todo toggled successfully".to_string());
+
+                        if completed && self.local_state.is_blocked(&todo_id, &self.todos) {
+                            self.show_error(
+                                "Completed, but this todo is still blocked by unfinished prerequisites"
+                                    .to_string(),
+                            );
+                        } else {
+                            self.show_success("Todo toggled successfully".to_string());
+                        }
                     }
                     Err(_) => {
                         self.show_error(
@@ -409,7 +1855,10 @@ impl App {
                 self.loading = true;
                 self.clear_messages();
 
-                match self.api_client.delete_todo(&todo_id).await {
+                let entry = ActivityEntry::new(Source::Tui, "delete")
+                    .with_id(&todo_id)
+                    .with_title(&todo_title);
+                match activity::track(entry, self.api_client.delete_todo(&todo_id).await) {
                     Ok(()) => {
                         // Remove from main todos list
                         self.todos.retain(|t| t.id != todo_id);
@@ -447,22 +1896,9 @@ impl App {
         if let Some(index) = self.selected_todo {
             if let Some(todo) = self.filtered_todos.get(index) {
                 // Pre-populate the form with current todo data
-                self.input_form.title = todo.title.clone();
-                self.input_form.description = todo.description.clone().unwrap_or_default();
-                self.input_form.priority = todo.priority;
-
-                // Pre-populate due date if present
-                self.input_form.due_date = if let Some(due_ts) = todo.due_date {
-                    chrono::DateTime::from_timestamp(due_ts, 0)
-                        .map(|dt| {
-                            dt.with_timezone(&chrono::Local)
-                                .format("%Y-%m-%d %H:%M:%S")
-                                .to_string()
-                        })
-                        .unwrap_or_default()
-                } else {
-                    String::new()
-                };
+                self.input_form = InputForm::from_todo(todo);
+                // Kept to notice changes made elsewhere while editing
+                self.edit_base = Some(todo.clone());
 
                 self.current_screen = AppScreen::EditTodo;
                 self.input_mode = InputMode::Editing;
@@ -472,6 +1908,54 @@ impl App {
         Ok(())
     }
 
+    /// Asks the event loop to open the selected todo's title and description in `$EDITOR`
+    pub fn edit_selected_in_editor(&mut self) {
+        let Some(todo) = self
+            .selected_todo
+            .and_then(|index| self.filtered_todos.get(index))
+        else {
+            return;
+        };
+        self.pending_editor = Some(editor::to_text(&todo.title, todo.description.as_deref()));
+        // Saving goes through the edit form, which also checks for conflicts
+        self.input_form = InputForm::from_todo(todo);
+        self.edit_base = Some(todo.clone());
+        self.editor_screen = self.current_screen;
+    }
+
+    /// Saves what was written in `$EDITOR`, given the result of [`editor::edit`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if updating the todo fails unexpectedly; failed
+    /// requests are shown to the user
+    pub async fn finish_editor(&mut self, edited: Result<Option<String>>) -> Result<()> {
+        let text = match edited {
+            Ok(Some(text)) => text,
+            Ok(None) => {
+                self.edit_base = None;
+                self.show_success("No changes made".to_string());
+                return Ok(());
+            }
+            Err(err) => {
+                self.edit_base = None;
+                self.show_error(format!("{err:#}"));
+                return Ok(());
+            }
+        };
+        let (title, description) = editor::parse(&text);
+        self.input_form.title = title;
+        self.input_form.description = description;
+        self.current_screen = AppScreen::EditTodo;
+        self.input_mode = InputMode::Editing;
+        self.update_selected_todo().await?;
+        // Saved without a conflict to resolve: back to where editing started
+        if self.current_screen == AppScreen::TodoList && self.conflict.is_none() {
+            self.current_screen = self.editor_screen;
+        }
+        Ok(())
+    }
+
     /// Updates the currently selected todo with form data
     ///
     /// # Errors
@@ -491,6 +1975,14 @@ impl App {
         if let Some(index) = self.selected_todo {
             if let Some(todo) = self.filtered_todos.get(index) {
                 let todo_id = todo.id.clone();
+                if let Some(conflict) = self.detect_conflict(&todo_id).await {
+                    self.conflict = Some(conflict);
+                    self.input_mode = InputMode::Normal;
+                    self.show_error(
+                        "This todo was changed elsewhere while you were editing it".to_string(),
+                    );
+                    return Ok(());
+                }
                 self.loading = true;
                 self.clear_messages();
 
@@ -516,7 +2008,8 @@ impl App {
                     due_date,
                 };
 
-                match self.api_client.update_todo(&todo_id, update_request).await {
+                let result = self.api_client.update_todo(&todo_id, update_request).await;
+                match activity::track_todo(ActivityEntry::new(Source::Tui, "update"), result) {
                     Ok(updated_todo) => {
                         // Update in main todos list
                         if let Some(main_index) = self.todos.iter().position(|t| t.id == todo_id) {
@@ -525,6 +2018,7 @@ impl App {
                         // Update in filtered list
                         self.filtered_todos[index] = updated_todo.clone();
                         self.input_form.clear();
+                        self.edit_base = None;
                         self.current_screen = AppScreen::TodoList;
                         self.input_mode = InputMode::Normal;
                         self.show_success(format!("Updated: {title}", title = updated_todo.title));
@@ -540,6 +2034,75 @@ impl App {
         Ok(())
     }
 
+    /// Checks whether the todo being edited changed on the server since editing started
+    ///
+    /// Returns the conflict only if saving would undo one of those changes.
+    /// If the server can't be asked, the save goes ahead as before.
+    async fn detect_conflict(&self, todo_id: &str) -> Option<EditConflict> {
+        let base = self.edit_base.as_ref().filter(|base| base.id == todo_id)?;
+        let server = match self.api_client.get_todo(todo_id).await {
+            Ok(server) => server,
+            Err(err) => {
+                log::debug!("Could not check for conflicting changes: {err}");
+                return None;
+            }
+        };
+        if server.updated_at == base.updated_at {
+            return None;
+        }
+        let conflict = EditConflict::new(base, self.input_form.clone(), server);
+        conflict.needs_resolution().then_some(conflict)
+    }
+
+    /// Handles the merge dialog: keep mine, take the server's version, or merge field by field
+    async fn handle_conflict_key(&mut self, key: Key) -> Result<()> {
+        let Some(conflict) = self.conflict.as_mut() else {
+            return Ok(());
+        };
+        match key {
+            Key::Up | Key::Char('k') => conflict.previous_field(),
+            Key::Down | Key::Char('j') => conflict.next_field(),
+            Key::Char(' ') | Key::Left | Key::Right | Key::Tab => {
+                conflict.toggle_selected();
+            }
+            Key::Esc => {
+                // Back to the form; saving checks again
+                self.conflict = None;
+                self.input_mode = InputMode::Editing;
+            }
+            Key::Char('t') => {
+                let server_todo = conflict.server_todo.clone();
+                self.conflict = None;
+                self.edit_base = None;
+                if let Some(todo) = self.todos.iter_mut().find(|t| t.id == server_todo.id) {
+                    *todo = server_todo.clone();
+                }
+                if let Some(todo) = self
+                    .filtered_todos
+                    .iter_mut()
+                    .find(|t| t.id == server_todo.id)
+                {
+                    *todo = server_todo;
+                }
+                self.input_form.clear();
+                self.current_screen = AppScreen::TodoList;
+                self.show_success("Kept the version from the server".to_string());
+            }
+            Key::Char('m') | Key::Enter => {
+                if key == Key::Enter {
+                    self.input_form = conflict.merged();
+                }
+                // Saving against the server's version now overwrites it
+                self.edit_base = Some(conflict.server_todo.clone());
+                self.conflict = None;
+                self.input_mode = InputMode::Editing;
+                self.update_selected_todo().await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     /// Creates a new todo using the input form content
     ///
     /// # Errors
@@ -568,7 +2131,8 @@ impl App {
             }
         };
 
-        match self.api_client.create_todo(request).await {
+        let entry = ActivityEntry::new(Source::Tui, "add").with_title(&request.title);
+        match activity::track_todo(entry, self.api_client.create_todo(request).await) {
             Ok(todo) => {
                 self.todos.push(todo.clone());
                 self.apply_filters(); // Reapply filters to include new todo
@@ -591,84 +2155,239 @@ impl App {
         Ok(())
     }
 
-    /// Handles keyboard input events
+    /// Handles an input event from any frontend
+    ///
+    /// Ctrl+C quits and Ctrl+R switches the search between text and regex;
+    /// other keys go to [`Self::handle_key`], Ctrl or not, and clicks to
+    /// [`Self::handle_click`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if key handling fails
+    pub async fn handle_input(&mut self, input: Input) -> Result<()> {
+        match input {
+            Input::Key {
+                key: Key::Char('c'),
+                ctrl: true,
+            } => self.handle_ctrl_c(),
+            // Plain `r` has to stay typeable in the query
+            Input::Key {
+                key: Key::Char('r'),
+                ctrl: true,
+            } if self.current_screen == AppScreen::Search => self.toggle_search_regex(),
+            Input::Key { key, .. } => return self.handle_key(key).await,
+            Input::Click { column, row } => self.handle_click(column, row),
+        }
+        Ok(())
+    }
+
+    /// Handles a key press
     ///
     /// # Errors
     ///
     /// Returns an error if key handling fails
-    pub async fn handle_key(&mut self, key: crossterm::event::KeyCode) -> Result<()> {
+    pub async fn handle_key(&mut self, key: Key) -> Result<()> {
         self.clear_messages();
 
+        if key == Key::F(12) {
+            self.show_debug_pane = !self.show_debug_pane;
+            return Ok(());
+        }
+
         match self.input_mode {
             InputMode::Normal => self.handle_normal_key(key).await,
             InputMode::Editing => self.handle_editing_key(key).await,
         }
     }
 
-    async fn handle_normal_key(&mut self, key: crossterm::event::KeyCode) -> Result<()> {
-        use crossterm::event::KeyCode;
+    async fn handle_normal_key(&mut self, key: Key) -> Result<()> {
+        if self.tour.is_some() {
+            self.handle_tour_key(key);
+            return Ok(());
+        }
+        if self.conflict.is_some() {
+            return self.handle_conflict_key(key).await;
+        }
+        if self.url_picker.is_some() {
+            self.handle_url_picker_key(key);
+            return Ok(());
+        }
+        if self.history.is_some() {
+            self.handle_history_key(key);
+            return Ok(());
+        }
+        if self.export_prompt.is_some() {
+            self.handle_export_prompt_key(key);
+            return Ok(());
+        }
+        if self.filter_popup.is_some() {
+            self.handle_filter_popup_key(key);
+            return Ok(());
+        }
+        if self.yank_pending {
+            // Any key other than a link number cancels the copy
+            self.yank_pending = false;
+            if let Key::Char(number @ '1'..='9') = key {
+                self.copy_detail_link(number);
+            }
+            return Ok(());
+        }
 
         match self.current_screen {
             AppScreen::TodoList => match key {
-                KeyCode::Char('q') | KeyCode::Esc => {
+                Key::Esc if self.multi_select => self.toggle_multi_select(),
+                Key::Char(' ') if self.multi_select => self.toggle_mark_selected(),
+                Key::Char('x') if self.multi_select => self.start_export(),
+                Key::Char('V') => self.toggle_multi_select(),
+                Key::Esc if self.filter_due_day.is_some() => self.close_week_day(),
+                Key::Char('q') | Key::Esc => {
                     self.quit();
                 }
-                KeyCode::Char('r') => {
+                Key::Char('r') => {
                     self.load_todos().await?;
                 }
-                KeyCode::Char('n' | 'a') => {
+                Key::Char('n' | 'a') => {
                     self.current_screen = AppScreen::AddTodo;
                     self.input_mode = InputMode::Editing;
                     self.input_form.clear();
                 }
-                KeyCode::Char('e') => {
+                Key::Char('e') => {
                     self.start_edit_selected_todo().await?;
                 }
-                KeyCode::Char('h' | '?') => {
+                Key::Char('E') => self.edit_selected_in_editor(),
+                Key::Char('h' | '?') => {
                     self.current_screen = AppScreen::Help;
                 }
-                KeyCode::Char('s') => {
+                Key::Char('s') => {
                     self.current_screen = AppScreen::Settings;
                 }
-                KeyCode::Char('/') => {
+                Key::Char('/') => {
                     self.start_search();
                 }
-                KeyCode::Char('f') => {
+                Key::Char('f') => {
                     self.toggle_show_all();
                 }
-                KeyCode::Char('1') => {
+                Key::Char('F') => self.open_filter_popup(),
+                Key::Char('N') => self.open_notifications(),
+                Key::Char('A') => {
+                    self.toggle_archived_view();
+                }
+                Key::Char('x') => {
+                    self.toggle_archive_selected();
+                }
+                Key::Char('P') => self.toggle_pin_selected(),
+                Key::Char('1') => {
                     self.set_priority_filter(Some(1));
                 }
-                KeyCode::Char('2') => {
+                Key::Char('2') => {
                     self.set_priority_filter(Some(2));
                 }
-                KeyCode::Char('3') => {
+                Key::Char('3') => {
                     self.set_priority_filter(Some(3));
                 }
-                KeyCode::Char('0') => {
+                Key::Char('0') => {
                     self.set_priority_filter(None);
                 }
-                KeyCode::Char('v') => {
-                    self.show_todo_detail();
+                Key::Char('D') => self.dismiss_reminder(),
+                Key::Char('o') => self.toggle_overdue_filter(),
+                Key::Char('T') => self.open_today(),
+                Key::Char('S') => self.cycle_sort(),
+                Key::Char('w') => self.open_week_view(),
+                Key::Char('R') => {
+                    self.rerun_last_search().await?;
+                }
+                Key::Char('v') => {
+                    self.show_todo_detail().await;
+                }
+                Key::Char('p') => {
+                    self.toggle_pomodoro();
                 }
-                KeyCode::Up | KeyCode::Char('k') => self.previous_todo(),
-                KeyCode::Down | KeyCode::Char('j') => self.next_todo(),
-                KeyCode::Enter | KeyCode::Char(' ') => {
+                Key::Up | Key::Char('k') => self.previous_todo(),
+                Key::Down | Key::Char('j') => self.next_todo(),
+                Key::Enter | Key::Char(' ') => {
                     self.toggle_selected_todo().await?;
                 }
-                KeyCode::Char('d') => {
+                Key::Char('d') => {
                     self.delete_selected_todo().await?;
                 }
                 _ => {}
             },
-            AppScreen::Help | AppScreen::Settings | AppScreen::TodoDetail => match key {
-                KeyCode::Esc | KeyCode::Char('q') => {
+            AppScreen::TodoDetail => match key {
+                Key::Esc | Key::Char('q') => {
+                    self.current_screen = AppScreen::TodoList;
+                }
+                Key::Char('E') => self.edit_selected_in_editor(),
+                Key::Up | Key::Char('k') => self.scroll_detail(-1),
+                Key::Down | Key::Char('j') => self.scroll_detail(1),
+                Key::PageUp => self.page_detail(false),
+                Key::PageDown => self.page_detail(true),
+                Key::Home | Key::Char('g') => self.detail_scroll = 0,
+                Key::End | Key::Char('G') => self.detail_scroll = self.detail_max_scroll,
+                Key::BackTab => self.previous_checklist_item(),
+                Key::Tab => self.next_checklist_item(),
+                Key::Enter | Key::Char(' ') => {
+                    self.toggle_checklist_item().await?;
+                }
+                Key::Char('O') => self.open_selected_urls(),
+                Key::Char('H') => self.open_history().await,
+                Key::Char(number @ '1'..='9') => self.open_detail_link(number),
+                Key::Char('y') => self.yank_pending = true,
+                Key::Char(']') => self.scroll_comments(5),
+                Key::Char('[') => self.scroll_comments(-5),
+                _ => {}
+            },
+            AppScreen::Week => match key {
+                Key::Esc if self.moving_todo.is_some() => self.cancel_move(),
+                Key::Esc | Key::Char('q' | 'w') => {
+                    self.moving_todo = None;
+                    self.current_screen = AppScreen::TodoList;
+                }
+                Key::Left => self.shift_week(-1),
+                Key::Right => self.shift_week(1),
+                Key::Char('h') => self.select_week_day(-1),
+                Key::Char('l') => self.select_week_day(1),
+                Key::Char('t') => self.open_week_view(),
+                Key::Up | Key::Char('k') => self.previous_week_todo(),
+                Key::Down | Key::Char('j') => self.next_week_todo(),
+                Key::Enter if self.moving_todo.is_some() => {
+                    self.toggle_move_week_todo().await?;
+                }
+                Key::Enter => self.open_week_day(),
+                Key::Char('m') => {
+                    self.toggle_move_week_todo().await?;
+                }
+                _ => {}
+            },
+            AppScreen::Notifications => match key {
+                Key::Esc | Key::Char('q' | 'N') => {
+                    // Whatever arrived while reading has been seen too
+                    self.notifications.mark_read();
+                    self.current_screen = AppScreen::TodoList;
+                }
+                Key::Up | Key::Char('k') => {
+                    self.notification_scroll = self.notification_scroll.saturating_sub(1);
+                }
+                Key::Down | Key::Char('j') => {
+                    let last = self.notifications.len().saturating_sub(1);
+                    self.notification_scroll = self
+                        .notification_scroll
+                        .saturating_add(1)
+                        .min(u16::try_from(last).unwrap_or(u16::MAX));
+                }
+                Key::Char('c') => self.notifications.clear(),
+                _ => {}
+            },
+            AppScreen::Help | AppScreen::Settings => match key {
+                Key::Esc | Key::Char('q') => {
                     self.current_screen = AppScreen::TodoList;
                 }
+                Key::Char('t') if self.current_screen == AppScreen::Settings => {
+                    self.start_tour();
+                }
                 _ => {}
             },
             AppScreen::AddTodo | AppScreen::EditTodo | AppScreen::Search => {
-                if key == KeyCode::Esc {
+                if key == Key::Esc {
                     self.current_screen = AppScreen::TodoList;
                     self.input_mode = InputMode::Normal;
                     self.input_form.clear();
@@ -680,16 +2399,15 @@ impl App {
         Ok(())
     }
 
-    async fn handle_editing_key(&mut self, key: crossterm::event::KeyCode) -> Result<()> {
-        use crossterm::event::KeyCode;
-
+    async fn handle_editing_key(&mut self, key: Key) -> Result<()> {
         match key {
-            KeyCode::Esc => {
+            Key::Esc => {
                 self.current_screen = AppScreen::TodoList;
                 self.input_mode = InputMode::Normal;
                 self.input_form.clear();
+                self.edit_base = None;
             }
-            KeyCode::Enter => match self.current_screen {
+            Key::Enter => match self.current_screen {
                 AppScreen::AddTodo => {
                     self.create_todo().await?;
                 }
@@ -701,20 +2419,24 @@ impl App {
                 }
                 _ => {}
             },
-            KeyCode::Tab | KeyCode::Down => {
+            Key::Up if self.current_screen == AppScreen::Search => self.recall_search(true),
+            Key::Down if self.current_screen == AppScreen::Search => {
+                self.recall_search(false);
+            }
+            Key::Tab | Key::Down => {
                 self.input_form.next_field();
             }
-            KeyCode::BackTab | KeyCode::Up => {
+            Key::BackTab | Key::Up => {
                 self.input_form.previous_field();
             }
-            KeyCode::Char(c) => {
+            Key::Char(c) => {
                 if self.current_screen == AppScreen::Search {
                     self.search_query.push(c);
                 } else {
                     self.input_form.handle_char(c);
                 }
             }
-            KeyCode::Backspace => {
+            Key::Backspace => {
                 if self.current_screen == AppScreen::Search {
                     self.search_query.pop();
                 } else {
@@ -1,5 +1,7 @@
 //! TUI reusable components
 
+use crate::config::PriorityLabels;
+use crate::term::symbols;
 use crate::ID_DISPLAY_LENGTH;
 use pali_types::Todo;
 use ratatui::{
@@ -72,7 +74,11 @@ impl TodoListWidget {
             .todos
             .iter()
             .map(|todo| {
-                let status = if todo.completed { "✓" } else { "○" };
+                let status = if todo.completed {
+                    symbols().done
+                } else {
+                    symbols().pending
+                };
                 let id_short = if todo.id.len() > ID_DISPLAY_LENGTH {
                     &todo.id[..ID_DISPLAY_LENGTH]
                 } else {
@@ -121,6 +127,7 @@ impl TodoListWidget {
 }
 
 /// Input form component for adding/editing todos
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct InputForm {
     pub title: String,
     pub description: String,
@@ -137,6 +144,25 @@ pub enum InputField {
     DueDate,
 }
 
+impl InputField {
+    pub const ALL: [Self; 4] = [
+        Self::Title,
+        Self::Description,
+        Self::Priority,
+        Self::DueDate,
+    ];
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Title => "Title",
+            Self::Description => "Description",
+            Self::Priority => "Priority",
+            Self::DueDate => "Due date",
+        }
+    }
+}
+
 impl InputForm {
     #[must_use]
     pub fn new() -> Self {
@@ -149,6 +175,48 @@ impl InputForm {
         }
     }
 
+    /// Creates a form filled in with the values of `todo`, for editing it
+    #[must_use]
+    pub fn from_todo(todo: &Todo) -> Self {
+        let due_date = todo
+            .due_date
+            .and_then(|due_ts| chrono::DateTime::from_timestamp(due_ts, 0))
+            .map(|dt| {
+                dt.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        Self {
+            title: todo.title.clone(),
+            description: todo.description.clone().unwrap_or_default(),
+            priority: todo.priority,
+            due_date,
+            current_field: InputField::Title,
+        }
+    }
+
+    /// The text of `field`, as typed in the form
+    #[must_use]
+    pub fn value(&self, field: InputField) -> String {
+        match field {
+            InputField::Title => self.title.trim().to_string(),
+            InputField::Description => self.description.trim().to_string(),
+            InputField::Priority => self.priority.to_string(),
+            InputField::DueDate => self.due_date.trim().to_string(),
+        }
+    }
+
+    /// Copies `field` from `other`
+    pub fn copy_field(&mut self, other: &Self, field: InputField) {
+        match field {
+            InputField::Title => self.title.clone_from(&other.title),
+            InputField::Description => self.description.clone_from(&other.description),
+            InputField::Priority => self.priority = other.priority,
+            InputField::DueDate => self.due_date.clone_from(&other.due_date),
+        }
+    }
+
     pub fn next_field(&mut self) {
         self.current_field = match self.current_field {
             InputField::Title => InputField::Description,
@@ -214,7 +282,7 @@ impl InputForm {
         self.current_field = InputField::Title;
     }
 
-    pub fn render(&self, frame: &mut Frame, area: Rect) {
+    pub fn render(&self, frame: &mut Frame, area: Rect, labels: &PriorityLabels) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -253,21 +321,25 @@ impl InputForm {
         frame.render_widget(desc_widget, chunks[1]);
 
         // Priority field
-        let priority_text = match self.priority {
-            1 => "1 - Low",
-            3 => "3 - High",
-            _ => "2 - Medium", // Default for 2 or any invalid value
+        // Invalid values display as medium, like everywhere else
+        let level = if (1..=3).contains(&self.priority) {
+            self.priority
+        } else {
+            2
         };
+        let priority_text = format!("{level} - {}", labels.label(level));
         let priority_style = if self.current_field == InputField::Priority {
             Style::default().fg(Color::Yellow)
         } else {
             Style::default().fg(Color::White)
         };
-        let priority_widget = Paragraph::new(priority_text).style(priority_style).block(
-            Block::default()
-                .title("Priority (1-3)")
-                .borders(Borders::ALL),
-        );
+        let priority_widget = Paragraph::new(priority_text.as_str())
+            .style(priority_style)
+            .block(
+                Block::default()
+                    .title("Priority (1-3)")
+                    .borders(Borders::ALL),
+            );
         frame.render_widget(priority_widget, chunks[2]);
 
         // Due date field
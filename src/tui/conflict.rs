@@ -0,0 +1,148 @@
+//! Resolving edits that raced with a change on the server
+//!
+//! When a todo was updated elsewhere between opening the Edit screen and
+//! saving, the form is compared field by field with the version the edit
+//! started from (the base) and the server's current one. Fields changed
+//! on one side only take that side's value; fields changed on both are
+//! conflicts, which default to the local edit.
+
+use crate::tui::components::{InputField, InputForm};
+use pali_types::Todo;
+
+/// Which version a field of the merged todo comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Mine,
+    Server,
+}
+
+#[derive(Debug, Clone)]
+pub struct EditConflict {
+    /// The todo as it was when editing started
+    pub base: InputForm,
+    /// The edited form
+    pub mine: InputForm,
+    /// The todo as it is now on the server
+    pub server: InputForm,
+    pub server_todo: Todo,
+    /// Chosen side per field, in [`InputField::ALL`] order
+    pub choices: [Side; 4],
+    /// Highlighted field in the merge dialog
+    pub selected: usize,
+}
+
+impl EditConflict {
+    /// Compares an edit with the server's version, pre-selecting a side per field
+    #[must_use]
+    pub fn new(base: &Todo, mine: InputForm, server_todo: Todo) -> Self {
+        let base = InputForm::from_todo(base);
+        let server = InputForm::from_todo(&server_todo);
+        let choices = InputField::ALL.map(|field| {
+            if mine.value(field) == base.value(field) {
+                Side::Server
+            } else {
+                Side::Mine
+            }
+        });
+        Self {
+            base,
+            mine,
+            server,
+            server_todo,
+            choices,
+            selected: 0,
+        }
+    }
+
+    /// Returns `true` if both sides changed `field`, differently
+    #[must_use]
+    pub fn is_conflict(&self, field: InputField) -> bool {
+        let base = self.base.value(field);
+        let mine = self.mine.value(field);
+        let server = self.server.value(field);
+        mine != base && server != base && mine != server
+    }
+
+    /// Returns `true` if saving the edit as is would undo a change made on the server
+    #[must_use]
+    pub fn needs_resolution(&self) -> bool {
+        InputField::ALL.into_iter().any(|field| {
+            let server = self.server.value(field);
+            server != self.base.value(field) && server != self.mine.value(field)
+        })
+    }
+
+    pub fn next_field(&mut self) {
+        self.selected = (self.selected + 1) % InputField::ALL.len();
+    }
+
+    pub fn previous_field(&mut self) {
+        self.selected = self
+            .selected
+            .checked_sub(1)
+            .unwrap_or(InputField::ALL.len() - 1);
+    }
+
+    /// Switches the highlighted field to the other side
+    pub fn toggle_selected(&mut self) {
+        let choice = &mut self.choices[self.selected];
+        *choice = match choice {
+            Side::Mine => Side::Server,
+            Side::Server => Side::Mine,
+        };
+    }
+
+    /// The form with each field taken from the chosen side
+    #[must_use]
+    pub fn merged(&self) -> InputForm {
+        let mut merged = self.mine.clone();
+        for (field, side) in InputField::ALL.into_iter().zip(self.choices) {
+            if side == Side::Server {
+                merged.copy_field(&self.server, field);
+            }
+        }
+        merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn todo(title: &str, description: Option<&str>, priority: i32) -> Todo {
+        testing::todo("a")
+            .title(title)
+            .description(description)
+            .priority(priority)
+            .build()
+    }
+
+    #[test]
+    fn test_three_way_merge() {
+        let base = todo("Buy milk", None, 2);
+        let mut mine = InputForm::from_todo(&base);
+        mine.title = "Buy oat milk".to_string();
+        mine.priority = 3;
+        let server = todo("Buy milk", Some("at the corner shop"), 1);
+
+        let mut conflict = EditConflict::new(&base, mine, server);
+        // Title changed here only, description on the server only, priority on both
+        assert_eq!(
+            conflict.choices,
+            [Side::Mine, Side::Server, Side::Mine, Side::Server]
+        );
+        assert!(conflict.is_conflict(InputField::Priority));
+        assert!(!conflict.is_conflict(InputField::Title));
+        assert!(conflict.needs_resolution());
+
+        let merged = conflict.merged();
+        assert_eq!(merged.title, "Buy oat milk");
+        assert_eq!(merged.description, "at the corner shop");
+        assert_eq!(merged.priority, 3);
+
+        conflict.selected = 2;
+        conflict.toggle_selected();
+        assert_eq!(conflict.merged().priority, 1);
+    }
+}
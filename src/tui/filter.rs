@@ -0,0 +1,225 @@
+//! Composable list filters and the quick filter popup (`F`)
+//!
+//! Options checked within a facet are alternatives (high *or* medium
+//! priority); every facet with something checked has to match. A facet
+//! with nothing checked doesn't filter.
+
+use crate::config::PriorityLabels;
+use crate::due::DueBucket;
+use pali_types::Todo;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Pending,
+    Completed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Filters {
+    pub statuses: BTreeSet<Status>,
+    pub priorities: BTreeSet<i32>,
+    /// Tags, matched case insensitively
+    pub tags: BTreeSet<String>,
+    pub due: BTreeSet<DueBucket>,
+}
+
+impl Default for Filters {
+    /// Pending todos only, like a fresh `patui`
+    fn default() -> Self {
+        Self {
+            statuses: BTreeSet::from([Status::Pending]),
+            priorities: BTreeSet::new(),
+            tags: BTreeSet::new(),
+            due: BTreeSet::new(),
+        }
+    }
+}
+
+/// One checkbox of the filter popup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Facet {
+    Status(Status),
+    Priority(i32),
+    Tag(String),
+    Due(DueBucket),
+}
+
+impl Facet {
+    /// The heading of the group this checkbox is listed under
+    #[must_use]
+    pub fn group(&self) -> &'static str {
+        match self {
+            Self::Status(_) => "Status",
+            Self::Priority(_) => "Priority",
+            Self::Tag(_) => "Tags",
+            Self::Due(_) => "Due",
+        }
+    }
+
+    #[must_use]
+    pub fn label(&self, labels: &PriorityLabels) -> String {
+        match self {
+            Self::Status(Status::Pending) => "Pending".to_string(),
+            Self::Status(Status::Completed) => "Completed".to_string(),
+            Self::Priority(level) => labels.label(*level).to_string(),
+            Self::Tag(tag) => format!("#{tag}"),
+            Self::Due(bucket) => bucket.label().to_string(),
+        }
+    }
+}
+
+impl Filters {
+    /// Returns `true` if `todo`, tagged with `tags`, passes every facet
+    ///
+    /// `check_status` is `false` in the archived view, which lists archived
+    /// todos completed or not.
+    #[must_use]
+    pub fn matches(
+        &self,
+        todo: &Todo,
+        tags: &[String],
+        due: DueBucket,
+        check_status: bool,
+    ) -> bool {
+        let status = if todo.completed {
+            Status::Completed
+        } else {
+            Status::Pending
+        };
+        (!check_status || self.statuses.is_empty() || self.statuses.contains(&status))
+            && (self.priorities.is_empty() || self.priorities.contains(&todo.priority))
+            && (self.due.is_empty() || self.due.contains(&due))
+            && (self.tags.is_empty()
+                || tags
+                    .iter()
+                    .any(|tag| self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))))
+    }
+
+    /// Returns `true` if completed todos are listed
+    #[must_use]
+    pub fn shows_completed(&self) -> bool {
+        self.statuses.is_empty() || self.statuses.contains(&Status::Completed)
+    }
+
+    #[must_use]
+    pub fn is_checked(&self, facet: &Facet) -> bool {
+        match facet {
+            Facet::Status(status) => self.statuses.contains(status),
+            Facet::Priority(level) => self.priorities.contains(level),
+            Facet::Tag(tag) => self.tags.contains(tag),
+            Facet::Due(bucket) => self.due.contains(bucket),
+        }
+    }
+
+    /// Checks or unchecks `facet`
+    pub fn toggle(&mut self, facet: &Facet) {
+        fn flip<T: Ord + Clone>(set: &mut BTreeSet<T>, value: &T) {
+            if !set.remove(value) {
+                set.insert(value.clone());
+            }
+        }
+        match facet {
+            Facet::Status(status) => flip(&mut self.statuses, status),
+            Facet::Priority(level) => flip(&mut self.priorities, level),
+            Facet::Tag(tag) => flip(&mut self.tags, tag),
+            Facet::Due(bucket) => flip(&mut self.due, bucket),
+        }
+    }
+}
+
+/// State of the filter popup: its checkboxes and the highlighted one
+#[derive(Debug, Clone)]
+pub struct FilterPopup {
+    pub facets: Vec<Facet>,
+    pub cursor: usize,
+}
+
+impl FilterPopup {
+    /// Lists every status, priority and due window, and the tags in use
+    #[must_use]
+    pub fn new(tags: &[&str]) -> Self {
+        let mut facets = vec![
+            Facet::Status(Status::Pending),
+            Facet::Status(Status::Completed),
+        ];
+        facets.extend((1..=3).rev().map(Facet::Priority));
+        facets.extend(tags.iter().map(|tag| Facet::Tag((*tag).to_string())));
+        facets.extend(DueBucket::ALL.map(Facet::Due));
+        Self { facets, cursor: 0 }
+    }
+
+    #[must_use]
+    pub fn selected(&self) -> Option<&Facet> {
+        self.facets.get(self.cursor)
+    }
+
+    /// Moves the highlight a row down or up, wrapping around
+    pub fn move_cursor(&mut self, down: bool) {
+        let len = self.facets.len();
+        if len > 0 {
+            self.cursor = if down {
+                (self.cursor + 1) % len
+            } else {
+                (self.cursor + len - 1) % len
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn todo(completed: bool, priority: i32) -> Todo {
+        testing::todo("a")
+            .title("Todo")
+            .completed(completed)
+            .priority(priority)
+            .build()
+    }
+
+    #[test]
+    fn test_matches() {
+        let tags = ["Work".to_string()];
+        let mut filters = Filters::default();
+        assert!(filters.matches(&todo(false, 1), &[], DueBucket::NoDueDate, true));
+        assert!(!filters.matches(&todo(true, 1), &[], DueBucket::NoDueDate, true));
+        // The archived view lists completed todos too
+        assert!(filters.matches(&todo(true, 1), &[], DueBucket::NoDueDate, false));
+
+        filters.toggle(&Facet::Priority(3));
+        filters.toggle(&Facet::Priority(2));
+        assert!(filters.matches(&todo(false, 2), &[], DueBucket::Today, true));
+        assert!(!filters.matches(&todo(false, 1), &[], DueBucket::Today, true));
+
+        filters.toggle(&Facet::Tag("work".to_string()));
+        filters.toggle(&Facet::Due(DueBucket::Overdue));
+        assert!(filters.matches(&todo(false, 3), &tags, DueBucket::Overdue, true));
+        assert!(!filters.matches(&todo(false, 3), &tags, DueBucket::Today, true));
+        assert!(!filters.matches(&todo(false, 3), &[], DueBucket::Overdue, true));
+
+        filters.toggle(&Facet::Status(Status::Pending));
+        assert!(filters.shows_completed());
+        assert!(filters.is_checked(&Facet::Priority(2)));
+        filters.toggle(&Facet::Priority(2));
+        assert!(!filters.is_checked(&Facet::Priority(2)));
+    }
+
+    #[test]
+    fn test_popup_cursor() {
+        let mut popup = FilterPopup::new(&["home"]);
+        assert_eq!(popup.facets.len(), 2 + 3 + 1 + DueBucket::ALL.len());
+        assert_eq!(popup.selected(), Some(&Facet::Status(Status::Pending)));
+        popup.move_cursor(false);
+        assert_eq!(popup.selected(), Some(&Facet::Due(DueBucket::NoDueDate)));
+        for _ in 0..6 {
+            popup.move_cursor(true);
+        }
+        assert_eq!(popup.selected(), Some(&Facet::Tag("home".to_string())));
+    }
+}
@@ -0,0 +1,280 @@
+//! Input events of the TUI, independent of the terminal backend
+//!
+//! [`App`](crate::tui::app::App) reacts to [`Input`]s only. `patui` turns
+//! crossterm events into them with [`Input::from_event`], the SSH server
+//! parses the bytes a remote terminal sends with [`Input::parse`]; tests,
+//! macros and other frontends build them directly.
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind,
+};
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A key, without modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Esc,
+    Backspace,
+    Tab,
+    /// Shift+Tab
+    BackTab,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    /// A function key, e.g. `F(12)`
+    F(u8),
+    /// A key the TUI has no binding for
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    /// A key pressed, with `ctrl` held or not
+    Key { key: Key, ctrl: bool },
+    /// A left click on a cell
+    Click { column: u16, row: u16 },
+}
+
+impl Input {
+    /// A key pressed without Ctrl
+    #[must_use]
+    pub fn key(key: Key) -> Self {
+        Self::Key { key, ctrl: false }
+    }
+
+    /// A key pressed with Ctrl
+    #[must_use]
+    pub fn ctrl(key: Key) -> Self {
+        Self::Key { key, ctrl: true }
+    }
+
+    /// Converts a crossterm event; events the TUI ignores give `None`
+    #[must_use]
+    pub fn from_event(event: &Event) -> Option<Self> {
+        match event {
+            Event::Key(key) => Self::from_key_event(key),
+            Event::Mouse(mouse) if mouse.kind == MouseEventKind::Down(MouseButton::Left) => {
+                Some(Self::Click {
+                    column: mouse.column,
+                    row: mouse.row,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses what a terminal in raw mode sends: characters, control keys,
+    /// xterm escape sequences and SGR mouse clicks
+    ///
+    /// Sequences the TUI has no use for are dropped. An escape sequence split
+    /// between two reads is not recognized.
+    #[must_use]
+    pub fn parse(bytes: &[u8]) -> Vec<Self> {
+        let text = String::from_utf8_lossy(bytes);
+        let mut chars = text.chars().peekable();
+        let mut inputs = Vec::new();
+        while let Some(c) = chars.next() {
+            let input = match c {
+                '\x1b' => match chars.peek() {
+                    Some('[') => {
+                        chars.next();
+                        Self::parse_csi(&mut chars)
+                    }
+                    Some('O') => {
+                        chars.next();
+                        Self::parse_ss3(chars.next())
+                    }
+                    // A lone Escape, or Alt with a key
+                    _ => Some(Self::key(Key::Esc)),
+                },
+                '\r' | '\n' => Some(Self::key(Key::Enter)),
+                '\t' => Some(Self::key(Key::Tab)),
+                '\x7f' | '\x08' => Some(Self::key(Key::Backspace)),
+                // Ctrl+A is 1, Ctrl+Z is 26
+                '\x01'..='\x1a' => char::from_u32(u32::from(c) - 1 + u32::from('a'))
+                    .map(|letter| Self::ctrl(Key::Char(letter))),
+                c if c.is_control() => None,
+                c => Some(Self::key(Key::Char(c))),
+            };
+            inputs.extend(input);
+        }
+        inputs
+    }
+
+    /// Parses a control sequence after `ESC [`
+    fn parse_csi(chars: &mut Peekable<Chars>) -> Option<Self> {
+        let mut params = String::new();
+        let last = loop {
+            let c = chars.next()?;
+            if ('@'..='~').contains(&c) {
+                break c;
+            }
+            params.push(c);
+        };
+        let key = match (last, params.as_str()) {
+            ('M', params) if params.starts_with('<') => return Self::parse_sgr_mouse(&params[1..]),
+            ('A', _) => Key::Up,
+            ('B', _) => Key::Down,
+            ('C', _) => Key::Right,
+            ('D', _) => Key::Left,
+            ('H', _) | ('~', "1" | "7") => Key::Home,
+            ('F', _) | ('~', "4" | "8") => Key::End,
+            ('Z', _) => Key::BackTab,
+            ('~', "5") => Key::PageUp,
+            ('~', "6") => Key::PageDown,
+            ('~', number) => match number.split(';').next()? {
+                "15" => Key::F(5),
+                "17" => Key::F(6),
+                "18" => Key::F(7),
+                "19" => Key::F(8),
+                "20" => Key::F(9),
+                "21" => Key::F(10),
+                "23" => Key::F(11),
+                "24" => Key::F(12),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(Self::key(key))
+    }
+
+    /// Parses the key after `ESC O`, sent for F1-F4 and by arrows in application mode
+    fn parse_ss3(c: Option<char>) -> Option<Self> {
+        let key = match c? {
+            'P' => Key::F(1),
+            'Q' => Key::F(2),
+            'R' => Key::F(3),
+            'S' => Key::F(4),
+            'A' => Key::Up,
+            'B' => Key::Down,
+            'C' => Key::Right,
+            'D' => Key::Left,
+            'H' => Key::Home,
+            'F' => Key::End,
+            _ => return None,
+        };
+        Some(Self::key(key))
+    }
+
+    /// Parses `button;column;row` of an SGR mouse press, counted from 1
+    fn parse_sgr_mouse(params: &str) -> Option<Self> {
+        let mut numbers = params.split(';').map(str::parse::<u16>);
+        let (Some(Ok(0)), Some(Ok(column)), Some(Ok(row))) =
+            (numbers.next(), numbers.next(), numbers.next())
+        else {
+            return None;
+        };
+        Some(Self::Click {
+            column: column.saturating_sub(1),
+            row: row.saturating_sub(1),
+        })
+    }
+
+    fn from_key_event(event: &KeyEvent) -> Option<Self> {
+        // Windows reports releases too
+        if event.kind == KeyEventKind::Release {
+            return None;
+        }
+        Some(Self::Key {
+            key: Key::from(event.code),
+            ctrl: event.modifiers.contains(KeyModifiers::CONTROL),
+        })
+    }
+}
+
+impl From<KeyCode> for Key {
+    fn from(code: KeyCode) -> Self {
+        match code {
+            KeyCode::Char(c) => Self::Char(c),
+            KeyCode::Enter => Self::Enter,
+            KeyCode::Esc => Self::Esc,
+            KeyCode::Backspace => Self::Backspace,
+            KeyCode::Tab => Self::Tab,
+            KeyCode::BackTab => Self::BackTab,
+            KeyCode::Up => Self::Up,
+            KeyCode::Down => Self::Down,
+            KeyCode::Left => Self::Left,
+            KeyCode::Right => Self::Right,
+            KeyCode::Home => Self::Home,
+            KeyCode::End => Self::End,
+            KeyCode::PageUp => Self::PageUp,
+            KeyCode::PageDown => Self::PageDown,
+            KeyCode::F(n) => Self::F(n),
+            _ => Self::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::MouseEvent;
+
+    #[test]
+    fn test_from_event() {
+        let ctrl_r = Event::Key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        assert_eq!(
+            Input::from_event(&ctrl_r),
+            Some(Input::ctrl(Key::Char('r')))
+        );
+        let f12 = Event::Key(KeyEvent::new(KeyCode::F(12), KeyModifiers::NONE));
+        assert_eq!(Input::from_event(&f12), Some(Input::key(Key::F(12))));
+        let insert = Event::Key(KeyEvent::new(KeyCode::Insert, KeyModifiers::SHIFT));
+        assert_eq!(Input::from_event(&insert), Some(Input::key(Key::Other)));
+
+        let mut release = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        release.kind = KeyEventKind::Release;
+        assert_eq!(Input::from_event(&Event::Key(release)), None);
+
+        let click = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 3,
+            row: 7,
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(
+            Input::from_event(&click),
+            Some(Input::Click { column: 3, row: 7 })
+        );
+        assert_eq!(Input::from_event(&Event::Resize(80, 24)), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Input::parse("aé\r\x7f\x03\t".as_bytes()),
+            [
+                Input::key(Key::Char('a')),
+                Input::key(Key::Char('é')),
+                Input::key(Key::Enter),
+                Input::key(Key::Backspace),
+                Input::ctrl(Key::Char('c')),
+                Input::key(Key::Tab),
+            ]
+        );
+        assert_eq!(
+            Input::parse(b"\x1b[A\x1b[1;5B\x1bOP\x1b[24~\x1b[5~\x1b[Z\x1b"),
+            [
+                Input::key(Key::Up),
+                Input::key(Key::Down),
+                Input::key(Key::F(1)),
+                Input::key(Key::F(12)),
+                Input::key(Key::PageUp),
+                Input::key(Key::BackTab),
+                Input::key(Key::Esc),
+            ]
+        );
+        assert_eq!(
+            Input::parse(b"\x1b[<0;10;3M\x1b[<0;10;3m\x1b[<2;1;1M\x1b[3~"),
+            [Input::Click { column: 9, row: 2 }]
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! History of the messages `patui` showed, for the notification center (`N`)
+//!
+//! Toasts disappear after a few seconds and are easy to miss while moving
+//! around quickly, so each one is also kept here with sync results and
+//! reminders, until `patui` quits.
+
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+
+/// Notifications kept; older ones are dropped
+pub const CAPACITY: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Confirmation of something done in the TUI
+    Info,
+    Error,
+    /// Result of loading the list from the server
+    Sync,
+    /// A todo became due soon
+    Reminder,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub kind: Kind,
+    pub message: String,
+    /// When it last happened
+    pub at: DateTime<Local>,
+    /// How many times in a row it happened
+    pub repeats: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct Notifications {
+    entries: VecDeque<Notification>,
+    unread: usize,
+}
+
+impl Notifications {
+    /// Records a notification; a repeat of the latest one only bumps its count and time
+    pub fn push(&mut self, kind: Kind, message: String) {
+        let now = Local::now();
+        if let Some(last) = self
+            .entries
+            .back_mut()
+            .filter(|last| last.kind == kind && last.message == message)
+        {
+            last.at = now;
+            last.repeats += 1;
+            self.unread = self.unread.max(1);
+            return;
+        }
+        if self.entries.len() == CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(Notification {
+            kind,
+            message,
+            at: now,
+            repeats: 1,
+        });
+        self.unread = (self.unread + 1).min(self.entries.len());
+    }
+
+    /// Number of notifications added since the notification center was last opened
+    #[must_use]
+    pub fn unread(&self) -> usize {
+        self.unread
+    }
+
+    pub fn mark_read(&mut self) {
+        self.unread = 0;
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.unread = 0;
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The notifications, newest first
+    pub fn newest_first(&self) -> impl Iterator<Item = &Notification> {
+        self.entries.iter().rev()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push() {
+        let mut notifications = Notifications::default();
+        notifications.push(Kind::Sync, "Loaded 3 todo(s)".to_string());
+        notifications.push(Kind::Sync, "Loaded 3 todo(s)".to_string());
+        notifications.push(Kind::Error, "Unable to load todos".to_string());
+        assert_eq!(notifications.len(), 2);
+        assert_eq!(notifications.unread(), 2);
+        let newest: Vec<(Kind, usize)> = notifications
+            .newest_first()
+            .map(|n| (n.kind, n.repeats))
+            .collect();
+        assert_eq!(newest, [(Kind::Error, 1), (Kind::Sync, 2)]);
+
+        notifications.mark_read();
+        notifications.push(Kind::Error, "Unable to load todos".to_string());
+        assert_eq!(notifications.unread(), 1);
+
+        for i in 0..CAPACITY {
+            notifications.push(Kind::Info, format!("Message {i}"));
+        }
+        assert_eq!(notifications.len(), CAPACITY);
+        assert_eq!(notifications.unread(), CAPACITY);
+        assert_eq!(
+            notifications.newest_first().last().unwrap().message,
+            "Message 0"
+        );
+
+        notifications.clear();
+        assert!(notifications.is_empty());
+        assert_eq!(notifications.unread(), 0);
+    }
+}
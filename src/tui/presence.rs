@@ -0,0 +1,172 @@
+//! Who else changes the todos of a shared list
+//!
+//! Servers with [`Capabilities::MODIFIERS`] report who last changed each
+//! todo. Todos whose `updated_at` moved between two refreshes were changed
+//! somewhere other than this `patui` (its own changes update the list right
+//! away), so for a few minutes the list says who did it, e.g. "updated just
+//! now by ci-bot".
+
+use crate::api::{ApiClient, Capabilities, TodoModifier};
+use pali_types::Todo;
+use std::collections::HashMap;
+
+/// How long a change made elsewhere stays marked in the list
+pub const RECENT_SECS: i64 = 5 * 60;
+
+/// Fetches who last changed each todo; `None` if the server doesn't say
+pub async fn fetch(client: &ApiClient) -> Option<Vec<TodoModifier>> {
+    if !client
+        .capabilities()
+        .await
+        .supports(Capabilities::MODIFIERS)
+    {
+        return None;
+    }
+    client
+        .list_modifiers()
+        .await
+        .map_err(|err| log::debug!("Failed to load todo modifiers: {err:#}"))
+        .ok()
+}
+
+#[derive(Debug, Default)]
+pub struct Presence {
+    modifiers: HashMap<String, TodoModifier>,
+    /// Todos changed elsewhere, with when a refresh noticed it
+    changed: HashMap<String, i64>,
+}
+
+impl Presence {
+    /// Replaces what the server reported about who changed which todo
+    pub fn set_modifiers(&mut self, modifiers: Vec<TodoModifier>) {
+        self.modifiers = modifiers
+            .into_iter()
+            .map(|modifier| (modifier.todo_id.clone(), modifier))
+            .collect();
+    }
+
+    /// Notes the todos of `new` that are new or updated compared to `old`
+    ///
+    /// Nothing is noted on the first load, when `old` is empty.
+    pub fn note_changes(&mut self, old: &[Todo], new: &[Todo], now: i64) {
+        self.changed
+            .retain(|_, noticed| now - *noticed < RECENT_SECS);
+        if old.is_empty() {
+            return;
+        }
+        let before: HashMap<&str, i64> = old
+            .iter()
+            .map(|todo| (todo.id.as_str(), todo.updated_at))
+            .collect();
+        for todo in new {
+            if before.get(todo.id.as_str()) != Some(&todo.updated_at) {
+                self.changed.insert(todo.id.clone(), now);
+            }
+        }
+    }
+
+    /// Who last changed the todo, if the server said
+    #[must_use]
+    pub fn modifier(&self, todo_id: &str) -> Option<&TodoModifier> {
+        self.modifiers.get(todo_id)
+    }
+
+    /// "updated just now by ci-bot" for a todo recently changed elsewhere
+    #[must_use]
+    pub fn indicator(&self, todo_id: &str, now: i64) -> Option<String> {
+        let noticed = self.changed.get(todo_id)?;
+        if now - noticed >= RECENT_SECS {
+            return None;
+        }
+        let modifier = self.modifiers.get(todo_id)?;
+        Some(format!(
+            "updated {} by {}",
+            ago(now - modifier.modified_at),
+            modifier.who()
+        ))
+    }
+
+    /// The indicator of the latest change made elsewhere
+    #[must_use]
+    pub fn latest(&self, now: i64) -> Option<String> {
+        let todo_id = self
+            .changed
+            .keys()
+            .filter_map(|id| self.modifiers.get(id))
+            .max_by_key(|modifier| modifier.modified_at)?
+            .todo_id
+            .as_str();
+        self.indicator(todo_id, now)
+    }
+}
+
+/// "just now", "5m ago", "3h ago" or "2d ago"
+#[must_use]
+pub fn ago(seconds: i64) -> String {
+    match seconds {
+        s if s < 60 => "just now".to_string(),
+        s if s < 60 * 60 => format!("{}m ago", s / 60),
+        s if s < 24 * 60 * 60 => format!("{}h ago", s / (60 * 60)),
+        s => format!("{}d ago", s / (24 * 60 * 60)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn todo(id: &str, updated_at: i64) -> Todo {
+        testing::todo(id).updated(updated_at).build()
+    }
+
+    fn modifier(todo_id: &str, modified_at: i64, key_name: Option<&str>) -> TodoModifier {
+        TodoModifier {
+            todo_id: todo_id.to_string(),
+            modified_at,
+            key_id: Some("k1".to_string()),
+            key_name: key_name.map(str::to_string),
+            client: None,
+        }
+    }
+
+    #[test]
+    fn test_presence() {
+        let now = 10_000;
+        let mut presence = Presence::default();
+        presence.set_modifiers(vec![
+            modifier("a", now - 10, Some("ci-bot")),
+            modifier("b", now - 300, None),
+            modifier("c", 100, Some("ana")),
+        ]);
+
+        // Nothing is marked on the first load
+        let old = vec![todo("a", 1), todo("b", 1), todo("c", 1)];
+        presence.note_changes(&[], &old, now);
+        assert_eq!(presence.indicator("a", now), None);
+
+        let new = vec![todo("a", 2), todo("b", 3), todo("c", 1), todo("d", 1)];
+        presence.note_changes(&old, &new, now);
+        assert_eq!(
+            presence.indicator("a", now).as_deref(),
+            Some("updated just now by ci-bot")
+        );
+        assert_eq!(
+            presence.indicator("b", now).as_deref(),
+            Some("updated 5m ago by k1")
+        );
+        // Unchanged, or changed without a known modifier
+        assert_eq!(presence.indicator("c", now), None);
+        assert_eq!(presence.indicator("d", now), None);
+        assert_eq!(
+            presence.latest(now).as_deref(),
+            Some("updated just now by ci-bot")
+        );
+        assert_eq!(presence.modifier("c").map(TodoModifier::who), Some("ana"));
+
+        // The mark fades
+        assert_eq!(presence.indicator("a", now + RECENT_SECS), None);
+        presence.note_changes(&new, &new, now + RECENT_SECS);
+        assert_eq!(presence.latest(now + RECENT_SECS), None);
+    }
+}
@@ -0,0 +1,187 @@
+//! The part of the TUI state that survives restarts
+//!
+//! Saved to `tui_session.json` in the data directory when `patui` quits and
+//! restored at startup, so it reopens with the same filters, view and
+//! selected todo.
+
+use crate::config::Config;
+use crate::due::DueBucket;
+use crate::tui::app::AppScreen;
+use crate::tui::filter::{Filters, Status};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Number of past search queries kept
+pub const SEARCH_HISTORY_LEN: usize = 20;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    #[serde(default)]
+    pub show_archived: bool,
+    #[serde(default)]
+    pub filters: Option<Filters>,
+    /// Filters as saved before the filter popup, read into `filters`
+    #[serde(default, skip_serializing)]
+    pub show_all_todos: bool,
+    #[serde(default, skip_serializing)]
+    pub filter_priority: Option<i32>,
+    #[serde(default, skip_serializing)]
+    pub filter_tag: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub filter_overdue: bool,
+    /// Sort spec such as `priority,due`; `None` keeps the server's order
+    #[serde(default)]
+    pub sort: Option<String>,
+    #[serde(default)]
+    pub search_query: String,
+    /// `search_query` is a regular expression
+    #[serde(default)]
+    pub search_regex: bool,
+    /// ID of the selected todo; selected again once it is loaded
+    #[serde(default)]
+    pub selected_id: Option<String>,
+    #[serde(default)]
+    pub screen: Option<AppScreen>,
+    /// Past search queries, most recent first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub search_history: Vec<String>,
+    /// The first-launch tour was finished or skipped
+    #[serde(default)]
+    pub tour_done: bool,
+}
+
+impl Session {
+    /// Returns the path to the session file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data directory cannot be determined
+    pub fn path() -> Result<PathBuf> {
+        Ok(Config::data_dir()?.join("tui_session.json"))
+    }
+
+    /// Returns the session file of an SSH user, named after their key's fingerprint
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data directory cannot be determined
+    pub fn ssh_path(fingerprint: &str) -> Result<PathBuf> {
+        let name: String = fingerprint
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        Ok(Config::data_dir()?
+            .join("ssh_sessions")
+            .join(format!("{name}.json")))
+    }
+
+    /// Loads the last session; a missing or corrupt file gives the defaults
+    ///
+    /// `file` defaults to [`Session::path`].
+    #[must_use]
+    pub fn load(file: Option<&Path>) -> Self {
+        file.map_or_else(Self::path, |file| Ok(file.to_path_buf()))
+            .and_then(|path| Ok(fs::read_to_string(path)?))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Saves the session to `file`, by default [`Session::path`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session file cannot be written
+    pub fn save(&self, file: Option<&Path>) -> Result<()> {
+        let path = file.map_or_else(Self::path, |file| Ok(file.to_path_buf()))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Adds `query` to the front of `history`, dropping duplicates and the oldest entries
+    pub fn remember_search(history: &mut Vec<String>, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+        history.retain(|past| past != query);
+        history.insert(0, query.to_string());
+        history.truncate(SEARCH_HISTORY_LEN);
+    }
+
+    /// The filters to restore, converting those of older versions
+    #[must_use]
+    pub fn filters(&self) -> Filters {
+        if let Some(filters) = &self.filters {
+            return filters.clone();
+        }
+        let mut filters = Filters::default();
+        if self.show_all_todos {
+            filters.statuses.insert(Status::Completed);
+        }
+        filters.priorities.extend(self.filter_priority);
+        filters.tags.extend(self.filter_tag.clone());
+        if self.filter_overdue {
+            filters.due.insert(DueBucket::Overdue);
+        }
+        filters
+    }
+
+    /// The screen to reopen: forms and search prompts start over on the list
+    #[must_use]
+    pub fn restored_screen(&self) -> AppScreen {
+        match self.screen {
+            Some(
+                screen @ (AppScreen::TodoDetail
+                | AppScreen::Help
+                | AppScreen::Settings
+                | AppScreen::Week),
+            ) => screen,
+            _ => AppScreen::TodoList,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restored_screen() {
+        let mut session = Session {
+            screen: Some(AppScreen::TodoDetail),
+            ..Session::default()
+        };
+        assert_eq!(session.restored_screen(), AppScreen::TodoDetail);
+
+        session.screen = Some(AppScreen::EditTodo);
+        assert_eq!(session.restored_screen(), AppScreen::TodoList);
+
+        // Files from older versions lack fields
+        let session: Session = serde_json::from_str(r#"{"show_all_todos":true}"#).unwrap();
+        assert!(session.show_all_todos);
+        assert_eq!(session.restored_screen(), AppScreen::TodoList);
+        assert!(session.filters().shows_completed());
+    }
+
+    #[test]
+    fn test_remember_search() {
+        let mut history = Vec::new();
+        Session::remember_search(&mut history, "milk");
+        Session::remember_search(&mut history, " dentist ");
+        Session::remember_search(&mut history, "milk");
+        Session::remember_search(&mut history, "  ");
+        assert_eq!(history, ["milk", "dentist"]);
+
+        for i in 0..SEARCH_HISTORY_LEN {
+            Session::remember_search(&mut history, &format!("query {i}"));
+        }
+        assert_eq!(history.len(), SEARCH_HISTORY_LEN);
+        assert_eq!(history[0], format!("query {}", SEARCH_HISTORY_LEN - 1));
+    }
+}
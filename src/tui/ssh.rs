@@ -0,0 +1,534 @@
+//! Serving the TUI over SSH (`patui --serve-ssh :2222`)
+//!
+//! Each SSH session gets its own [`App`], drawn into the session's pseudo
+//! terminal, while all of them share the configuration, local state and
+//! cache of the machine serving them. A team can then use one configured
+//! instance without handing out its API key: access is granted by the public
+//! keys in an `authorized_keys` file instead. Filters, search history and
+//! the like are kept per key rather than in the local `tui_session.json`.
+//!
+//! Sessions run on a thread of their own, so a slow one doesn't hold up the
+//! others. Editing in `$EDITOR`, exporting to files and job control are not
+//! available remotely, and links are copied to the client's clipboard
+//! instead of opened.
+
+use crate::tui::app::App;
+use crate::tui::input::{Input, Key};
+use crate::tui::session::Session as SavedSession;
+use crate::tui::ui;
+use crate::{term, Config};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use crossterm::{
+    cursor,
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::Rect;
+use ratatui::{Terminal, TerminalOptions, Viewport};
+use russh::server::{self, Auth, Msg, Server as _, Session};
+use russh::{Channel, ChannelId, CryptoVec, MethodSet};
+use russh_keys::key::{KeyPair, PublicKey};
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Host key of the server, kept in the data directory
+const HOST_KEY_FILE: &str = "ssh_host_ed25519_key";
+/// Tick rate while something animates (spinner, timers)
+const TICK_RATE: Duration = Duration::from_millis(250);
+/// How long to wait for input when nothing changes on its own
+const IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Terminal size until the client tells us its own
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+/// Serves the TUI on `address` until the process is stopped
+///
+/// `address` may leave out the host, as in `:2222`, to listen on every
+/// interface. Only the keys in the `authorized_keys` file may log in; keys
+/// with options such as `from=` or `restrict` are left out, since the TUI
+/// couldn't honor them.
+///
+/// # Errors
+///
+/// Returns an error if the config, the authorized keys or the host key
+/// cannot be loaded, or the address cannot be listened on
+pub async fn serve(address: &str, authorized_keys: &Path) -> Result<()> {
+    let config = Config::load()?;
+    let content = std::fs::read_to_string(authorized_keys)
+        .with_context(|| format!("Failed to read {}", authorized_keys.display()))?;
+    let authorized = parse_authorized_keys(&content);
+    if authorized.is_empty() {
+        anyhow::bail!(
+            "{} has no public keys, so nobody could log in",
+            authorized_keys.display()
+        );
+    }
+
+    let ssh_config = server::Config {
+        keys: vec![host_key()?],
+        methods: MethodSet::PUBLICKEY,
+        inactivity_timeout: Some(Duration::from_secs(60 * 60)),
+        auth_rejection_time: Duration::from_secs(1),
+        auth_rejection_time_initial: Some(Duration::ZERO),
+        ..Default::default()
+    };
+    let address = listen_address(address);
+    let mut server = Server {
+        config,
+        authorized: Arc::new(authorized),
+    };
+    println!(
+        "Serving patui over SSH on {address} for the {} keys in {}",
+        server.authorized.len(),
+        authorized_keys.display()
+    );
+    server
+        .run_on_address(Arc::new(ssh_config), address.as_str())
+        .await
+        .with_context(|| format!("Failed to serve SSH on {address}"))
+}
+
+/// Fills in the host of addresses like `:2222`
+fn listen_address(address: &str) -> String {
+    if address.starts_with(':') {
+        format!("0.0.0.0{address}")
+    } else {
+        address.to_string()
+    }
+}
+
+/// Fingerprints of the keys in an `authorized_keys` file
+///
+/// Lines whose key has options in front, such as `from=`, `command=`,
+/// `no-pty` or `restrict`, are skipped: they limit what the key may do, and
+/// a session here always gets the whole TUI.
+fn parse_authorized_keys(content: &str) -> HashSet<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            let key_type = words.next()?;
+            if !is_key_type(key_type) {
+                log::warn!("Skipping an authorized key with options: {line}");
+                return None;
+            }
+            russh_keys::parse_public_key_base64(words.next()?).ok()
+        })
+        .map(|key| key.fingerprint())
+        .collect()
+}
+
+/// Whether the first word of an `authorized_keys` line is a key type rather than options
+fn is_key_type(word: &str) -> bool {
+    ["ssh-", "ecdsa-", "sk-"]
+        .iter()
+        .any(|prefix| word.starts_with(prefix))
+}
+
+/// Loads the host key, generating it on first use
+fn host_key() -> Result<KeyPair> {
+    let path = Config::data_dir()?.join(HOST_KEY_FILE);
+    if path.exists() {
+        return russh_keys::load_secret_key(&path, None)
+            .with_context(|| format!("Failed to load host key {}", path.display()));
+    }
+
+    let key = KeyPair::generate_ed25519().context("Failed to generate a host key")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // Private from the start: the key is never readable by others, not even
+    // before its permissions could be changed
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let file = options
+        .open(&path)
+        .with_context(|| format!("Failed to write host key {}", path.display()))?;
+    russh_keys::encode_pkcs8_pem(&key, file)?;
+    log::info!("Generated SSH host key {}", path.display());
+    Ok(key)
+}
+
+struct Server {
+    config: Config,
+    authorized: Arc<HashSet<String>>,
+}
+
+impl server::Server for Server {
+    type Handler = Connection;
+
+    fn new_client(&mut self, peer: Option<SocketAddr>) -> Connection {
+        Connection {
+            config: self.config.clone(),
+            authorized: Arc::clone(&self.authorized),
+            peer,
+            fingerprint: None,
+            size: DEFAULT_SIZE,
+            events: None,
+        }
+    }
+}
+
+/// What the SSH client sends to a session's app
+enum Event {
+    Input(Input),
+    Resize(u16, u16),
+}
+
+/// One SSH connection; its shell runs an [`App`] of its own
+struct Connection {
+    config: Config,
+    authorized: Arc<HashSet<String>>,
+    peer: Option<SocketAddr>,
+    /// Fingerprint of the key the user logged in with
+    fingerprint: Option<String>,
+    size: (u16, u16),
+    /// Set once the shell runs
+    events: Option<mpsc::UnboundedSender<Event>>,
+}
+
+impl Connection {
+    fn send(&self, event: Event) {
+        if let Some(events) = &self.events {
+            // The app is gone once the user quit; the channel closes then
+            let _ = events.send(event);
+        }
+    }
+}
+
+#[async_trait]
+impl server::Handler for Connection {
+    type Error = anyhow::Error;
+
+    async fn auth_publickey(&mut self, user: &str, public_key: &PublicKey) -> Result<Auth> {
+        let fingerprint = public_key.fingerprint();
+        if self.authorized.contains(&fingerprint) {
+            log::info!("SSH login of {user} from {:?} ({fingerprint})", self.peer);
+            self.fingerprint = Some(fingerprint);
+            Ok(Auth::Accept)
+        } else {
+            log::warn!(
+                "Rejected SSH key {fingerprint} of {user} from {:?}",
+                self.peer
+            );
+            Ok(Auth::Reject {
+                proceed_with_methods: None,
+            })
+        }
+    }
+
+    async fn channel_open_session(
+        &mut self,
+        _channel: Channel<Msg>,
+        _session: &mut Session,
+    ) -> Result<bool> {
+        // One app per connection
+        Ok(self.events.is_none())
+    }
+
+    async fn pty_request(
+        &mut self,
+        channel: ChannelId,
+        _term: &str,
+        col_width: u32,
+        row_height: u32,
+        _pix_width: u32,
+        _pix_height: u32,
+        _modes: &[(russh::Pty, u32)],
+        session: &mut Session,
+    ) -> Result<()> {
+        self.size = (terminal_size(col_width), terminal_size(row_height));
+        session.channel_success(channel);
+        Ok(())
+    }
+
+    async fn window_change_request(
+        &mut self,
+        _channel: ChannelId,
+        col_width: u32,
+        row_height: u32,
+        _pix_width: u32,
+        _pix_height: u32,
+        _session: &mut Session,
+    ) -> Result<()> {
+        self.size = (terminal_size(col_width), terminal_size(row_height));
+        self.send(Event::Resize(self.size.0, self.size.1));
+        Ok(())
+    }
+
+    async fn shell_request(&mut self, channel: ChannelId, session: &mut Session) -> Result<()> {
+        let (events, receiver) = mpsc::unbounded_channel();
+        self.events = Some(events);
+        let config = self.config.clone();
+        // Each key keeps a session of its own, apart from the local `patui`'s
+        let session_file = self
+            .fingerprint
+            .as_deref()
+            .map(SavedSession::ssh_path)
+            .transpose()?;
+        let handle = session.handle();
+        let size = self.size;
+        let peer = self.peer;
+        std::thread::Builder::new()
+            .name("patui-ssh".to_string())
+            .spawn(move || {
+                let result = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .map_err(anyhow::Error::from)
+                    .and_then(|runtime| {
+                        runtime.block_on(run_session(
+                            config,
+                            session_file,
+                            handle,
+                            channel,
+                            size,
+                            receiver,
+                        ))
+                    });
+                if let Err(err) = result {
+                    log::error!("SSH session from {peer:?} failed: {err:#}");
+                }
+            })?;
+        session.channel_success(channel);
+        Ok(())
+    }
+
+    async fn data(
+        &mut self,
+        _channel: ChannelId,
+        data: &[u8],
+        _session: &mut Session,
+    ) -> Result<()> {
+        for input in Input::parse(data) {
+            self.send(Event::Input(input));
+        }
+        Ok(())
+    }
+}
+
+/// Pseudo terminal sizes come as `u32`; anything beyond `u16` is nonsense
+fn terminal_size(cells: u32) -> u16 {
+    u16::try_from(cells).unwrap_or(u16::MAX).max(1)
+}
+
+/// Collects what ratatui draws and hands it to the SSH channel on flush
+struct ChannelWriter {
+    buffer: Vec<u8>,
+    output: mpsc::UnboundedSender<Vec<u8>>,
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        self.output
+            .send(std::mem::take(&mut self.buffer))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "SSH channel closed"))
+    }
+}
+
+type SessionTerminal = Terminal<CrosstermBackend<ChannelWriter>>;
+
+/// A terminal of the client's size; a fixed viewport, since ratatui cannot
+/// ask a remote terminal for its size
+fn session_terminal(
+    output: &mpsc::UnboundedSender<Vec<u8>>,
+    (width, height): (u16, u16),
+) -> Result<SessionTerminal> {
+    let writer = ChannelWriter {
+        buffer: Vec::new(),
+        output: output.clone(),
+    };
+    let terminal = Terminal::with_options(
+        CrosstermBackend::new(writer),
+        TerminalOptions {
+            viewport: Viewport::Fixed(Rect::new(0, 0, width, height)),
+        },
+    )?;
+    Ok(terminal)
+}
+
+/// Runs one app for an SSH session, then closes the channel
+async fn run_session(
+    config: Config,
+    session_file: Option<PathBuf>,
+    handle: server::Handle,
+    channel: ChannelId,
+    size: (u16, u16),
+    events: mpsc::UnboundedReceiver<Event>,
+) -> Result<()> {
+    let (output, mut outgoing) = mpsc::unbounded_channel::<Vec<u8>>();
+    let forward = tokio::spawn(async move {
+        while let Some(bytes) = outgoing.recv().await {
+            if handle
+                .data(channel, CryptoVec::from_slice(&bytes))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+        let _ = handle.close(channel).await;
+    });
+
+    let mut app = App::load(config, session_file)?;
+    app.remote = true;
+    let mut terminal = session_terminal(&output, size)?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        cursor::Hide
+    )?;
+    let result = run_app(&mut terminal, &mut app, &output, events).await;
+    app.save_session();
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        cursor::Show
+    )?;
+
+    // The forwarding task ends, closing the channel, once every sender is gone
+    drop(terminal);
+    drop(output);
+    forward.await?;
+    result
+}
+
+/// The event loop of `patui`, with input from the SSH channel
+async fn run_app(
+    terminal: &mut SessionTerminal,
+    app: &mut App,
+    output: &mpsc::UnboundedSender<Vec<u8>>,
+    mut events: mpsc::UnboundedReceiver<Event>,
+) -> Result<()> {
+    app.check_connection();
+    app.start_background_refresh();
+
+    let mut last_tick = Instant::now();
+    let mut dirty = true;
+
+    loop {
+        dirty |= app.poll_background_refresh();
+        dirty |= app.check_connection();
+        dirty |= app.check_reminders(chrono::Utc::now().timestamp());
+        if dirty {
+            terminal.draw(|f| ui::render(f, app))?;
+            dirty = false;
+        }
+        dirty |= app.resume_detail().await;
+
+        let timeout = if app.is_animating() {
+            TICK_RATE.saturating_sub(last_tick.elapsed())
+        } else {
+            IDLE_TIMEOUT
+        };
+        tokio::select! {
+            event = events.recv() => match event {
+                // The client disconnected
+                None => break,
+                // There is no job control over SSH
+                Some(Event::Input(Input::Key { key: Key::Char('z'), ctrl: true })) => {}
+                Some(Event::Input(input)) => {
+                    app.handle_input(input).await?;
+                    dirty = true;
+                }
+                Some(Event::Resize(width, height)) => {
+                    // A fixed viewport keeps its size, so start over with a new one
+                    *terminal = session_terminal(output, (width, height))?;
+                    terminal.clear()?;
+                    dirty = true;
+                }
+            },
+            () = tokio::time::sleep(timeout) => {}
+        }
+
+        if last_tick.elapsed() >= TICK_RATE {
+            if app.is_animating() {
+                app.tick_spinner();
+                app.tick_messages();
+                app.tick_pomodoro();
+                dirty = true;
+            }
+            last_tick = Instant::now();
+        }
+
+        // The same escape sequences as in a local terminal reach the client's
+        let writer = terminal.backend_mut();
+        if let Some(message) = app.take_notification() {
+            write!(writer, "{}", term::notification(&message))?;
+            writer.flush()?;
+        }
+        if let Some(text) = app.take_clipboard() {
+            write!(writer, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+            writer.flush()?;
+        }
+        if app.take_editor().is_some() {
+            let unavailable = anyhow::anyhow!("Editing in $EDITOR isn't available over SSH");
+            app.finish_editor(Err(unavailable)).await?;
+            dirty = true;
+        }
+
+        if app.should_quit {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorized_keys() {
+        let content = "# The team\n\n\
+            ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIE7CNhyQdDVX4LMgTkQUUtxUNOMl6pELp/YgqXzfSGQR ana@laptop\n\
+            no-pty ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIE7CNhyQdDVX4LMgTkQUUtxUNOMl6pELp/YgqXzfSGQR\n\
+            ssh-ed25519 not-a-key\n";
+        assert_eq!(parse_authorized_keys(content).len(), 1);
+        // Restricted keys don't get the TUI
+        for options in [
+            "no-pty",
+            "restrict",
+            "from=\"10.0.0.1\"",
+            "command=\"true\"",
+        ] {
+            let line = format!(
+                "{options} ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIE7CNhyQdDVX4LMgTkQUUtxUNOMl6pELp/YgqXzfSGQR"
+            );
+            assert!(parse_authorized_keys(&line).is_empty(), "{line}");
+        }
+        assert!(parse_authorized_keys(
+            "# ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIE7CNhyQdDVX4LMgTkQUUtxUNOMl6pELp/YgqXzfSGQR"
+        )
+        .is_empty());
+
+        assert_eq!(listen_address(":2222"), "0.0.0.0:2222");
+        assert_eq!(listen_address("127.0.0.1:2222"), "127.0.0.1:2222");
+    }
+}
@@ -0,0 +1,105 @@
+//! The guided tour shown on the first launch of `patui`
+//!
+//! Each step is a callout pointing at a part of the screen. Finishing or
+//! skipping the tour is remembered in the session, so it is shown once;
+//! `t` in Settings takes it again.
+
+/// The part of the screen a step points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// Nothing in particular; the callout is centered
+    Screen,
+    Header,
+    List,
+    Footer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub title: &'static str,
+    pub text: &'static str,
+    pub target: Target,
+}
+
+pub const STEPS: [Step; 5] = [
+    Step {
+        title: "Welcome to Pali",
+        text: "A quick look around: Enter or → goes to the next tip, ← back, Esc skips the tour.",
+        target: Target::Screen,
+    },
+    Step {
+        title: "Your todos",
+        text: "↑/↓ or j/k select a todo, Enter marks it done, n adds one, e edits it and v shows its details.",
+        target: Target::List,
+    },
+    Step {
+        title: "The header",
+        text: "Counts and the filters in effect. Badges for overdue todos, today's progress and unread notifications can be clicked.",
+        target: Target::Header,
+    },
+    Step {
+        title: "The footer",
+        text: "The keys of the current screen are always listed here.",
+        target: Target::Footer,
+    },
+    Step {
+        title: "Where to go next",
+        text: "/ searches, F filters, w opens the week view and N the notifications. ? lists every key; Settings (s) replays this tour.",
+        target: Target::Screen,
+    },
+];
+
+/// Progress through [`STEPS`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Tour {
+    step: usize,
+}
+
+impl Tour {
+    #[must_use]
+    pub fn step(&self) -> &'static Step {
+        &STEPS[self.step]
+    }
+
+    /// Position of the current step, counting from 1
+    #[must_use]
+    pub fn number(&self) -> usize {
+        self.step + 1
+    }
+
+    /// Moves to the next step, returning `false` if this was the last one
+    pub fn advance(&mut self) -> bool {
+        if self.step + 1 < STEPS.len() {
+            self.step += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.step = self.step.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps() {
+        let mut tour = Tour::default();
+        tour.previous();
+        assert_eq!(tour.number(), 1);
+        assert_eq!(tour.step().target, Target::Screen);
+
+        let mut steps = 1;
+        while tour.advance() {
+            steps += 1;
+        }
+        assert_eq!(steps, STEPS.len());
+        assert_eq!(tour.number(), STEPS.len());
+        tour.previous();
+        assert_eq!(tour.step().target, Target::Footer);
+    }
+}
@@ -1,18 +1,154 @@
 //! TUI rendering and layout logic
 
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    backend::TestBackend,
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
-    Frame,
+    widgets::{
+        Block, BorderType, Borders, Clear, List, ListItem, Paragraph, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, Wrap,
+    },
+    Frame, Terminal,
 };
 
-use crate::tui::app::{App, AppScreen};
-use crate::ID_DISPLAY_LENGTH;
+use crate::search::snippet;
+use crate::tui::app::{App, AppScreen, ConnectionState};
+use crate::tui::components::{InputField, InputForm};
+use crate::tui::conflict::{EditConflict, Side};
+use crate::tui::filter::{FilterPopup, Status};
+use crate::tui::notifications::Kind as NotificationKind;
+use crate::tui::presence;
+use crate::tui::tour::{Target, Tour, STEPS};
+use crate::{
+    api::Revision,
+    checklist, highlight, links, markdown, revisions,
+    state::format_duration,
+    term::{self, symbols},
+    ID_DISPLAY_LENGTH,
+};
+use std::collections::BTreeSet;
+use std::ops::Range;
 
 use chrono::{Local, TimeZone, Utc};
 
+/// Height of the F12 log pane, including borders
+const DEBUG_PANE_HEIGHT: u16 = 10;
+
+/// Shows the most recent log lines, colored by level
+fn render_debug_pane(frame: &mut Frame, area: Rect) {
+    let visible = usize::from(area.height.saturating_sub(2));
+    let lines: Vec<Line> = crate::logging::recent_logs(visible)
+        .into_iter()
+        .map(|line| {
+            let color = if line.contains("[ERROR]") {
+                Color::Red
+            } else if line.contains("[WARN ]") {
+                Color::Yellow
+            } else if line.contains("[DEBUG]") || line.contains("[TRACE]") {
+                Color::DarkGray
+            } else {
+                Color::Gray
+            };
+            // Drop the date; the time is enough on screen
+            let line = match line.get(..11) {
+                Some(date)
+                    if date.ends_with(' ') && date.starts_with(|c: char| c.is_ascii_digit()) =>
+                {
+                    line[11..].to_string()
+                }
+                _ => line,
+            };
+            Line::from(Span::styled(line, Style::default().fg(color)))
+        })
+        .collect();
+
+    let pane = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Log (F12 to hide) ")
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+    frame.render_widget(pane, area);
+}
+
+/// Shows this session's API calls per endpoint, busiest first (with `metrics = true`)
+fn render_metrics_pane(frame: &mut Frame, area: Rect) {
+    let metrics = crate::metrics::current();
+    let mut endpoints: Vec<_> = metrics.endpoints.iter().collect();
+    endpoints.sort_by(|a, b| b.1.calls.cmp(&a.1.calls).then_with(|| a.0.cmp(b.0)));
+
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "{:<24} {:>5} {:>7} {:>7}",
+            "endpoint", "calls", "avg", "p95"
+        ),
+        Style::default().fg(Color::DarkGray),
+    ))];
+    lines.extend(
+        endpoints
+            .into_iter()
+            .take(usize::from(area.height.saturating_sub(3)))
+            .map(|(endpoint, stats)| {
+                let endpoint = if endpoint.chars().count() > 24 {
+                    format!("{}…", endpoint.chars().take(23).collect::<String>())
+                } else {
+                    endpoint.clone()
+                };
+                let color = if stats.errors > 0 {
+                    Color::Yellow
+                } else {
+                    Color::Gray
+                };
+                Line::from(Span::styled(
+                    format!(
+                        "{:<24} {:>5} {:>5}ms {:>5}ms",
+                        endpoint,
+                        stats.calls,
+                        stats.average_ms(),
+                        stats.percentile_ms(95).unwrap_or(0)
+                    ),
+                    Style::default().fg(color),
+                ))
+            }),
+    );
+
+    let pane = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" API calls this session ")
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+    frame.render_widget(pane, area);
+}
+
+/// Highlights the parts of a todo that match the search or the tag filter
+const MATCH_STYLE: Style = Style::new().fg(Color::Black).bg(Color::Yellow);
+
+/// Past queries listed on the search screen
+const RECENT_SEARCHES_SHOWN: usize = 5;
+
+/// Characters of description shown around a search match
+const SNIPPET_WIDTH: usize = 40;
+
+/// Splits `text` into spans, styling `ranges` (sorted, non-overlapping byte ranges) with `highlight`
+fn highlighted_spans(text: &str, ranges: &[Range<usize>], highlight: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::with_capacity(ranges.len() * 2 + 1);
+    let mut pos = 0;
+    for range in ranges {
+        if range.start > pos {
+            spans.push(Span::raw(text[pos..range.start].to_string()));
+        }
+        spans.push(Span::styled(text[range.clone()].to_string(), highlight));
+        pos = range.end;
+    }
+    if pos < text.len() {
+        spans.push(Span::raw(text[pos..].to_string()));
+    }
+    spans
+}
+
 /// Formats due date timestamp for display in TUI
 fn format_due_date(due_ts: i64) -> Option<(String, Color)> {
     let due_dt = Utc.timestamp_opt(due_ts, 0).latest()?;
@@ -42,27 +178,75 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         .margin(1)
         .constraints([
             Constraint::Length(4), // Header (with status bar)
+            Constraint::Length(u16::from(!app.reminders.is_empty())), // Reminder banner
             Constraint::Min(0),    // Main content
+            Constraint::Length(if app.show_debug_pane {
+                DEBUG_PANE_HEIGHT
+            } else {
+                0
+            }),
             Constraint::Length(3), // Footer (fixed size)
         ])
         .split(size);
 
     // Render header
     render_header(frame, chunks[0], app);
+    render_reminder_banner(frame, chunks[1], app);
 
     // Render main content based on current screen
     match app.current_screen {
-        AppScreen::TodoList => render_todo_list(frame, chunks[1], app),
-        AppScreen::AddTodo => render_add_todo(frame, chunks[1], app),
-        AppScreen::EditTodo => render_edit_todo(frame, chunks[1], app),
-        AppScreen::Help => render_help(frame, chunks[1]),
-        AppScreen::Settings => render_settings(frame, chunks[1], app),
-        AppScreen::Search => render_search(frame, chunks[1], app),
-        AppScreen::TodoDetail => render_todo_detail(frame, chunks[1], app),
+        AppScreen::TodoList => render_todo_list(frame, chunks[2], app),
+        AppScreen::AddTodo => render_add_todo(frame, chunks[2], app),
+        AppScreen::EditTodo => render_edit_todo(frame, chunks[2], app),
+        AppScreen::Help => render_help(frame, chunks[2], app),
+        AppScreen::Settings => render_settings(frame, chunks[2], app),
+        AppScreen::Search => render_search(frame, chunks[2], app),
+        AppScreen::TodoDetail => {
+            if let Some((max_scroll, page)) = render_todo_detail(frame, chunks[2], app) {
+                app.detail_scroll = app.detail_scroll.min(max_scroll);
+                app.detail_max_scroll = max_scroll;
+                app.detail_page = page;
+            }
+        }
+        AppScreen::Week => render_week(frame, chunks[2], app),
+        AppScreen::Notifications => render_notifications(frame, chunks[2], app),
+    }
+
+    if app.show_debug_pane {
+        if crate::metrics::is_enabled() {
+            let panes = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Length(48)])
+                .split(chunks[3]);
+            render_debug_pane(frame, panes[0]);
+            render_metrics_pane(frame, panes[1]);
+        } else {
+            render_debug_pane(frame, chunks[3]);
+        }
     }
 
     // Render footer
-    render_footer(frame, chunks[2], app);
+    render_footer(frame, chunks[4], app);
+
+    if let Some(conflict) = &app.conflict {
+        render_conflict_dialog(frame, chunks[2], conflict, app);
+    }
+    if let Some(urls) = &app.url_picker {
+        render_url_picker(frame, chunks[2], urls);
+    }
+    if let Some(revisions) = &app.history {
+        render_history(frame, chunks[2], revisions, app.history_scroll);
+    }
+    if let Some(popup) = &app.filter_popup {
+        render_filter_popup(frame, chunks[2], popup, app);
+    }
+    if let Some(path) = &app.export_prompt {
+        render_export_prompt(frame, chunks[2], path, app.marked.len());
+    }
+
+    if let Some(tour) = &app.tour {
+        render_tour(frame, tour, [chunks[0], chunks[2], chunks[4]]);
+    }
 
     // Render loading overlay if needed
     if app.loading {
@@ -73,9 +257,26 @@ pub fn render(frame: &mut Frame, app: &mut App) {
     if app.error_message.is_some() || app.success_message.is_some() {
         render_toast_notification(frame, size, app);
     }
+
+    term::adapt_buffer(frame.buffer_mut());
 }
 
-fn render_header(frame: &mut Frame, area: Rect, app: &App) {
+/// Draws `app` off-screen on a `width` × `height` grid and returns the cells
+///
+/// Renders exactly what [`render`] draws on a terminal, with symbols adapted
+/// to the detected terminal, so screens can be checked in tests or turned
+/// into text and images without a TTY.
+///
+/// # Errors
+///
+/// Returns an error if drawing fails
+pub fn render_to_buffer(app: &mut App, width: u16, height: u16) -> anyhow::Result<Buffer> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    terminal.draw(|frame| render(frame, app))?;
+    Ok(terminal.backend().buffer().clone())
+}
+
+fn render_header(frame: &mut Frame, area: Rect, app: &mut App) {
     // Split header into title and status bar
     let header_chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -89,15 +290,51 @@ fn render_header(frame: &mut Frame, area: Rect, app: &App) {
         AppScreen::TodoList => {
             let completed = app.todos.iter().filter(|t| t.completed).count();
             let pending = app.todos.len() - completed;
-            let filter_info = if app.show_all_todos { "all" } else { "pending" };
-            let priority_filter = match app.filter_priority {
-                Some(1) => " (low priority)",
-                Some(2) => " (medium priority)",
-                Some(3) => " (high priority)",
-                _ => "",
+            let filters = &app.filters;
+            let filter_info = if app.show_archived {
+                "archived"
+            } else if !filters.shows_completed() {
+                "pending"
+            } else if filters.statuses == BTreeSet::from([Status::Completed]) {
+                "completed"
+            } else {
+                "all"
+            };
+            let priority_filter = if filters.priorities.is_empty() {
+                String::new()
+            } else {
+                let labels: Vec<&str> = filters
+                    .priorities
+                    .iter()
+                    .rev()
+                    .map(|level| app.config.priority_labels.label(*level))
+                    .collect();
+                format!(" ({} priority)", labels.join("/"))
+            };
+            let mut facet_filter = String::new();
+            if !filters.due.is_empty() {
+                let windows: Vec<String> = filters
+                    .due
+                    .iter()
+                    .map(|bucket| bucket.label().to_lowercase())
+                    .collect();
+                facet_filter.push_str(&format!(", {}", windows.join("/")));
+            }
+            if !filters.tags.is_empty() {
+                let tags: Vec<String> = filters.tags.iter().map(|tag| format!("#{tag}")).collect();
+                facet_filter.push_str(&format!(", {}", tags.join("/")));
+            }
+            let day_filter = app
+                .filter_due_day
+                .map(|day| format!(", due {}", day.format("%a %b %-d")))
+                .unwrap_or_default();
+            let sort = if app.sort.is_empty() {
+                String::new()
+            } else {
+                format!(", by {}", app.sort)
             };
             format!(
-                "Pali Todo Manager - {pending} pending, {completed} completed (showing {filter_info}{priority_filter})"
+                "Pali Todo Manager - {pending} pending, {completed} completed (showing {filter_info}{facet_filter}{day_filter}{priority_filter}{sort})"
             )
         }
         AppScreen::AddTodo => "Pali Todo Manager - Add New Todo".to_string(),
@@ -123,21 +360,194 @@ fn render_header(frame: &mut Frame, area: Rect, app: &App) {
         AppScreen::Settings => "Pali Todo Manager - Configuration".to_string(),
         AppScreen::Search => "Pali Todo Manager - Search Todos".to_string(),
         AppScreen::TodoDetail => "Pali Todo Manager - Todo Details".to_string(),
+        AppScreen::Notifications => "Pali Todo Manager - Notifications".to_string(),
+        AppScreen::Week => {
+            let end = app.week_start + chrono::Duration::days(6);
+            format!(
+                "Pali Todo Manager - Week of {} – {}",
+                app.week_start.format("%b %-d"),
+                end.format("%b %-d")
+            )
+        }
     };
 
+    let title_text = if let Some(pomodoro) = &app.pomodoro {
+        let remaining = pomodoro.remaining().as_secs();
+        format!(
+            "{title_text} │ 🍅 {:02}:{:02} {}",
+            remaining / 60,
+            remaining % 60,
+            pomodoro.todo_title
+        )
+    } else {
+        title_text
+    };
+
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .title_top(connection_indicator(app.connection).right_aligned());
+    app.overdue_badge_area = None;
+    if let Some(badge) = overdue_badge(app.due_counts()) {
+        // Left-aligned titles start right after the top-left corner
+        let badge_area = header_chunks[0];
+        app.overdue_badge_area = Some(Rect::new(
+            badge_area.x + 1,
+            badge_area.y,
+            u16::try_from(badge.width())
+                .unwrap_or(u16::MAX)
+                .min(badge_area.width.saturating_sub(2)),
+            1,
+        ));
+        block = block.title_top(badge);
+    }
+    app.progress_area = None;
+    if let Some(gauge) = progress_gauge(app.today_progress()) {
+        // Right-aligned titles end right before the bottom-right corner
+        let gauge_area = header_chunks[0];
+        let width = u16::try_from(gauge.width())
+            .unwrap_or(u16::MAX)
+            .min(gauge_area.width.saturating_sub(2));
+        app.progress_area = Some(Rect::new(
+            gauge_area.right().saturating_sub(width + 1),
+            gauge_area.bottom().saturating_sub(1),
+            width,
+            1,
+        ));
+        block = block.title_bottom(gauge.right_aligned());
+    }
+    app.unread_badge_area = None;
+    if let Some(badge) = unread_badge(app.notifications.unread()) {
+        let badge_area = header_chunks[0];
+        app.unread_badge_area = Some(Rect::new(
+            badge_area.x + 1,
+            badge_area.bottom().saturating_sub(1),
+            u16::try_from(badge.width())
+                .unwrap_or(u16::MAX)
+                .min(badge_area.width.saturating_sub(2)),
+            1,
+        ));
+        block = block.title_bottom(badge);
+    }
+
     let title = Paragraph::new(title_text)
         .style(
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD),
         )
-        .block(Block::default().borders(Borders::ALL));
+        .block(block);
     frame.render_widget(title, header_chunks[0]);
 
     // Render status bar
     render_status_bar(frame, header_chunks[1], app);
 }
 
+/// One line naming the soonest due todo, e.g. "⏰ Due in 12m: Call Bob (+2 more)"
+fn render_reminder_banner(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(todo) = app
+        .reminders
+        .first()
+        .and_then(|id| app.todos.iter().find(|todo| &todo.id == id))
+    else {
+        return;
+    };
+    let Some(due_ts) = todo.due_date else {
+        return;
+    };
+
+    let minutes = (due_ts - Utc::now().timestamp()) / 60;
+    let when = match minutes {
+        m if m > 0 => format!("Due in {m}m"),
+        0 => "Due now".to_string(),
+        m => format!("Due {}m ago", -m),
+    };
+    let more = match app.reminders.len() {
+        1 => String::new(),
+        n => format!(" (+{} more)", n - 1),
+    };
+
+    let banner = Paragraph::new(Line::from(vec![
+        Span::styled(
+            format!(" ⏰ {when}: {}{more} ", todo.title),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw("  D to dismiss"),
+    ]))
+    .style(Style::default().fg(Color::Black).bg(Color::Yellow));
+    frame.render_widget(banner, area);
+}
+
+/// "⚠ 2 overdue / 1 due today" for the header border, or `None` if neither
+fn overdue_badge((overdue, due_today): (usize, usize)) -> Option<Line<'static>> {
+    if overdue == 0 && due_today == 0 {
+        return None;
+    }
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    Some(Line::from(vec![
+        Span::styled(
+            format!(" {} {overdue} overdue", symbols().warning),
+            bold.fg(Color::Red),
+        ),
+        Span::styled(" / ", Style::default().fg(Color::DarkGray)),
+        Span::styled(format!("{due_today} due today "), bold.fg(Color::Yellow)),
+    ]))
+}
+
+/// "3 unread (N)" for the header border, or `None` if everything was seen
+fn unread_badge(unread: usize) -> Option<Line<'static>> {
+    (unread > 0).then(|| {
+        Line::from(Span::styled(
+            format!(" {} {unread} unread (N) ", symbols().info),
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        ))
+    })
+}
+
+/// "Today: 3/7 done ████░░░░░░" for the header border, or `None` if nothing is due or done today
+fn progress_gauge((done, total): (usize, usize)) -> Option<Line<'static>> {
+    const BAR_WIDTH: usize = 10;
+
+    if total == 0 {
+        return None;
+    }
+    let filled = (done * BAR_WIDTH + total / 2) / total;
+    let color = if done == total {
+        Color::Green
+    } else {
+        Color::Yellow
+    };
+    Some(Line::from(vec![
+        Span::styled(
+            format!(" Today: {done}/{total} done "),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            term::glyph("█", "#").repeat(filled),
+            Style::default().fg(Color::Green),
+        ),
+        Span::styled(
+            term::glyph("░", "-").repeat(BAR_WIDTH - filled),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::raw(" "),
+    ]))
+}
+
+/// "● online 42ms" / "● offline" for the header border
+fn connection_indicator(connection: ConnectionState) -> Line<'static> {
+    let (text, color) = match connection {
+        ConnectionState::Unknown => (" ○ connecting… ".to_string(), Color::DarkGray),
+        ConnectionState::Online(latency) => (
+            format!(" ● online {}ms ", latency.as_millis()),
+            Color::Green,
+        ),
+        ConnectionState::Offline => (" ● offline ".to_string(), Color::Red),
+    };
+    Line::from(Span::styled(text, Style::default().fg(color)))
+}
+
 fn render_status_bar(frame: &mut Frame, area: Rect, app: &App) {
     let (status_text, status_style) = if let Some(error) = &app.error_message {
         (
@@ -151,6 +561,8 @@ fn render_status_bar(frame: &mut Frame, area: Rect, app: &App) {
                 .fg(Color::Green)
                 .add_modifier(Modifier::BOLD),
         )
+    } else if app.is_refreshing() {
+        ("↻ Refreshing…", Style::default().fg(Color::DarkGray))
     } else {
         ("Ready", Style::default().fg(Color::Gray))
     };
@@ -160,12 +572,18 @@ fn render_status_bar(frame: &mut Frame, area: Rect, app: &App) {
 }
 
 fn render_todo_list(frame: &mut Frame, area: Rect, app: &mut App) {
+    let query = app.search_matcher();
+    let now = Utc::now().timestamp();
     let todos: Vec<ListItem> = app
         .filtered_todos
         .iter()
         .enumerate()
         .map(|(i, todo)| {
-            let status = if todo.completed { "✓" } else { "○" };
+            let status = if todo.completed {
+                symbols().done
+            } else {
+                symbols().pending
+            };
             let id_short = if todo.id.len() > ID_DISPLAY_LENGTH {
                 &todo.id[..ID_DISPLAY_LENGTH]
             } else {
@@ -195,11 +613,55 @@ fn render_todo_list(frame: &mut Frame, area: Rect, app: &mut App) {
                 style = style.bg(Color::Blue);
             }
 
-            // Build the line with due date if present
-            let mut line = format!(
-                "{} [{}] {} {}",
-                status, id_short, todo.title, priority_indicator
-            );
+            // Build the line with due date if present; search matches and
+            // the filtered tag are highlighted to show why a todo is listed
+            let title_matches = query
+                .as_ref()
+                .map(|query| query.find_ranges(&todo.title))
+                .unwrap_or_default();
+            let mut spans = Vec::new();
+            if app.multi_select {
+                let mark = if app.marked.contains(&todo.id) {
+                    term::glyph("●", "*")
+                } else {
+                    " "
+                };
+                spans.push(Span::styled(
+                    format!("{mark} "),
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+            spans.push(Span::raw(format!("{status} [{id_short}] ")));
+            if app.local_state.is_pinned(&todo.id) {
+                spans.push(Span::styled(
+                    format!("{} ", symbols().pinned),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            if let Some(alias) = app.local_state.alias_of(&todo.id) {
+                spans.push(Span::raw(format!("@{alias} ")));
+            }
+            spans.extend(highlighted_spans(&todo.title, &title_matches, MATCH_STYLE));
+            let mut line = format!(" {priority_indicator}");
+
+            if let Some((done, total)) = todo.description.as_deref().and_then(checklist::progress) {
+                line.push_str(&format!(" [{done}/{total}]"));
+            }
+
+            if !todo.completed && app.local_state.is_blocked(&todo.id, &app.todos) {
+                line.push_str(&format!(" {} blocked", symbols().blocked));
+                style = style.add_modifier(Modifier::DIM);
+            }
+
+            for tag in app.local_state.tags_of(&todo.id) {
+                if app.filters.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                    spans.push(Span::raw(std::mem::take(&mut line)));
+                    spans.push(Span::raw(" "));
+                    spans.push(Span::styled(format!("#{tag}"), MATCH_STYLE));
+                } else {
+                    line.push_str(&format!(" #{tag}"));
+                }
+            }
 
             if let Some(due_ts) = todo.due_date {
                 if let Some((due_str, due_color)) = format_due_date(due_ts) {
@@ -216,7 +678,35 @@ fn render_todo_list(frame: &mut Frame, area: Rect, app: &mut App) {
                 }
             }
 
-            ListItem::new(line).style(style)
+            if let Some(rule_style) =
+                highlight::style_for(&app.color_rules, todo, app.local_state.tags_of(&todo.id))
+            {
+                style = rule_style.patch(style);
+            }
+
+            spans.push(Span::raw(line));
+            if let Some(indicator) = app.presence.indicator(&todo.id, now) {
+                spans.push(Span::styled(
+                    format!(" · {indicator}"),
+                    Style::default().add_modifier(Modifier::DIM | Modifier::ITALIC),
+                ));
+            }
+            // A match only in the description is shown as a snippet
+            if let (true, Some(query)) = (title_matches.is_empty(), &query) {
+                if let Some(description) = &todo.description {
+                    let matches = query.find_ranges(description);
+                    let (snippet, matches) = snippet(description, &matches, SNIPPET_WIDTH);
+                    if !snippet.is_empty() {
+                        spans.push(Span::styled(
+                            " — ",
+                            Style::default().add_modifier(Modifier::DIM),
+                        ));
+                        spans.extend(highlighted_spans(&snippet, &matches, MATCH_STYLE));
+                    }
+                }
+            }
+
+            ListItem::new(Line::from(spans)).style(style)
         })
         .collect();
 
@@ -248,8 +738,67 @@ fn render_todo_list(frame: &mut Frame, area: Rect, app: &mut App) {
     }
 }
 
+/// Seven day columns with the todos due on each, for planning the week
+fn render_week(frame: &mut Frame, area: Rect, app: &App) {
+    let today = Local::now().date_naive();
+    let selected = app.selected_week_date();
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 7); 7])
+        .split(area);
+
+    for (offset, column) in columns.iter().enumerate() {
+        let date = app.week_start + chrono::Duration::days(offset as i64);
+        let todos = app.week_todos(date);
+        let items: Vec<ListItem> = todos
+            .iter()
+            .enumerate()
+            .map(|(i, todo)| {
+                let mut style = match todo.priority {
+                    3 => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    1 => Style::default().fg(Color::Gray),
+                    _ => Style::default().fg(Color::White),
+                };
+                if todo.completed {
+                    style = style.fg(Color::Green).add_modifier(Modifier::CROSSED_OUT);
+                }
+                if let Some(rule_style) =
+                    highlight::style_for(&app.color_rules, todo, app.local_state.tags_of(&todo.id))
+                {
+                    style = rule_style.patch(style);
+                }
+                let moving = app.moving_todo.as_deref() == Some(todo.id.as_str());
+                let marker = if moving { "↔ " } else { "" };
+                if date == selected && i == app.week_index {
+                    style = style.bg(if moving { Color::Magenta } else { Color::Blue });
+                }
+                ListItem::new(format!("{marker}{}", todo.title)).style(style)
+            })
+            .collect();
+
+        let border_style = if date == selected {
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        } else if date == today {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+        let title = format!("{} ({})", date.format("%a %-d"), todos.len());
+        let list = List::new(items).block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(border_style),
+        );
+        frame.render_widget(list, *column);
+    }
+}
+
 fn render_add_todo(frame: &mut Frame, area: Rect, app: &App) {
-    app.input_form.render(frame, area);
+    app.input_form
+        .render(frame, area, &app.config.priority_labels);
 }
 
 fn render_edit_todo(frame: &mut Frame, area: Rect, app: &App) {
@@ -262,13 +811,15 @@ fn render_edit_todo(frame: &mut Frame, area: Rect, app: &App) {
         ])
         .split(area);
 
-    let title = Paragraph::new("Edit Todo")
-        .style(
+    // The title row only has room for the border, so the title goes into it
+    let title = Block::default()
+        .title(Span::styled(
+            "Edit Todo",
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD),
-        )
-        .block(Block::default().borders(Borders::TOP | Borders::LEFT | Borders::RIGHT));
+        ))
+        .borders(Borders::TOP | Borders::LEFT | Borders::RIGHT);
     frame.render_widget(title, chunks[0]);
 
     // Render the input form in the remaining space
@@ -278,10 +829,12 @@ fn render_edit_todo(frame: &mut Frame, area: Rect, app: &App) {
         width: chunks[1].width,
         height: chunks[1].height + 1, // Extend to connect with title border
     };
-    app.input_form.render(frame, form_area);
+    app.input_form
+        .render(frame, form_area, &app.config.priority_labels);
 }
 
-fn render_help(frame: &mut Frame, area: Rect) {
+fn render_help(frame: &mut Frame, area: Rect, app: &App) {
+    let labels = &app.config.priority_labels;
     let help_text = vec![
         Line::from(vec![Span::styled(
             "Pali TUI Help",
@@ -308,10 +861,21 @@ fn render_help(frame: &mut Frame, area: Rect) {
         )]),
         Line::from("  n/a        - Add new todo"),
         Line::from("  e          - Edit selected todo"),
+        Line::from("  E          - Edit title and description in $EDITOR"),
         Line::from("  Enter/Space- Toggle completion"),
         Line::from("  d          - Delete selected todo"),
         Line::from("  v          - View todo details"),
+        Line::from("  O          - Open a link from the todo (details view)"),
+        Line::from("  1-9, y1-9  - Open or copy the link hinted [n] (details view)"),
+        Line::from("  ↑↓/PgUp/Dn- Scroll the details view (g/G top/bottom)"),
+        Line::from("  Tab/Space  - Select/toggle checklist items (details view)"),
+        Line::from("  [/]        - Scroll comments (details view)"),
+        Line::from("  H          - Revision history with changes (details view)"),
         Line::from("  r          - Refresh todo list"),
+        Line::from("  p          - Start/cancel a 25 min pomodoro"),
+        Line::from("  x          - Archive (or restore) selected todo"),
+        Line::from("  P          - Pin (or unpin) selected todo to the top of the list"),
+        Line::from("  V          - Multi-select (Space mark, x export to .json/.md/.csv)"),
         Line::from(""),
         Line::from(vec![Span::styled(
             "Search & Filtering:",
@@ -321,6 +885,8 @@ fn render_help(frame: &mut Frame, area: Rect) {
         )]),
         Line::from("  /          - Search todos"),
         Line::from("  f          - Toggle show all/pending"),
+        Line::from("  F          - Filter popup (status, priority, tags, due; combine freely)"),
+        Line::from("  A          - Toggle archived view"),
         Line::from("  1/2/3      - Filter by priority"),
         Line::from("  0          - Clear priority filter"),
         Line::from(""),
@@ -332,6 +898,15 @@ fn render_help(frame: &mut Frame, area: Rect) {
         )]),
         Line::from("  h/?        - Show this help"),
         Line::from("  s          - Settings"),
+        Line::from("  D          - Dismiss the due-soon reminder"),
+        Line::from("  N          - Notifications: past messages, syncs and reminders"),
+        Line::from("  o          - Show only overdue todos (or click the header badge)"),
+        Line::from("  T          - Today's list (or click the Today gauge)"),
+        Line::from("  R          - Re-run the last search"),
+        Line::from("  S          - Cycle sort order (priority, due date, newest, title)"),
+        Line::from("  w          - Week view (h/l day, ←/→ week, Enter day list, m move todo)"),
+        Line::from("  F12        - Show/hide the log pane"),
+        Line::from("  Ctrl+Z     - Suspend to the shell (resume with fg)"),
         Line::from(""),
         Line::from(vec![Span::styled(
             "Priority Indicators:",
@@ -342,12 +917,12 @@ fn render_help(frame: &mut Frame, area: Rect) {
         Line::from(vec![
             Span::raw("  "),
             Span::styled("!", Style::default().fg(Color::Gray)),
-            Span::raw("   - Low priority"),
+            Span::raw(format!("   - {} priority", labels.label(1))),
         ]),
         Line::from(vec![
             Span::raw("  "),
             Span::styled("!!", Style::default().fg(Color::White)),
-            Span::raw("  - Medium priority"),
+            Span::raw(format!("  - {} priority", labels.label(2))),
         ]),
         Line::from(vec![
             Span::raw("  "),
@@ -355,7 +930,7 @@ fn render_help(frame: &mut Frame, area: Rect) {
                 "!!!",
                 Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
             ),
-            Span::raw(" - High priority"),
+            Span::raw(format!(" - {} priority", labels.label(3))),
         ]),
     ];
 
@@ -369,12 +944,18 @@ fn render_help(frame: &mut Frame, area: Rect) {
 fn render_settings(frame: &mut Frame, area: Rect, app: &App) {
     let key_status = if app.config.api_key.is_some() {
         (
-            Span::styled("✓ Configured", Style::default().fg(Color::Green)),
+            Span::styled(
+                format!("{} Configured", symbols().ok),
+                Style::default().fg(Color::Green),
+            ),
             Color::Green,
         )
     } else {
         (
-            Span::styled("✗ Not set", Style::default().fg(Color::Red)),
+            Span::styled(
+                format!("{} Not set", symbols().error),
+                Style::default().fg(Color::Red),
+            ),
             Color::Red,
         )
     };
@@ -414,6 +995,14 @@ fn render_settings(frame: &mut Frame, area: Rect, app: &App) {
             Span::raw("Use 'pacli config' to modify settings from the command line"),
         ]),
         Line::from(""),
+        Line::from(vec![
+            Span::styled("Press ", Style::default().fg(Color::Gray)),
+            Span::styled("t", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                " to take the tour of patui again",
+                Style::default().fg(Color::Gray),
+            ),
+        ]),
         Line::from(vec![
             Span::styled("Press ", Style::default().fg(Color::Gray)),
             Span::styled("Esc", Style::default().fg(Color::Yellow)),
@@ -442,13 +1031,20 @@ fn render_search(frame: &mut Frame, area: Rect, app: &App) {
         .split(area);
 
     // Search input field
+    let (title, input_color) = if !app.search_regex {
+        (" Search Todos (Ctrl+R: regex) ", Color::Yellow)
+    } else if app.search_query.is_empty() || app.search_matcher().is_some() {
+        (" Search Todos [regex] (Ctrl+R: text) ", Color::Magenta)
+    } else {
+        (" Search Todos [invalid regex] (Ctrl+R: text) ", Color::Red)
+    };
     let search_input = Paragraph::new(app.search_query.as_str())
-        .style(Style::default().fg(Color::Yellow))
-        .block(Block::default().title("Search Todos").borders(Borders::ALL));
+        .style(Style::default().fg(input_color))
+        .block(Block::default().title(title).borders(Borders::ALL));
     frame.render_widget(search_input, chunks[0]);
 
     // Instructions
-    let instructions_text = vec![
+    let mut instructions_text = vec![
         Line::from(vec![Span::styled(
             "Search Tips:",
             Style::default()
@@ -457,8 +1053,10 @@ fn render_search(frame: &mut Frame, area: Rect, app: &App) {
         )]),
         Line::from(""),
         Line::from("• Search matches both todo titles and descriptions"),
-        Line::from("• Search is case-insensitive"),
+        Line::from("• Search is case-insensitive; Ctrl+R switches to regular expressions,"),
+        Line::from("  matched locally (case-sensitive unless they start with (?i))"),
         Line::from("• Empty search returns to regular todo list"),
+        Line::from("• ↑/↓ recall previous searches; R in the list re-runs the last one"),
         Line::from(""),
         Line::from(vec![
             Span::styled("Press ", Style::default().fg(Color::Gray)),
@@ -468,6 +1066,18 @@ fn render_search(frame: &mut Frame, area: Rect, app: &App) {
             Span::styled(" to cancel", Style::default().fg(Color::Gray)),
         ]),
     ];
+    if !app.search_history.is_empty() {
+        instructions_text.push(Line::from(""));
+        instructions_text.push(Line::from(Span::styled(
+            "Recent Searches:",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )));
+        for query in app.search_history.iter().take(RECENT_SEARCHES_SHOWN) {
+            instructions_text.push(Line::from(format!("  {query}")));
+        }
+    }
 
     let instructions = Paragraph::new(instructions_text)
         .block(Block::default().title("Instructions").borders(Borders::ALL))
@@ -479,149 +1089,354 @@ fn render_search(frame: &mut Frame, area: Rect, app: &App) {
     frame.set_cursor_position((cursor_x, chunks[0].y + 1));
 }
 
-fn render_todo_detail(frame: &mut Frame, area: Rect, app: &App) {
-    if let Some(index) = app.selected_todo {
-        if let Some(todo) = app.filtered_todos.get(index) {
-            // Pre-format dates to avoid lifetime issues
-            let created_str = chrono::DateTime::from_timestamp(todo.created_at, 0)
-                .map(|dt| {
-                    dt.with_timezone(&chrono::Local)
-                        .format("%Y-%m-%d %H:%M:%S")
-                        .to_string()
-                })
-                .unwrap_or_else(|| "Invalid date".to_string());
-
-            let updated_str = chrono::DateTime::from_timestamp(todo.updated_at, 0)
-                .map(|dt| {
-                    dt.with_timezone(&chrono::Local)
-                        .format("%Y-%m-%d %H:%M:%S")
-                        .to_string()
-                })
-                .unwrap_or_else(|| "Invalid date".to_string());
-
-            let due_date_str = if let Some(due_ts) = todo.due_date {
-                chrono::DateTime::from_timestamp(due_ts, 0)
-                    .map(|dt| {
-                        dt.with_timezone(&chrono::Local)
-                            .format("%Y-%m-%d %H:%M:%S")
-                            .to_string()
-                    })
-                    .unwrap_or_else(|| "Invalid date".to_string())
-            } else {
-                "Not set".to_string()
-            };
+/// Rows a paragraph of `lines` takes when wrapped to `width` columns (at least, as words are not split)
+fn wrapped_height(lines: &[Line], width: u16) -> usize {
+    let width = usize::from(width.max(1));
+    lines
+        .iter()
+        .map(|line| line.width().div_ceil(width).max(1))
+        .sum()
+}
+
+/// Underlines the URLs in `line`, following those in `urls` with their `[n]` hint
+fn hint_links<'a>(line: Line<'a>, urls: &[String]) -> Line<'a> {
+    let mut spans = Vec::new();
+    for span in line.spans {
+        let ranges = links::url_ranges(&span.content);
+        if ranges.is_empty() {
+            spans.push(span);
+            continue;
+        }
+        let text = &span.content;
+        let mut end = 0;
+        for range in ranges {
+            if range.start > end {
+                spans.push(Span::styled(text[end..range.start].to_string(), span.style));
+            }
+            let url = &text[range.clone()];
+            spans.push(Span::styled(
+                url.to_string(),
+                span.style.add_modifier(Modifier::UNDERLINED),
+            ));
+            if let Some(index) = urls.iter().position(|known| known == url) {
+                spans.push(Span::styled(
+                    format!("[{}]", index + 1),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            end = range.end;
+        }
+        if end < text.len() {
+            spans.push(Span::styled(text[end..].to_string(), span.style));
+        }
+    }
+    Line { spans, ..line }
+}
+
+/// Renders the details of the selected todo as a scrollable viewport
+///
+/// Returns the largest useful scroll offset and the height of the viewport,
+/// or `None` if no todo is selected.
+fn render_todo_detail(frame: &mut Frame, area: Rect, app: &App) -> Option<(u16, u16)> {
+    let todo = app.filtered_todos.get(app.selected_todo?)?;
+    // Pre-format dates to avoid lifetime issues
+    let created_str = chrono::DateTime::from_timestamp(todo.created_at, 0)
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|| "Invalid date".to_string());
+
+    let updated_str = chrono::DateTime::from_timestamp(todo.updated_at, 0)
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|| "Invalid date".to_string());
+
+    let due_date_str = if let Some(due_ts) = todo.due_date {
+        chrono::DateTime::from_timestamp(due_ts, 0)
+            .map(|dt| {
+                dt.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_else(|| "Invalid date".to_string())
+    } else {
+        "Not set".to_string()
+    };
+
+    let due_date_color = if let Some(due_ts) = todo.due_date {
+        format_due_date(due_ts)
+            .map(|(_, color)| color)
+            .unwrap_or(Color::White)
+    } else {
+        Color::Gray
+    };
+
+    let tracked_seconds = app
+        .local_state
+        .tracked_seconds(&todo.id, Utc::now().timestamp());
+    let tracked_str = if tracked_seconds == 0 {
+        "None".to_string()
+    } else if app.local_state.is_timer_running(&todo.id) {
+        format!("{} (timer running)", format_duration(tracked_seconds))
+    } else {
+        format_duration(tracked_seconds)
+    };
+
+    let description = todo.description.as_deref().unwrap_or_default();
+    let description_text = checklist::strip_items(description);
+    let checklist_items = checklist::items(description);
 
-            let due_date_color = if let Some(due_ts) = todo.due_date {
-                format_due_date(due_ts)
-                    .map(|(_, color)| color)
-                    .unwrap_or(Color::White)
+    let mut detail_text = vec![
+        Line::from(vec![Span::styled(
+            "Todo Details",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("ID: ", Style::default().fg(Color::Yellow)),
+            Span::styled(&todo.id, Style::default().fg(Color::Gray)),
+        ]),
+        Line::from(vec![
+            Span::styled("Title: ", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                &todo.title,
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "Description:",
+            Style::default().fg(Color::Yellow),
+        )]),
+    ];
+    if description_text.is_empty() {
+        detail_text.push(Line::from("(no description)"));
+    } else {
+        detail_text.extend(markdown::to_lines(&description_text));
+    }
+
+    if !checklist_items.is_empty() {
+        let done = checklist_items.iter().filter(|item| item.done).count();
+        detail_text.push(Line::from(""));
+        detail_text.push(Line::from(vec![Span::styled(
+            format!("Checklist ({done}/{}):", checklist_items.len()),
+            Style::default().fg(Color::Yellow),
+        )]));
+
+        for (i, item) in checklist_items.iter().enumerate() {
+            let mut style = if item.done {
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::CROSSED_OUT)
             } else {
-                Color::Gray
+                Style::default().fg(Color::White)
             };
+            if i == app.checklist_index {
+                style = style.bg(Color::Blue);
+            }
+            let mark = if item.done { "[x]" } else { "[ ]" };
+            detail_text.push(Line::from(Span::styled(
+                format!("  {mark} {}", item.text),
+                style,
+            )));
+        }
+    }
+
+    if !app.detail_attachments.is_empty() {
+        detail_text.push(Line::from(""));
+        detail_text.push(Line::from(vec![Span::styled(
+            format!("Attachments ({}):", app.detail_attachments.len()),
+            Style::default().fg(Color::Yellow),
+        )]));
+        for attachment in &app.detail_attachments {
+            detail_text.push(Line::from(vec![
+                Span::raw(format!("  📎 {} ", attachment.filename)),
+                Span::styled(
+                    format!("({})", attachment.display_size()),
+                    Style::default().fg(Color::Gray),
+                ),
+            ]));
+        }
+    }
 
-            let detail_text = vec![
-                Line::from(vec![Span::styled(
-                    "Todo Details",
+    detail_text.extend(vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Status: ", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                if todo.completed {
+                    "Completed"
+                } else {
+                    "Pending"
+                },
+                if todo.completed {
                     Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
-                )]),
-                Line::from(""),
-                Line::from(vec![
-                    Span::styled("ID: ", Style::default().fg(Color::Yellow)),
-                    Span::styled(&todo.id, Style::default().fg(Color::Gray)),
-                ]),
-                Line::from(vec![
-                    Span::styled("Title: ", Style::default().fg(Color::Yellow)),
-                    Span::styled(
-                        &todo.title,
-                        Style::default()
-                            .fg(Color::White)
-                            .add_modifier(Modifier::BOLD),
-                    ),
-                ]),
-                Line::from(""),
-                Line::from(vec![Span::styled(
-                    "Description:",
-                    Style::default().fg(Color::Yellow),
-                )]),
-                Line::from(match &todo.description {
-                    Some(desc) => desc.as_str(),
-                    None => "(no description)",
-                }),
-                Line::from(""),
-                Line::from(vec![
-                    Span::styled("Status: ", Style::default().fg(Color::Yellow)),
-                    Span::styled(
-                        if todo.completed {
-                            "Completed"
-                        } else {
-                            "Pending"
-                        },
-                        if todo.completed {
-                            Style::default()
-                                .fg(Color::Green)
-                                .add_modifier(Modifier::BOLD)
-                        } else {
-                            Style::default().fg(Color::Yellow)
-                        },
-                    ),
-                ]),
-                Line::from(vec![
-                    Span::styled("Priority: ", Style::default().fg(Color::Yellow)),
-                    Span::styled(
-                        match todo.priority {
-                            1 => "Low (!)",
-                            2 => "Medium (!!)",
-                            3 => "High (!!!)",
-                            _ => "Unknown (?)",
-                        },
-                        match todo.priority {
-                            1 => Style::default().fg(Color::Gray),
-                            2 => Style::default().fg(Color::White),
-                            3 => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-                            _ => Style::default().fg(Color::Gray),
-                        },
-                    ),
-                ]),
-                Line::from(""),
-                Line::from(vec![
-                    Span::styled("Due Date: ", Style::default().fg(Color::Yellow)),
-                    Span::styled(
-                        &due_date_str,
-                        Style::default().fg(due_date_color).add_modifier(
-                            if due_date_color == Color::Red {
-                                Modifier::BOLD
-                            } else {
-                                Modifier::empty()
-                            },
-                        ),
-                    ),
-                ]),
-                Line::from(""),
-                Line::from(vec![
-                    Span::styled("Created: ", Style::default().fg(Color::Yellow)),
-                    Span::styled(&created_str, Style::default().fg(Color::Gray)),
-                ]),
-                Line::from(vec![
-                    Span::styled("Updated: ", Style::default().fg(Color::Yellow)),
-                    Span::styled(&updated_str, Style::default().fg(Color::Gray)),
-                ]),
-                Line::from(""),
-                Line::from(vec![
-                    Span::styled("Press ", Style::default().fg(Color::Gray)),
-                    Span::styled("Esc", Style::default().fg(Color::Yellow)),
-                    Span::styled(" to return to todo list", Style::default().fg(Color::Gray)),
-                ]),
-            ];
-
-            let detail = Paragraph::new(detail_text)
-                .block(Block::default().title("Todo Details").borders(Borders::ALL))
-                .wrap(Wrap { trim: true });
-
-            frame.render_widget(detail, area);
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::Yellow)
+                },
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Priority: ", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                match todo.priority {
+                    1 => format!("{} (!)", app.config.priority_labels.label(1)),
+                    2 => format!("{} (!!)", app.config.priority_labels.label(2)),
+                    3 => format!("{} (!!!)", app.config.priority_labels.label(3)),
+                    _ => "Unknown (?)".to_string(),
+                },
+                match todo.priority {
+                    1 => Style::default().fg(Color::Gray),
+                    2 => Style::default().fg(Color::White),
+                    3 => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    _ => Style::default().fg(Color::Gray),
+                },
+            ),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Due Date: ", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                &due_date_str,
+                Style::default()
+                    .fg(due_date_color)
+                    .add_modifier(if due_date_color == Color::Red {
+                        Modifier::BOLD
+                    } else {
+                        Modifier::empty()
+                    }),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Time Tracked: ", Style::default().fg(Color::Yellow)),
+            Span::styled(&tracked_str, Style::default().fg(Color::White)),
+        ]),
+        Line::from(vec![
+            Span::styled("Pomodoros: ", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                app.local_state.pomodoro_count(&todo.id).to_string(),
+                Style::default().fg(Color::White),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Created: ", Style::default().fg(Color::Yellow)),
+            Span::styled(&created_str, Style::default().fg(Color::Gray)),
+        ]),
+        Line::from(vec![
+            Span::styled("Updated: ", Style::default().fg(Color::Yellow)),
+            Span::styled(&updated_str, Style::default().fg(Color::Gray)),
+        ]),
+    ]);
+    if let Some(modifier) = app.presence.modifier(&todo.id) {
+        let ago = presence::ago(Utc::now().timestamp() - modifier.modified_at);
+        detail_text.push(Line::from(vec![
+            Span::styled("Modified by: ", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                format!("{} ({ago})", modifier.who()),
+                Style::default().fg(Color::Gray),
+            ),
+        ]));
+    }
+    let urls = links::todo_urls(todo);
+    let detail_text: Vec<Line> = detail_text
+        .into_iter()
+        .map(|line| hint_links(line, &urls))
+        .collect();
+
+    let detail_area = if app.detail_comments.is_empty() {
+        area
+    } else {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Percentage(35)])
+            .split(area);
+        render_comments(frame, chunks[1], app);
+        chunks[0]
+    };
+
+    // Inside the borders, leaving the right column to the scrollbar
+    let page = detail_area.height.saturating_sub(2);
+    let total = wrapped_height(&detail_text, detail_area.width.saturating_sub(3));
+    let max_scroll = u16::try_from(total.saturating_sub(usize::from(page))).unwrap_or(u16::MAX);
+    let scroll = app.detail_scroll.min(max_scroll);
+
+    let title = if max_scroll > 0 {
+        format!(
+            "Todo Details ({}%)",
+            u32::from(scroll) * 100 / u32::from(max_scroll)
+        )
+    } else {
+        "Todo Details".to_string()
+    };
+    let detail = Paragraph::new(detail_text)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+    frame.render_widget(detail, detail_area);
+
+    if max_scroll > 0 {
+        let mut scrollbar_state = ScrollbarState::new(total)
+            .viewport_content_length(usize::from(page))
+            .position(usize::from(scroll));
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight),
+            detail_area.inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar_state,
+        );
+    }
+
+    Some((max_scroll, page))
+}
+
+fn render_comments(frame: &mut Frame, area: Rect, app: &App) {
+    let mut lines = Vec::new();
+    for comment in &app.detail_comments {
+        let when = chrono::DateTime::from_timestamp(comment.created_at, 0)
+            .map(|dt| {
+                dt.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_else(|| "Invalid date".to_string());
+
+        lines.push(Line::from(Span::styled(
+            when,
+            Style::default().fg(Color::Gray),
+        )));
+        for text_line in comment.text.lines() {
+            lines.push(Line::from(format!("  {text_line}")));
         }
     }
+
+    let comments = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(format!(
+                    "Comments ({}) - [/] to scroll",
+                    app.detail_comments.len()
+                ))
+                .borders(Borders::ALL),
+        )
+        .scroll((app.comment_scroll, 0));
+
+    frame.render_widget(comments, area);
 }
 
 fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
@@ -629,6 +1444,14 @@ fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
 
     // Render help text based on current screen
     let help_text = match app.current_screen {
+        AppScreen::TodoList if app.multi_select => vec![
+            Span::styled("Space", Style::default().fg(Color::Yellow)),
+            Span::raw(" mark │ "),
+            Span::styled("x", Style::default().fg(Color::Yellow)),
+            Span::raw(format!(" export {} │ ", app.marked.len())),
+            Span::styled("Esc", Style::default().fg(Color::Yellow)),
+            Span::raw(" done"),
+        ],
         AppScreen::TodoList => vec![
             Span::styled("q", Style::default().fg(Color::Yellow)),
             Span::raw(" quit │ "),
@@ -663,12 +1486,56 @@ fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
             Span::styled("Esc", Style::default().fg(Color::Yellow)),
             Span::raw(" cancel"),
         ],
-        AppScreen::Help | AppScreen::Settings | AppScreen::TodoDetail => vec![
+        AppScreen::TodoDetail => vec![
+            Span::styled("↑↓ PgUp/PgDn", Style::default().fg(Color::Yellow)),
+            Span::raw(" scroll │ "),
+            Span::styled("Tab", Style::default().fg(Color::Yellow)),
+            Span::raw(" checklist item │ "),
+            Span::styled("Space", Style::default().fg(Color::Yellow)),
+            Span::raw(" toggle item │ "),
+            Span::styled("1-9/y1-9", Style::default().fg(Color::Yellow)),
+            Span::raw(" open/copy link │ "),
+            Span::styled("H", Style::default().fg(Color::Yellow)),
+            Span::raw(" history │ "),
+            Span::styled("Esc", Style::default().fg(Color::Yellow)),
+            Span::raw(" back to todos"),
+        ],
+        AppScreen::Notifications => vec![
+            Span::styled("↑↓", Style::default().fg(Color::Yellow)),
+            Span::raw(" scroll │ "),
+            Span::styled("c", Style::default().fg(Color::Yellow)),
+            Span::raw(" clear │ "),
+            Span::styled("Esc", Style::default().fg(Color::Yellow)),
+            Span::raw(" back to todos"),
+        ],
+        AppScreen::Help | AppScreen::Settings => vec![
             Span::styled("Esc", Style::default().fg(Color::Yellow)),
             Span::raw(" back to todos │ "),
             Span::styled("q", Style::default().fg(Color::Yellow)),
             Span::raw(" quit"),
         ],
+        AppScreen::Week if app.moving_todo.is_some() => vec![
+            Span::styled("h/l ←/→", Style::default().fg(Color::Yellow)),
+            Span::raw(" pick day │ "),
+            Span::styled("m/Enter", Style::default().fg(Color::Yellow)),
+            Span::raw(" drop │ "),
+            Span::styled("Esc", Style::default().fg(Color::Yellow)),
+            Span::raw(" cancel move"),
+        ],
+        AppScreen::Week => vec![
+            Span::styled("h/l", Style::default().fg(Color::Yellow)),
+            Span::raw(" day │ "),
+            Span::styled("←/→", Style::default().fg(Color::Yellow)),
+            Span::raw(" week │ "),
+            Span::styled("t", Style::default().fg(Color::Yellow)),
+            Span::raw(" today │ "),
+            Span::styled("Enter", Style::default().fg(Color::Yellow)),
+            Span::raw(" day list │ "),
+            Span::styled("m", Style::default().fg(Color::Yellow)),
+            Span::raw(" move todo │ "),
+            Span::styled("Esc", Style::default().fg(Color::Yellow)),
+            Span::raw(" back to todos"),
+        ],
         AppScreen::Search => vec![
             Span::styled("Enter", Style::default().fg(Color::Yellow)),
             Span::raw(" search │ "),
@@ -683,6 +1550,359 @@ fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(help, area);
 }
 
+/// Value of a form field for the merge dialog
+fn conflict_value(form: &InputForm, field: InputField, app: &App) -> String {
+    let value = match field {
+        InputField::Priority => app.config.priority_labels.label(form.priority).to_string(),
+        field => form.value(field).replace('\n', " "),
+    };
+    if value.is_empty() {
+        "(none)".to_string()
+    } else {
+        value
+    }
+}
+
+/// The three-way choice shown when a todo changed on the server during editing
+fn render_conflict_dialog(frame: &mut Frame, area: Rect, conflict: &EditConflict, app: &App) {
+    let key = Style::default().fg(Color::Yellow);
+    let chosen = Style::default()
+        .fg(Color::Green)
+        .add_modifier(Modifier::BOLD);
+    let other = Style::default().fg(Color::DarkGray);
+
+    let mut lines = vec![
+        Line::from("This todo was changed on the server while you were editing it."),
+        Line::from(""),
+    ];
+    for (i, (field, side)) in InputField::ALL
+        .into_iter()
+        .zip(conflict.choices)
+        .enumerate()
+    {
+        let marker = if i == conflict.selected { "▶ " } else { "  " };
+        let label = if conflict.is_conflict(field) {
+            Span::styled(
+                format!("{marker}{} {}", field.name(), symbols().warning),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Span::styled(
+                format!("{marker}{}", field.name()),
+                Style::default().add_modifier(Modifier::BOLD),
+            )
+        };
+        let (mine_style, server_style) = match side {
+            Side::Mine => (chosen, other),
+            Side::Server => (other, chosen),
+        };
+        lines.push(Line::from(label));
+        lines.push(Line::from(vec![
+            Span::raw("    mine:   "),
+            Span::styled(conflict_value(&conflict.mine, field, app), mine_style),
+        ]));
+        lines.push(Line::from(vec![
+            Span::raw("    server: "),
+            Span::styled(conflict_value(&conflict.server, field, app), server_style),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("m", key),
+        Span::raw(" keep mine │ "),
+        Span::styled("t", key),
+        Span::raw(" take server │ "),
+        Span::styled("↑↓ Space", key),
+        Span::raw(" pick per field │ "),
+        Span::styled("Enter", key),
+        Span::raw(" save merge │ "),
+        Span::styled("Esc", key),
+        Span::raw(" back to editing"),
+    ]));
+
+    let dialog = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(" Edit conflict ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red)),
+        )
+        .wrap(Wrap { trim: false });
+
+    let popup_area = centered_rect(80, 90, area);
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(dialog, popup_area);
+}
+
+/// Lists the URLs of a todo, numbered, to open one
+fn render_url_picker(frame: &mut Frame, area: Rect, urls: &[String]) {
+    let mut lines: Vec<Line> = urls
+        .iter()
+        .take(9)
+        .enumerate()
+        .map(|(i, url)| {
+            Line::from(vec![
+                Span::styled(format!(" {} ", i + 1), Style::default().fg(Color::Yellow)),
+                Span::raw(url.clone()),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        " 1-9 open │ Esc cancel",
+        Style::default().fg(Color::Gray),
+    )));
+
+    let picker = Paragraph::new(lines).block(
+        Block::default()
+            .title(" Open link ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    let popup_area = centered_rect(70, 50, area);
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(picker, popup_area);
+}
+
+/// Shows the revisions of a todo from `first` on, each with what it changed
+fn render_history(frame: &mut Frame, area: Rect, revisions: &[Revision], first: usize) {
+    let mut lines = Vec::new();
+    for (i, revision) in revisions.iter().enumerate().skip(first) {
+        let mut header = vec![Span::styled(
+            revisions::describe(revision),
+            Style::default().fg(Color::Gray),
+        )];
+        let Some(previous) = i.checked_sub(1).map(|previous| &revisions[previous]) else {
+            header.push(Span::styled(" created", Style::default().fg(Color::Green)));
+            lines.push(Line::from(header));
+            lines.push(Line::from(format!("  {}", revision.title)));
+            continue;
+        };
+        lines.push(Line::from(header));
+        for change in revisions::changes(previous, revision) {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", change.field),
+                Style::default().fg(Color::Yellow),
+            )));
+            for line in &change.removed {
+                lines.push(Line::from(Span::styled(
+                    format!("    - {line}"),
+                    Style::default().fg(Color::Red),
+                )));
+            }
+            for line in &change.added {
+                lines.push(Line::from(Span::styled(
+                    format!("    + {line}"),
+                    Style::default().fg(Color::Green),
+                )));
+            }
+        }
+        lines.push(Line::from(""));
+    }
+
+    let history = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title(format!(
+                " History - revision {}/{} (↑↓ move, Esc close) ",
+                first + 1,
+                revisions.len()
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    let popup_area = centered_rect(80, 80, area);
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(history, popup_area);
+}
+
+/// Lists the filter checkboxes by facet, each with the number of todos listed if toggled
+fn render_filter_popup(frame: &mut Frame, area: Rect, popup: &FilterPopup, app: &App) {
+    let mut items = Vec::new();
+    let mut selected = 0;
+    let mut group = "";
+    for (i, facet) in popup.facets.iter().enumerate() {
+        if facet.group() != group {
+            group = facet.group();
+            items.push(ListItem::new(Line::from(Span::styled(
+                group,
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ))));
+        }
+        if i == popup.cursor {
+            selected = items.len();
+        }
+        let checked = app.filters.is_checked(facet);
+        let mut preview = app.filters.clone();
+        preview.toggle(facet);
+        items.push(ListItem::new(Line::from(vec![
+            Span::raw(format!("  [{}] ", if checked { 'x' } else { ' ' })),
+            Span::raw(format!("{:<20}", facet.label(&app.config.priority_labels))),
+            Span::styled(
+                format!("{} {}", symbols().arrow, app.count_listed(&preview)),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ])));
+    }
+
+    let popup_area = centered_rect(50, 80, area);
+    let [list_area, keys_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .areas(popup_area.inner(Margin::new(1, 1)));
+    let list = List::new(items).highlight_style(Style::default().bg(Color::Blue));
+    let mut state = ratatui::widgets::ListState::default().with_selected(Some(selected));
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Block::default()
+            .title(format!(" Filters - {} todos ", app.filtered_todos.len()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+        popup_area,
+    );
+    frame.render_stateful_widget(list, list_area, &mut state);
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            "Space toggle │ c reset │ Esc close",
+            Style::default().fg(Color::Gray),
+        )),
+        keys_area,
+    );
+}
+
+/// Lists every toast, sync result and reminder of this session, newest first
+fn render_notifications(frame: &mut Frame, area: Rect, app: &App) {
+    let lines: Vec<Line> = if app.notifications.is_empty() {
+        vec![Line::from(Span::styled(
+            "No notifications yet",
+            Style::default().fg(Color::Gray),
+        ))]
+    } else {
+        app.notifications
+            .newest_first()
+            .map(|notification| {
+                let (symbol, color) = match notification.kind {
+                    NotificationKind::Info => (symbols().ok, Color::Green),
+                    NotificationKind::Error => (symbols().error, Color::Red),
+                    NotificationKind::Sync => (symbols().info, Color::Cyan),
+                    NotificationKind::Reminder => (symbols().warning, Color::Yellow),
+                };
+                let mut spans = vec![
+                    Span::styled(
+                        notification.at.format("%H:%M:%S ").to_string(),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(format!("{symbol} "), Style::default().fg(color)),
+                    Span::raw(notification.message.clone()),
+                ];
+                if notification.repeats > 1 {
+                    spans.push(Span::styled(
+                        format!(" ({}{})", term::glyph("×", "x"), notification.repeats),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                Line::from(spans)
+            })
+            .collect()
+    };
+
+    let list = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(format!(" Notifications ({}) ", app.notifications.len()))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .scroll((app.notification_scroll, 0));
+    frame.render_widget(list, area);
+}
+
+/// Outlines the part of the screen the tour step is about, with the tip next to it
+fn render_tour(frame: &mut Frame, tour: &Tour, [header, main, footer]: [Rect; 3]) {
+    const CALLOUT_HEIGHT: u16 = 7;
+
+    let step = tour.step();
+    let target = match step.target {
+        Target::Screen => None,
+        Target::Header => Some(header),
+        Target::List => Some(main),
+        Target::Footer => Some(footer),
+    };
+    if let Some(target) = target {
+        frame.render_widget(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Thick)
+                .border_style(Style::default().fg(Color::Yellow)),
+            target,
+        );
+    }
+
+    // Next to the target: below the header, above the footer, else centered
+    let width = main.width.saturating_sub(4).min(64);
+    let height = CALLOUT_HEIGHT.min(main.height);
+    let x = main.x + (main.width - width) / 2;
+    let y = match step.target {
+        Target::Header => main.y,
+        Target::Footer => main.bottom().saturating_sub(height),
+        Target::Screen | Target::List => main.y + (main.height - height) / 2,
+    };
+    let callout_area = Rect::new(x, y, width, height);
+
+    let callout = Paragraph::new(vec![
+        Line::from(step.text),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "{}/{} │ Enter next │ {} back │ Esc skip",
+                tour.number(),
+                STEPS.len(),
+                term::glyph("←", "Left")
+            ),
+            Style::default().fg(Color::Gray),
+        )),
+    ])
+    .wrap(Wrap { trim: true })
+    .block(
+        Block::default()
+            .title(format!(" {} ", step.title))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow)),
+    );
+    frame.render_widget(Clear, callout_area);
+    frame.render_widget(callout, callout_area);
+}
+
+fn render_export_prompt(frame: &mut Frame, area: Rect, path: &str, count: usize) {
+    let lines = vec![
+        Line::from(format!(" Export {count} marked todos to:")),
+        Line::from(Span::styled(
+            format!(" {path}"),
+            Style::default().fg(Color::Yellow),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            " .json, .md or .csv │ Enter export │ Esc cancel",
+            Style::default().fg(Color::Gray),
+        )),
+    ];
+
+    let prompt = Paragraph::new(lines).block(
+        Block::default()
+            .title(" Export ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    let popup_area = centered_rect(60, 30, area);
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(prompt, popup_area);
+
+    let cursor_x = popup_area.x + u16::try_from(path.chars().count()).unwrap_or(0) + 2;
+    frame.set_cursor_position((cursor_x, popup_area.y + 2));
+}
+
 fn render_loading_overlay(frame: &mut Frame, area: Rect, app: &App) {
     let spinner_chars = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
     let spinner_char = spinner_chars[app.loading_spinner_state % spinner_chars.len()];
@@ -811,14 +2031,21 @@ fn render_empty_state_welcome(frame: &mut Frame, area: Rect) {
 fn render_empty_state_filtered(frame: &mut Frame, area: Rect, app: &App) {
     let mut filter_info = Vec::new();
 
-    if !app.show_all_todos {
+    if app.show_archived {
+        filter_info.push("• Showing archived todos (press A to go back)".to_string());
+    } else if !app.filters.shows_completed() {
         filter_info.push("• Showing pending todos only".to_string());
     } else {
         filter_info.push("• Showing all todos".to_string());
     }
 
-    if let Some(priority) = app.filter_priority {
-        filter_info.push(format!("• Priority filter: {priority}"));
+    if !app.filters.priorities.is_empty() {
+        let levels: Vec<String> = app.filters.priorities.iter().map(i32::to_string).collect();
+        filter_info.push(format!("• Priority filter: {}", levels.join(", ")));
+    }
+
+    if !app.filters.tags.is_empty() || !app.filters.due.is_empty() {
+        filter_info.push("• Tag or due date filters (press F to change)".to_string());
     }
 
     if !app.search_query.is_empty() {
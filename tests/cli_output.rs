@@ -0,0 +1,97 @@
+//! Checks what `pacli` prints for different terminals
+//!
+//! These run without a server (against a throwaway config file), so CI runs
+//! them on Windows too.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Variables that influence terminal detection, cleared for every run
+const TERMINAL_VARS: [&str; 11] = [
+    "NO_COLOR",
+    "CLICOLOR_FORCE",
+    "CI",
+    "TERM",
+    "COLORTERM",
+    "WT_SESSION",
+    "TERM_PROGRAM",
+    "ConEmuANSI",
+    "LC_ALL",
+    "LC_CTYPE",
+    "LANG",
+];
+
+fn pacli(dir: &Path, env: &[(&str, &str)], args: &[&str]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_pacli"));
+    command
+        .current_dir(dir)
+        .arg("--config")
+        .arg(dir.join("config.json"))
+        .args(args);
+    for var in TERMINAL_VARS {
+        command.env_remove(var);
+    }
+    command.envs(env.iter().copied());
+    let output = command.output().expect("pacli should run");
+    assert!(
+        output.status.success(),
+        "pacli {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+#[test]
+fn test_plain_output_when_piped() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = pacli(
+        dir.path(),
+        &[("TERM", "dumb")],
+        &["config", "command-alias", "all", "list --all"],
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        !stdout.contains('\u{1b}'),
+        "unexpected escape codes: {stdout:?}"
+    );
+    assert!(stdout.is_ascii(), "unexpected Unicode symbols: {stdout:?}");
+    assert_eq!(
+        stdout.trim_end(),
+        "v 'pacli all' now runs 'pacli list --all'"
+    );
+
+    let config = std::fs::read_to_string(dir.path().join("config.json")).unwrap();
+    assert!(config.contains("list --all"));
+}
+
+#[test]
+fn test_forced_color_and_unicode() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = pacli(
+        dir.path(),
+        &[
+            ("CLICOLOR_FORCE", "1"),
+            ("TERM", "xterm-256color"),
+            ("WT_SESSION", "1"),
+            ("LANG", "en_US.UTF-8"),
+        ],
+        &["config", "command-alias", "all", "list --all"],
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("\u{1b}["),
+        "expected escape codes: {stdout:?}"
+    );
+    assert!(stdout.contains('✓'));
+}
+
+#[test]
+fn test_no_color_wins() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = pacli(
+        dir.path(),
+        &[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")],
+        &["config", "command-alias", "all", "list --all"],
+    );
+    assert!(!String::from_utf8(output.stdout).unwrap().contains('\u{1b}'));
+}
@@ -0,0 +1,37 @@
+//! Runs `pacli --demo` against its built-in server
+//!
+//! The demo brings its own config and data directory, so these also check
+//! that nothing is written to the directory `pacli` runs in.
+
+use std::path::Path;
+use std::process::Command;
+
+fn pacli(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_pacli"))
+        .current_dir(dir)
+        .env("NO_COLOR", "1")
+        .arg("--demo")
+        .args(args)
+        .output()
+        .expect("pacli should run");
+    assert!(
+        output.status.success(),
+        "pacli --demo {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_demo_todos() {
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(pacli(dir.path(), &["count"]).trim(), "8");
+    assert_eq!(pacli(dir.path(), &["count", "--all"]).trim(), "10");
+    assert!(pacli(dir.path(), &["list"]).contains("Renew passport"));
+
+    // Each run starts over from the sample todos
+    pacli(dir.path(), &["add", "Try the demo"]);
+    assert_eq!(pacli(dir.path(), &["count"]).trim(), "8");
+
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
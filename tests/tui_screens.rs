@@ -0,0 +1,126 @@
+//! Renders every `patui` screen off-screen and checks what it shows
+//!
+//! The app is created with [`App::with_config`], so nothing saved on this
+//! machine (session, cache, local state) changes what is drawn.
+
+#![cfg(feature = "tui")]
+
+use chrono::Local;
+use pali_terminal::api::Todo;
+use pali_terminal::tui::app::{App, AppScreen};
+use pali_terminal::tui::input::{Input, Key};
+use pali_terminal::tui::render_to_buffer;
+use pali_terminal::tui::tour::STEPS;
+use pali_terminal::Config;
+use ratatui::buffer::{Buffer, Cell};
+
+const WIDTH: u16 = 110;
+const HEIGHT: u16 = 36;
+
+fn todo(id: &str, title: &str, priority: i32, completed: bool) -> Todo {
+    Todo {
+        id: id.to_string(),
+        title: title.to_string(),
+        description: Some(format!("Notes about {title}")),
+        completed,
+        priority,
+        due_date: None,
+        created_at: 1_700_000_000,
+        updated_at: 1_700_000_000,
+    }
+}
+
+fn sample_app() -> App {
+    let mut app = App::with_config(Config::default()).unwrap();
+    app.todos = vec![
+        todo("a1b2c3d4", "Renew passport", 3, false),
+        todo("b2c3d4e5", "Water the plants", 1, false),
+        todo("c3d4e5f6", "Buy groceries", 1, true),
+    ];
+    app.apply_filters();
+    app
+}
+
+/// The text of the buffer, one line per row
+fn text(buffer: &Buffer) -> String {
+    buffer
+        .content()
+        .chunks(usize::from(buffer.area.width))
+        .map(|row| row.iter().map(Cell::symbol).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render(app: &mut App) -> String {
+    let buffer = render_to_buffer(app, WIDTH, HEIGHT).unwrap();
+    assert_eq!(buffer.area.width, WIDTH);
+    assert_eq!(buffer.area.height, HEIGHT);
+    text(&buffer)
+}
+
+#[test]
+fn test_todo_list() {
+    let mut app = sample_app();
+    let screen = render(&mut app);
+    assert!(screen.contains("Renew passport"), "{screen}");
+    assert!(screen.contains("Water the plants"), "{screen}");
+    // Completed todos are hidden by default
+    assert!(!screen.contains("Buy groceries"), "{screen}");
+
+    let mut empty = App::with_config(Config::default()).unwrap();
+    assert!(render(&mut empty).contains("Welcome to Pali!"));
+}
+
+#[test]
+fn test_every_screen() {
+    let today = Local::now().date_naive().format("%a %-d").to_string();
+    let screens = [
+        (AppScreen::AddTodo, "Title *"),
+        (AppScreen::EditTodo, "Edit Todo"),
+        (AppScreen::Help, "Pali TUI Help"),
+        (AppScreen::Settings, "Current Configuration"),
+        (AppScreen::Search, "Search Todos"),
+        (AppScreen::TodoDetail, "Todo Details"),
+        (AppScreen::Week, today.as_str()),
+        (AppScreen::Notifications, "Notifications (1)"),
+    ];
+    for (screen, expected) in screens {
+        let mut app = sample_app();
+        app.show_success("Saved the todo".to_string());
+        app.current_screen = screen;
+        let rendered = render(&mut app);
+        assert!(
+            rendered.contains(expected),
+            "{screen:?} doesn't show {expected:?}:\n{rendered}"
+        );
+    }
+}
+
+#[test]
+fn test_popups() {
+    let mut app = sample_app();
+    app.open_filter_popup();
+    assert!(render(&mut app).contains("Filters - 2 todos"));
+
+    let mut app = sample_app();
+    app.start_tour();
+    assert!(render(&mut app).contains(STEPS[0].title));
+}
+
+#[tokio::test]
+async fn test_driven_by_keys() {
+    let mut app = sample_app();
+    app.handle_input(Input::key(Key::Char('s'))).await.unwrap();
+    assert!(render(&mut app).contains("Current Configuration"));
+    app.handle_input(Input::key(Key::Char('t'))).await.unwrap();
+    assert!(render(&mut app).contains(STEPS[0].title));
+    app.handle_input(Input::key(Key::Esc)).await.unwrap();
+    app.handle_input(Input::key(Key::Esc)).await.unwrap();
+    assert_eq!(app.current_screen, AppScreen::TodoList);
+
+    app.handle_input(Input::key(Key::Char('/'))).await.unwrap();
+    app.handle_input(Input::ctrl(Key::Char('r'))).await.unwrap();
+    assert!(render(&mut app).contains("Search Todos [regex]"));
+    app.handle_input(Input::ctrl(Key::Char('c'))).await.unwrap();
+    assert!(app.should_quit);
+}